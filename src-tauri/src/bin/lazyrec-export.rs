@@ -0,0 +1,98 @@
+//! Headless export for scripts and CI, rendering exactly like the app's
+//! Export button:
+//!
+//! ```text
+//! lazyrec-export <project.lazyrec> [output.mp4] [--frames <dir>]
+//! ```
+//!
+//! Without an output path the file goes beside the package as
+//! `export_<timestamp>.<ext>`. `--frames` writes numbered PNGs instead.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use lazyrec_lib::core::encoder::ImageSequenceOptions;
+use lazyrec_lib::core::export::{export_project, ExportTarget};
+use lazyrec_lib::core::project::Project;
+use lazyrec_lib::core::render::ExportState;
+
+const USAGE: &str = "usage: lazyrec-export <project.lazyrec> [output] [--frames <dir>]";
+
+struct Args {
+    package_dir: PathBuf,
+    output: Option<PathBuf>,
+    frames: Option<PathBuf>,
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut positional = Vec::new();
+    let mut frames = None;
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--frames" => frames = Some(PathBuf::from(args.next().ok_or("--frames needs a directory")?)),
+            "-h" | "--help" => return Err(USAGE.into()),
+            flag if flag.starts_with("--") => return Err(format!("unknown option {flag}\n{USAGE}")),
+            _ => positional.push(PathBuf::from(arg)),
+        }
+    }
+    let mut positional = positional.into_iter();
+    let package_dir = positional.next().ok_or(USAGE)?;
+    let output = positional.next();
+    if positional.next().is_some() {
+        return Err(USAGE.into());
+    }
+    Ok(Args { package_dir, output, frames })
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{message}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let project = match Project::load(&args.package_dir) {
+        Ok(project) => project,
+        Err(e) => {
+            eprintln!("Can't open {}: {e}", args.package_dir.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let sequence = args.frames.map(|directory| ImageSequenceOptions { directory: Some(directory), ..Default::default() });
+    let mut target = ExportTarget::beside_package(&project, &args.package_dir, sequence);
+    if let Some(output) = args.output {
+        target.output_path = output;
+    }
+
+    let mut last_percent = None;
+    let result = export_project(&project, &args.package_dir, &target, None, |progress| {
+        let percent = (progress.progress * 100.0).floor() as u32;
+        if progress.state == ExportState::Rendering && last_percent != Some(percent) {
+            last_percent = Some(percent);
+            print!(
+                "\rRendering {percent:3}% (frame {}/{}, {:.0}s left)",
+                progress.current_frame, progress.total_frames, progress.eta_seconds,
+            );
+            let _ = std::io::stdout().flush();
+        }
+    });
+    if last_percent.is_some() {
+        println!();
+    }
+
+    match result {
+        Ok(path) => {
+            println!("Exported {}", path.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Export failed: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
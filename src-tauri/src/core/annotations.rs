@@ -0,0 +1,254 @@
+//! Caption annotations: timestamped action descriptions ("Clicked at 0:03",
+//! "Pressed Ctrl+C at 0:07") from recorded input, written as SRT or WebVTT.
+
+use serde::Deserialize;
+
+use super::generators::{
+    is_typing_key, key_display_name, modifier_symbols, typed_edit, ClickType, KeyEventType, MouseData, TypedEdit,
+};
+
+/// How long each annotation stays on screen (seconds)
+const ANNOTATION_DURATION: f64 = 2.0;
+
+/// Plain keystrokes closer than this are merged into one "Typed" entry
+const TYPING_GAP: f64 = 1.5;
+
+/// Subtitle file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnotationFormat {
+    Srt,
+    Vtt,
+}
+
+impl AnnotationFormat {
+    pub fn file_extension(&self) -> &str {
+        match self {
+            Self::Srt => "srt",
+            Self::Vtt => "vtt",
+        }
+    }
+}
+
+/// Single caption entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+// ============================================================================
+// Collection
+// ============================================================================
+
+/// Build annotations for clicks, shortcuts/named keys and typed text,
+/// sorted by time. Entries end when the next one starts so captions never stack.
+pub fn collect_annotations(data: &MouseData) -> Vec<Annotation> {
+    let mut entries: Vec<(f64, String)> = Vec::new();
+
+    for click in &data.clicks {
+        let action = match click.click_type {
+            ClickType::LeftDown => "Clicked",
+            ClickType::RightDown => "Right-clicked",
+            ClickType::DoubleClick => "Double-clicked",
+            ClickType::LeftUp | ClickType::RightUp => continue,
+        };
+        entries.push((click.time, action.to_string()));
+    }
+
+    // Typing (as the keystroke overlays group it) accumulates into a typed string
+    let mut typed: Option<(f64, f64, String)> = None;
+    let flush = |typed: &mut Option<(f64, f64, String)>, entries: &mut Vec<(f64, String)>| {
+        if let Some((start, _, text)) = typed.take() {
+            if !text.trim().is_empty() {
+                entries.push((start, format!("Typed \"{}\"", text.trim())));
+            }
+        }
+    };
+
+    for event in data.keyboard_events.iter().filter(|e| e.event_type == KeyEventType::KeyDown) {
+        let edit = if is_typing_key(event) { typed_edit(event.key_code, event.character.as_deref()) } else { None };
+        if let Some(edit) = edit {
+            if typed.as_ref().is_some_and(|(_, last, _)| event.time - last > TYPING_GAP) {
+                flush(&mut typed, &mut entries);
+            }
+            let run = typed.get_or_insert_with(|| (event.time, event.time, String::new()));
+            run.1 = event.time;
+            match edit {
+                TypedEdit::Insert(c) => run.2.push_str(&c),
+                TypedEdit::Backspace => {
+                    run.2.pop();
+                }
+            }
+            continue;
+        }
+
+        let Some(key_name) = key_display_name(event.key_code, event.character.as_deref()) else {
+            continue;
+        };
+        flush(&mut typed, &mut entries);
+        entries.push((
+            event.time,
+            format!("Pressed {}{}", modifier_symbols(&event.modifiers), key_name),
+        ));
+    }
+    flush(&mut typed, &mut entries);
+
+    entries.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut annotations: Vec<Annotation> = Vec::with_capacity(entries.len());
+    for (i, (time, action)) in entries.iter().enumerate() {
+        let mut end = time + ANNOTATION_DURATION;
+        if let Some((next, _)) = entries.get(i + 1) {
+            end = end.min(*next);
+        }
+        if data.duration > 0.0 {
+            end = end.min(data.duration);
+        }
+        if end <= *time {
+            end = time + 0.1;
+        }
+        annotations.push(Annotation {
+            start: *time,
+            end,
+            text: format!("{action} at {}", short_timestamp(*time)),
+        });
+    }
+    annotations
+}
+
+// ============================================================================
+// Formatting
+// ============================================================================
+
+/// Render annotations as a subtitle file
+pub fn format_annotations(annotations: &[Annotation], format: AnnotationFormat) -> String {
+    let mut out = String::new();
+    if format == AnnotationFormat::Vtt {
+        out.push_str("WEBVTT\n\n");
+    }
+    for (i, a) in annotations.iter().enumerate() {
+        if format == AnnotationFormat::Srt {
+            out.push_str(&format!("{}\n", i + 1));
+        }
+        out.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            cue_timestamp(a.start, format),
+            cue_timestamp(a.end, format),
+            a.text,
+        ));
+    }
+    out
+}
+
+/// "HH:MM:SS,mmm" (SRT) or "HH:MM:SS.mmm" (WebVTT)
+fn cue_timestamp(seconds: f64, format: AnnotationFormat) -> String {
+    let total_ms = (seconds.max(0.0) * 1000.0).round() as u64;
+    let separator = match format {
+        AnnotationFormat::Srt => ',',
+        AnnotationFormat::Vtt => '.',
+    };
+    format!(
+        "{:02}:{:02}:{:02}{separator}{:03}",
+        total_ms / 3_600_000,
+        total_ms / 60_000 % 60,
+        total_ms / 1000 % 60,
+        total_ms % 1000,
+    )
+}
+
+/// "M:SS" for the caption text
+fn short_timestamp(seconds: f64) -> String {
+    let total = seconds.max(0.0).floor() as u64;
+    format!("{}:{:02}", total / 60, total % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::coordinates::NormalizedPoint;
+    use crate::core::generators::{ClickEvent, KeyboardEvent, Modifiers};
+
+    fn key(time: f64, key_code: u16, character: &str, control: bool) -> KeyboardEvent {
+        KeyboardEvent {
+            time,
+            event_type: KeyEventType::KeyDown,
+            key_code,
+            character: Some(character.to_string()),
+            modifiers: Modifiers { control, ..Default::default() },
+        }
+    }
+
+    fn sample_data() -> MouseData {
+        MouseData {
+            positions: vec![],
+            clicks: vec![ClickEvent {
+                time: 3.2,
+                position: NormalizedPoint::new(0.5, 0.5),
+                click_type: ClickType::LeftDown,
+                duration: 0.1,
+            }],
+            keyboard_events: vec![
+                key(5.0, 0x48, "h", false),
+                key(5.1, 0x49, "i", false),
+                key(7.0, 0x43, "c", true),
+            ],
+            drags: vec![],
+            windows: vec![],
+            duration: 10.0,
+        }
+    }
+
+    #[test]
+    fn test_collect_annotations() {
+        let annotations = collect_annotations(&sample_data());
+        let texts: Vec<&str> = annotations.iter().map(|a| a.text.as_str()).collect();
+        assert_eq!(texts, vec![
+            "Clicked at 0:03",
+            "Typed \"hi\" at 0:05",
+            "Pressed Ctrl+C at 0:07",
+        ]);
+        // Captions end when the next one starts
+        assert!((annotations[0].end - 5.0).abs() < 1e-9);
+        assert!((annotations[2].end - 9.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_collect_annotations_groups_shifted_typing() {
+        let shifted = |time: f64, key_code: u16, character: &str| KeyboardEvent {
+            modifiers: Modifiers { shift: true, ..Default::default() },
+            ..key(time, key_code, character, false)
+        };
+        let data = MouseData {
+            keyboard_events: vec![
+                shifted(1.0, 0x48, "H"),
+                key(1.1, 0x49, "i", false),
+                key(1.2, 0x58, "x", false),
+                KeyboardEvent { character: None, ..key(1.3, 0x08, "", false) },
+                shifted(1.4, 0x31, "!"),
+            ],
+            ..sample_data()
+        };
+        let texts: Vec<String> = collect_annotations(&data).into_iter().map(|a| a.text).collect();
+        assert_eq!(texts, vec!["Typed \"Hi!\" at 0:01", "Clicked at 0:03"]);
+    }
+
+    #[test]
+    fn test_format_srt_and_vtt() {
+        let annotations = vec![Annotation { start: 3.2, end: 5.0, text: "Clicked at 0:03".into() }];
+
+        let srt = format_annotations(&annotations, AnnotationFormat::Srt);
+        assert_eq!(srt, "1\n00:00:03,200 --> 00:00:05,000\nClicked at 0:03\n\n");
+
+        let vtt = format_annotations(&annotations, AnnotationFormat::Vtt);
+        assert!(vtt.starts_with("WEBVTT\n\n"));
+        assert!(vtt.contains("00:00:03.200 --> 00:00:05.000"));
+    }
+
+    #[test]
+    fn test_cue_timestamp_hours() {
+        assert_eq!(cue_timestamp(3723.5, AnnotationFormat::Srt), "01:02:03,500");
+        assert_eq!(short_timestamp(125.9), "2:05");
+    }
+}
@@ -3,6 +3,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::permissions::PermissionStatus;
+
 /// Capture target selection
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -51,13 +53,51 @@ pub enum CaptureSourceType {
     Window,
 }
 
+/// Source id prefix for windows; the suffix is the native window handle
+const WINDOW_SOURCE_PREFIX: &str = "window-";
+
+impl CaptureSource {
+    /// Build the source id for a window from its native handle
+    pub fn window_source_id(window_id: u64) -> String {
+        format!("{WINDOW_SOURCE_PREFIX}{window_id}")
+    }
+
+    /// Native window handle encoded in a window source id
+    pub fn window_id(&self) -> Option<u64> {
+        if self.source_type != CaptureSourceType::Window {
+            return None;
+        }
+        self.id.strip_prefix(WINDOW_SOURCE_PREFIX)?.parse().ok()
+    }
+
+    /// What recording this source captures: its window, or its display
+    /// (`display-<index>` ids; anything else is display 0)
+    pub fn target(&self) -> CaptureTarget {
+        if let Some(window_id) = self.window_id() {
+            return CaptureTarget::Window { window_id, title: self.name.clone() };
+        }
+        let display_id = self.id.rsplit('-').next().and_then(|i| i.parse().ok()).unwrap_or(0);
+        CaptureTarget::Display { display_id }
+    }
+}
+
+/// Default max frames buffered between capture and the encoder (2s at 60fps)
+pub const DEFAULT_FRAME_CHANNEL_CAPACITY: usize = 120;
+
 /// Capture configuration
 #[derive(Debug, Clone)]
 pub struct CaptureConfig {
     pub target_fps: u32,
     pub pixel_format: PixelFormat,
-    /// Exclude the app's own windows from capture
+    /// Exclude the app's own windows from display and region captures
     pub exclude_self: bool,
+    /// Max frames buffered between capture and the encoder
+    pub channel_capacity: usize,
+    /// What to do with new frames when that buffer is full
+    pub backpressure: BackpressureMode,
+    /// Keep the system cursor in the captured frames. Off by default: the
+    /// renderer draws its own cursor, and baking both in shows two.
+    pub capture_cursor: bool,
 }
 
 impl Default for CaptureConfig {
@@ -66,10 +106,37 @@ impl Default for CaptureConfig {
             target_fps: 60,
             pixel_format: PixelFormat::Bgra8,
             exclude_self: true,
+            channel_capacity: DEFAULT_FRAME_CHANNEL_CAPACITY,
+            backpressure: BackpressureMode::Drop,
+            capture_cursor: false,
         }
     }
 }
 
+impl CaptureConfig {
+    /// Whether the app's windows are hidden while capturing `target`. A
+    /// window capture is left alone: it only shows the app if the user
+    /// picked one of its windows on purpose.
+    pub fn excludes_self_from(&self, target: &CaptureTarget) -> bool {
+        self.exclude_self && !matches!(target, CaptureTarget::Window { .. })
+    }
+}
+
+/// Behavior when the encoder can't keep up with capture
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum BackpressureMode {
+    /// Drop the new frame (lowest latency)
+    #[default]
+    Drop,
+    /// Wait up to `timeout_ms` for buffer space before dropping,
+    /// trading capture latency for completeness
+    Block {
+        #[serde(rename = "timeoutMs")]
+        timeout_ms: u64,
+    },
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PixelFormat {
     Bgra8,
@@ -99,6 +166,8 @@ pub enum CaptureError {
     PermissionDenied,
     #[error("Capture target not found")]
     TargetNotFound,
+    #[error("No capture sources found")]
+    NoSources,
     #[error("Configuration failed: {0}")]
     ConfigurationFailed(String),
     #[error("Platform error: {0}")]
@@ -111,6 +180,11 @@ pub trait ScreenCapture: Send {
     /// Enumerate available capture sources (displays and windows)
     fn enumerate_sources(&self) -> Result<Vec<CaptureSource>, CaptureError>;
 
+    /// Query just the primary display, for when full enumeration fails
+    fn primary_display(&self) -> Option<CaptureSource> {
+        None
+    }
+
     /// Start capturing frames from the given target.
     /// Frames will be delivered via the callback.
     fn start_capture(
@@ -193,6 +267,7 @@ pub mod windows {
     use windows_capture::frame::Frame;
     use windows_capture::graphics_capture_api::InternalCaptureControl;
     use windows_capture::monitor::Monitor;
+    use windows_capture::window::Window;
     use windows_capture::settings::{
         ColorFormat, CursorCaptureSettings, DirtyRegionSettings, DrawBorderSettings,
         MinimumUpdateIntervalSettings, SecondaryWindowSettings, Settings,
@@ -258,6 +333,7 @@ pub mod windows {
         capturing: bool,
         control: Option<CaptureControl<CaptureHandler, Box<dyn std::error::Error + Send + Sync>>>,
         flags: Option<Arc<CaptureFlags>>,
+        exclusion: Option<SelfExclusion>,
     }
 
     impl WindowsCapture {
@@ -266,6 +342,69 @@ pub mod windows {
                 capturing: false,
                 control: None,
                 flags: None,
+                exclusion: None,
+            }
+        }
+    }
+
+    /// This process's top-level windows, hidden from screen capture
+    /// (`WDA_EXCLUDEFROMCAPTURE`, Windows 10 2004+) until dropped. The
+    /// compositor leaves them out of the captured surface, so the frames show
+    /// whatever is behind the app instead.
+    struct SelfExclusion {
+        /// Raw HWNDs (kept as integers so the capture stays `Send`)
+        hwnds: Vec<isize>,
+    }
+
+    impl SelfExclusion {
+        fn apply() -> Self {
+            use ::windows::Win32::Foundation::{BOOL, HWND, LPARAM};
+            use ::windows::Win32::System::Threading::GetCurrentProcessId;
+            use ::windows::Win32::UI::WindowsAndMessaging::{
+                EnumWindows, GetWindowThreadProcessId, SetWindowDisplayAffinity, WDA_EXCLUDEFROMCAPTURE,
+            };
+
+            unsafe extern "system" fn collect(hwnd: HWND, lparam: LPARAM) -> BOOL {
+                let mut process_id = 0u32;
+                GetWindowThreadProcessId(hwnd, Some(&mut process_id as *mut u32));
+                if process_id == GetCurrentProcessId() {
+                    // SAFETY: lparam is the Vec passed to EnumWindows below, alive for the call
+                    (*(lparam.0 as *mut Vec<isize>)).push(hwnd.0 as isize);
+                }
+                true.into()
+            }
+
+            let mut own = Vec::<isize>::new();
+            // SAFETY: `collect` only runs inside EnumWindows, while `own` is borrowed
+            if let Err(e) = unsafe { EnumWindows(Some(collect), LPARAM(&mut own as *mut Vec<isize> as isize)) } {
+                log::warn!("Couldn't list the app's windows to exclude from capture: {e}");
+            }
+            let hwnds = own
+                .into_iter()
+                .filter(|&hwnd| {
+                    // SAFETY: a window handle of this process, just enumerated
+                    match unsafe { SetWindowDisplayAffinity(HWND(hwnd as *mut std::ffi::c_void), WDA_EXCLUDEFROMCAPTURE) } {
+                        Ok(()) => true,
+                        Err(e) => {
+                            log::warn!("Couldn't exclude window {hwnd:#x} from capture: {e}");
+                            false
+                        }
+                    }
+                })
+                .collect::<Vec<_>>();
+            log::info!("Excluded {} app window(s) from capture", hwnds.len());
+            Self { hwnds }
+        }
+    }
+
+    impl Drop for SelfExclusion {
+        fn drop(&mut self) {
+            use ::windows::Win32::Foundation::HWND;
+            use ::windows::Win32::UI::WindowsAndMessaging::{SetWindowDisplayAffinity, WDA_NONE};
+
+            for &hwnd in &self.hwnds {
+                // SAFETY: the handle may have been destroyed since; the call then just fails
+                let _ = unsafe { SetWindowDisplayAffinity(HWND(hwnd as *mut std::ffi::c_void), WDA_NONE) };
             }
         }
     }
@@ -275,45 +414,77 @@ pub mod windows {
             let mut sources = Vec::new();
 
             // Enumerate monitors
-            if let Ok(monitors) = Monitor::enumerate() {
-                for (i, monitor) in monitors.iter().enumerate() {
-                    let name = monitor.name().unwrap_or_else(|_| format!("Display {}", i + 1));
-                    let w = monitor.width().unwrap_or(1920);
-                    let h = monitor.height().unwrap_or(1080);
-                    sources.push(CaptureSource {
-                        id: format!("display-{}", i),
-                        name,
-                        source_type: CaptureSourceType::Display,
-                        width: w,
-                        height: h,
-                    });
-                }
+            let monitors = Monitor::enumerate()
+                .map_err(|e| CaptureError::Platform(format!("Enumerate monitors: {e}")))?;
+            for (i, monitor) in monitors.iter().enumerate() {
+                let name = monitor.name().unwrap_or_else(|_| format!("Display {}", i + 1));
+                let w = monitor.width().unwrap_or(0);
+                let h = monitor.height().unwrap_or(0);
+                sources.push(CaptureSource {
+                    id: format!("display-{}", i),
+                    name,
+                    source_type: CaptureSourceType::Display,
+                    width: w,
+                    height: h,
+                });
             }
 
-            if sources.is_empty() {
-                // Fallback
+            // Enumerate capturable top-level windows, keyed by HWND.
+            // Windows are optional: a failure here still lists the displays.
+            let windows = Window::enumerate().unwrap_or_else(|e| {
+                log::warn!("Window enumeration failed: {e}");
+                Vec::new()
+            });
+            for window in windows {
+                let title = match window.title() {
+                    Ok(t) if !t.trim().is_empty() => t,
+                    _ => continue,
+                };
+                let hwnd = window.as_raw_hwnd();
+                let (w, h) = window_size(hwnd).unwrap_or((0, 0));
                 sources.push(CaptureSource {
-                    id: "display-0".into(),
-                    name: "Primary Display".into(),
-                    source_type: CaptureSourceType::Display,
-                    width: 1920,
-                    height: 1080,
+                    id: CaptureSource::window_source_id(hwnd as usize as u64),
+                    name: title,
+                    source_type: CaptureSourceType::Window,
+                    width: w,
+                    height: h,
                 });
             }
 
             Ok(sources)
         }
 
+        fn primary_display(&self) -> Option<CaptureSource> {
+            let monitor = Monitor::primary().ok()?;
+            Some(CaptureSource {
+                // Display id 0 always starts the primary monitor
+                id: "display-0".into(),
+                name: monitor.name().unwrap_or_else(|_| "Primary Display".into()),
+                source_type: CaptureSourceType::Display,
+                width: monitor.width().ok()?,
+                height: monitor.height().ok()?,
+            })
+        }
+
         fn start_capture(
             &mut self,
             target: CaptureTarget,
-            _config: CaptureConfig,
+            config: CaptureConfig,
             on_frame: Box<dyn FnMut(CapturedFrame) + Send>,
         ) -> Result<(), CaptureError> {
             if self.capturing {
                 return Err(CaptureError::AlreadyCapturing);
             }
 
+            let cursor = if config.capture_cursor {
+                CursorCaptureSettings::WithCursor
+            } else {
+                CursorCaptureSettings::WithoutCursor
+            };
+
+            // Hidden before the session starts, so not even the first frame shows the app
+            let exclusion = config.excludes_self_from(&target).then(SelfExclusion::apply);
+
             let flags = Arc::new(CaptureFlags {
                 on_frame: Mutex::new(on_frame),
                 start_time: Instant::now(),
@@ -333,7 +504,7 @@ pub mod windows {
 
                     let settings = Settings::new(
                         monitor,
-                        CursorCaptureSettings::WithCursor,
+                        cursor,
                         DrawBorderSettings::Default,
                         SecondaryWindowSettings::Default,
                         MinimumUpdateIntervalSettings::Default,
@@ -345,15 +516,13 @@ pub mod windows {
                     CaptureHandler::start_free_threaded(settings)
                         .map_err(|e| CaptureError::Platform(e.to_string()))?
                 }
-                CaptureTarget::Window { .. } => {
-                    // Window capture removed — fall back to primary display
-                    log::warn!("Window capture not supported, falling back to primary display");
-                    let monitor = Monitor::primary()
-                        .map_err(|e| CaptureError::Platform(format!("Monitor not found: {e}")))?;
+                CaptureTarget::Window { window_id, title } => {
+                    let window = resolve_window(window_id, &title)
+                        .ok_or(CaptureError::TargetNotFound)?;
 
                     let settings = Settings::new(
-                        monitor,
-                        CursorCaptureSettings::WithCursor,
+                        window,
+                        cursor,
                         DrawBorderSettings::Default,
                         SecondaryWindowSettings::Default,
                         MinimumUpdateIntervalSettings::Default,
@@ -377,7 +546,7 @@ pub mod windows {
 
                     let settings = Settings::new(
                         monitor,
-                        CursorCaptureSettings::WithCursor,
+                        cursor,
                         DrawBorderSettings::Default,
                         SecondaryWindowSettings::Default,
                         MinimumUpdateIntervalSettings::Default,
@@ -393,6 +562,7 @@ pub mod windows {
 
             self.flags = Some(flags);
             self.control = Some(control);
+            self.exclusion = exclusion;
             self.capturing = true;
             Ok(())
         }
@@ -433,6 +603,7 @@ pub mod windows {
             }
 
             self.flags = None;
+            self.exclusion = None;
             self.capturing = false;
             Ok(())
         }
@@ -441,6 +612,37 @@ pub mod windows {
             self.capturing
         }
     }
+
+    /// Find the window to capture: prefer the HWND, fall back to the title
+    /// (exact, then substring) only when the handle is stale.
+    fn resolve_window(window_id: u64, title: &str) -> Option<Window> {
+        if window_id != 0 {
+            let window = Window::from_raw_hwnd(window_id as usize as *mut std::ffi::c_void);
+            if window.is_valid() {
+                return Some(window);
+            }
+            log::warn!("Window handle {window_id:#x} is stale, falling back to title \"{title}\"");
+        }
+
+        if title.is_empty() {
+            return None;
+        }
+        Window::from_name(title)
+            .or_else(|_| Window::from_contains_name(title))
+            .ok()
+    }
+
+    /// Outer window size in pixels
+    fn window_size(hwnd: *mut std::ffi::c_void) -> Option<(u32, u32)> {
+        use ::windows::Win32::Foundation::{HWND, RECT};
+        use ::windows::Win32::UI::WindowsAndMessaging::GetWindowRect;
+
+        let mut rect = RECT::default();
+        unsafe { GetWindowRect(HWND(hwnd), &mut rect) }.ok()?;
+        let w = (rect.right - rect.left).max(0) as u32;
+        let h = (rect.bottom - rect.top).max(0) as u32;
+        Some((w, h))
+    }
 }
 
 #[cfg(target_os = "linux")]
@@ -497,6 +699,35 @@ pub mod linux {
     }
 }
 
+/// Sources to offer for recording. A failed enumeration falls back to the
+/// primary display alone, and zero-size sources (minimized or cloaked
+/// windows) are dropped. Fails only when capture isn't permitted or nothing
+/// at all can be captured.
+pub fn list_sources(
+    capture: &dyn ScreenCapture,
+    permission: PermissionStatus,
+) -> Result<Vec<CaptureSource>, CaptureError> {
+    if permission == PermissionStatus::Denied {
+        return Err(CaptureError::PermissionDenied);
+    }
+    let mut sources = match capture.enumerate_sources() {
+        Ok(sources) => sources,
+        Err(CaptureError::PermissionDenied) => return Err(CaptureError::PermissionDenied),
+        Err(e) => {
+            log::warn!("Capture source enumeration failed, falling back to the primary display: {e}");
+            Vec::new()
+        }
+    };
+    sources.retain(|s| s.width > 0 && s.height > 0);
+    if !sources.iter().any(|s| s.source_type == CaptureSourceType::Display) {
+        sources.extend(capture.primary_display().filter(|s| s.width > 0 && s.height > 0));
+    }
+    if sources.is_empty() {
+        return Err(CaptureError::NoSources);
+    }
+    Ok(sources)
+}
+
 /// Create the platform-appropriate capture backend
 pub fn create_capture() -> Box<dyn ScreenCapture> {
     #[cfg(target_os = "windows")]
@@ -508,3 +739,137 @@ pub fn create_capture() -> Box<dyn ScreenCapture> {
     #[cfg(not(any(target_os = "windows", target_os = "linux")))]
     { Box::new(stub::StubCapture::new()) }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_source_id_roundtrip() {
+        let source = CaptureSource {
+            id: CaptureSource::window_source_id(0x1234),
+            name: "Editor".into(),
+            source_type: CaptureSourceType::Window,
+            width: 800,
+            height: 600,
+        };
+        assert_eq!(source.window_id(), Some(0x1234));
+    }
+
+    #[test]
+    fn test_source_target() {
+        let source = |id: &str, source_type| CaptureSource {
+            id: id.into(),
+            name: "Editor".into(),
+            source_type,
+            width: 800,
+            height: 600,
+        };
+        let window = source(&CaptureSource::window_source_id(0x1234), CaptureSourceType::Window);
+        assert!(matches!(
+            window.target(),
+            CaptureTarget::Window { window_id: 0x1234, ref title } if title == "Editor"
+        ));
+        assert!(matches!(source("display-2", CaptureSourceType::Display).target(), CaptureTarget::Display { display_id: 2 }));
+        assert!(matches!(source("stub-display", CaptureSourceType::Display).target(), CaptureTarget::Display { display_id: 0 }));
+    }
+
+    /// Backend whose enumeration result is fixed, with an optional primary display
+    struct FixedCapture {
+        sources: Result<Vec<CaptureSource>, fn() -> CaptureError>,
+        primary: Option<CaptureSource>,
+    }
+
+    impl ScreenCapture for FixedCapture {
+        fn enumerate_sources(&self) -> Result<Vec<CaptureSource>, CaptureError> {
+            self.sources.clone().map_err(|e| e())
+        }
+
+        fn primary_display(&self) -> Option<CaptureSource> {
+            self.primary.clone()
+        }
+
+        fn start_capture(
+            &mut self,
+            _target: CaptureTarget,
+            _config: CaptureConfig,
+            _on_frame: Box<dyn FnMut(CapturedFrame) + Send>,
+        ) -> Result<(), CaptureError> {
+            Ok(())
+        }
+
+        fn stop_capture(&mut self) -> Result<(), CaptureError> {
+            Ok(())
+        }
+
+        fn is_capturing(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_list_sources_fallbacks() {
+        let source = |id: &str, source_type, width| CaptureSource {
+            id: id.into(), name: id.into(), source_type, width, height: 720,
+        };
+        let primary = Some(source("display-0", CaptureSourceType::Display, 2560));
+        let granted = PermissionStatus::Granted;
+
+        // Enumeration failure: the primary display alone
+        let failing = FixedCapture { sources: Err(|| CaptureError::Platform("hiccup".into())), primary: primary.clone() };
+        let sources = list_sources(&failing, granted).unwrap();
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].width, 2560);
+
+        // Zero-size windows dropped; missing displays filled in
+        let windows_only = FixedCapture {
+            sources: Ok(vec![
+                source("window-1", CaptureSourceType::Window, 800),
+                source("window-2", CaptureSourceType::Window, 0),
+            ]),
+            primary: primary.clone(),
+        };
+        let ids: Vec<String> = list_sources(&windows_only, granted).unwrap().into_iter().map(|s| s.id).collect();
+        assert_eq!(ids, ["window-1", "display-0"]);
+
+        // No permission vs nothing to capture
+        assert!(matches!(list_sources(&failing, PermissionStatus::Denied), Err(CaptureError::PermissionDenied)));
+        let denied = FixedCapture { sources: Err(|| CaptureError::PermissionDenied), primary: primary.clone() };
+        assert!(matches!(list_sources(&denied, granted), Err(CaptureError::PermissionDenied)));
+        let empty = FixedCapture { sources: Ok(Vec::new()), primary: None };
+        assert!(matches!(list_sources(&empty, granted), Err(CaptureError::NoSources)));
+    }
+
+    #[test]
+    fn test_exclude_self_skips_window_targets() {
+        let config = CaptureConfig::default();
+        let display = CaptureTarget::Display { display_id: 0 };
+        let region = CaptureTarget::Region { display_id: 0, x: 0.0, y: 0.0, width: 640.0, height: 480.0 };
+        let window = CaptureTarget::Window { window_id: 0x1234, title: "LazyRec".into() };
+        assert!(config.excludes_self_from(&display));
+        assert!(config.excludes_self_from(&region));
+        assert!(!config.excludes_self_from(&window));
+
+        let shown = CaptureConfig { exclude_self: false, ..CaptureConfig::default() };
+        assert!(!shown.excludes_self_from(&display));
+    }
+
+    #[test]
+    fn test_window_id_ignores_displays_and_bad_ids() {
+        let display = CaptureSource {
+            id: "display-0".into(),
+            name: "Primary Display".into(),
+            source_type: CaptureSourceType::Display,
+            width: 1920,
+            height: 1080,
+        };
+        assert_eq!(display.window_id(), None);
+
+        let bad = CaptureSource {
+            id: "window-abc".into(),
+            source_type: CaptureSourceType::Window,
+            ..display
+        };
+        assert_eq!(bad.window_id(), None);
+    }
+}
@@ -0,0 +1,183 @@
+//! Content-based activity: frame differencing over the recorded video.
+//! Finds regions that change while the cursor doesn't (video playback,
+//! animations, build output) and reports them as SmartZoom activities.
+
+use super::coordinates::NormalizedPoint;
+use super::generators::{ActivityEvent, ActivityType};
+use super::render::{ExportError, FrameBuffer, VideoSource};
+
+/// Seconds between analysed frames
+const SAMPLE_INTERVAL: f64 = 0.5;
+/// Width of the luma thumbnail frames are reduced to before diffing
+const THUMBNAIL_WIDTH: u32 = 64;
+/// Grid the thumbnail is split into for per-region change
+const GRID: usize = 8;
+/// Luma difference (0-255) for a thumbnail pixel to count as changed
+const PIXEL_THRESHOLD: u8 = 24;
+/// Fraction of changed pixels for a cell to count as active
+const CELL_THRESHOLD: f64 = 0.2;
+/// More active cells than this fraction is a whole-screen change (window
+/// switch, scroll), which has no focus point
+const MAX_ACTIVE_FRACTION: f64 = 0.5;
+
+/// Box-filtered luma thumbnail of a BGRA frame, `THUMBNAIL_WIDTH` wide
+struct Thumbnail {
+    luma: Vec<u8>,
+    width: usize,
+    height: usize,
+}
+
+impl Thumbnail {
+    fn from_frame(frame: &FrameBuffer) -> Self {
+        let block = (frame.width / THUMBNAIL_WIDTH).max(1);
+        let width = (frame.width / block) as usize;
+        let height = (frame.height / block) as usize;
+        let mut luma = vec![0u8; width * height];
+
+        for ty in 0..height {
+            for tx in 0..width {
+                let mut sum = 0u32;
+                for y in ty as u32 * block..(ty as u32 + 1) * block {
+                    let row = (y * frame.stride) as usize;
+                    for x in tx as u32 * block..(tx as u32 + 1) * block {
+                        let i = row + x as usize * 4;
+                        let (b, g, r) = (frame.data[i] as u32, frame.data[i + 1] as u32, frame.data[i + 2] as u32);
+                        sum += (r * 77 + g * 150 + b * 29) >> 8;
+                    }
+                }
+                luma[ty * width + tx] = (sum / (block * block)) as u8;
+            }
+        }
+        Self { luma, width, height }
+    }
+
+    /// Fraction of changed pixels in each grid cell (row-major)
+    fn cell_changes(&self, previous: &Thumbnail) -> [f64; GRID * GRID] {
+        let mut changed = [0u32; GRID * GRID];
+        let mut total = [0u32; GRID * GRID];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let cell = (y * GRID / self.height) * GRID + x * GRID / self.width;
+                let i = y * self.width + x;
+                total[cell] += 1;
+                if self.luma[i].abs_diff(previous.luma[i]) >= PIXEL_THRESHOLD {
+                    changed[cell] += 1;
+                }
+            }
+        }
+        std::array::from_fn(|c| if total[c] == 0 { 0.0 } else { changed[c] as f64 / total[c] as f64 })
+    }
+}
+
+/// Centre of the strongest changing region, or None when nothing (or nearly
+/// everything) changed. The region is the most-changed cell plus its active
+/// neighbours, weighted by how much each changed.
+fn change_focus(changes: &[f64; GRID * GRID]) -> Option<NormalizedPoint> {
+    let active = changes.iter().filter(|&&c| c >= CELL_THRESHOLD).count();
+    if active == 0 || active as f64 > MAX_ACTIVE_FRACTION * (GRID * GRID) as f64 {
+        return None;
+    }
+    let (peak, _) = changes
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))?;
+    let (peak_row, peak_col) = (peak / GRID, peak % GRID);
+
+    let (mut x, mut y, mut weight) = (0.0, 0.0, 0.0);
+    for (cell, &change) in changes.iter().enumerate() {
+        let (row, col) = (cell / GRID, cell % GRID);
+        if change < CELL_THRESHOLD || row.abs_diff(peak_row) > 1 || col.abs_diff(peak_col) > 1 {
+            continue;
+        }
+        x += (col as f64 + 0.5) / GRID as f64 * change;
+        y += (row as f64 + 0.5) / GRID as f64 * change;
+        weight += change;
+    }
+    Some(NormalizedPoint::new(x / weight, y / weight))
+}
+
+/// Sample `source` every `SAMPLE_INTERVAL` seconds and emit a
+/// `ContentChange` activity wherever a region changed since the previous
+/// sample. Frames that fail to decode are skipped.
+pub fn analyze_content(source: &mut dyn VideoSource) -> Result<Vec<ActivityEvent>, ExportError> {
+    let duration = source.duration();
+    let mut activities = Vec::new();
+    let mut previous: Option<Thumbnail> = None;
+
+    let samples = (duration / SAMPLE_INTERVAL).floor() as usize;
+    for i in 0..=samples {
+        let time = i as f64 * SAMPLE_INTERVAL;
+        let frame = match source.read_frame(time) {
+            Ok(frame) => frame,
+            Err(ExportError::Cancelled) => return Err(ExportError::Cancelled),
+            Err(e) => {
+                log::debug!("Content analysis skipped frame at {time:.2}s: {e}");
+                continue;
+            }
+        };
+        if frame.width == 0 || frame.height == 0 {
+            continue;
+        }
+        let thumbnail = Thumbnail::from_frame(&frame);
+        if let Some(previous) = previous.as_ref().filter(|p| p.luma.len() == thumbnail.luma.len()) {
+            if let Some(position) = change_focus(&thumbnail.cell_changes(previous)) {
+                activities.push(ActivityEvent { time, position, activity_type: ActivityType::ContentChange });
+            }
+        }
+        previous = Some(thumbnail);
+    }
+    Ok(activities)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Dark frames with a bright square in the top-right quarter that
+    /// flickers every half second (a playing video); the whole screen
+    /// flips brightness from 3s on.
+    struct FlickerSource;
+
+    impl VideoSource for FlickerSource {
+        fn total_frames(&self) -> u64 {
+            120
+        }
+
+        fn frame_rate(&self) -> f64 {
+            30.0
+        }
+
+        fn duration(&self) -> f64 {
+            4.0
+        }
+
+        fn read_frame(&mut self, time: f64) -> Result<FrameBuffer, ExportError> {
+            let step = (time / SAMPLE_INTERVAL).round() as u32;
+            let base = if time >= 3.0 && step.is_multiple_of(2) { 200 } else { 20 };
+            let mut frame = FrameBuffer::solid(320, 180, base, base, base, 255);
+            let square = if step.is_multiple_of(2) { 240 } else { 40 };
+            for y in 20..60 {
+                for x in 220..280 {
+                    let i = (y * frame.stride + x * 4) as usize;
+                    frame.data[i..i + 3].fill(square);
+                }
+            }
+            Ok(frame)
+        }
+    }
+
+    #[test]
+    fn test_analyze_content_finds_changing_region() {
+        let activities = analyze_content(&mut FlickerSource).unwrap();
+
+        // One per sample after the first, up to the whole-screen flips
+        let times: Vec<f64> = activities.iter().map(|a| a.time).collect();
+        assert_eq!(times, [0.5, 1.0, 1.5, 2.0, 2.5]);
+        for activity in &activities {
+            assert_eq!(activity.activity_type, ActivityType::ContentChange);
+            // Square centre is (250/320, 40/180) ≈ (0.78, 0.22)
+            assert!((activity.position.x - 0.78).abs() < 0.1, "{:?}", activity.position);
+            assert!((activity.position.y - 0.22).abs() < 0.1, "{:?}", activity.position);
+        }
+    }
+}
@@ -0,0 +1,317 @@
+//! Cursor bitmaps per `CursorStyle` for `RenderSettings::cursor_theme`: the
+//! built-in packs are drawn from vector shapes once per export, a user pack
+//! is a directory of PNGs copied into the project.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+use serde::Deserialize;
+
+use super::keyframe::CursorStyle;
+use super::project::CursorTheme;
+use super::render::{load_background_image, FrameBuffer};
+
+const ALL_STYLES: [CursorStyle; 7] = [
+    CursorStyle::Arrow,
+    CursorStyle::Pointer,
+    CursorStyle::IBeam,
+    CursorStyle::Crosshair,
+    CursorStyle::OpenHand,
+    CursorStyle::ClosedHand,
+    CursorStyle::ContextMenu,
+];
+
+/// Bitmap height of a built-in cursor; with `density` above 1 it stays
+/// sharp when `cursor_scale` enlarges it
+const BUILTIN_RESOLUTION: f64 = 64.0;
+
+/// Empty border around a built-in shape, as a fraction of its height
+const BUILTIN_MARGIN: f64 = 0.06;
+
+/// Outline width of a built-in shape, as a fraction of its height
+const BUILTIN_STROKE: f64 = 0.035;
+
+/// Options file in a user pack: per-style hotspots
+pub const THEME_FILE: &str = "theme.json";
+
+/// One style's bitmap
+#[derive(Debug, Clone)]
+pub struct CursorImage {
+    /// Straight-alpha BGRA
+    pub image: FrameBuffer,
+    /// The pixel that sits on the pointer position, in image pixels
+    pub hotspot: (f64, f64),
+    /// Image pixels per output pixel at cursor scale 1 (2 for `@2x` PNGs)
+    pub density: f64,
+}
+
+/// Bitmaps for every cursor style; styles a user pack lacks use its arrow
+#[derive(Debug, Clone, Default)]
+pub struct CursorPack {
+    /// Per style, least dense first
+    images: HashMap<CursorStyle, Vec<CursorImage>>,
+}
+
+impl CursorPack {
+    /// The built-in pack for `theme` (None for `Dot`, which draws no bitmap,
+    /// and `Custom`, which is loaded from the package). Drawn once, then shared.
+    pub fn builtin(theme: &CursorTheme) -> Option<Arc<Self>> {
+        static SYSTEM: OnceLock<Arc<CursorPack>> = OnceLock::new();
+        static LARGE_YELLOW: OnceLock<Arc<CursorPack>> = OnceLock::new();
+        // BGR fill and outline, height at scale 1
+        let (cache, fill, outline, height) = match theme {
+            CursorTheme::Dot | CursorTheme::Custom { .. } => return None,
+            CursorTheme::System => (&SYSTEM, [255, 255, 255], [0, 0, 0], 22.0),
+            CursorTheme::LargeYellow => (&LARGE_YELLOW, [0, 214, 255], [0, 0, 0], 36.0),
+        };
+        let pack = cache.get_or_init(|| {
+            let images = ALL_STYLES
+                .into_iter()
+                .map(|style| (style, vec![draw_builtin(style, fill, outline, height)]))
+                .collect();
+            Arc::new(Self { images })
+        });
+        Some(pack.clone())
+    }
+
+    /// Load a user pack: `<style>.png` per style (`arrow.png`, `pointer.png`,
+    /// `ibeam.png`, `crosshair.png`, `open_hand.png`, `closed_hand.png`,
+    /// `context_menu.png`), optionally with a sharper `<style>@2x.png`, and
+    /// a `theme.json` of hotspots in 1x pixels
+    /// (`{"pointer": {"hotspotX": 6, "hotspotY": 0}, ...}`; default top-left).
+    /// None when the directory has no usable arrow.
+    pub fn load(dir: &Path) -> Option<Self> {
+        let hotspots: HashMap<String, Hotspot> = match std::fs::read_to_string(dir.join(THEME_FILE)) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                log::warn!("Ignoring {}: {e}", dir.join(THEME_FILE).display());
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+
+        let mut images = HashMap::new();
+        for style in ALL_STYLES {
+            let stem = file_stem(style);
+            let hotspot = hotspots.get(stem).copied().unwrap_or_default();
+            let variants: Vec<CursorImage> = [(format!("{stem}.png"), 1.0), (format!("{stem}@2x.png"), 2.0)]
+                .into_iter()
+                .filter(|(name, _)| dir.join(name).is_file())
+                .filter_map(|(name, density)| {
+                    let image = load_background_image(&dir.join(name))?;
+                    (image.width > 0 && image.height > 0).then_some(CursorImage {
+                        image,
+                        hotspot: (hotspot.hotspot_x * density, hotspot.hotspot_y * density),
+                        density,
+                    })
+                })
+                .collect();
+            if !variants.is_empty() {
+                images.insert(style, variants);
+            }
+        }
+        if !images.contains_key(&CursorStyle::Arrow) {
+            log::warn!("Cursor theme {} has no arrow.png", dir.display());
+            return None;
+        }
+        Some(Self { images })
+    }
+
+    /// `load`, falling back to the system theme (with a warning) when `dir`
+    /// isn't a usable pack
+    pub fn load_or_system(dir: &Path) -> Arc<Self> {
+        Self::load(dir).map(Arc::new).unwrap_or_else(|| {
+            log::warn!("Cursor theme {} not loaded, using the system theme", dir.display());
+            Self::builtin(&CursorTheme::System).unwrap_or_default()
+        })
+    }
+
+    /// The bitmap for `style` drawn at `scale`: the least dense that isn't
+    /// enlarged past its resolution, or the densest there is
+    pub fn image(&self, style: CursorStyle, scale: f64) -> Option<&CursorImage> {
+        let variants = self.images.get(&style).or_else(|| self.images.get(&CursorStyle::Arrow))?;
+        variants.iter().find(|v| v.density >= scale).or(variants.last())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct Hotspot {
+    hotspot_x: f64,
+    hotspot_y: f64,
+}
+
+/// File name of a style's bitmap in a user pack, without extension
+fn file_stem(style: CursorStyle) -> &'static str {
+    match style {
+        CursorStyle::Arrow => "arrow",
+        CursorStyle::Pointer => "pointer",
+        CursorStyle::IBeam => "ibeam",
+        CursorStyle::Crosshair => "crosshair",
+        CursorStyle::OpenHand => "open_hand",
+        CursorStyle::ClosedHand => "closed_hand",
+        CursorStyle::ContextMenu => "context_menu",
+    }
+}
+
+/// A convex or concave outline in units of the cursor's height
+type Polygon = Vec<(f64, f64)>;
+
+fn rect(x0: f64, y0: f64, x1: f64, y1: f64) -> Polygon {
+    vec![(x0, y0), (x1, y0), (x1, y1), (x0, y1)]
+}
+
+/// Shape (a union of polygons) and hotspot of a built-in style
+fn builtin_shape(style: CursorStyle) -> (Vec<Polygon>, (f64, f64)) {
+    let arrow = vec![(0.0, 0.0), (0.0, 0.8), (0.19, 0.63), (0.32, 0.93), (0.43, 0.88), (0.3, 0.6), (0.55, 0.6)];
+    let fingers = |top: f64| (0..4).map(move |i| rect(0.15 + i as f64 * 0.17, top, 0.29 + i as f64 * 0.17, 0.55));
+    match style {
+        CursorStyle::Arrow => (vec![arrow], (0.0, 0.0)),
+        CursorStyle::Pointer => (
+            vec![
+                rect(0.22, 0.0, 0.38, 0.6),
+                rect(0.38, 0.35, 0.78, 0.6),
+                rect(0.14, 0.5, 0.78, 0.92),
+                vec![(0.0, 0.58), (0.1, 0.5), (0.22, 0.62), (0.22, 0.82)],
+            ],
+            (0.3, 0.0),
+        ),
+        CursorStyle::IBeam => (
+            vec![rect(0.15, 0.08, 0.25, 0.92), rect(0.04, 0.04, 0.36, 0.12), rect(0.04, 0.88, 0.36, 0.96)],
+            (0.2, 0.5),
+        ),
+        CursorStyle::Crosshair => (vec![rect(0.04, 0.45, 0.96, 0.55), rect(0.45, 0.04, 0.55, 0.96)], (0.5, 0.5)),
+        CursorStyle::OpenHand => {
+            let mut shape: Vec<Polygon> = fingers(0.12).collect();
+            shape.push(rect(0.15, 0.45, 0.83, 0.95));
+            shape.push(vec![(0.0, 0.55), (0.08, 0.48), (0.2, 0.6), (0.2, 0.8)]);
+            (shape, (0.5, 0.5))
+        }
+        CursorStyle::ClosedHand => {
+            let mut shape: Vec<Polygon> = fingers(0.3).collect();
+            shape.push(rect(0.15, 0.42, 0.83, 0.9));
+            (shape, (0.5, 0.55))
+        }
+        CursorStyle::ContextMenu => (vec![arrow, rect(0.5, 0.3, 0.95, 0.75)], (0.0, 0.0)),
+    }
+}
+
+/// Even-odd point-in-polygon test
+fn inside(polygon: &[(f64, f64)], x: f64, y: f64) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len() - 1;
+    for (i, &(xi, yi)) in polygon.iter().enumerate() {
+        let (xj, yj) = polygon[j];
+        if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Rasterize a built-in style: `fill` inside, an `outline` border around it,
+/// 3x3 supersampled. `height` is the cursor's height in output pixels at scale 1.
+fn draw_builtin(style: CursorStyle, fill: [u8; 3], outline: [u8; 3], height: f64) -> CursorImage {
+    const SUBSAMPLES: u32 = 3;
+    let (shape, hotspot) = builtin_shape(style);
+    let size = (BUILTIN_RESOLUTION * (1.0 + 2.0 * BUILTIN_MARGIN)).ceil() as u32;
+
+    // Which subsamples are inside the shape
+    let grid = (size * SUBSAMPLES) as i64;
+    let step = 1.0 / (BUILTIN_RESOLUTION * SUBSAMPLES as f64);
+    let mask: Vec<bool> = (0..grid * grid)
+        .map(|i| {
+            let x = ((i % grid) as f64 + 0.5) * step - BUILTIN_MARGIN;
+            let y = ((i / grid) as f64 + 0.5) * step - BUILTIN_MARGIN;
+            shape.iter().any(|p| inside(p, x, y))
+        })
+        .collect();
+    let at = |x: i64, y: i64| (0..grid).contains(&x) && (0..grid).contains(&y) && mask[(y * grid + x) as usize];
+    // A subsample is filled when everything a stroke away is inside too
+    let ring: Vec<(i64, i64)> = (0..8)
+        .map(|i| {
+            let (sin, cos) = (i as f64 * std::f64::consts::FRAC_PI_4).sin_cos();
+            let reach = BUILTIN_STROKE / step;
+            ((cos * reach).round() as i64, (sin * reach).round() as i64)
+        })
+        .collect();
+
+    let mut image = FrameBuffer::new(size, size);
+    for py in 0..size {
+        for px in 0..size {
+            let (mut fill_hits, mut outline_hits) = (0u32, 0u32);
+            for sy in 0..SUBSAMPLES {
+                for sx in 0..SUBSAMPLES {
+                    let (x, y) = ((px * SUBSAMPLES + sx) as i64, (py * SUBSAMPLES + sy) as i64);
+                    let near = ring.iter().filter(|(dx, dy)| at(x + dx, y + dy)).count();
+                    if at(x, y) && near == ring.len() {
+                        fill_hits += 1;
+                    } else if at(x, y) || near > 0 {
+                        outline_hits += 1;
+                    }
+                }
+            }
+            let hits = fill_hits + outline_hits;
+            if hits == 0 {
+                continue;
+            }
+            // Straight alpha: the hits' mean color at their coverage
+            let mix = |c: usize| ((fill[c] as u32 * fill_hits + outline[c] as u32 * outline_hits) / hits) as u8;
+            let alpha = (hits * 255 / (SUBSAMPLES * SUBSAMPLES)) as u8;
+            let offset = (py * image.stride + px * 4) as usize;
+            image.data[offset..offset + 4].copy_from_slice(&[mix(0), mix(1), mix(2), alpha]);
+        }
+    }
+
+    CursorImage {
+        image,
+        hotspot: ((hotspot.0 + BUILTIN_MARGIN) * BUILTIN_RESOLUTION, (hotspot.1 + BUILTIN_MARGIN) * BUILTIN_RESOLUTION),
+        density: BUILTIN_RESOLUTION / height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_packs_cover_every_style() {
+        assert!(CursorPack::builtin(&CursorTheme::Dot).is_none());
+        let system = CursorPack::builtin(&CursorTheme::System).unwrap();
+        let large = CursorPack::builtin(&CursorTheme::LargeYellow).unwrap();
+        for style in ALL_STYLES {
+            let image = system.image(style, 1.0).unwrap();
+            assert!(image.image.data.chunks_exact(4).any(|p| p[3] == 255), "{style:?} is empty");
+            // Same bitmap, drawn bigger
+            assert!(large.image(style, 1.0).unwrap().density < image.density);
+        }
+
+        // The arrow's tip is on the hotspot: outline there, white fill inside
+        let arrow = system.image(CursorStyle::Arrow, 1.0).unwrap();
+        let pixel = |x: f64, y: f64| {
+            let offset = (y as u32 * arrow.image.stride + x as u32 * 4) as usize;
+            &arrow.image.data[offset..offset + 4]
+        };
+        let (hx, hy) = arrow.hotspot;
+        assert_eq!(pixel(hx, hy + 2.0)[..3], [0, 0, 0]);
+        assert_eq!(pixel(hx + 8.0, hy + 30.0), [255, 255, 255, 255]);
+        assert_eq!(pixel(hx + 30.0, hy + 4.0)[3], 0);
+    }
+
+    #[test]
+    fn test_user_pack_picks_density_for_scale() {
+        let density = |d: f64| CursorImage { image: FrameBuffer::new(1, 1), hotspot: (0.0, 0.0), density: d };
+        let pack = CursorPack { images: HashMap::from([(CursorStyle::Arrow, vec![density(1.0), density(2.0)])]) };
+        assert_eq!(pack.image(CursorStyle::Arrow, 1.0).unwrap().density, 1.0);
+        assert_eq!(pack.image(CursorStyle::Arrow, 1.5).unwrap().density, 2.0);
+        assert_eq!(pack.image(CursorStyle::Arrow, 3.0).unwrap().density, 2.0);
+        // Missing styles use the arrow
+        assert!(pack.image(CursorStyle::IBeam, 1.0).is_some());
+        // An empty directory isn't a pack
+        let dir = std::env::temp_dir().join(format!("lazyrec_cursor_theme_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(CursorPack::load(&dir).is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+/// Lowest spring damping ratio honoured; below this a spring is still
+/// visibly oscillating when its segment ends
+pub const MIN_SPRING_DAMPING: f64 = 0.4;
+
 /// Easing curve types.
 /// Defines how interpolation happens between keyframes.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -25,6 +29,11 @@ pub enum EasingCurve {
         #[serde(rename = "dampingRatio")]
         damping_ratio: f64,
         response: f64,
+        /// Honour `damping_ratio` and `response` (springs from
+        /// `EasingCurve::spring`). Presets and springs saved before this keep
+        /// the original critically damped curve settling over half the segment.
+        #[serde(default)]
+        tuned: bool,
     },
 }
 
@@ -53,6 +62,15 @@ impl EasingCurve {
         result.clamp(0.0, 1.0)
     }
 
+    /// Like `apply`, but an underdamped spring may overshoot past 1 (for
+    /// camera motion, where the bounce is the point). Other curves are clamped.
+    pub fn apply_overshoot(&self, t: f64, duration: f64) -> f64 {
+        match self {
+            Self::Spring { .. } => self.spring_value(t.clamp(0.0, 1.0), duration).max(0.0),
+            _ => self.apply(t, duration),
+        }
+    }
+
     /// Return the raw value without clamping output
     pub fn apply_unclamped(&self, t: f64) -> f64 {
         match self {
@@ -95,39 +113,73 @@ impl EasingCurve {
         }
     }
 
-    // Spring calculation: critically damped spring
-    fn spring_value(&self, t: f64, duration: f64) -> f64 {
-        let response = duration * 0.5;
+    /// Angular frequency and damping ratio of a spring over a `duration`
+    /// segment. A tuned spring's `response` (seconds per undamped
+    /// oscillation) is capped at half the segment so the spring settles
+    /// before the next keyframe; untuned springs always take the half.
+    fn spring_params(&self, duration: f64) -> (f64, f64) {
+        let Self::Spring { damping_ratio, response, tuned } = *self else {
+            return (0.0, 1.0);
+        };
+        let cap = duration * 0.5;
+        if !tuned {
+            return (2.0 * std::f64::consts::PI / cap.max(0.01), 1.0);
+        }
+        let response = if response > 0.0 { response.min(cap) } else { cap };
         let omega = 2.0 * std::f64::consts::PI / response.max(0.01);
+        (omega, damping_ratio.clamp(MIN_SPRING_DAMPING, 1.0))
+    }
+
+    // Spring step response: critically damped at ratio 1, oscillating below
+    fn spring_value(&self, t: f64, duration: f64) -> f64 {
+        let (omega, zeta) = self.spring_params(duration);
         let actual_time = t * duration;
-        let decay = (-omega * actual_time).exp();
-        1.0 - (1.0 + omega * actual_time) * decay
+        let decay = (-zeta * omega * actual_time).exp();
+        if zeta >= 1.0 {
+            return 1.0 - (1.0 + omega * actual_time) * decay;
+        }
+        let omega_d = omega * (1.0 - zeta * zeta).sqrt();
+        1.0 - decay * ((omega_d * actual_time).cos() + zeta * omega / omega_d * (omega_d * actual_time).sin())
     }
 
     fn spring_derivative(&self, t: f64, duration: f64) -> f64 {
-        let response = duration * 0.5;
-        let omega = 2.0 * std::f64::consts::PI / response.max(0.01);
+        let (omega, zeta) = self.spring_params(duration);
         let actual_time = t * duration;
-        let decay = (-omega * actual_time).exp();
-        omega * omega * actual_time * decay * duration
+        let decay = (-zeta * omega * actual_time).exp();
+        if zeta >= 1.0 {
+            return omega * omega * actual_time * decay * duration;
+        }
+        let omega_d = omega * (1.0 - zeta * zeta).sqrt();
+        omega * omega / omega_d * decay * (omega_d * actual_time).sin() * duration
     }
 
     // Presets
 
+    /// Spring with the given feel: `damping_ratio` 1 never overshoots, lower
+    /// bounces (down to `MIN_SPRING_DAMPING`); `response` is seconds per
+    /// oscillation, lower is snappier
+    pub fn spring(damping_ratio: f64, response: f64) -> Self {
+        Self::Spring {
+            damping_ratio: damping_ratio.clamp(MIN_SPRING_DAMPING, 1.0),
+            response: response.max(0.05),
+            tuned: true,
+        }
+    }
+
     pub fn spring_default() -> Self {
-        Self::Spring { damping_ratio: 1.0, response: 0.8 }
+        Self::Spring { damping_ratio: 1.0, response: 0.8, tuned: false }
     }
 
     pub fn spring_smooth() -> Self {
-        Self::Spring { damping_ratio: 1.0, response: 1.0 }
+        Self::Spring { damping_ratio: 1.0, response: 1.0, tuned: false }
     }
 
     pub fn spring_bouncy() -> Self {
-        Self::Spring { damping_ratio: 0.75, response: 0.9 }
+        Self::Spring { damping_ratio: 0.75, response: 0.9, tuned: false }
     }
 
     pub fn spring_snappy() -> Self {
-        Self::Spring { damping_ratio: 0.95, response: 0.5 }
+        Self::Spring { damping_ratio: 0.95, response: 0.5, tuned: false }
     }
 
     pub fn css_ease() -> Self {
@@ -297,6 +349,50 @@ mod tests {
         assert!((e.apply(1.0, 1.0) - 1.0).abs() < 0.05);
     }
 
+    #[test]
+    fn test_spring_damping_controls_overshoot() {
+        let peak = |e: &EasingCurve| (0..=100).map(|i| e.apply_overshoot(i as f64 / 100.0, 1.0)).fold(0.0, f64::max);
+
+        // Critically damped: no overshoot however snappy
+        assert!(peak(&EasingCurve::spring(1.0, 0.2)) <= 1.0);
+        assert!(peak(&EasingCurve::spring(1.0, 0.5)) <= 1.0);
+        // Underdamped bounces more as damping drops, and still lands
+        let bouncy = EasingCurve::spring(0.5, 0.4);
+        let bouncier = EasingCurve::spring(0.4, 0.4);
+        assert!(peak(&bouncy) > 1.1);
+        assert!(peak(&bouncier) > peak(&bouncy));
+        assert!((bouncy.apply_overshoot(1.0, 1.0) - 1.0).abs() < 0.02);
+        // `apply` keeps the clamp
+        assert!((0..=100).all(|i| bouncy.apply(i as f64 / 100.0, 1.0) <= 1.0));
+
+        // Lower response settles sooner
+        let at = |e: EasingCurve| e.apply(0.2, 1.0);
+        assert!(at(EasingCurve::spring(1.0, 0.2)) > at(EasingCurve::spring(1.0, 0.5)));
+
+        // The derivative matches the value's slope
+        let (t, h) = (0.3, 1e-5);
+        let slope = (bouncy.apply_overshoot(t + h, 1.0) - bouncy.apply_overshoot(t - h, 1.0)) / (2.0 * h);
+        assert!((bouncy.derivative(t, 1.0) - slope).abs() < 1e-3, "{slope}");
+    }
+
+    #[test]
+    fn test_preset_springs_keep_segment_timing() {
+        // Presets settle over half the segment whatever its length, as they
+        // always have; a tuned spring with the same response settles sooner
+        let legacy = |t: f64, duration: f64| {
+            let omega = 2.0 * std::f64::consts::PI / (duration * 0.5);
+            let x = t * duration;
+            1.0 - (1.0 + omega * x) * (-omega * x).exp()
+        };
+        for duration in [0.5, 1.6, 4.0] {
+            for curve in [EasingCurve::spring_default(), EasingCurve::spring_smooth(), EasingCurve::spring_bouncy()] {
+                assert!((curve.apply(0.1, duration) - legacy(0.1, duration)).abs() < 1e-12);
+            }
+        }
+        let tuned = EasingCurve::spring(1.0, 0.8);
+        assert!(tuned.apply(0.1, 4.0) > EasingCurve::spring_default().apply(0.1, 4.0));
+    }
+
     #[test]
     fn test_derivative_linear() {
         let e = EasingCurve::Linear;
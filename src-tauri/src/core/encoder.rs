@@ -1,9 +1,11 @@
 //! Video encoding abstraction via FFmpeg (ffmpeg-next crate).
 //! Trait-based design allows future alternative backends.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use super::project::{ExportQuality, VideoCodec};
+use super::sfx::SfxMixer;
 
 /// Whether the encoder is used for live recording or offline export.
 /// Recording needs speed (ultrafast); export can trade speed for quality.
@@ -24,10 +26,47 @@ pub struct EncoderConfig {
     pub output_path: PathBuf,
     /// Key frame interval (GOP size)
     pub keyframe_interval: u32,
+    /// Closed GOPs at a fixed interval: every keyframe is a clean seek point
+    pub closed_gop: bool,
     /// Whether this encoder is for live recording or offline export
     pub purpose: EncoderPurpose,
+    /// FFmpeg encoder name to use instead of auto-detection (e.g. "libx264")
+    pub preferred_encoder: Option<String>,
+    /// Click sounds encoded as a mono AAC track, mixed a chunk at a time
+    /// as the video frames are written
+    pub audio: Option<Arc<SfxMixer>>,
+    /// Average bit rate to hit instead of constant quality (e.g. for a target file size)
+    pub target_bit_rate: Option<u64>,
+    /// Keep the frames' alpha channel (yuva420p; only VP9 supports it)
+    pub alpha: bool,
+    /// Software encoder threads; 0 uses every core
+    pub threads: usize,
+    /// Speed/size preset from `encoder_presets` for the chosen encoder;
+    /// None keeps the built-in default
+    pub preset: Option<String>,
+    /// Full-range (0-255) YUV instead of limited (16-235). Output is
+    /// always tagged BT.709 so players pick the matching conversion.
+    pub full_range: bool,
+    /// Chapter metadata written into the container (output seconds)
+    pub chapters: Vec<Chapter>,
+    /// Encode 10-bit (yuv420p10le) when `ten_bit_supported`, else 8-bit
+    pub ten_bit: bool,
 }
 
+/// Named span of the output, shown as a chapter by players
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chapter {
+    pub start: f64,
+    pub end: f64,
+    pub title: String,
+}
+
+/// Default GOP size (2s at 60 fps)
+pub const DEFAULT_KEYFRAME_INTERVAL: u32 = 120;
+
+/// Longest GOP accepted from render settings
+pub const MAX_KEYFRAME_INTERVAL: u32 = 600;
+
 impl EncoderConfig {
     pub fn new(width: u32, height: u32, output_path: PathBuf) -> Self {
         Self {
@@ -37,14 +76,233 @@ impl EncoderConfig {
             codec: VideoCodec::H264,
             quality: ExportQuality::High,
             output_path,
-            keyframe_interval: 120,
+            keyframe_interval: DEFAULT_KEYFRAME_INTERVAL,
+            closed_gop: false,
             purpose: EncoderPurpose::Recording,
+            preferred_encoder: None,
+            audio: None,
+            target_bit_rate: None,
+            alpha: false,
+            threads: 0,
+            preset: None,
+            full_range: false,
+            chapters: Vec::new(),
+            ten_bit: false,
+        }
+    }
+
+    /// Encoder threads with 0 resolved to the machine's core count
+    pub fn thread_count(&self) -> usize {
+        if self.threads > 0 {
+            self.threads
+        } else {
+            std::thread::available_parallelism().map_or(4, |n| n.get())
         }
     }
 
     pub fn bit_rate(&self) -> u64 {
-        self.quality.bit_rate(self.width as f64, self.height as f64)
+        self.target_bit_rate
+            .unwrap_or_else(|| self.quality.bit_rate(self.width as f64, self.height as f64))
+    }
+
+    /// Move the MP4/MOV index (moov atom) to the front when finishing, so
+    /// the file starts playing before it's fully downloaded. Exports only:
+    /// it rewrites the file, which isn't worth it for raw recordings.
+    pub fn faststart(&self) -> bool {
+        self.purpose == EncoderPurpose::Export && supports_faststart(&self.output_path)
+    }
+
+    /// Whether a requested 10-bit encode can happen: the codec supports it,
+    /// there's no alpha (yuva420p is 8-bit only) and a preferred encoder,
+    /// if set, is one that accepts 10-bit input. The encoder checks the
+    /// chosen FFmpeg encoder's formats again when it starts.
+    pub fn ten_bit_supported(&self) -> Result<(), String> {
+        if self.alpha {
+            return Err("alpha output is 8-bit only".into());
+        }
+        if !self.codec.supports_ten_bit() {
+            return Err(format!("{} doesn't support 10-bit", self.codec.display_name()));
+        }
+        match self.preferred_encoder.as_deref() {
+            Some(name) if encoder_codec(name) == Some(self.codec) && !TEN_BIT_ENCODERS.contains(&name) => {
+                Err(format!("{name} doesn't accept 10-bit input"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Known encoders that take yuv420p10le frames directly (hardware encoders
+/// want P010 or their own surfaces)
+pub const TEN_BIT_ENCODERS: &[&str] = &["libx265", "libsvtav1", "libvpx-vp9"];
+
+/// MP4/MOV containers, whose index can be moved to the front
+pub fn supports_faststart(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| ["mp4", "mov", "m4v"].contains(&e.to_ascii_lowercase().as_str()))
+}
+
+/// Whether an MP4/MOV file's moov atom comes before its mdat, from the
+/// top-level box headers. None when the file has neither.
+pub fn moov_before_mdat(path: &Path) -> std::io::Result<Option<bool>> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let mut offset = 0u64;
+    while offset + 8 <= len {
+        let mut header = [0u8; 8];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut header)?;
+        let mut size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        match &header[4..8] {
+            b"moov" => return Ok(Some(true)),
+            b"mdat" => return Ok(Some(false)),
+            _ => {}
+        }
+        if size == 1 {
+            // 64-bit size follows the type
+            let mut large = [0u8; 8];
+            file.read_exact(&mut large)?;
+            size = u64::from_be_bytes(large);
+        } else if size == 0 {
+            // Box runs to the end of the file
+            break;
+        }
+        if size < 8 {
+            break;
+        }
+        offset += size;
     }
+    Ok(None)
+}
+
+/// Known FFmpeg encoders: (name, label, codec, hardware), in auto-detection order.
+pub const KNOWN_ENCODERS: &[(&str, &str, VideoCodec, bool)] = &[
+    ("h264_nvenc", "NVIDIA NVENC", VideoCodec::H264, true),
+    ("h264_amf", "AMD AMF", VideoCodec::H264, true),
+    ("h264_qsv", "Intel QSV", VideoCodec::H264, true),
+    ("libx264", "Software (x264)", VideoCodec::H264, false),
+    ("hevc_nvenc", "NVIDIA NVENC", VideoCodec::H265, true),
+    ("hevc_amf", "AMD AMF", VideoCodec::H265, true),
+    ("hevc_qsv", "Intel QSV", VideoCodec::H265, true),
+    ("libx265", "Software (x265)", VideoCodec::H265, false),
+    ("av1_nvenc", "NVIDIA NVENC", VideoCodec::Av1, true),
+    ("av1_amf", "AMD AMF", VideoCodec::Av1, true),
+    ("av1_qsv", "Intel QSV", VideoCodec::Av1, true),
+    ("libsvtav1", "Software (SVT-AV1)", VideoCodec::Av1, false),
+    ("libvpx-vp9", "Software (libvpx)", VideoCodec::Vp9, false),
+];
+
+/// Software encoder used for a codec when no hardware encoder works
+pub fn software_encoder(codec: VideoCodec) -> &'static str {
+    KNOWN_ENCODERS
+        .iter()
+        .find(|(_, _, c, hardware)| *c == codec && !hardware)
+        .map(|(name, ..)| *name)
+        .unwrap_or("libx264")
+}
+
+/// Consecutive frames an encoder may fail on before it's given up on
+pub const MAX_CONSECUTIVE_FRAME_FAILURES: u32 = 3;
+
+/// Frames written before which a failing hardware encoder is swapped for the
+/// software one. Frames already muxed can't be re-encoded, so the restart
+/// stands the failing frame in for them; past this it would show.
+pub const SOFTWARE_FALLBACK_FRAMES: u64 = 30;
+
+/// What an encoder does after a frame fails to encode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameFailureAction {
+    /// Drop the frame and keep going (a one-off hiccup)
+    Skip,
+    /// Restart the stream with the software encoder
+    FallBack,
+    /// Stop with `EncoderError::Failed` rather than write a broken file
+    Abort,
+}
+
+/// How to handle the `consecutive_failures`th failure in a row after
+/// `frames_encoded` good frames
+pub fn frame_failure_action(consecutive_failures: u32, frames_encoded: u64, hardware: bool) -> FrameFailureAction {
+    if consecutive_failures < MAX_CONSECUTIVE_FRAME_FAILURES {
+        FrameFailureAction::Skip
+    } else if hardware && frames_encoded < SOFTWARE_FALLBACK_FRAMES {
+        FrameFailureAction::FallBack
+    } else {
+        FrameFailureAction::Abort
+    }
+}
+
+/// Codec produced by a known encoder name
+pub fn encoder_codec(name: &str) -> Option<VideoCodec> {
+    KNOWN_ENCODERS
+        .iter()
+        .find(|(n, ..)| *n == name)
+        .map(|(_, _, codec, _)| *codec)
+}
+
+/// The option an encoder's speed/size preset sets and its accepted values,
+/// fastest first
+pub fn encoder_presets(name: &str) -> Option<(&'static str, &'static [&'static str])> {
+    const X26X: &[&str] = &[
+        "ultrafast", "superfast", "veryfast", "faster", "fast", "medium", "slow", "slower", "veryslow",
+    ];
+    let presets: (&str, &[&str]) = match name {
+        "libx264" | "libx265" => ("preset", X26X),
+        "h264_nvenc" | "hevc_nvenc" | "av1_nvenc" => ("preset", &["p1", "p2", "p3", "p4", "p5", "p6", "p7"]),
+        "h264_amf" | "hevc_amf" | "av1_amf" => ("quality", &["speed", "balanced", "quality"]),
+        "h264_qsv" | "hevc_qsv" | "av1_qsv" => {
+            ("preset", &["veryfast", "faster", "fast", "medium", "slow", "slower", "veryslow"])
+        }
+        "libsvtav1" => ("preset", &["13", "12", "11", "10", "9", "8", "7", "6", "5", "4", "3", "2", "1", "0"]),
+        "libvpx-vp9" => ("cpu-used", &["8", "7", "6", "5", "4", "3", "2", "1", "0"]),
+        _ => return None,
+    };
+    Some(presets)
+}
+
+/// Availability of a single encoder on this machine
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncoderInfo {
+    pub name: String,
+    pub label: String,
+    pub codec: VideoCodec,
+    pub hardware: bool,
+    /// The encoder exists and a context could be opened (driver present)
+    pub available: bool,
+    /// Values accepted as `preset` in the encoder config, fastest first
+    pub presets: Vec<String>,
+}
+
+/// Probe every known encoder.
+/// Without the `ffmpeg` feature nothing is available.
+pub fn list_encoders() -> Vec<EncoderInfo> {
+    KNOWN_ENCODERS
+        .iter()
+        .map(|(name, label, codec, hardware)| EncoderInfo {
+            name: name.to_string(),
+            label: label.to_string(),
+            codec: *codec,
+            hardware: *hardware,
+            available: probe_encoder(name),
+            presets: encoder_presets(name)
+                .map(|(_, values)| values.iter().map(|v| v.to_string()).collect())
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+#[cfg(feature = "ffmpeg")]
+fn probe_encoder(name: &str) -> bool {
+    ffmpeg_next::init().is_ok() && ffmpeg_encoder::probe(name).is_some()
+}
+
+#[cfg(not(feature = "ffmpeg"))]
+fn probe_encoder(_name: &str) -> bool {
+    false
 }
 
 /// A raw video frame to encode
@@ -68,6 +326,8 @@ pub enum EncoderError {
     Ffmpeg(String),
     #[error("Invalid configuration: {0}")]
     InvalidConfig(String),
+    #[error("{encoder} kept failing at frame {frame}: {reason}")]
+    Failed { encoder: String, frame: u64, reason: String },
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
@@ -90,6 +350,9 @@ pub trait VideoEncoder: Send {
 
     /// Get the number of frames encoded so far
     fn frames_encoded(&self) -> u64;
+
+    /// Name of the encoder implementation (known once started)
+    fn encoder_name(&self) -> &str;
 }
 
 /// Stub encoder for development (writes no actual video)
@@ -142,6 +405,125 @@ impl VideoEncoder for StubEncoder {
     fn frames_encoded(&self) -> u64 {
         self.frame_count
     }
+
+    fn encoder_name(&self) -> &str {
+        "stub"
+    }
+}
+
+/// Where and how an image-sequence export writes its frames
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageSequenceOptions {
+    /// Output directory (None: next to the video export)
+    #[serde(default)]
+    pub directory: Option<PathBuf>,
+    /// Digits in frame numbers (`frame_00001.png` is 5)
+    #[serde(default = "default_sequence_padding")]
+    pub padding: usize,
+}
+
+fn default_sequence_padding() -> usize {
+    5
+}
+
+impl Default for ImageSequenceOptions {
+    fn default() -> Self {
+        Self { directory: None, padding: default_sequence_padding() }
+    }
+}
+
+/// Most digits accepted for frame numbers
+pub const MAX_SEQUENCE_PADDING: usize = 10;
+
+/// File name of the `index`-th frame (counting from 1) of an image sequence
+pub fn sequence_frame_name(index: u64, padding: usize) -> String {
+    format!("frame_{index:0width$}.png", width = padding.min(MAX_SEQUENCE_PADDING))
+}
+
+/// Tightly packed RGBA bytes from BGRA pixel data (dropping any row padding),
+/// as PNG encoding and HTML Canvas ImageData expect
+pub fn bgra_to_rgba(data: &[u8], width: u32, height: u32, stride: u32) -> Vec<u8> {
+    let row_bytes = width as usize * 4;
+    let mut rgba = Vec::with_capacity(row_bytes * height as usize);
+    for row in data.chunks(stride.max(1) as usize).take(height as usize) {
+        for px in row[..row_bytes.min(row.len())].chunks_exact(4) {
+            rgba.extend_from_slice(&[px[2], px[1], px[0], px[3]]);
+        }
+    }
+    rgba
+}
+
+/// "Encoder" writing each frame as a numbered PNG into a directory, keeping
+/// the alpha channel. `finish` returns the directory.
+pub struct ImageSequenceWriter {
+    directory: PathBuf,
+    padding: usize,
+    encoding: bool,
+    frame_count: u64,
+}
+
+impl ImageSequenceWriter {
+    pub fn new(directory: PathBuf, padding: usize) -> Self {
+        Self { directory, padding, encoding: false, frame_count: 0 }
+    }
+}
+
+impl VideoEncoder for ImageSequenceWriter {
+    fn start(&mut self) -> Result<(), EncoderError> {
+        if self.encoding {
+            return Err(EncoderError::AlreadyStarted);
+        }
+        std::fs::create_dir_all(&self.directory)?;
+        self.encoding = true;
+        self.frame_count = 0;
+        Ok(())
+    }
+
+    fn append_frame(&mut self, frame: &VideoFrame) -> Result<(), EncoderError> {
+        if !self.encoding {
+            return Err(EncoderError::NotStarted);
+        }
+        if frame.width == 0 || frame.height == 0 {
+            return Err(EncoderError::InvalidConfig("Empty frame".into()));
+        }
+        let png = encode_png(&bgra_to_rgba(&frame.data, frame.width, frame.height, frame.stride), frame.width, frame.height)?;
+        let path = self.directory.join(sequence_frame_name(self.frame_count + 1, self.padding));
+        std::fs::write(path, png)?;
+        self.frame_count += 1;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<PathBuf, EncoderError> {
+        if !self.encoding {
+            return Err(EncoderError::NotStarted);
+        }
+        self.encoding = false;
+        log::info!("Image sequence complete: {} frames in {}", self.frame_count, self.directory.display());
+        Ok(self.directory.clone())
+    }
+
+    fn is_encoding(&self) -> bool {
+        self.encoding
+    }
+
+    fn frames_encoded(&self) -> u64 {
+        self.frame_count
+    }
+
+    fn encoder_name(&self) -> &str {
+        "png"
+    }
+}
+
+#[cfg(feature = "ffmpeg")]
+fn encode_png(rgba: &[u8], width: u32, height: u32) -> Result<Vec<u8>, EncoderError> {
+    ffmpeg_encoder::encode_png(rgba, width, height)
+}
+
+#[cfg(not(feature = "ffmpeg"))]
+fn encode_png(_rgba: &[u8], _width: u32, _height: u32) -> Result<Vec<u8>, EncoderError> {
+    Err(EncoderError::Ffmpeg("PNG export requires the ffmpeg feature".into()))
 }
 
 /// FFmpeg-based video encoder using ffmpeg-next crate.
@@ -169,19 +551,115 @@ pub mod ffmpeg_encoder {
         fn deref_mut(&mut self) -> &mut Self::Target { &mut self.0 }
     }
 
-    /// Hardware encoder candidates for H.264, tried in order.
-    const HW_H264: &[(&str, &str)] = &[
-        ("h264_nvenc", "NVIDIA NVENC"),
-        ("h264_amf", "AMD AMF"),
-        ("h264_qsv", "Intel QSV"),
-    ];
+    /// Use BT.709 coefficients for the YUV side of an RGB↔YUV scaler
+    /// (swscale defaults to BT.601). `to_yuv` is the conversion direction;
+    /// the RGB side is always full range.
+    pub(crate) fn set_scaler_colorspace(
+        scaler: &mut scaling::Context,
+        to_yuv: bool,
+        full_range: bool,
+    ) -> Result<(), EncoderError> {
+        let yuv_range = full_range as i32;
+        let (src_range, dst_range) = if to_yuv { (1, yuv_range) } else { (yuv_range, 1) };
+        // SAFETY: the scaler is a live SwsContext; the coefficient tables are static
+        let status = unsafe {
+            let coefficients = ffmpeg::ffi::sws_getCoefficients(ffmpeg::ffi::SWS_CS_ITU709 as i32);
+            // Brightness 0, contrast and saturation 1.0 (16.16 fixed point)
+            ffmpeg::ffi::sws_setColorspaceDetails(
+                scaler.as_mut_ptr(),
+                coefficients, src_range,
+                coefficients, dst_range,
+                0, 1 << 16, 1 << 16,
+            )
+        };
+        if status < 0 {
+            return Err(EncoderError::Ffmpeg("Scaler colorspace not supported".into()));
+        }
+        Ok(())
+    }
 
-    /// Hardware encoder candidates for H.265, tried in order.
-    const HW_H265: &[(&str, &str)] = &[
-        ("hevc_nvenc", "NVIDIA NVENC"),
-        ("hevc_amf", "AMD AMF"),
-        ("hevc_qsv", "Intel QSV"),
-    ];
+    /// Find an encoder by name and verify it can actually be opened
+    /// (FFmpeg may know about a hardware encoder whose driver is missing).
+    pub(super) fn probe(name: &str) -> Option<ffmpeg::Codec> {
+        let c = codec::encoder::find_by_name(name)?;
+        let probe_ok = codec::context::Context::new_with_codec(c)
+            .encoder()
+            .video()
+            .is_ok();
+        if !probe_ok {
+            log::warn!("Encoder {name} found but context creation failed");
+            return None;
+        }
+        Some(c)
+    }
+
+    /// Frame format of 10-bit encodes
+    const TEN_BIT_FORMAT: format::Pixel = format::Pixel::YUV420P10LE;
+
+    /// Whether the encoder lists `pixel` among its input formats
+    fn accepts_format(codec: ffmpeg::Codec, pixel: format::Pixel) -> bool {
+        codec
+            .video()
+            .ok()
+            .and_then(|video| video.formats())
+            .is_some_and(|mut formats| formats.any(|f| f == pixel))
+    }
+
+    /// Preferred encoder from the config, if it is valid for the codec and works
+    fn preferred(config: &EncoderConfig) -> Option<(ffmpeg::Codec, String)> {
+        let name = config.preferred_encoder.as_deref()?;
+        if encoder_codec(name) != Some(config.codec) {
+            log::warn!("Preferred encoder {name} does not produce {:?}, auto-detecting", config.codec);
+            return None;
+        }
+        match probe(name) {
+            Some(c) => Some((c, name.to_string())),
+            None => {
+                log::warn!("Preferred encoder {name} unavailable, auto-detecting");
+                None
+            }
+        }
+    }
+
+    /// Encode one RGBA image (tightly packed rows) as a PNG file's bytes
+    pub(super) fn encode_png(rgba: &[u8], width: u32, height: u32) -> Result<Vec<u8>, EncoderError> {
+        ffmpeg::init().map_err(|e| EncoderError::Ffmpeg(format!("FFmpeg init: {e}")))?;
+        let png = codec::encoder::find(codec::Id::PNG)
+            .ok_or_else(|| EncoderError::Ffmpeg("PNG encoder not found".into()))?;
+        let mut ctx = codec::context::Context::new_with_codec(png)
+            .encoder()
+            .video()
+            .map_err(|e| EncoderError::Ffmpeg(format!("PNG encoder context: {e}")))?;
+        ctx.set_width(width);
+        ctx.set_height(height);
+        ctx.set_format(ffmpeg::format::Pixel::RGBA);
+        ctx.set_time_base(ffmpeg::Rational::new(1, 1));
+        let mut encoder = ctx.open_as(png)
+            .map_err(|e| EncoderError::Ffmpeg(format!("Open PNG encoder: {e}")))?;
+
+        let mut frame = FfmpegFrame::new(ffmpeg::format::Pixel::RGBA, width, height);
+        let row_bytes = width as usize * 4;
+        let dst_stride = frame.stride(0);
+        let dst = frame.data_mut(0);
+        for (y, row) in rgba.chunks_exact(row_bytes).take(height as usize).enumerate() {
+            dst[y * dst_stride..y * dst_stride + row_bytes].copy_from_slice(row);
+        }
+        frame.set_pts(Some(0));
+
+        encoder.send_frame(&frame)
+            .map_err(|e| EncoderError::Ffmpeg(format!("Send PNG frame: {e}")))?;
+        encoder.send_eof()
+            .map_err(|e| EncoderError::Ffmpeg(format!("Send PNG EOF: {e}")))?;
+        let mut bytes = Vec::new();
+        let mut packet = ffmpeg::Packet::empty();
+        while encoder.receive_packet(&mut packet).is_ok() {
+            bytes.extend_from_slice(packet.data().unwrap_or_default());
+        }
+        if bytes.is_empty() {
+            return Err(EncoderError::Ffmpeg("PNG encoder produced no data".into()));
+        }
+        Ok(bytes)
+    }
 
     pub struct FfmpegEncoder {
         config: EncoderConfig,
@@ -193,6 +671,132 @@ pub mod ffmpeg_encoder {
         stream_index: usize,
         time_base: ffmpeg::Rational,
         encoder_name: String,
+        /// `encoder_name` is a hardware encoder
+        hardware: bool,
+        /// Skip preferred and hardware encoders (after a hardware one failed)
+        force_software: bool,
+        /// Frames failed in a row
+        send_failures: u32,
+        audio: Option<AudioStream>,
+    }
+
+    /// AAC stream fed from `EncoderConfig::audio` as the video frames go out
+    struct AudioStream {
+        encoder: codec::encoder::audio::Encoder,
+        stream_index: usize,
+        time_base: ffmpeg::Rational,
+        /// First sample not yet sent (the next frame's pts)
+        next_sample: usize,
+    }
+
+    impl AudioStream {
+        /// Add a mono AAC stream to `output_ctx` (before the header is written)
+        fn add(
+            output_ctx: &mut format::context::Output,
+            sample_rate: u32,
+            global_header: bool,
+        ) -> Result<Self, EncoderError> {
+            let aac = codec::encoder::find(codec::Id::AAC)
+                .ok_or_else(|| EncoderError::Ffmpeg("AAC encoder not found".into()))?;
+            let mut stream = output_ctx.add_stream(aac)
+                .map_err(|e| EncoderError::Ffmpeg(format!("Add audio stream: {e}")))?;
+
+            let mut ctx = codec::context::Context::new_with_codec(aac)
+                .encoder()
+                .audio()
+                .map_err(|e| EncoderError::Ffmpeg(format!("Audio encoder context: {e}")))?;
+            let time_base = ffmpeg::Rational::new(1, sample_rate as i32);
+            ctx.set_rate(sample_rate as i32);
+            ctx.set_format(format::Sample::F32(format::sample::Type::Planar));
+            ctx.set_bit_rate(128_000);
+            ctx.set_time_base(time_base);
+            // SAFETY: the context is owned and not yet opened; this only fills its channel layout.
+            unsafe {
+                ffmpeg::ffi::av_channel_layout_default(&mut (*ctx.as_mut_ptr()).ch_layout, 1);
+            }
+            if global_header {
+                ctx.set_flags(codec::Flags::GLOBAL_HEADER);
+            }
+
+            let encoder = ctx.open_as(aac)
+                .map_err(|e| EncoderError::Ffmpeg(format!("Open AAC encoder: {e}")))?;
+            stream.set_parameters(&encoder);
+            Ok(Self { encoder, stream_index: stream.index(), time_base, next_sample: 0 })
+        }
+
+        fn frame_size(&self) -> usize {
+            match self.encoder.frame_size() {
+                0 => 1024,
+                n => n as usize,
+            }
+        }
+
+        /// Encode whole encoder-sized frames of `mixer` up to sample `end`
+        fn write_until(
+            &mut self,
+            mixer: &SfxMixer,
+            end: usize,
+            output_ctx: &mut format::context::Output,
+        ) -> Result<(), EncoderError> {
+            let frame_size = self.frame_size();
+            let end = end.min(mixer.len());
+            while self.next_sample + frame_size <= end {
+                self.write_chunk(mixer, self.next_sample + frame_size, output_ctx)?;
+            }
+            Ok(())
+        }
+
+        /// Encode the rest of `mixer` (ending on a short frame), then flush
+        fn finish(&mut self, mixer: &SfxMixer, output_ctx: &mut format::context::Output) -> Result<(), EncoderError> {
+            self.write_until(mixer, mixer.len(), output_ctx)?;
+            if self.next_sample < mixer.len() {
+                self.write_chunk(mixer, mixer.len(), output_ctx)?;
+            }
+            self.encoder.send_eof()
+                .map_err(|e| EncoderError::Ffmpeg(format!("Send audio EOF: {e}")))?;
+            self.drain(output_ctx)
+        }
+
+        /// Encode samples `next_sample..end` as one frame
+        fn write_chunk(
+            &mut self,
+            mixer: &SfxMixer,
+            end: usize,
+            output_ctx: &mut format::context::Output,
+        ) -> Result<(), EncoderError> {
+            let chunk = mixer.mix(self.next_sample, end);
+            let mut frame = ffmpeg::frame::Audio::empty();
+            // SAFETY: the frame is freshly allocated; describe its layout, then allocate buffers.
+            unsafe {
+                let raw = frame.as_mut_ptr();
+                (*raw).nb_samples = chunk.len() as i32;
+                (*raw).format = ffmpeg::ffi::AVSampleFormat::AV_SAMPLE_FMT_FLTP as i32;
+                (*raw).sample_rate = mixer.sample_rate() as i32;
+                ffmpeg::ffi::av_channel_layout_default(&mut (*raw).ch_layout, 1);
+                if ffmpeg::ffi::av_frame_get_buffer(raw, 0) < 0 {
+                    return Err(EncoderError::Ffmpeg("Allocate audio frame".into()));
+                }
+            }
+            let bytes: Vec<u8> = chunk.iter().flat_map(|s| s.to_ne_bytes()).collect();
+            frame.data_mut(0)[..bytes.len()].copy_from_slice(&bytes);
+            frame.set_pts(Some(self.next_sample as i64));
+
+            self.encoder.send_frame(&frame)
+                .map_err(|e| EncoderError::Ffmpeg(format!("Send audio frame: {e}")))?;
+            self.next_sample += chunk.len();
+            self.drain(output_ctx)
+        }
+
+        fn drain(&mut self, output_ctx: &mut format::context::Output) -> Result<(), EncoderError> {
+            let mut packet = ffmpeg::Packet::empty();
+            while self.encoder.receive_packet(&mut packet).is_ok() {
+                packet.set_stream(self.stream_index);
+                packet.rescale_ts(self.time_base, output_ctx.stream(self.stream_index).unwrap().time_base());
+                packet.write_interleaved(output_ctx)
+                    .map_err(|e| EncoderError::Ffmpeg(format!("Write audio packet: {e}")))?;
+            }
+            Ok(())
+        }
     }
 
     impl FfmpegEncoder {
@@ -209,8 +813,103 @@ pub mod ffmpeg_encoder {
                 stream_index: 0,
                 time_base: ffmpeg::Rational::new(1, 60),
                 encoder_name: String::new(),
+                hardware: false,
+                force_software: false,
+                send_failures: 0,
+                audio: None,
             })
         }
+
+        /// Convert `frame`, send it at pts `frame_count` and write out
+        /// whatever packets the encoder has ready
+        fn send(&mut self, frame: &VideoFrame) -> Result<(), EncoderError> {
+            let encoder = self.encoder.as_mut().unwrap();
+            let scaler = self.scaler.as_mut().unwrap();
+            let output_ctx = self.output_ctx.as_mut().unwrap();
+
+            // Create BGRA input frame — copy row-by-row to handle FFmpeg stride padding.
+            // FFmpeg aligns row strides to 32 bytes, but our FrameBuffer uses width*4.
+            // e.g. 2580px: our stride=10320, FFmpeg linesize=10336 (padded to 32).
+            let mut bgra_frame = FfmpegFrame::new(
+                ffmpeg::format::Pixel::BGRA,
+                frame.width,
+                frame.height,
+            );
+            let src_stride = frame.stride as usize;
+            let dst_stride = bgra_frame.stride(0) as usize;
+            if dst_stride == src_stride {
+                bgra_frame.data_mut(0)[..frame.data.len()].copy_from_slice(&frame.data);
+            } else {
+                let dst = bgra_frame.data_mut(0);
+                for y in 0..frame.height as usize {
+                    let src_off = y * src_stride;
+                    let dst_off = y * dst_stride;
+                    dst[dst_off..dst_off + src_stride]
+                        .copy_from_slice(&frame.data[src_off..src_off + src_stride]);
+                }
+            }
+
+            // Convert BGRA -> YUV420P
+            let mut yuv_frame = FfmpegFrame::empty();
+            scaler.run(&bgra_frame, &mut yuv_frame)
+                .map_err(|e| EncoderError::Ffmpeg(format!("Scale frame: {e}")))?;
+
+            let pts = self.frame_count as i64;
+            yuv_frame.set_pts(Some(pts));
+
+            // Send frame to encoder
+            encoder.send_frame(&yuv_frame)
+                .map_err(|e| EncoderError::Ffmpeg(format!("Send frame: {e}")))?;
+
+            // Receive and write encoded packets
+            let mut packet = ffmpeg::Packet::empty();
+            while encoder.receive_packet(&mut packet).is_ok() {
+                packet.set_stream(self.stream_index);
+                packet.rescale_ts(self.time_base, output_ctx.stream(self.stream_index).unwrap().time_base());
+                packet.write_interleaved(output_ctx)
+                    .map_err(|e| EncoderError::Ffmpeg(format!("Write packet: {e}")))?;
+            }
+
+            // Audio up to the end of this frame
+            if let (Some(audio), Some(mixer)) = (self.audio.as_mut(), self.config.audio.as_ref()) {
+                let end = (self.frame_count + 1) * mixer.sample_rate() as u64 / self.config.frame_rate.max(1) as u64;
+                audio.write_until(mixer, end as usize, output_ctx)?;
+            }
+            Ok(())
+        }
+
+        /// The hardware encoder failed within `SOFTWARE_FALLBACK_FRAMES`:
+        /// reopen the output with the software encoder and send `frame` once
+        /// for every frame attempted so far, so the stream keeps its length
+        /// (and the recording its sync with the input track)
+        fn fall_back_to_software(&mut self, frame: &VideoFrame, reason: EncoderError) -> Result<(), EncoderError> {
+            let attempted = self.frame_count + self.send_failures as u64;
+            log::warn!(
+                "{} failed after {} frames ({reason}); restarting with {}",
+                self.encoder_name, self.frame_count, software_encoder(self.config.codec),
+            );
+            // Dropping the output without a trailer abandons the partial file; start() overwrites it
+            self.encoder = None;
+            self.scaler = None;
+            self.audio = None;
+            self.output_ctx = None;
+            self.encoding = false;
+            self.force_software = true;
+            self.start()?;
+            for _ in 0..attempted {
+                self.send(frame).map_err(|e| self.failed(e))?;
+                self.frame_count += 1;
+            }
+            Ok(())
+        }
+
+        fn failed(&self, reason: EncoderError) -> EncoderError {
+            EncoderError::Failed {
+                encoder: self.encoder_name.clone(),
+                frame: self.frame_count,
+                reason: reason.to_string(),
+            }
+        }
     }
 
     impl VideoEncoder for FfmpegEncoder {
@@ -226,41 +925,53 @@ pub mod ffmpeg_encoder {
             // Probe for hardware encoder availability BEFORE creating output context.
             // We try to open a test encoder context to verify the hw encoder actually works,
             // not just that FFmpeg knows about it (driver may be missing).
-            let hw_candidates = match self.config.codec {
-                VideoCodec::H264 => HW_H264,
-                VideoCodec::H265 => HW_H265,
-            };
-
             let mut use_hw_codec: Option<(ffmpeg::Codec, String)> = None;
-            for (name, label) in hw_candidates {
-                if let Some(c) = codec::encoder::find_by_name(name) {
-                    // Probe: try to create an encoder context to verify it works
-                    let probe_ok = codec::context::Context::new_with_codec(c)
-                        .encoder()
-                        .video()
-                        .is_ok();
-                    if probe_ok {
+            let mut ten_bit = self.config.ten_bit;
+            if ten_bit {
+                if let Err(reason) = self.config.ten_bit_supported() {
+                    log::warn!("10-bit output unavailable ({reason}), encoding 8-bit");
+                    ten_bit = false;
+                }
+            }
+            let preferred = if self.force_software { None } else { preferred(&self.config) };
+            if let Some((_, name)) = &preferred {
+                log::info!("Using preferred encoder: {name}");
+            } else if !self.force_software {
+                let hw_candidates = KNOWN_ENCODERS
+                    .iter()
+                    .filter(|(_, _, codec, hardware)| *hardware && *codec == self.config.codec);
+                for (name, label, ..) in hw_candidates {
+                    if let Some(c) = probe(name).filter(|c| !ten_bit || accepts_format(*c, TEN_BIT_FORMAT)) {
                         log::info!("Found working hardware encoder: {name} ({label})");
                         use_hw_codec = Some((c, name.to_string()));
                         break;
-                    } else {
-                        log::warn!("Hardware encoder {name} found but context creation failed");
                     }
                 }
             }
 
-            let (codec, encoder_name, is_hw) = if let Some((c, name)) = use_hw_codec {
+            let (codec, encoder_name, is_hw) = if let Some((c, name)) = preferred {
+                let is_hw = KNOWN_ENCODERS.iter().any(|(n, _, _, hw)| *n == name && *hw);
+                (c, name, is_hw)
+            } else if let Some((c, name)) = use_hw_codec {
                 (c, name, true)
             } else {
                 let codec_id = match self.config.codec {
                     VideoCodec::H264 => codec::Id::H264,
                     VideoCodec::H265 => codec::Id::HEVC,
+                    VideoCodec::Av1 => codec::Id::AV1,
+                    VideoCodec::Vp9 => codec::Id::VP9,
                 };
-                let c = codec::encoder::find(codec_id)
+                let name = software_encoder(self.config.codec);
+                // By name first: FFmpeg's default AV1 encoder may be libaom, which is far slower
+                let c = codec::encoder::find_by_name(name)
+                    .or_else(|| codec::encoder::find(codec_id))
                     .ok_or_else(|| EncoderError::Ffmpeg(format!("Codec {:?} not found", codec_id)))?;
-                let name = if matches!(self.config.codec, VideoCodec::H264) { "libx264" } else { "libx265" };
                 (c, name.to_string(), false)
             };
+            if ten_bit && !accepts_format(codec, TEN_BIT_FORMAT) {
+                log::warn!("{encoder_name} doesn't accept 10-bit input, encoding 8-bit");
+                ten_bit = false;
+            }
 
             let mut output_ctx = format::output(path)
                 .map_err(|e| EncoderError::Ffmpeg(format!("Open output: {e}")))?;
@@ -277,51 +988,134 @@ pub mod ffmpeg_encoder {
 
             encoder_ctx.set_width(self.config.width);
             encoder_ctx.set_height(self.config.height);
-            encoder_ctx.set_format(ffmpeg::format::Pixel::YUV420P);
+            let pixel_format = if self.config.alpha {
+                if self.config.codec != VideoCodec::Vp9 {
+                    return Err(EncoderError::InvalidConfig("Alpha requires the VP9 codec".into()));
+                }
+                ffmpeg::format::Pixel::YUVA420P
+            } else if ten_bit {
+                TEN_BIT_FORMAT
+            } else {
+                ffmpeg::format::Pixel::YUV420P
+            };
+            encoder_ctx.set_format(pixel_format);
             encoder_ctx.set_time_base(time_base);
             encoder_ctx.set_bit_rate(self.config.bit_rate() as usize);
-            encoder_ctx.set_gop(self.config.keyframe_interval);
+            encoder_ctx.set_gop(self.config.keyframe_interval.max(1));
+            encoder_ctx.set_colorspace(ffmpeg::color::Space::BT709);
+            encoder_ctx.set_color_range(if self.config.full_range {
+                ffmpeg::color::Range::JPEG
+            } else {
+                ffmpeg::color::Range::MPEG
+            });
+            // SAFETY: plain field writes on the unopened codec context
+            unsafe {
+                let raw = encoder_ctx.as_mut_ptr();
+                (*raw).color_primaries = ffmpeg::ffi::AVColorPrimaries::AVCOL_PRI_BT709;
+                (*raw).color_trc = ffmpeg::ffi::AVColorTransferCharacteristic::AVCOL_TRC_BT709;
+            }
 
             if !is_hw {
-                encoder_ctx.set_threading(codec::threading::Config::count(4));
+                encoder_ctx.set_threading(codec::threading::Config::count(self.config.thread_count()));
             }
 
+            let mut flags = codec::Flags::empty();
             if needs_global_header {
-                encoder_ctx.set_flags(codec::Flags::GLOBAL_HEADER);
+                flags |= codec::Flags::GLOBAL_HEADER;
+            }
+            if self.config.closed_gop {
+                flags |= codec::Flags::CLOSED_GOP;
             }
+            encoder_ctx.set_flags(flags);
 
             let mut opts = ffmpeg::Dictionary::new();
-            if is_hw {
+            if self.config.closed_gop {
+                // Fixed spacing: no early keyframes at scene cuts
+                opts.set("keyint_min", &self.config.keyframe_interval.max(1).to_string());
+            }
+            if self.config.target_bit_rate.is_some() {
+                // Average bit rate: leave out crf/cq so the encoder follows set_bit_rate
+                if is_hw {
+                    opts.set("preset", "p4");
+                    opts.set("rc", "vbr");
+                } else if self.config.codec == VideoCodec::Av1 {
+                    opts.set("preset", "10");
+                } else if self.config.codec == VideoCodec::Vp9 {
+                    opts.set("deadline", "good");
+                    opts.set("cpu-used", "4");
+                } else {
+                    opts.set("preset", "medium");
+                }
+            } else if is_hw {
                 opts.set("preset", "p4");       // NVENC: balanced speed/quality
                 opts.set("rc", "vbr");          // Variable bitrate
                 opts.set("cq", "23");           // Constant quality (NVENC)
                 opts.set("quality", "balanced"); // AMF
+            } else if self.config.codec == VideoCodec::Av1 {
+                opts.set("preset", "10");       // SVT-AV1: 0 (slowest) to 13 (fastest)
+                opts.set("crf", "35");          // AV1 CRF scale runs to 63
+            } else if self.config.codec == VideoCodec::Vp9 {
+                opts.set("deadline", "good");
+                opts.set("cpu-used", "4");      // 0 (slowest) to 8 (fastest)
+                opts.set("crf", "31");
+                opts.set("row-mt", "1");
             } else {
                 opts.set("preset", "ultrafast");
                 opts.set("crf", "23");
             }
+            if self.config.alpha {
+                // libvpx drops the alpha plane when alternate reference frames are on
+                opts.set("auto-alt-ref", "0");
+            }
+            if let Some(preset) = &self.config.preset {
+                match encoder_presets(&encoder_name) {
+                    Some((option, values)) if values.contains(&preset.as_str()) => opts.set(option, preset),
+                    _ => log::warn!("Preset {preset:?} not supported by {encoder_name}, using its default"),
+                }
+            }
 
             let encoder = encoder_ctx.open_as_with(codec, opts)
                 .map_err(|e| EncoderError::Ffmpeg(format!("Open encoder {encoder_name}: {e}")))?;
             log::info!("Using encoder: {encoder_name}");
 
             self.encoder_name = encoder_name;
+            self.hardware = is_hw;
             stream.set_parameters(&encoder);
-            output_ctx.write_header()
-                .map_err(|e| EncoderError::Ffmpeg(format!("Write header: {e}")))?;
+            self.audio = match &self.config.audio {
+                Some(mixer) => Some(AudioStream::add(&mut output_ctx, mixer.sample_rate(), needs_global_header)?),
+                None => None,
+            };
+            // Chapters go in the header, so they're added before it's written
+            for (id, chapter) in self.config.chapters.iter().enumerate() {
+                let millis = |secs: f64| (secs * 1000.0).round() as i64;
+                output_ctx
+                    .add_chapter(id as i64, (1, 1000), millis(chapter.start), millis(chapter.end), &chapter.title)
+                    .map_err(|e| EncoderError::Ffmpeg(format!("Add chapter: {e}")))?;
+            }
+            if self.config.faststart() {
+                let mut muxer_opts = ffmpeg::Dictionary::new();
+                muxer_opts.set("movflags", "+faststart");
+                output_ctx.write_header_with(muxer_opts)
+                    .map_err(|e| EncoderError::Ffmpeg(format!("Write header: {e}")))?;
+            } else {
+                output_ctx.write_header()
+                    .map_err(|e| EncoderError::Ffmpeg(format!("Write header: {e}")))?;
+            }
 
-            // BGRA -> YUV420P scaler
-            let scaler = scaling::Context::get(
+            // BGRA -> YUV420P (or YUVA420P / YUV420P10LE) scaler, matching the stream's color tags
+            let mut scaler = scaling::Context::get(
                 ffmpeg::format::Pixel::BGRA, self.config.width, self.config.height,
-                ffmpeg::format::Pixel::YUV420P, self.config.width, self.config.height,
+                pixel_format, self.config.width, self.config.height,
                 scaling::Flags::FAST_BILINEAR,
             ).map_err(|e| EncoderError::Ffmpeg(format!("Scaler init: {e}")))?;
+            set_scaler_colorspace(&mut scaler, true, self.config.full_range)?;
 
             self.output_ctx = Some(output_ctx);
             self.encoder = Some(encoder);
             self.scaler = Some(SendScaler(scaler));
             self.encoding = true;
             self.frame_count = 0;
+            self.send_failures = 0;
 
             Ok(())
         }
@@ -331,55 +1125,20 @@ pub mod ffmpeg_encoder {
                 return Err(EncoderError::NotStarted);
             }
 
-            let encoder = self.encoder.as_mut().unwrap();
-            let scaler = self.scaler.as_mut().unwrap();
-            let output_ctx = self.output_ctx.as_mut().unwrap();
-
-            // Create BGRA input frame — copy row-by-row to handle FFmpeg stride padding.
-            // FFmpeg aligns row strides to 32 bytes, but our FrameBuffer uses width*4.
-            // e.g. 2580px: our stride=10320, FFmpeg linesize=10336 (padded to 32).
-            let mut bgra_frame = FfmpegFrame::new(
-                ffmpeg::format::Pixel::BGRA,
-                frame.width,
-                frame.height,
-            );
-            let src_stride = frame.stride as usize;
-            let dst_stride = bgra_frame.stride(0) as usize;
-            if dst_stride == src_stride {
-                bgra_frame.data_mut(0)[..frame.data.len()].copy_from_slice(&frame.data);
-            } else {
-                let dst = bgra_frame.data_mut(0);
-                for y in 0..frame.height as usize {
-                    let src_off = y * src_stride;
-                    let dst_off = y * dst_stride;
-                    dst[dst_off..dst_off + src_stride]
-                        .copy_from_slice(&frame.data[src_off..src_off + src_stride]);
+            let reason = match self.send(frame) {
+                Ok(()) => {
+                    self.send_failures = 0;
+                    self.frame_count += 1;
+                    return Ok(());
                 }
+                Err(e) => e,
+            };
+            self.send_failures += 1;
+            match frame_failure_action(self.send_failures, self.frame_count, self.hardware) {
+                FrameFailureAction::Skip => Err(reason),
+                FrameFailureAction::FallBack => self.fall_back_to_software(frame, reason),
+                FrameFailureAction::Abort => Err(self.failed(reason)),
             }
-
-            // Convert BGRA -> YUV420P
-            let mut yuv_frame = FfmpegFrame::empty();
-            scaler.run(&bgra_frame, &mut yuv_frame)
-                .map_err(|e| EncoderError::Ffmpeg(format!("Scale frame: {e}")))?;
-
-            let pts = self.frame_count as i64;
-            yuv_frame.set_pts(Some(pts));
-
-            // Send frame to encoder
-            encoder.send_frame(&yuv_frame)
-                .map_err(|e| EncoderError::Ffmpeg(format!("Send frame: {e}")))?;
-
-            // Receive and write encoded packets
-            let mut packet = ffmpeg::Packet::empty();
-            while encoder.receive_packet(&mut packet).is_ok() {
-                packet.set_stream(self.stream_index);
-                packet.rescale_ts(self.time_base, output_ctx.stream(self.stream_index).unwrap().time_base());
-                packet.write_interleaved(output_ctx)
-                    .map_err(|e| EncoderError::Ffmpeg(format!("Write packet: {e}")))?;
-            }
-
-            self.frame_count += 1;
-            Ok(())
         }
 
         fn finish(&mut self) -> Result<PathBuf, EncoderError> {
@@ -402,8 +1161,17 @@ pub mod ffmpeg_encoder {
                     .map_err(|e| EncoderError::Ffmpeg(format!("Write packet: {e}")))?;
             }
 
+            if let (Some(audio), Some(mixer)) = (self.audio.as_mut(), self.config.audio.as_ref()) {
+                audio.finish(mixer, output_ctx)?;
+            }
+
             output_ctx.write_trailer()
                 .map_err(|e| EncoderError::Ffmpeg(format!("Write trailer: {e}")))?;
+            if self.config.faststart() {
+                if let Ok(Some(false)) = super::moov_before_mdat(&self.config.output_path) {
+                    log::warn!("Faststart didn't apply: {} has its index at the end", self.config.output_path.display());
+                }
+            }
 
             self.encoding = false;
             log::info!("Export complete: {} frames encoded with {}", self.frame_count, self.encoder_name);
@@ -417,6 +1185,10 @@ pub mod ffmpeg_encoder {
         fn frames_encoded(&self) -> u64 {
             self.frame_count
         }
+
+        fn encoder_name(&self) -> &str {
+            &self.encoder_name
+        }
     }
 }
 
@@ -437,6 +1209,59 @@ mod tests {
         assert_eq!(cfg.keyframe_interval, 120);
         assert!(matches!(cfg.codec, VideoCodec::H264));
         assert!(matches!(cfg.quality, ExportQuality::High));
+        assert_eq!(cfg.bit_rate(), ExportQuality::High.bit_rate(1920.0, 1080.0));
+        let cfg = EncoderConfig { target_bit_rate: Some(2_000_000), ..cfg };
+        assert_eq!(cfg.bit_rate(), 2_000_000);
+    }
+
+    #[test]
+    fn test_ten_bit_supported() {
+        let h265 = EncoderConfig { codec: VideoCodec::H265, ten_bit: true, ..test_config() };
+        assert!(h265.ten_bit_supported().is_ok());
+        assert!(EncoderConfig { codec: VideoCodec::H264, ..h265.clone() }.ten_bit_supported().is_err());
+        assert!(EncoderConfig { alpha: true, ..h265.clone() }.ten_bit_supported().is_err());
+
+        let nvenc = EncoderConfig { preferred_encoder: Some("hevc_nvenc".into()), ..h265.clone() };
+        assert!(nvenc.ten_bit_supported().is_err());
+        let x265 = EncoderConfig { preferred_encoder: Some("libx265".into()), ..h265 };
+        assert!(x265.ten_bit_supported().is_ok());
+        assert!(TEN_BIT_ENCODERS.iter().all(|name| encoder_codec(name).is_some_and(|c| c.supports_ten_bit())));
+    }
+
+    #[test]
+    fn test_encoder_codec_lookup() {
+        assert_eq!(encoder_codec("libx264"), Some(VideoCodec::H264));
+        assert_eq!(encoder_codec("hevc_nvenc"), Some(VideoCodec::H265));
+        assert_eq!(encoder_codec("av1_nvenc"), Some(VideoCodec::Av1));
+        assert_eq!(encoder_codec("not_an_encoder"), None);
+        assert_eq!(software_encoder(VideoCodec::H265), "libx265");
+        assert_eq!(software_encoder(VideoCodec::Av1), "libsvtav1");
+        assert_eq!(software_encoder(VideoCodec::Vp9), "libvpx-vp9");
+        assert!(test_config().preferred_encoder.is_none());
+    }
+
+    #[test]
+    fn test_encoder_presets_and_threads() {
+        let (option, presets) = encoder_presets("libx264").unwrap();
+        assert_eq!(option, "preset");
+        assert!(presets.contains(&"ultrafast") && presets.contains(&"veryslow"));
+        assert_eq!(encoder_presets("hevc_nvenc").unwrap().1.first(), Some(&"p1"));
+        assert_eq!(encoder_presets("h264_amf").unwrap().0, "quality");
+        assert!(encoder_presets("not_an_encoder").is_none());
+        // Every known encoder has presets
+        assert!(KNOWN_ENCODERS.iter().all(|(name, ..)| encoder_presets(name).is_some()));
+
+        let cfg = test_config();
+        assert_eq!(cfg.threads, 0);
+        assert!(cfg.thread_count() >= 1);
+        assert_eq!(EncoderConfig { threads: 6, ..cfg }.thread_count(), 6);
+    }
+
+    #[test]
+    fn test_list_encoders_covers_known() {
+        let encoders = list_encoders();
+        assert_eq!(encoders.len(), KNOWN_ENCODERS.len());
+        assert!(encoders.iter().any(|e| e.name == "libx264" && !e.hardware));
     }
 
     #[test]
@@ -446,6 +1271,68 @@ mod tests {
         assert!(br > 0, "Bit rate should be positive");
     }
 
+    #[test]
+    fn test_faststart_only_for_exported_mp4() {
+        let mut config = test_config();
+        assert!(!config.faststart());
+        config.purpose = EncoderPurpose::Export;
+        assert!(config.faststart());
+        config.output_path = PathBuf::from("/tmp/export.part.MOV");
+        assert!(config.faststart());
+        config.output_path = PathBuf::from("/tmp/export.webm");
+        assert!(!config.faststart());
+    }
+
+    #[test]
+    fn test_moov_before_mdat() {
+        let dir = std::env::temp_dir().join(format!("lazyrec_faststart_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let boxed = |kind: &[u8; 4], body: usize| {
+            let mut b = ((8 + body) as u32).to_be_bytes().to_vec();
+            b.extend_from_slice(kind);
+            b.extend(std::iter::repeat_n(0u8, body));
+            b
+        };
+        let write = |name: &str, boxes: &[Vec<u8>]| {
+            let path = dir.join(name);
+            std::fs::write(&path, boxes.concat()).unwrap();
+            path
+        };
+
+        let fast = write("fast.mp4", &[boxed(b"ftyp", 12), boxed(b"moov", 40), boxed(b"mdat", 100)]);
+        assert_eq!(moov_before_mdat(&fast).unwrap(), Some(true));
+        let slow = write("slow.mp4", &[boxed(b"ftyp", 12), boxed(b"free", 0), boxed(b"mdat", 100), boxed(b"moov", 40)]);
+        assert_eq!(moov_before_mdat(&slow).unwrap(), Some(false));
+        let neither = write("neither.mp4", &[boxed(b"ftyp", 12)]);
+        assert_eq!(moov_before_mdat(&neither).unwrap(), None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_image_sequence_naming_and_rgba() {
+        assert_eq!(sequence_frame_name(1, 5), "frame_00001.png");
+        assert_eq!(sequence_frame_name(123456, 3), "frame_123456.png");
+        assert_eq!(sequence_frame_name(7, 0), "frame_7.png");
+
+        // 1x2 BGRA frame with 4 bytes of row padding
+        let frame = VideoFrame {
+            data: vec![1, 2, 3, 4, 0, 0, 0, 0, 5, 6, 7, 8, 0, 0, 0, 0],
+            width: 1,
+            height: 2,
+            stride: 8,
+            pts: 0.0,
+        };
+        assert_eq!(bgra_to_rgba(&frame.data, frame.width, frame.height, frame.stride), vec![3, 2, 1, 4, 7, 6, 5, 8]);
+
+        let dir = std::env::temp_dir().join(format!("lazyrec_sequence_{}", uuid::Uuid::new_v4()));
+        let mut writer = ImageSequenceWriter::new(dir.clone(), 5);
+        assert!(matches!(writer.append_frame(&frame), Err(EncoderError::NotStarted)));
+        writer.start().unwrap();
+        assert!(matches!(writer.start(), Err(EncoderError::AlreadyStarted)));
+        assert_eq!(writer.finish().unwrap(), dir);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_stub_encoder_lifecycle() {
         let cfg = test_config();
@@ -507,6 +1394,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_frame_failure_action() {
+        // One-off failures are skipped
+        assert_eq!(frame_failure_action(1, 0, true), FrameFailureAction::Skip);
+        assert_eq!(frame_failure_action(MAX_CONSECUTIVE_FRAME_FAILURES - 1, 500, true), FrameFailureAction::Skip);
+        // Repeated: a hardware encoder falls back while little has been written
+        let repeated = MAX_CONSECUTIVE_FRAME_FAILURES;
+        assert_eq!(frame_failure_action(repeated, 0, true), FrameFailureAction::FallBack);
+        assert_eq!(frame_failure_action(repeated, SOFTWARE_FALLBACK_FRAMES - 1, true), FrameFailureAction::FallBack);
+        assert_eq!(frame_failure_action(repeated, SOFTWARE_FALLBACK_FRAMES, true), FrameFailureAction::Abort);
+        // The software encoder has nothing to fall back to
+        assert_eq!(frame_failure_action(repeated, 0, false), FrameFailureAction::Abort);
+    }
+
     #[test]
     fn test_stub_encoder_start_resets_frame_count() {
         let mut enc = StubEncoder::new(test_config());
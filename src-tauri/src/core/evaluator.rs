@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 
 use super::coordinates::NormalizedPoint;
+use super::easing::EasingCurve;
 use super::keyframe::*;
+use super::project::Project;
 use super::timeline::Timeline;
 use super::track::*;
 
@@ -15,6 +17,16 @@ pub struct EvaluatedFrameState {
     pub keystrokes: Vec<ActiveKeystroke>,
 }
 
+/// One point of `FrameEvaluator::sample_transform_curve`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransformCurveSample {
+    pub time: f64,
+    pub zoom: f64,
+    pub center_x: f64,
+    pub center_y: f64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransformState {
     pub zoom: f64,
@@ -23,12 +35,32 @@ pub struct TransformState {
     pub velocity: f64,
 }
 
+impl TransformState {
+    pub const IDENTITY: TransformState = TransformState { zoom: 1.0, center: NormalizedPoint::CENTER, velocity: 0.0 };
+
+    /// Layer `inner` inside this camera: zooms multiply and `inner`'s center
+    /// is read in this camera's view (0.5 keeps this center, 0 its left edge)
+    pub fn nested(&self, inner: &TransformState) -> TransformState {
+        let zoom = self.zoom * inner.zoom;
+        let center = NormalizedPoint::new(
+            self.center.x + (inner.center.x - 0.5) / self.zoom,
+            self.center.y + (inner.center.y - 0.5) / self.zoom,
+        );
+        TransformState { zoom, center, velocity: self.velocity.max(inner.velocity) }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActiveRipple {
     pub position: NormalizedPoint,
     pub progress: f64,
     pub intensity: f64,
     pub color: (f64, f64, f64, f64),
+    #[serde(default)]
+    pub style: RippleStyle,
+    /// Fully grown size relative to the reference radius
+    #[serde(default = "default_ripple_radius_scale")]
+    pub radius_scale: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,6 +71,9 @@ pub struct CursorState {
     pub visible: bool,
     pub velocity: f64,
     pub movement_direction: f64,
+    /// Recent positions for the motion trail, oldest first (empty when disabled or slow)
+    #[serde(default)]
+    pub trail: Vec<NormalizedPoint>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,60 +90,188 @@ pub struct MousePosition {
     pub position: NormalizedPoint,
 }
 
+/// Mouse click for the keyframeless cursor click animation
+#[derive(Debug, Clone)]
+pub struct MouseClick {
+    pub time: f64,
+    /// How long the button was held
+    pub duration: f64,
+}
+
+/// Cursor scale when no cursor keyframes exist
+const DEFAULT_CURSOR_SCALE: f64 = 2.5;
+/// Scale multiplier while a button is held (matches the generator's 2.0 / 2.5)
+const CLICK_SCALE_FACTOR: f64 = 0.8;
+/// Shrink time on mouse-down (capped by the click duration)
+const CLICK_PRESS_DURATION: f64 = 0.08;
+/// Grow-back time after mouse-up
+const CLICK_RELEASE_DURATION: f64 = 0.15;
+/// Cursor copies drawn in the motion trail
+const TRAIL_SAMPLES: usize = 6;
+/// Below this speed (normalized units per second) the trail is skipped
+const TRAIL_MIN_VELOCITY: f64 = 0.4;
+/// Vertical gap between stacked keystroke overlays (normalized, ~1.25 pill heights)
+const KEYSTROKE_STACK_SPACING: f64 = 0.06;
+
+/// Largest accepted effect multiplier
+pub const MAX_EFFECT_SCALE: f64 = 4.0;
+
+/// Global multipliers over the authored effect strengths, applied after
+/// evaluation so keyframes stay untouched (1.0 = as authored)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EffectScales {
+    pub ripple_intensity: f64,
+    pub ripple_size: f64,
+    /// Multiplies the zoom past 1x (0.5 turns 2x into 1.5x)
+    pub zoom: f64,
+    pub cursor: f64,
+    pub keystroke_opacity: f64,
+}
+
+impl Default for EffectScales {
+    fn default() -> Self {
+        Self { ripple_intensity: 1.0, ripple_size: 1.0, zoom: 1.0, cursor: 1.0, keystroke_opacity: 1.0 }
+    }
+}
+
+impl EffectScales {
+    fn apply(&self, state: &mut EvaluatedFrameState, window_mode: bool, view: (f64, f64)) {
+        let factor = |scale: f64| if scale.is_finite() { scale.clamp(0.0, MAX_EFFECT_SCALE) } else { 1.0 };
+
+        let transform = &mut state.transform;
+        transform.zoom = (1.0 + (transform.zoom - 1.0) * factor(self.zoom)).max(1.0);
+        if !window_mode && transform.zoom > 1.0 {
+            transform.center = clamp_center(transform.center, transform.zoom, view);
+        }
+
+        let (ripple, ripple_size) = (factor(self.ripple_intensity), factor(self.ripple_size));
+        state.ripples.retain_mut(|r| {
+            r.intensity *= ripple;
+            r.radius_scale *= ripple_size;
+            r.intensity > 0.0 && r.radius_scale > 0.0
+        });
+
+        state.cursor.scale *= factor(self.cursor);
+        if state.cursor.scale <= 0.0 {
+            state.cursor.visible = false;
+        }
+
+        let keystroke = factor(self.keystroke_opacity);
+        state.keystrokes.retain_mut(|k| {
+            k.opacity = (k.opacity * keystroke).min(1.0);
+            k.opacity > 0.0
+        });
+    }
+}
+
 /// Frame evaluator: evaluates timeline state at any point in time
 pub struct FrameEvaluator {
     pub window_mode: bool,
+    /// Cursor trail history length (seconds); 0 disables the trail
+    pub trail_length: f64,
+    /// Never show the drawn cursor (the recording already contains one)
+    pub hide_cursor: bool,
+    /// Global effect strength (see `RenderSettings::effect_scales`)
+    pub scales: EffectScales,
+    /// Part of the source the output shows at 1x (see `Project::view_extent`)
+    pub view: (f64, f64),
 }
 
 impl FrameEvaluator {
     pub fn new(window_mode: bool) -> Self {
-        Self { window_mode }
+        Self { window_mode, trail_length: 0.0, hide_cursor: false, scales: EffectScales::default(), view: FULL_VIEW }
+    }
+
+    /// Evaluator set up from `project`'s capture and render settings, as the
+    /// export, preview and timeline dumps all evaluate it
+    pub fn for_project(project: &Project) -> Self {
+        let settings = &project.render_settings;
+        Self {
+            window_mode: project.is_window_mode(),
+            trail_length: if settings.cursor_trail.enabled { settings.cursor_trail.length } else { 0.0 },
+            hide_cursor: project.capture_meta.cursor_captured,
+            scales: settings.effect_scales(),
+            view: project.view_extent(),
+        }
     }
 
-    /// Evaluate all tracks at the given time
+    /// Evaluate all tracks at the given time.
+    /// `mouse_clicks` drive the cursor click animation when the cursor track has
+    /// no keyframes; pass an empty slice to disable it.
     pub fn evaluate(
         &self,
         timeline: &Timeline,
         time: f64,
         mouse_positions: &[MousePosition],
+        mouse_clicks: &[MouseClick],
     ) -> EvaluatedFrameState {
-        let transform = self.evaluate_transform(timeline.transform_track(), time);
+        let transform = self.evaluate_transforms(timeline.transform_tracks(), time);
         let ripples = self.evaluate_ripples(timeline.ripple_track(), time);
-        let cursor = self.evaluate_cursor(
+        let mut cursor = self.evaluate_cursor(
             timeline.cursor_track(),
             time,
             mouse_positions,
+            mouse_clicks,
         );
+        if self.hide_cursor {
+            cursor.visible = false;
+        }
+        if cursor.visible {
+            cursor.trail = cursor_trail(mouse_positions, time, self.trail_length);
+        }
         let keystrokes = self.evaluate_keystrokes(timeline.keystroke_track(), time);
 
-        EvaluatedFrameState {
+        let mut state = EvaluatedFrameState {
             time,
             transform,
             ripples,
             cursor,
             keystrokes,
+        };
+        if self.scales != EffectScales::default() {
+            self.scales.apply(&mut state, self.window_mode, self.view);
+        }
+        state
+    }
+
+    /// Evaluate stacked transform tracks (bottom first), nesting each inside
+    /// the ones below. Disabled or empty tracks leave the camera as is.
+    pub fn evaluate_transforms<'a>(
+        &self,
+        tracks: impl IntoIterator<Item = &'a TransformTrack>,
+        time: f64,
+    ) -> TransformState {
+        let mut tracks = tracks.into_iter();
+        let base = self.evaluate_transform(tracks.next(), time);
+        let mut layered = false;
+        let combined = tracks.fold(base, |camera, track| {
+            layered = true;
+            camera.nested(&self.evaluate_transform(Some(track), time))
+        });
+        // A punch-in near the base view's edge can reach outside the source
+        if layered && !self.window_mode && combined.zoom > 1.0 {
+            TransformState { center: clamp_center(combined.center, combined.zoom, self.view), ..combined }
+        } else {
+            combined
         }
     }
 
     /// Evaluate transform track: binary search + easing interpolation
-    fn evaluate_transform(
+    pub fn evaluate_transform(
         &self,
         track: Option<&TransformTrack>,
         time: f64,
     ) -> TransformState {
         let track = match track {
             Some(t) if t.is_enabled && !t.keyframes.is_empty() => t,
-            _ => {
-                return TransformState {
-                    zoom: 1.0,
-                    center: NormalizedPoint::CENTER,
-                    velocity: 0.0,
-                };
-            }
+            _ => return TransformState::IDENTITY,
         };
 
-        let keyframes = &track.keyframes;
+        self.interpolate_transform(&track.keyframes, time)
+    }
 
+    /// Camera at `time` between (non-empty, sorted) `keyframes`
+    fn interpolate_transform(&self, keyframes: &[TransformKeyframe], time: f64) -> TransformState {
         // Before first keyframe
         if time <= keyframes[0].time {
             return TransformState {
@@ -143,20 +306,22 @@ impl FrameEvaluator {
         }
 
         let t = (time - from_kf.time) / segment_duration;
-        let eased_t = from_kf.easing.apply(t, segment_duration);
+        let eased_t = from_kf.easing.apply_overshoot(t, segment_duration);
 
         let from_val = from_kf.value();
         let to_val = to_kf.value();
 
-        let interpolated = if self.window_mode {
+        let mut interpolated = if self.window_mode {
             from_val.interpolated_for_window_mode(&to_val, eased_t)
         } else {
             from_val.interpolated(&to_val, eased_t)
         };
+        // A bouncy spring toward 1x would otherwise dip below it
+        interpolated.zoom = interpolated.zoom.max(1.0);
 
         // Clamp center to valid range based on zoom (prevents crop exceeding image)
         let center = if !self.window_mode && interpolated.zoom > 1.0 {
-            clamp_center(interpolated.center, interpolated.zoom)
+            clamp_center(interpolated.center, interpolated.zoom, self.view)
         } else {
             interpolated.center
         };
@@ -171,6 +336,29 @@ impl FrameEvaluator {
         }
     }
 
+    /// Camera values at `steps + 1` evenly spaced times from `from_time` to
+    /// `to_time` on `track`, through its easing and any keyframes between,
+    /// for drawing the motion curve. Disabled tracks are sampled too.
+    pub fn sample_transform_curve(
+        &self,
+        track: &TransformTrack,
+        from_time: f64,
+        to_time: f64,
+        steps: usize,
+    ) -> Vec<TransformCurveSample> {
+        if track.keyframes.is_empty() {
+            return Vec::new();
+        }
+        let steps = steps.max(1);
+        (0..=steps)
+            .map(|i| {
+                let time = from_time + (to_time - from_time) * i as f64 / steps as f64;
+                let state = self.interpolate_transform(&track.keyframes, time);
+                TransformCurveSample { time, zoom: state.zoom, center_x: state.center.x, center_y: state.center.y }
+            })
+            .collect()
+    }
+
     /// Evaluate ripple track: find all active ripples
     fn evaluate_ripples(
         &self,
@@ -194,25 +382,30 @@ impl FrameEvaluator {
                     progress: eased_progress,
                     intensity: k.intensity,
                     color: k.color.rgba(),
+                    style: k.style,
+                    radius_scale: k.max_radius_scale,
                 }
             })
             .collect()
     }
 
-    /// Evaluate cursor track: interpolate position + discrete style
+    /// Evaluate cursor track: interpolate position + discrete style.
+    /// Without style keyframes the cursor shrinks on clicks instead.
     fn evaluate_cursor(
         &self,
         track: Option<&CursorTrack>,
         time: f64,
         mouse_positions: &[MousePosition],
+        mouse_clicks: &[MouseClick],
     ) -> CursorState {
         let default = CursorState {
             position: interpolate_mouse_position(mouse_positions, time),
             style: CursorStyle::Arrow,
-            scale: 2.5,
+            scale: DEFAULT_CURSOR_SCALE * click_scale_factor(mouse_clicks, time),
             visible: true,
             velocity: 0.0,
             movement_direction: 0.0,
+            trail: Vec::new(),
         };
 
         let track = match track {
@@ -253,6 +446,7 @@ impl FrameEvaluator {
             visible,
             velocity,
             movement_direction: direction,
+            trail: Vec::new(),
         }
     }
 
@@ -267,7 +461,7 @@ impl FrameEvaluator {
             _ => return Vec::new(),
         };
 
-        track
+        let mut keystrokes: Vec<ActiveKeystroke> = track
             .keyframes
             .iter()
             .filter(|k| k.is_active(time))
@@ -276,17 +470,90 @@ impl FrameEvaluator {
                 position: k.position,
                 opacity: k.opacity(time),
             })
-            .collect()
+            .collect();
+        stack_keystrokes(&mut keystrokes);
+        keystrokes
+    }
+}
+
+/// Move older overlays sharing a position out of the way of newer ones:
+/// upwards in the bottom half of the frame, downwards in the top half.
+/// `keystrokes` must be oldest first.
+fn stack_keystrokes(keystrokes: &mut [ActiveKeystroke]) {
+    let anchors: Vec<NormalizedPoint> = keystrokes.iter().map(|k| k.position).collect();
+    for (i, keystroke) in keystrokes.iter_mut().enumerate() {
+        let anchor = anchors[i];
+        let newer = anchors[i + 1..]
+            .iter()
+            .filter(|p| p.distance(&anchor) < KEYSTROKE_STACK_SPACING / 2.0)
+            .count();
+        if newer > 0 {
+            let direction = if anchor.y > 0.5 { -1.0 } else { 1.0 };
+            let y = anchor.y + direction * KEYSTROKE_STACK_SPACING * newer as f64;
+            keystroke.position = NormalizedPoint::new(anchor.x, y.clamp(0.0, 1.0));
+        }
+    }
+}
+
+/// Mouse positions over the last `length` seconds (oldest first, current excluded),
+/// or nothing if the cursor moved too little for a trail to help
+fn cursor_trail(positions: &[MousePosition], time: f64, length: f64) -> Vec<NormalizedPoint> {
+    if length <= 0.0 || positions.len() < 2 || time <= 0.0 {
+        return Vec::new();
+    }
+    let length = length.min(time);
+    let current = interpolate_mouse_position(positions, time);
+    let oldest = interpolate_mouse_position(positions, time - length);
+    let (dx, dy) = (current.x - oldest.x, current.y - oldest.y);
+    if (dx * dx + dy * dy).sqrt() / length < TRAIL_MIN_VELOCITY {
+        return Vec::new();
     }
+
+    (0..TRAIL_SAMPLES)
+        .map(|i| {
+            let t = time - length * (TRAIL_SAMPLES - i) as f64 / TRAIL_SAMPLES as f64;
+            interpolate_mouse_position(positions, t)
+        })
+        .collect()
 }
 
+/// Cursor scale multiplier for the click animation: eases down to
+/// `CLICK_SCALE_FACTOR` on mouse-down, holds while pressed, eases back after release.
+fn click_scale_factor(clicks: &[MouseClick], time: f64) -> f64 {
+    let pressed = clicks
+        .iter()
+        .filter(|c| time >= c.time)
+        .map(|c| {
+            let press = CLICK_PRESS_DURATION.min(c.duration).max(1e-3);
+            let release_start = c.time + c.duration.max(press);
+            let elapsed = time - c.time;
+            if elapsed < press {
+                EasingCurve::EaseOut.apply(elapsed / press, press)
+            } else if time < release_start {
+                1.0
+            } else {
+                1.0 - EasingCurve::EaseInOut.apply(
+                    (time - release_start) / CLICK_RELEASE_DURATION,
+                    CLICK_RELEASE_DURATION,
+                )
+            }
+        })
+        .fold(0.0, f64::max);
+    1.0 - (1.0 - CLICK_SCALE_FACTOR) * pressed
+}
+
+/// The whole source at 1x: an output of the source's aspect ratio
+pub(crate) const FULL_VIEW: (f64, f64) = (1.0, 1.0);
+
 /// Clamp center to valid range based on zoom level.
 /// Prevents the crop rectangle from exceeding the normalized image bounds.
-fn clamp_center(center: NormalizedPoint, zoom: f64) -> NormalizedPoint {
-    let half_crop = 0.5 / zoom;
+/// An output showing only `view` (width, height fractions) of the source at
+/// 1x crops that much less on each axis, so the centre can get closer to the edges.
+pub(crate) fn clamp_center(center: NormalizedPoint, zoom: f64, view: (f64, f64)) -> NormalizedPoint {
+    let (half_x, half_y) = (0.5 * view.0.clamp(0.0, 1.0) / zoom, 0.5 * view.1.clamp(0.0, 1.0) / zoom);
     NormalizedPoint {
-        x: center.x.clamp(half_crop, 1.0 - half_crop),
-        y: center.y.clamp(half_crop, 1.0 - half_crop),
+        x: center.x.clamp(half_x, 1.0 - half_x),
+        y: center.y.clamp(half_y, 1.0 - half_y),
     }
 }
 
@@ -419,9 +686,29 @@ mod tests {
     #[test]
     fn test_clamp_center() {
         // At 2x zoom, half crop = 0.25, valid range is [0.25, 0.75]
-        let c = clamp_center(NormalizedPoint::new(0.1, 0.9), 2.0);
+        let c = clamp_center(NormalizedPoint::new(0.1, 0.9), 2.0, FULL_VIEW);
         assert!((c.x - 0.25).abs() < 1e-10);
         assert!((c.y - 0.75).abs() < 1e-10);
+
+        // Showing half the width, the crop is half as wide
+        let c = clamp_center(NormalizedPoint::new(0.1, 0.9), 2.0, (0.5, 1.0));
+        assert!((c.x - 0.125).abs() < 1e-10);
+        assert!((c.y - 0.75).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_stack_keystrokes() {
+        let overlay = |text: &str, y: f64| ActiveKeystroke {
+            display_text: text.into(),
+            position: NormalizedPoint::new(0.5, y),
+            opacity: 1.0,
+        };
+        let mut keystrokes = vec![overlay("a", 0.95), overlay("b", 0.95), overlay("c", 0.05)];
+        stack_keystrokes(&mut keystrokes);
+        // Oldest bottom overlay moves up above the newer one; the lone top one stays
+        assert!((keystrokes[0].position.y - (0.95 - KEYSTROKE_STACK_SPACING)).abs() < 1e-9);
+        assert_eq!(keystrokes[1].position.y, 0.95);
+        assert_eq!(keystrokes[2].position.y, 0.05);
     }
 
     #[test]
@@ -492,6 +779,109 @@ mod tests {
         assert!((state.zoom - 2.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_sample_transform_curve_follows_easing() {
+        let mut track = TransformTrack::new();
+        track.add_keyframe(TransformKeyframe::new(1.0, 1.0, NormalizedPoint::new(0.2, 0.5), EasingCurve::EaseInOut));
+        track.add_keyframe(TransformKeyframe::new(3.0, 3.0, NormalizedPoint::new(0.6, 0.5), EasingCurve::Linear));
+        track.is_enabled = false;
+
+        let evaluator = FrameEvaluator::new(true);
+        let samples = evaluator.sample_transform_curve(&track, 1.0, 3.0, 4);
+        assert_eq!(samples.len(), 5);
+        assert_eq!(samples[0].time, 1.0);
+        assert_eq!(samples[4].time, 3.0);
+        assert!((samples[0].zoom - 1.0).abs() < 1e-10);
+        assert!((samples[4].zoom - 3.0).abs() < 1e-10);
+        assert!((samples[4].center_x - 0.6).abs() < 1e-10);
+        // Ease-in-out: a slow first quarter, halfway at the midpoint
+        assert!(samples[1].zoom < 1.5);
+        assert!((samples[2].zoom - 2.0).abs() < 1e-6);
+        assert!(samples.windows(2).all(|w| w[1].zoom >= w[0].zoom));
+    }
+
+    #[test]
+    fn test_stacked_transform_tracks_nest() {
+        use super::super::track::AnyTrack;
+
+        // Slow establishing zoom on the base track, a quick punch-in layered on top
+        let mut timeline = Timeline::with_default_tracks(4.0);
+        let base = timeline.transform_track_mut().unwrap();
+        base.add_keyframe(TransformKeyframe::new(0.0, 1.0, NormalizedPoint::CENTER, EasingCurve::Linear));
+        base.add_keyframe(TransformKeyframe::new(4.0, 1.5, NormalizedPoint::new(0.6, 0.5), EasingCurve::Linear));
+        let mut punch = TransformTrack::new();
+        punch.add_keyframe(TransformKeyframe::new(1.0, 1.0, NormalizedPoint::CENTER, EasingCurve::Linear));
+        punch.add_keyframe(TransformKeyframe::new(2.0, 2.0, NormalizedPoint::new(0.75, 0.5), EasingCurve::Linear));
+        timeline.add_track(AnyTrack::Transform(punch));
+
+        let evaluator = FrameEvaluator::new(false);
+        let at = |timeline: &Timeline, time| evaluator.evaluate(timeline, time, &[], &[]).transform;
+
+        // Before the punch-in only the base camera moves
+        let early = at(&timeline, 0.5);
+        let base_only = evaluator.evaluate_transform(timeline.transform_track(), 0.5);
+        assert!((early.zoom - base_only.zoom).abs() < 1e-10);
+        assert_eq!(early.center, base_only.center);
+
+        // Zooms multiply; the layer's center is read inside the base view
+        let late = at(&timeline, 4.0);
+        assert!((late.zoom - 3.0).abs() < 1e-10);
+        assert!((late.center.x - (0.6 + 0.25 / 1.5)).abs() < 1e-10, "{:?}", late.center);
+        assert!((late.center.y - 0.5).abs() < 1e-10);
+
+        // A disabled layer drops out
+        if let Some(AnyTrack::Transform(t)) = timeline.tracks.last_mut() {
+            t.is_enabled = false;
+        }
+        assert!((at(&timeline, 4.0).zoom - 1.5).abs() < 1e-10);
+
+        // The layered camera never crops past the source edge
+        let edge = TransformState { zoom: 2.0, center: NormalizedPoint::new(0.75, 0.5), velocity: 0.0 }
+            .nested(&TransformState { zoom: 2.0, center: NormalizedPoint::new(1.0, 0.5), velocity: 0.0 });
+        assert!((edge.center.x - 1.0).abs() < 1e-10);
+        let mut stacked = TransformTrack::new();
+        stacked.add_keyframe(TransformKeyframe::new(0.0, 2.0, NormalizedPoint::new(1.0, 0.5), EasingCurve::Linear));
+        let mut under = TransformTrack::new();
+        under.add_keyframe(TransformKeyframe::new(0.0, 2.0, NormalizedPoint::new(0.75, 0.5), EasingCurve::Linear));
+        let clamped = evaluator.evaluate_transforms([&under, &stacked], 0.0);
+        assert!((clamped.center.x - 0.875).abs() < 1e-10, "{:?}", clamped.center);
+    }
+
+    #[test]
+    fn test_effect_scales() {
+        let mut timeline = Timeline::with_default_tracks(5.0);
+        timeline.transform_track_mut().unwrap().add_keyframe(TransformKeyframe::new(
+            0.0, 3.0, NormalizedPoint::new(0.4, 0.4), EasingCurve::Linear,
+        ));
+        timeline.ripple_track_mut().unwrap().add_keyframe(RippleKeyframe::new(1.0, NormalizedPoint::CENTER));
+        timeline.keystroke_track_mut().unwrap().add_keyframe(KeystrokeKeyframe::new(1.0, "Ctrl+S".into()));
+
+        let mut evaluator = FrameEvaluator::new(false);
+        let authored = evaluator.evaluate(&timeline, 1.1, &[], &[]);
+        assert_eq!(authored.ripples.len(), 1);
+
+        evaluator.scales = EffectScales { ripple_intensity: 0.0, ripple_size: 1.0, zoom: 0.5, cursor: 2.0, keystroke_opacity: 0.5 };
+        let scaled = evaluator.evaluate(&timeline, 1.1, &[], &[]);
+        // Half the zoom delta: 3x -> 2x
+        assert!((scaled.transform.zoom - 2.0).abs() < 1e-10);
+        assert!(scaled.ripples.is_empty());
+        assert!((scaled.cursor.scale - authored.cursor.scale * 2.0).abs() < 1e-10);
+        assert!((scaled.keystrokes[0].opacity - authored.keystrokes[0].opacity * 0.5).abs() < 1e-10);
+
+        // Zoom never drops below 1x, and an enlarged zoom keeps the crop inside the frame
+        evaluator.scales = EffectScales { zoom: 4.0, ..Default::default() };
+        let zoomed = evaluator.evaluate(&timeline, 1.1, &[], &[]).transform;
+        assert!((zoomed.zoom - 9.0).abs() < 1e-10);
+        assert_eq!(zoomed.center, clamp_center(NormalizedPoint::new(0.4, 0.4), 9.0, FULL_VIEW));
+        evaluator.scales = EffectScales { zoom: -1.0, ..Default::default() };
+        assert_eq!(evaluator.evaluate(&timeline, 1.1, &[], &[]).transform.zoom, 1.0);
+
+        // Ripple size multiplies the keyframe's own radius scale
+        timeline.ripple_track_mut().unwrap().keyframes[0].max_radius_scale = 1.5;
+        evaluator.scales = EffectScales { ripple_size: 2.0, ..Default::default() };
+        assert!((evaluator.evaluate(&timeline, 1.1, &[], &[]).ripples[0].radius_scale - 3.0).abs() < 1e-10);
+    }
+
     #[test]
     fn test_interpolate_angle_wraparound() {
         let pi = std::f64::consts::PI;
@@ -499,4 +889,55 @@ mod tests {
         // Should go through pi (the short way around), not through 0
         assert!(result.abs() > pi * 0.8);
     }
+
+    #[test]
+    fn test_click_scale_animation() {
+        let clicks = [MouseClick { time: 1.0, duration: 0.3 }];
+        let evaluator = FrameEvaluator::new(false);
+        let scale_at = |t: f64| {
+            evaluator.evaluate(&Timeline::new(5.0), t, &[], &clicks).cursor.scale
+        };
+
+        assert!((scale_at(0.9) - 2.5).abs() < 1e-10);
+        // Mid-press is partway down, held is fully down
+        let mid = scale_at(1.04);
+        assert!(mid < 2.5 && mid > 2.0);
+        assert!((scale_at(1.2) - 2.0).abs() < 1e-10);
+        // Eases back after release, then returns to normal
+        let releasing = scale_at(1.35);
+        assert!(releasing > 2.0 && releasing < 2.5);
+        assert!((scale_at(2.0) - 2.5).abs() < 1e-10);
+        // No clicks: no animation
+        assert!((evaluator.evaluate(&Timeline::new(5.0), 1.2, &[], &[]).cursor.scale - 2.5).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_cursor_trail_only_when_moving() {
+        let moving = [
+            MousePosition { time: 0.0, position: NormalizedPoint::new(0.0, 0.5) },
+            MousePosition { time: 1.0, position: NormalizedPoint::new(1.0, 0.5) },
+        ];
+        let mut evaluator = FrameEvaluator::new(false);
+        let timeline = Timeline::new(5.0);
+        assert!(evaluator.evaluate(&timeline, 0.5, &moving, &[]).cursor.trail.is_empty());
+
+        evaluator.trail_length = 0.3;
+        let trail = evaluator.evaluate(&timeline, 0.5, &moving, &[]).cursor.trail;
+        assert_eq!(trail.len(), TRAIL_SAMPLES);
+        // Oldest first, all behind the cursor
+        assert!((trail[0].x - 0.2).abs() < 1e-9);
+        assert!(trail.windows(2).all(|w| w[0].x < w[1].x) && trail[TRAIL_SAMPLES - 1].x < 0.5);
+
+        // Stationary cursor: no trail
+        let still = [
+            MousePosition { time: 0.0, position: NormalizedPoint::CENTER },
+            MousePosition { time: 1.0, position: NormalizedPoint::CENTER },
+        ];
+        assert!(evaluator.evaluate(&timeline, 0.5, &still, &[]).cursor.trail.is_empty());
+
+        // Cursor captured in the video: nothing drawn, no trail
+        evaluator.hide_cursor = true;
+        let cursor = evaluator.evaluate(&timeline, 0.5, &moving, &[]).cursor;
+        assert!(!cursor.visible && cursor.trail.is_empty());
+    }
 }
@@ -0,0 +1,322 @@
+//! Exporting a project package end to end: a plain copy of the recording
+//! when nothing needs rendering, otherwise a full render through
+//! `ExportEngine`. Shared by the `start_export` command and the headless
+//! `lazyrec-export` binary.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use super::cursor_theme::CursorPack;
+use super::encoder::ImageSequenceOptions;
+use super::evaluator::{MouseClick, MousePosition};
+use super::input::InputRecording;
+use super::project::{MouseSmoothing, OutputFrameRate, OutputResolution, Project, VideoCodec};
+use super::render::{
+    create_video_source_from_file, load_background_image, partial_output_path, stream_copy_range, ExportEngine,
+    stamp_progress, ExportError, ExportProgress, ExportState, TransparentVideoSource, VideoSource,
+};
+
+/// Where an export is written
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportTarget {
+    /// Video file (ignored for image sequences, apart from naming)
+    pub output_path: PathBuf,
+    /// Write numbered PNGs into (directory, padding) instead of a video
+    pub image_sequence: Option<(PathBuf, usize)>,
+}
+
+impl ExportTarget {
+    /// `export_<timestamp>.<ext>` (or `export_<timestamp>_frames/`) in the
+    /// directory holding the package
+    pub fn beside_package(project: &Project, package_dir: &Path, image_sequence: Option<ImageSequenceOptions>) -> Self {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let output_dir = package_dir.parent().unwrap_or(Path::new("."));
+        let output_path = output_dir.join(format!("export_{timestamp}.{}", output_extension(project)));
+        let image_sequence = image_sequence.map(|options| {
+            let directory = options
+                .directory
+                .unwrap_or_else(|| output_dir.join(format!("export_{timestamp}_frames")));
+            (directory, options.padding)
+        });
+        Self { output_path, image_sequence }
+    }
+}
+
+/// Container extension the project exports to
+pub fn output_extension(project: &Project) -> &str {
+    if project.render_settings.overlay_only {
+        VideoCodec::Vp9.file_extension()
+    } else {
+        project.render_settings.codec.file_extension()
+    }
+}
+
+/// Whether the export would reproduce the recording unchanged, so it can be
+/// copied (or stream-copied when trimmed) instead of decoded and re-encoded.
+/// Window mode always renders (background compositing), as does baking the
+/// cursor in.
+pub fn can_copy_recording(project: &Project, image_sequence: bool) -> bool {
+    let settings = &project.render_settings;
+    !settings.bake_cursor_only
+        && project.timeline.is_empty()
+        && !project.timeline.has_speed_segments()
+        && !project.timeline.has_cuts()
+        && (!settings.write_chapters || project.timeline.markers.is_empty())
+        && !settings.click_sfx
+        && !settings.corner_mask
+        && !settings.overlay_only
+        && !image_sequence
+        && settings.watermark.is_none()
+        && settings.output_resolution == OutputResolution::Original
+        && settings.output_frame_rate == OutputFrameRate::Original
+        && !project.is_window_mode()
+}
+
+/// Export `project` (stored in `package_dir`) to `target`, blocking until
+/// done. Returns the written file (or frame directory). Setting `cancel`
+/// stops a render with `ExportError::Cancelled`.
+pub fn export_project<F>(
+    project: &Project,
+    package_dir: &Path,
+    target: &ExportTarget,
+    cancel: Option<Arc<AtomicBool>>,
+    progress_callback: F,
+) -> Result<PathBuf, ExportError>
+where
+    F: FnMut(ExportProgress),
+{
+    let video_path = project.video_path(package_dir);
+    log::info!(
+        "Export: video_path={}, exists={}, package_dir={}",
+        video_path.display(),
+        video_path.exists(),
+        package_dir.display(),
+    );
+    log::info!(
+        "Export: timeline has {} keyframes, is_empty={}",
+        project.timeline.total_keyframe_count(),
+        project.timeline.is_empty(),
+    );
+
+    if can_copy_recording(project, target.image_sequence.is_some()) {
+        return copy_recording(project, &video_path, &target.output_path, progress_callback);
+    }
+
+    let cursor_only;
+    let project = if project.render_settings.bake_cursor_only {
+        if project.capture_meta.cursor_captured {
+            log::warn!("Baking the cursor into a recording that already shows it; the overlay cursor stays hidden");
+        }
+        cursor_only = project.cursor_only();
+        &cursor_only
+    } else {
+        project
+    };
+
+    let settings = &project.render_settings;
+    let overlay_only = settings.overlay_only;
+    let (width, height) = (project.media.pixel_size.width as u32, project.media.pixel_size.height as u32);
+    let source: Box<dyn VideoSource> = if overlay_only {
+        Box::new(TransparentVideoSource::new(width, height, project.duration(), project.media.frame_rate))
+    } else {
+        create_video_source_from_file(&video_path, width, height, project.duration(), project.media.frame_rate)
+    };
+
+    let recording = load_input_recording(project, package_dir);
+    let mouse_positions = recording
+        .as_ref()
+        .map(|r| input_to_evaluator_positions(r, &settings.mouse_smoothing))
+        .unwrap_or_default();
+
+    let mut engine = ExportEngine::from_project(project, source, mouse_positions, target.output_path.clone());
+    if let Some(recording) = &recording {
+        engine.set_mouse_clicks(input_to_evaluator_clicks(recording));
+        // WebM can't carry the AAC sound-effect track
+        if settings.click_sfx && !overlay_only && settings.codec != VideoCodec::Vp9 {
+            engine.set_sound_effects(&super::sfx::sfx_events(recording), settings.click_sfx_volume);
+        }
+    }
+    if project.is_window_mode() && !overlay_only {
+        if let Some(image) = project.background_image_path(package_dir).and_then(|path| load_background_image(&path)) {
+            engine.set_background_image(image);
+        }
+    }
+    if let Some(image) = project.watermark_path(package_dir).and_then(|path| load_background_image(&path)) {
+        engine.set_watermark_image(image);
+    }
+    if let Some(dir) = project.cursor_theme_path(package_dir) {
+        engine.set_cursor_pack(CursorPack::load_or_system(&dir));
+    }
+    if let Some((directory, padding)) = &target.image_sequence {
+        log::info!("Export: writing PNG frames to {}", directory.display());
+        engine.set_image_sequence(directory.clone(), *padding);
+    }
+    if let Some(cancel) = cancel {
+        engine.set_cancel_flag(cancel);
+    }
+
+    engine.export(progress_callback)
+}
+
+/// The fast path of `export_project`: copy the recording, or stream-copy
+/// the trimmed range, via the partial name like a rendered export
+fn copy_recording<F>(
+    project: &Project,
+    video_path: &Path,
+    output_path: &Path,
+    progress_callback: F,
+) -> Result<PathBuf, ExportError>
+where
+    F: FnMut(ExportProgress),
+{
+    let mut progress_callback = stamp_progress(progress_callback);
+    let trimmed = project.timeline.is_trimmed();
+    log::info!(
+        "No effects — {} recording to {}",
+        if trimmed { "stream-copying trimmed" } else { "fast-copying" },
+        output_path.display(),
+    );
+    let progress = |progress, state| ExportProgress { total_frames: 1, progress, state, ..Default::default() };
+    progress_callback(progress(0.5, ExportState::Rendering));
+
+    let partial = partial_output_path(output_path);
+    let copied = if trimmed {
+        let start = project.timeline.effective_trim_start();
+        let end = project.timeline.effective_trim_end();
+        stream_copy_range(video_path, &partial, start, end).map(|actual_start| {
+            if actual_start < start - 1e-3 {
+                log::info!(
+                    "Stream copy starts at keyframe {actual_start:.3}s ({:.3}s before trim start)",
+                    start - actual_start,
+                );
+            }
+        })
+    } else {
+        std::fs::copy(video_path, &partial).map(|_| ()).map_err(ExportError::from)
+    };
+    if let Err(e) = copied.and_then(|()| Ok(std::fs::rename(&partial, output_path)?)) {
+        log::error!("Fast copy failed: {e}");
+        let _ = std::fs::remove_file(&partial);
+        return Err(e);
+    }
+
+    progress_callback(progress(1.0, ExportState::Completed));
+    Ok(output_path.to_path_buf())
+}
+
+/// Read the project's recorded mouse/keyboard data, if present and valid,
+/// with the project's input time offset applied
+pub fn load_input_recording(project: &Project, package_dir: &Path) -> Option<InputRecording> {
+    let mouse_path = project.mouse_data_path(package_dir);
+    if !mouse_path.exists() {
+        return None;
+    }
+    let json = std::fs::read_to_string(&mouse_path).unwrap_or_default();
+    let mut recording = InputRecording::from_json(&json).ok()?;
+    project.align_input(&mut recording);
+    Some(recording)
+}
+
+/// Convert InputRecording positions to evaluator MousePosition format
+pub fn input_to_evaluator_positions(recording: &InputRecording, smoothing: &MouseSmoothing) -> Vec<MousePosition> {
+    let positions: Vec<_> = recording
+        .positions
+        .iter()
+        .map(|p| MousePosition { time: p.time, position: p.position })
+        .collect();
+    super::smoothing::smooth_positions(&positions, smoothing)
+}
+
+/// Convert InputRecording clicks to evaluator MouseClick format
+pub fn input_to_evaluator_clicks(recording: &InputRecording) -> Vec<MouseClick> {
+    recording
+        .clicks
+        .iter()
+        .map(|c| MouseClick { time: c.time, duration: c.duration })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::coordinates::NormalizedPoint;
+    use crate::core::keyframe::RippleKeyframe;
+    use crate::core::project::{CaptureMeta, MediaAsset, Rect, Size};
+    use crate::core::track::{AnyTrack, CursorTrack, RippleTrack};
+
+    fn project() -> Project {
+        let media = MediaAsset {
+            video_relative_path: "recording.mp4".into(),
+            mouse_data_relative_path: "recording_mouse.json".into(),
+            pixel_size: Size::new(64.0, 36.0),
+            frame_rate: 10.0,
+            duration: 1.0,
+        };
+        Project::new("CLI".into(), media, CaptureMeta::new(Rect::new(0.0, 0.0, 64.0, 36.0), 1.0))
+    }
+
+    #[test]
+    fn test_untouched_recording_is_copied() {
+        let dir = std::env::temp_dir().join(format!("lazyrec_export_copy_{}", uuid::Uuid::new_v4()));
+        let package_dir = dir.join("Demo.lazyrec");
+        std::fs::create_dir_all(&package_dir).unwrap();
+        let mut project = project();
+        let video_path = project.video_path(&package_dir);
+        std::fs::create_dir_all(video_path.parent().unwrap()).unwrap();
+        std::fs::write(&video_path, b"recording").unwrap();
+        project.timeline.tracks.clear();
+        assert!(can_copy_recording(&project, false));
+        assert!(!can_copy_recording(&project, true));
+
+        let target = ExportTarget::beside_package(&project, &package_dir, None);
+        assert_eq!(target.output_path.parent(), Some(dir.as_path()));
+        let mut states = Vec::new();
+        let path = export_project(&project, &package_dir, &target, None, |p| states.push(p.state)).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"recording");
+        assert!(!partial_output_path(&path).exists());
+        assert_eq!(states.last(), Some(&ExportState::Completed));
+
+        // Chapters are written by the encoder, so markers need a render when they're on
+        project.timeline.add_marker(0.5, "Intro".into());
+        project.render_settings.write_chapters = false;
+        assert!(can_copy_recording(&project, false));
+        project.render_settings.write_chapters = true;
+        assert!(!can_copy_recording(&project, false));
+        project.timeline.markers.clear();
+
+        // Any effect needs a render
+        let mut ripple = RippleTrack::new();
+        ripple.add_keyframe(RippleKeyframe::new(0.5, NormalizedPoint::CENTER));
+        project.timeline.add_track(AnyTrack::Ripple(ripple));
+        assert!(!can_copy_recording(&project, false));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_bake_cursor_only_drops_other_effects() {
+        let mut project = project();
+        project.timeline.tracks.clear();
+        project.render_settings.bake_cursor_only = true;
+        // Even an untouched recording renders, to draw the cursor in
+        assert!(!can_copy_recording(&project, false));
+
+        let mut ripple = RippleTrack::new();
+        ripple.add_keyframe(RippleKeyframe::new(0.5, NormalizedPoint::CENTER));
+        project.timeline.add_track(AnyTrack::Ripple(ripple));
+        project.timeline.add_track(AnyTrack::Cursor(CursorTrack::new()));
+        project.timeline.trim_start = 0.25;
+        project.render_settings.background_enabled = true;
+        project.render_settings.click_sfx = true;
+
+        let baked = project.cursor_only();
+        assert_eq!(baked.timeline.tracks.len(), 1);
+        assert!(baked.timeline.cursor_track().is_some());
+        assert_eq!(baked.timeline.trim_start, 0.25);
+        assert!(!baked.is_window_mode());
+        assert!(!baked.render_settings.click_sfx);
+    }
+}
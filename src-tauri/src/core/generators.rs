@@ -1,10 +1,15 @@
 //! Keyframe generators: auto-generate keyframes from mouse/keyboard data.
 
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use super::coordinates::NormalizedPoint;
 use super::easing::EasingCurve;
+use super::evaluator::{clamp_center, FrameEvaluator, FULL_VIEW};
 use super::keyframe::*;
+use super::project::Rect;
 use super::track::*;
 
 // ============================================================================
@@ -58,6 +63,12 @@ impl Modifiers {
     pub fn has_modifiers(&self) -> bool {
         self.command || self.shift || self.alt || self.control
     }
+
+    /// Whether the keys make a shortcut rather than typed text: Shift alone
+    /// only types capitals and symbols
+    pub fn has_shortcut_modifiers(&self) -> bool {
+        self.command || self.alt || self.control
+    }
 }
 
 /// Drag event from recording
@@ -69,12 +80,21 @@ pub struct DragEvent {
     pub end_position: NormalizedPoint,
 }
 
+/// Foreground window bounds from recording, in effect until the next one
+#[derive(Debug, Clone)]
+pub struct WindowEvent {
+    pub time: f64,
+    /// (min_x, min_y, width, height) in normalized coords
+    pub bounds: (f64, f64, f64, f64),
+}
+
 /// Complete mouse data source for generators
 pub struct MouseData {
     pub positions: Vec<(f64, NormalizedPoint)>,
     pub clicks: Vec<ClickEvent>,
     pub keyboard_events: Vec<KeyboardEvent>,
     pub drags: Vec<DragEvent>,
+    pub windows: Vec<WindowEvent>,
     pub duration: f64,
 }
 
@@ -89,6 +109,8 @@ pub enum ActivityType {
     Typing,
     DragStart,
     DragEnd,
+    /// Region of the video changed on its own (see `content::analyze_content`)
+    ContentChange,
 }
 
 /// Single activity event
@@ -99,8 +121,9 @@ pub struct ActivityEvent {
     pub activity_type: ActivityType,
 }
 
-/// Collect activities from raw mouse data
-pub fn collect_activities(data: &MouseData) -> Vec<ActivityEvent> {
+/// Collect activities from raw mouse data. Keystrokes further apart than
+/// `typing_session_timeout` start a new typing session.
+pub fn collect_activities(data: &MouseData, typing_session_timeout: f64) -> Vec<ActivityEvent> {
     let mut activities: Vec<ActivityEvent> = Vec::new();
 
     // Clicks (left down only)
@@ -129,7 +152,7 @@ pub fn collect_activities(data: &MouseData) -> Vec<ActivityEvent> {
     }
 
     // Typing sessions
-    let sessions = detect_typing_sessions(&data.keyboard_events);
+    let sessions = detect_typing_sessions(&data.keyboard_events, typing_session_timeout);
     for session in sessions {
         // Find cursor position at session start
         let position = find_position_at_time(&data.positions, session.start);
@@ -151,33 +174,53 @@ pub fn collect_activities(data: &MouseData) -> Vec<ActivityEvent> {
     activities
 }
 
-struct TypingSession {
-    start: f64,
-    end: f64,
+pub(crate) struct TypingSession {
+    pub(crate) start: f64,
+    pub(crate) end: f64,
 }
 
-const TYPING_SESSION_TIMEOUT: f64 = 1.5;
+/// Default pause between keystrokes that ends a typing session (seconds)
+pub(crate) const TYPING_SESSION_TIMEOUT: f64 = 1.5;
 
-fn detect_typing_sessions(events: &[KeyboardEvent]) -> Vec<TypingSession> {
-    let key_downs: Vec<&KeyboardEvent> = events
+fn default_typing_session_timeout() -> f64 {
+    TYPING_SESSION_TIMEOUT
+}
+
+/// Holds don't move the camera, so their easing only shows once edited
+fn default_hold_easing() -> EasingCurve {
+    EasingCurve::Linear
+}
+
+/// Key-down without shortcut modifiers (typing rather than a shortcut)
+pub(crate) fn is_typing_key(event: &KeyboardEvent) -> bool {
+    event.event_type == KeyEventType::KeyDown && !event.modifiers.has_shortcut_modifiers()
+}
+
+fn detect_typing_sessions(events: &[KeyboardEvent], timeout: f64) -> Vec<TypingSession> {
+    let times: Vec<f64> = events
         .iter()
-        .filter(|e| e.event_type == KeyEventType::KeyDown && !e.modifiers.has_modifiers())
+        .filter(|e| is_typing_key(e))
+        .map(|e| e.time)
         .collect();
+    group_typing_times(&times, timeout)
+}
 
-    if key_downs.is_empty() {
+/// Group sorted key-down times into sessions split by pauses longer than `timeout`
+pub(crate) fn group_typing_times(times: &[f64], timeout: f64) -> Vec<TypingSession> {
+    let Some(&first) = times.first() else {
         return Vec::new();
-    }
+    };
 
     let mut sessions = Vec::new();
-    let mut session_start = key_downs[0].time;
+    let mut session_start = first;
     let mut last_time = session_start;
 
-    for event in &key_downs[1..] {
-        if event.time - last_time > TYPING_SESSION_TIMEOUT {
+    for &time in &times[1..] {
+        if time - last_time > timeout {
             sessions.push(TypingSession { start: session_start, end: last_time });
-            session_start = event.time;
+            session_start = time;
         }
-        last_time = event.time;
+        last_time = time;
     }
     sessions.push(TypingSession { start: session_start, end: last_time });
 
@@ -232,6 +275,23 @@ impl WorkSession {
         self.update_work_area(activity.position, padding);
     }
 
+    /// Take over `other`'s activities and time span
+    fn absorb(&mut self, other: WorkSession, padding: f64) {
+        self.start_time = self.start_time.min(other.start_time);
+        self.end_time = self.end_time.max(other.end_time);
+        for activity in other.activities {
+            self.update_work_area(activity.position, padding);
+            self.activities.push(activity);
+        }
+        self.activities.sort_by(|a, b| a.time.total_cmp(&b.time));
+    }
+
+    /// Below `min_session_duration` or `min_activities_for_zoom`
+    fn is_brief(&self, settings: &SmartZoomSettings) -> bool {
+        self.end_time - self.start_time < settings.min_session_duration
+            || self.activities.len() < settings.min_activities_for_zoom
+    }
+
     fn update_work_area(&mut self, position: NormalizedPoint, padding: f64) {
         let (cur_x, cur_y, cur_w, cur_h) = self.work_area;
         let cur_max_x = cur_x + cur_w;
@@ -259,7 +319,8 @@ fn padded_bbox(a: NormalizedPoint, b: NormalizedPoint, padding: f64) -> (f64, f6
 }
 
 /// SmartZoom settings
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SmartZoomSettings {
     pub min_zoom: f64,
     pub max_zoom: f64,
@@ -271,11 +332,93 @@ pub struct SmartZoomSettings {
     pub session_merge_interval: f64,
     pub session_merge_distance: f64,
     pub work_area_padding: f64,
+    /// Pause between keystrokes that splits typing into separate sessions (seconds).
+    /// Raise it for slow typists so one burst of typing isn't many short zooms.
+    #[serde(default = "default_typing_session_timeout")]
+    pub typing_session_timeout: f64,
+    /// Also zoom to regions where the video content changes (playback,
+    /// animations). Decodes sampled frames, so generation is slower.
+    #[serde(default)]
+    pub analyze_content: bool,
+    /// Pull each zoom toward the centre of the window that was active,
+    /// keeping the session's activity in view. Needs recorded window bounds.
+    #[serde(default)]
+    pub focus_follow: bool,
+    /// Lead the cursor: shift each zoom centre along the cursor's motion at
+    /// the session start by this many seconds of that motion (0 = centred
+    /// on the activity), and pan between nearby sessions that much earlier.
+    /// Capped so the cursor stays in view.
+    #[serde(default)]
+    pub lead_factor: f64,
+    /// Sessions shorter than this (seconds) don't get their own zoom: they
+    /// join a neighbouring session within `session_merge_interval` or are
+    /// dropped, so a lone click isn't a quick punch-in. 0 = no minimum.
+    #[serde(default)]
+    pub min_session_duration: f64,
+    /// Sessions with fewer activities are treated the same way (0 = no minimum)
+    #[serde(default)]
+    pub min_activities_for_zoom: usize,
     pub zoom_in_easing: EasingCurve,
     pub zoom_out_easing: EasingCurve,
     pub move_easing: EasingCurve,
+    /// Rest at `min_zoom` before a zoom-in
+    #[serde(default = "default_hold_easing")]
+    pub pre_hold_easing: EasingCurve,
+    /// Hold on a session while zoomed in
+    #[serde(default = "default_hold_easing")]
+    pub hold_easing: EasingCurve,
+    /// Out of the last keyframe, toward any keyframe added after it
+    #[serde(default = "default_hold_easing")]
+    pub final_easing: EasingCurve,
+    /// Camera spring feel; when set, zoom-ins and moves use springs built
+    /// from it instead of `zoom_in_easing` / `move_easing`
+    #[serde(default)]
+    pub spring: Option<CameraSpring>,
+    /// Part of the source's width and height the export shows at 1x (from
+    /// `Project::view_extent`, not saved): work areas are framed and centres
+    /// clamped against it, so non-16:9 captures cropped to fit the output
+    /// aren't over-zoomed along the cropped axis
+    #[serde(skip, default = "full_view")]
+    pub view_extent: (f64, f64),
 }
 
+fn full_view() -> (f64, f64) {
+    FULL_VIEW
+}
+
+/// Spring feel of SmartZoom camera motion
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CameraSpring {
+    /// 1 settles without overshoot; lower bounces past the target
+    /// (down to `easing::MIN_SPRING_DAMPING`)
+    pub damping_ratio: f64,
+    /// Seconds per oscillation: lower is snappier, higher floatier
+    pub response: f64,
+}
+
+impl SmartZoomSettings {
+    /// Easing of zoom-in keyframes
+    pub fn zoom_in_curve(&self) -> EasingCurve {
+        match self.spring {
+            Some(spring) => EasingCurve::spring(spring.damping_ratio, spring.response),
+            None => self.zoom_in_easing.clone(),
+        }
+    }
+
+    /// Easing of pans between sessions. Slightly slower than the zoom-in, as
+    /// the default springs are.
+    pub fn move_curve(&self) -> EasingCurve {
+        match self.spring {
+            Some(spring) => EasingCurve::spring(spring.damping_ratio, spring.response * MOVE_SPRING_SLOWDOWN),
+            None => self.move_easing.clone(),
+        }
+    }
+}
+
+/// Move spring response relative to the zoom-in's (as `spring_smooth` is to `spring_default`)
+const MOVE_SPRING_SLOWDOWN: f64 = 1.25;
+
 impl Default for SmartZoomSettings {
     fn default() -> Self {
         Self {
@@ -290,9 +433,89 @@ impl Default for SmartZoomSettings {
             session_merge_interval: 3.0,
             session_merge_distance: 0.3,
             work_area_padding: 0.02,
+            typing_session_timeout: default_typing_session_timeout(),
+            analyze_content: false,
+            focus_follow: false,
+            lead_factor: 0.0,
+            min_session_duration: 0.0,
+            min_activities_for_zoom: 0,
             zoom_in_easing: EasingCurve::spring_default(),
             zoom_out_easing: EasingCurve::EaseOut,
             move_easing: EasingCurve::spring_smooth(),
+            pre_hold_easing: default_hold_easing(),
+            hold_easing: default_hold_easing(),
+            final_easing: default_hold_easing(),
+            spring: None,
+            view_extent: FULL_VIEW,
+        }
+    }
+}
+
+/// Named SmartZoom tunings. `Balanced` is the default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SmartZoomPreset {
+    /// Shallow zooms, slow transitions, long holds
+    Subtle,
+    Balanced,
+    /// Deep zooms, quick transitions, short holds
+    Dramatic,
+}
+
+impl SmartZoomPreset {
+    pub fn settings(self) -> SmartZoomSettings {
+        // (zoom scale, time scale): zoom amounts scale around 1.0, durations linearly
+        let (zoom, time) = match self {
+            Self::Subtle => (0.6, 1.3),
+            Self::Balanced => return SmartZoomSettings::default(),
+            Self::Dramatic => (1.5, 0.7),
+        };
+        let base = SmartZoomSettings::default();
+        let scale_zoom = |z: f64| 1.0 + (z - 1.0) * zoom;
+        SmartZoomSettings {
+            max_zoom: scale_zoom(base.max_zoom),
+            default_zoom: scale_zoom(base.default_zoom),
+            // Deeper zooms frame the work area more tightly
+            target_area_coverage: (base.target_area_coverage / zoom.sqrt()).clamp(0.3, 0.95),
+            focusing_duration: base.focusing_duration * time,
+            idle_timeout: base.idle_timeout * time,
+            transition_duration: base.transition_duration * time,
+            ..base
+        }
+    }
+}
+
+/// SmartZoom configuration stored on a project: a preset or custom values
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SmartZoomConfig {
+    Preset { preset: SmartZoomPreset },
+    Custom { settings: Box<SmartZoomSettings> },
+}
+
+impl Default for SmartZoomConfig {
+    fn default() -> Self {
+        Self::Preset { preset: SmartZoomPreset::Balanced }
+    }
+}
+
+impl SmartZoomConfig {
+    /// The same config with camera spring `spring` (None: the configured
+    /// easings). Stays a preset when the result is one.
+    pub fn with_spring(&self, spring: Option<CameraSpring>) -> Self {
+        let settings = SmartZoomSettings { spring, ..self.settings() };
+        for preset in [SmartZoomPreset::Subtle, SmartZoomPreset::Balanced, SmartZoomPreset::Dramatic] {
+            if preset.settings() == settings {
+                return Self::Preset { preset };
+            }
+        }
+        Self::Custom { settings: Box::new(settings) }
+    }
+
+    pub fn settings(&self) -> SmartZoomSettings {
+        match self {
+            Self::Preset { preset } => preset.settings(),
+            Self::Custom { settings } => settings.as_ref().clone(),
         }
     }
 }
@@ -337,11 +560,52 @@ pub fn cluster_activities(
 // Zoom Level Calculator
 // ============================================================================
 
+/// Fold brief sessions (`WorkSession::is_brief`) into the previous or next
+/// full session when it's within `session_merge_interval`, and drop the rest
+pub fn absorb_brief_sessions(sessions: Vec<WorkSession>, settings: &SmartZoomSettings) -> Vec<WorkSession> {
+    if !sessions.iter().any(|s| s.is_brief(settings)) {
+        return sessions;
+    }
+    let padding = settings.work_area_padding;
+    let mut kept: Vec<WorkSession> = Vec::new();
+    // Brief sessions since the last full one, waiting to join the next
+    let mut pending: Vec<WorkSession> = Vec::new();
+    for session in sessions {
+        if !session.is_brief(settings) {
+            let mut session = session;
+            for brief in pending.drain(..) {
+                if session.start_time - brief.end_time <= settings.session_merge_interval {
+                    session.absorb(brief, padding);
+                }
+            }
+            kept.push(session);
+            continue;
+        }
+        match kept.last_mut() {
+            Some(previous) if pending.is_empty()
+                && session.start_time - previous.end_time <= settings.session_merge_interval =>
+            {
+                previous.absorb(session, padding);
+            }
+            _ => pending.push(session),
+        }
+    }
+    kept
+}
+
+/// Larger side of a `w` x `h` normalized area as a fraction of the output's
+/// view of the source at 1x: `view` shrinks an axis the output crops
+fn framed_area_size(w: f64, h: f64, view: (f64, f64)) -> f64 {
+    let extent = |e: f64| if e > 0.0 { e.min(1.0) } else { 1.0 };
+    (w.abs() / extent(view.0)).max(h.abs() / extent(view.1))
+}
+
 /// Calculate zoom level for a session based on its work area.
-/// Matches Screenize: `zoom = targetAreaCoverage / areaSize`, clamped to [min, max].
+/// Matches Screenize: `zoom = targetAreaCoverage / areaSize`, clamped to [min, max],
+/// with the area measured against `view_extent` on each axis.
 pub fn calculate_session_zoom(session: &mut WorkSession, settings: &SmartZoomSettings) {
     let (_, _, w, h) = session.work_area;
-    let area_size = w.max(h);
+    let area_size = framed_area_size(w, h, settings.view_extent);
 
     if area_size <= 0.01 {
         session.zoom = settings.default_zoom;
@@ -352,21 +616,119 @@ pub fn calculate_session_zoom(session: &mut WorkSession, settings: &SmartZoomSet
     session.zoom = zoom.clamp(settings.min_zoom, settings.max_zoom);
 }
 
+/// How far focus follow moves a session centre toward the window centre
+const FOCUS_FOLLOW_WEIGHT: f64 = 0.5;
+/// Windows covering more of the screen than this are treated as maximized,
+/// whose centre says nothing about where to look
+const MAXIMIZED_WINDOW_AREA: f64 = 0.9;
+
+/// Window active halfway through the session, if it's worth following
+fn session_window(session: &WorkSession, windows: &[WindowEvent]) -> Option<(f64, f64, f64, f64)> {
+    let mid = (session.start_time + session.end_time) / 2.0;
+    let window = windows.iter().take_while(|w| w.time <= mid).last()?;
+    let (_, _, w, h) = window.bounds;
+    (w * h <= MAXIMIZED_WINDOW_AREA).then_some(window.bounds)
+}
+
+/// Blend the session centre toward the window centre, shift it by `lead`
+/// (see `lead_offset`), then clamp it so the work area stays inside the
+/// zoomed viewport (where it fits). `view` is `SmartZoomSettings::view_extent`.
+pub fn focus_session_on_window(
+    session: &mut WorkSession,
+    window: (f64, f64, f64, f64),
+    lead: (f64, f64),
+    view: (f64, f64),
+) {
+    let (wx, wy, ww, wh) = window;
+    let target = NormalizedPoint::new(wx + ww / 2.0, wy + wh / 2.0);
+    let blended = session.center.interpolated(&target, FOCUS_FOLLOW_WEIGHT);
+    let led = NormalizedPoint::new(blended.x + lead.0, blended.y + lead.1);
+
+    let zoom = session.zoom.max(1.0);
+    let (ax, ay, aw, ah) = session.work_area;
+    let keep_in_view = |value: f64, fallback: f64, min: f64, size: f64, extent: f64| {
+        let half = 0.5 * extent / zoom;
+        let (lo, hi) = (min + size - half, min + half);
+        if lo <= hi { value.clamp(lo, hi) } else { fallback }
+    };
+    session.center = NormalizedPoint::new(
+        keep_in_view(led.x, session.center.x, ax, aw, view.0),
+        keep_in_view(led.y, session.center.y, ay, ah, view.1),
+    );
+}
+
+/// Span after a session starts over which the cursor's motion is measured for the lead (seconds)
+const LEAD_SAMPLE_WINDOW: f64 = 0.5;
+/// Largest lead, as a fraction of the zoomed viewport's half-size
+const MAX_LEAD_FRACTION: f64 = 0.5;
+
+/// Cursor velocity (normalized units per second) over the first
+/// `LEAD_SAMPLE_WINDOW` of the session
+fn session_cursor_velocity(session: &WorkSession, positions: &[(f64, NormalizedPoint)]) -> (f64, f64) {
+    let window_end = session.start_time + LEAD_SAMPLE_WINDOW;
+    let mut samples = positions.iter().filter(|(t, _)| *t >= session.start_time && *t <= window_end);
+    let (Some(&(t0, p0)), Some(&(t1, p1))) = (samples.next(), samples.next_back()) else {
+        return (0.0, 0.0);
+    };
+    if t1 - t0 < 1e-3 {
+        return (0.0, 0.0);
+    }
+    ((p1.x - p0.x) / (t1 - t0), (p1.y - p0.y) / (t1 - t0))
+}
+
+/// How far `lead_factor` seconds of `velocity` moves a centre at `zoom`:
+/// at most `MAX_LEAD_FRACTION` of the viewport
+fn lead_offset(velocity: (f64, f64), lead_factor: f64, zoom: f64) -> (f64, f64) {
+    let max_lead = MAX_LEAD_FRACTION * 0.5 / zoom.max(1.0);
+    let (dx, dy) = (velocity.0 * lead_factor, velocity.1 * lead_factor);
+    let length = (dx * dx + dy * dy).sqrt();
+    let scale = if length > max_lead { max_lead / length } else { 1.0 };
+    (dx * scale, dy * scale)
+}
+
+/// Shift the session centre ahead of the cursor by `lead_factor` seconds of
+/// its motion (see `lead_offset`), keeping the crop (`view` of the source
+/// at 1x) inside the frame
+pub fn lead_session_center(session: &mut WorkSession, velocity: (f64, f64), lead_factor: f64, view: (f64, f64)) {
+    let zoom = session.zoom.max(1.0);
+    let (dx, dy) = lead_offset(velocity, lead_factor, zoom);
+    let led = NormalizedPoint::new(session.center.x + dx, session.center.y + dy);
+    session.center = clamp_center(led, zoom, view);
+}
+
 // ============================================================================
 // SmartZoom Generator (orchestrator)
 // ============================================================================
 
-/// Generate smart zoom transform keyframes from mouse data
+/// Generate smart zoom transform keyframes from mouse data, plus any
+/// `content` activities found by analysing the video
 pub fn generate_smart_zoom(
     data: &MouseData,
+    content: &[ActivityEvent],
     settings: &SmartZoomSettings,
 ) -> TransformTrack {
-    let activities = collect_activities(data);
-    let mut sessions = cluster_activities(&activities, settings);
+    let mut activities = collect_activities(data, settings.typing_session_timeout);
+    if !content.is_empty() {
+        activities.extend_from_slice(content);
+        activities.sort_by(|a, b| a.time.total_cmp(&b.time));
+    }
+    let mut sessions = absorb_brief_sessions(cluster_activities(&activities, settings), settings);
 
     // Calculate zoom for each session
     for session in &mut sessions {
         calculate_session_zoom(session, settings);
+        let velocity = if settings.lead_factor > 0.0 {
+            session_cursor_velocity(session, &data.positions)
+        } else {
+            (0.0, 0.0)
+        };
+        let window = if settings.focus_follow { session_window(session, &data.windows) } else { None };
+        if let Some(window) = window {
+            let lead = lead_offset(velocity, settings.lead_factor, session.zoom);
+            focus_session_on_window(session, window, lead, settings.view_extent);
+        } else if settings.lead_factor > 0.0 {
+            lead_session_center(session, velocity, settings.lead_factor, settings.view_extent);
+        }
     }
 
     // Generate keyframes
@@ -374,7 +736,7 @@ pub fn generate_smart_zoom(
 
     let mut track = TransformTrack::new();
     for kf in keyframes {
-        track.add_keyframe(kf);
+        track.add_keyframe(TransformKeyframe { generated: true, ..kf });
     }
     track
 }
@@ -387,11 +749,19 @@ pub fn generate_smart_zoom(
 /// 4. Between distant sessions: zoom out to 1.0x, then zoom in to next
 /// 5. Between nearby sessions: pan directly without zooming out
 /// 6. Final session: zoom out to 1.0x before video ends
+///
+/// Sessions starting too close to the end to zoom back out get no zoom.
+///
+/// Each keyframe is pushed with the easing of the phase arriving at it and
+/// shifted to the one before at the end (`ease_toward_next`), so a phase
+/// keeps its easing even when a hold in front of it is skipped.
 fn generate_zoom_keyframes(
     sessions: &[WorkSession],
     total_duration: f64,
     settings: &SmartZoomSettings,
 ) -> Vec<TransformKeyframe> {
+    let last_zoom_start = total_duration - END_ZOOM_OUT_MARGIN - MIN_FINAL_ZOOM_HOLD;
+    let sessions: Vec<&WorkSession> = sessions.iter().filter(|s| s.start_time <= last_zoom_start).collect();
     if sessions.is_empty() {
         return Vec::new();
     }
@@ -403,7 +773,7 @@ fn generate_zoom_keyframes(
         0.0,
         settings.min_zoom,
         NormalizedPoint::CENTER,
-        EasingCurve::Linear,
+        settings.pre_hold_easing.clone(),
     ));
 
     let mut last_session_end: f64 = 0.0;
@@ -425,7 +795,7 @@ fn generate_zoom_keyframes(
                     zoom_in_start,
                     settings.min_zoom,
                     NormalizedPoint::CENTER,
-                    settings.zoom_in_easing.clone(),
+                    settings.pre_hold_easing.clone(),
                 ));
             }
 
@@ -434,7 +804,7 @@ fn generate_zoom_keyframes(
                 session.start_time,
                 session.zoom,
                 session.center,
-                settings.zoom_in_easing.clone(),
+                settings.zoom_in_curve(),
             ));
         }
 
@@ -447,21 +817,25 @@ fn generate_zoom_keyframes(
                 time_between < settings.idle_timeout + settings.transition_duration;
 
             if should_transition_directly {
-                // Direct pan transition: hold current, then slide to next session
-                let move_start = session.end_time + (time_between * 0.3).min(1.0);
+                // Direct pan transition: hold current, then slide to next session.
+                // The pan leads the cursor like the centres do, starting up to
+                // `lead_factor` earlier (but not before the session ends).
+                let settle = (time_between * 0.3).min(1.0);
+                let lead = settings.lead_factor.clamp(0.0, settle.max(0.0));
+                let move_start = session.end_time + settle - lead;
                 keyframes.push(TransformKeyframe::new(
                     move_start,
                     session.zoom,
                     session.center,
-                    settings.move_easing.clone(),
+                    settings.hold_easing.clone(),
                 ));
 
-                let move_end = next_session.start_time - 0.05;
+                let move_end = next_session.start_time - 0.05 - lead;
                 keyframes.push(TransformKeyframe::new(
                     move_end,
                     next_session.zoom,
                     next_session.center,
-                    settings.move_easing.clone(),
+                    settings.move_curve(),
                 ));
             } else {
                 // Zoom out to 1.0x, then next iteration will zoom in again.
@@ -477,7 +851,7 @@ fn generate_zoom_keyframes(
                         zoom_out_start,
                         session.zoom,
                         session.center,
-                        settings.zoom_out_easing.clone(),
+                        settings.hold_easing.clone(),
                     ));
                 }
 
@@ -495,7 +869,7 @@ fn generate_zoom_keyframes(
             // Don't constrain by hold_end — it can exceed total_duration
             // for short recordings, causing the hold keyframe to sort AFTER
             // the zoom-out and leave the video stuck zoomed in.
-            let zoom_out_end = (total_duration - 0.1).max(0.0);
+            let zoom_out_end = (total_duration - END_ZOOM_OUT_MARGIN).max(0.0);
             let zoom_out_start = (zoom_out_end - settings.transition_duration)
                 .max(session.end_time + 0.05);
 
@@ -507,7 +881,7 @@ fn generate_zoom_keyframes(
                     zoom_out_start,
                     session.zoom,
                     session.center,
-                    settings.zoom_out_easing.clone(),
+                    settings.hold_easing.clone(),
                 ));
             }
 
@@ -527,18 +901,36 @@ fn generate_zoom_keyframes(
     for kf in &mut keyframes {
         kf.time = kf.time.clamp(0.0, total_duration);
         if kf.zoom > 1.0 {
-            let half = 0.5 / kf.zoom;
-            kf.center = NormalizedPoint::new(
-                kf.center.x.clamp(half, 1.0 - half),
-                kf.center.y.clamp(half, 1.0 - half),
-            );
+            kf.center = clamp_center(kf.center, kf.zoom, settings.view_extent);
         }
     }
 
     optimize_keyframes(&mut keyframes);
+    ease_toward_next(&mut keyframes, &settings.final_easing);
     keyframes
 }
 
+/// A keyframe's easing runs to the next keyframe: replace each arrival
+/// easing with the next keyframe's, the last getting `final_easing`
+fn ease_toward_next(keyframes: &mut [TransformKeyframe], final_easing: &EasingCurve) {
+    let outgoing: Vec<EasingCurve> = keyframes
+        .iter()
+        .skip(1)
+        .map(|kf| kf.easing.clone())
+        .chain(std::iter::once(final_easing.clone()))
+        .collect();
+    for (kf, easing) in keyframes.iter_mut().zip(outgoing) {
+        kf.easing = easing;
+    }
+}
+
+/// The final zoom-out ends this long before the video does (seconds)
+const END_ZOOM_OUT_MARGIN: f64 = 0.1;
+
+/// Shortest time zoomed in before that final zoom-out (seconds); sessions
+/// starting later don't zoom
+const MIN_FINAL_ZOOM_HOLD: f64 = 0.5;
+
 /// Remove duplicate keyframes that are too close in time
 fn optimize_keyframes(keyframes: &mut Vec<TransformKeyframe>) {
     keyframes.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap());
@@ -546,18 +938,117 @@ fn optimize_keyframes(keyframes: &mut Vec<TransformKeyframe>) {
 }
 
 // ============================================================================
-// Ripple Generator
+// Manual Zoom Region
 // ============================================================================
 
-/// Settings for ripple generation
+/// User-drawn zoom region: a normalized rect (top-left origin) held for a time range
 #[derive(Debug, Clone)]
+pub struct ZoomRegion {
+    pub start_time: f64,
+    pub end_time: f64,
+    /// (x, y, width, height), normalized
+    pub rect: (f64, f64, f64, f64),
+    pub easing: EasingCurve,
+}
+
+/// Zoom and center framing a normalized rect.
+/// Same coverage math as `calculate_session_zoom`.
+pub fn zoom_for_rect(rect: (f64, f64, f64, f64), settings: &SmartZoomSettings) -> (f64, NormalizedPoint) {
+    let (x, y, w, h) = rect;
+    let center = NormalizedPoint::new(x + w / 2.0, y + h / 2.0).clamped();
+    let area_size = framed_area_size(w, h, settings.view_extent);
+
+    let zoom = if area_size <= 0.01 {
+        settings.default_zoom
+    } else {
+        (settings.target_area_coverage / area_size).clamp(settings.min_zoom, settings.max_zoom)
+    };
+
+    // Keep the crop inside the frame
+    (zoom, clamp_center(center, zoom.max(1.0), settings.view_extent))
+}
+
+/// Insert zoom-in / hold / zoom-out keyframes for a region into the track.
+/// Keyframes inside the region's transition window are replaced; the camera
+/// starts from and returns to whatever the track was doing at the window edges.
+pub fn insert_zoom_region(
+    track: &mut TransformTrack,
+    region: &ZoomRegion,
+    settings: &SmartZoomSettings,
+    duration: f64,
+) {
+    let start = region.start_time.clamp(0.0, duration);
+    let end = region.end_time.clamp(start, duration);
+    let zoom_in_start = (start - settings.focusing_duration).max(0.0);
+    let zoom_out_end = (end + settings.transition_duration).min(duration);
+
+    let evaluator = FrameEvaluator::new(false);
+    let before = evaluator.evaluate_transform(Some(track), zoom_in_start);
+    let after = evaluator.evaluate_transform(Some(track), zoom_out_end);
+    // Continue the existing motion after the region with its own easing
+    let after_easing = track
+        .keyframes
+        .iter()
+        .rev()
+        .find(|kf| kf.time <= zoom_out_end)
+        .map_or_else(|| region.easing.clone(), |kf| kf.easing.clone());
+
+    track
+        .keyframes
+        .retain(|kf| kf.time < zoom_in_start - 0.01 || kf.time > zoom_out_end + 0.01);
+
+    let (zoom, center) = zoom_for_rect(region.rect, settings);
+
+    if zoom_in_start < start - 0.01 {
+        track.add_keyframe(TransformKeyframe::new(zoom_in_start, before.zoom, before.center, region.easing.clone()));
+    }
+    track.add_keyframe(TransformKeyframe::new(start, zoom, center, EasingCurve::Linear));
+    if end > start + 0.01 {
+        track.add_keyframe(TransformKeyframe::new(end, zoom, center, region.easing.clone()));
+    }
+    if zoom_out_end > end + 0.01 {
+        track.add_keyframe(TransformKeyframe::new(zoom_out_end, after.zoom, after.center, after_easing));
+    }
+}
+
+// ============================================================================
+// Ripple Generator
+// ============================================================================
+
+/// Settings for ripple generation, stored on the project
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct RippleSettings {
     pub intensity: f64,
     pub duration: f64,
     pub color: RippleColor,
     pub min_interval: f64,
+    pub style: RippleStyle,
+    /// Most ripples visible at once (0 = unlimited). Older ones are cut short.
+    pub max_concurrent: usize,
+    /// Fully grown size relative to the reference radius
+    pub max_radius_scale: f64,
+    /// Growth curve of the radius over the animation
+    pub easing: EasingCurve,
+    /// Normalized areas (e.g. a toolbar) whose clicks get no ripple
+    pub exclude_regions: Vec<Rect>,
+    /// Normalized area clicks must fall in to get a ripple (None: anywhere)
+    pub include_region: Option<Rect>,
+}
+
+impl RippleSettings {
+    /// Whether a click at `position` is in a region that gets ripples
+    fn allows(&self, position: NormalizedPoint) -> bool {
+        let inside = |r: &Rect| {
+            position.x >= r.x && position.x <= r.x + r.width && position.y >= r.y && position.y <= r.y + r.height
+        };
+        self.include_region.as_ref().is_none_or(inside) && !self.exclude_regions.iter().any(inside)
+    }
 }
 
+/// Time an over-limit ripple gets to finish once a newer one pushes it out (seconds)
+const RIPPLE_CUTOFF_FADE: f64 = 0.1;
+
 impl Default for RippleSettings {
     fn default() -> Self {
         Self {
@@ -565,6 +1056,12 @@ impl Default for RippleSettings {
             duration: 0.4,
             color: RippleColor::LeftClick,
             min_interval: 0.1,
+            style: RippleStyle::Ring,
+            max_concurrent: 3,
+            max_radius_scale: 1.0,
+            easing: EasingCurve::spring_bouncy(),
+            exclude_regions: Vec::new(),
+            include_region: None,
         }
     }
 }
@@ -579,7 +1076,7 @@ pub fn generate_ripples(clicks: &[ClickEvent], settings: &RippleSettings) -> Rip
             continue;
         }
 
-        if click.time - last_time < settings.min_interval {
+        if click.time - last_time < settings.min_interval || !settings.allows(click.position) {
             continue;
         }
 
@@ -590,16 +1087,52 @@ pub fn generate_ripples(clicks: &[ClickEvent], settings: &RippleSettings) -> Rip
             intensity: settings.intensity,
             duration: settings.duration,
             color: settings.color.clone(),
-            easing: EasingCurve::spring_bouncy(),
+            easing: settings.easing.clone(),
+            style: settings.style,
+            max_radius_scale: settings.max_radius_scale,
+            generated: true,
         };
 
         track.add_keyframe(kf);
         last_time = click.time;
     }
 
+    limit_concurrent(&mut track.keyframes, settings.max_concurrent, RIPPLE_CUTOFF_FADE, |k| {
+        (k.time, &mut k.duration)
+    });
     track
 }
 
+/// Cap how many time-sorted overlays run at once: when a new one starts while
+/// `max` are still active, the oldest are shortened to end `tail` seconds later
+fn limit_concurrent<K>(
+    keyframes: &mut [K],
+    max: usize,
+    tail: f64,
+    timing: impl Fn(&mut K) -> (f64, &mut f64),
+) {
+    if max == 0 {
+        return;
+    }
+    for i in 0..keyframes.len() {
+        let (start, _) = timing(&mut keyframes[i]);
+        let mut active: Vec<usize> = (0..i)
+            .filter(|&j| {
+                let (time, duration) = timing(&mut keyframes[j]);
+                time + *duration > start
+            })
+            .collect();
+        if active.len() < max {
+            continue;
+        }
+        active.truncate(active.len() + 1 - max);
+        for j in active {
+            let (time, duration) = timing(&mut keyframes[j]);
+            *duration = duration.min(start - time + tail);
+        }
+    }
+}
+
 // ============================================================================
 // Keystroke Generator
 // ============================================================================
@@ -612,6 +1145,18 @@ pub struct KeystrokeSettings {
     pub fade_in_duration: f64,
     pub fade_out_duration: f64,
     pub min_interval: f64,
+    /// Group consecutive plain keystrokes into one typed-text overlay
+    /// (only applies when `shortcuts_only` is off)
+    pub group_typing: bool,
+    /// Pause between keystrokes that starts a new typed-text overlay (seconds)
+    pub typing_session_timeout: f64,
+    /// Overlay center for generated keyframes
+    pub position: NormalizedPoint,
+    /// Most overlays visible at once (0 = unlimited). Older ones fade out early.
+    pub max_concurrent: usize,
+    /// Chord → label for discrete badges (e.g. "Ctrl+V" → "Paste").
+    /// Chords match in any case and modifier order.
+    pub shortcut_labels: BTreeMap<String, String>,
 }
 
 impl Default for KeystrokeSettings {
@@ -622,8 +1167,39 @@ impl Default for KeystrokeSettings {
             fade_in_duration: 0.15,
             fade_out_duration: 0.3,
             min_interval: 0.2,
+            group_typing: true,
+            typing_session_timeout: TYPING_SESSION_TIMEOUT,
+            position: NormalizedPoint::new(0.5, 0.95),
+            max_concurrent: 2,
+            shortcut_labels: BTreeMap::new(),
+        }
+    }
+}
+
+/// Friendly labels for common shortcuts, the default for new projects
+pub fn default_shortcut_labels() -> BTreeMap<String, String> {
+    [("C", "Copy"), ("V", "Paste"), ("X", "Cut"), ("Z", "Undo"), ("S", "Save")]
+        .into_iter()
+        .flat_map(|(key, label)| ["Ctrl", "Cmd"].map(|m| (format!("{m}+{key}"), label.to_string())))
+        .collect()
+}
+
+/// Canonical form of a chord such as "shift+ctrl+p": modifiers in overlay
+/// order (as `modifier_symbols`) then the uppercased key ("Ctrl+Shift+P").
+/// None when there is no key.
+pub fn normalize_chord(chord: &str) -> Option<String> {
+    let mut modifiers = Modifiers::default();
+    let mut key = None;
+    for part in chord.split('+').map(str::trim).filter(|p| !p.is_empty()) {
+        match part.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => modifiers.control = true,
+            "alt" | "option" | "opt" => modifiers.alt = true,
+            "shift" => modifiers.shift = true,
+            "cmd" | "command" | "meta" | "win" | "super" => modifiers.command = true,
+            _ => key = Some(part.to_uppercase()),
         }
     }
+    key.map(|key| format!("{}{key}", modifier_symbols(&modifiers)))
 }
 
 /// Generate keystroke overlay keyframes from keyboard events
@@ -639,7 +1215,36 @@ pub fn generate_keystrokes(
         .filter(|e| e.event_type == KeyEventType::KeyDown)
         .collect();
 
+    let group_typing = settings.group_typing && !settings.shortcuts_only;
+    let mut run: Option<TypedRun> = None;
+    let labels: HashMap<String, &str> = settings
+        .shortcut_labels
+        .iter()
+        .filter(|(_, label)| !label.trim().is_empty())
+        .filter_map(|(chord, label)| Some((normalize_chord(chord)?, label.trim())))
+        .collect();
+
     for event in key_downs {
+        // Typing grouping: accumulate plain keystrokes into a rolling string
+        if group_typing && is_typing_key(event) {
+            if let Some(edit) = typed_edit(event.key_code, event.character.as_deref()) {
+                if run.as_ref().is_some_and(|r| event.time - r.last_time > settings.typing_session_timeout) {
+                    flush_typed_run(run.take(), settings, &mut track);
+                }
+                let current = run.get_or_insert_with(|| TypedRun {
+                    start_time: event.time,
+                    last_time: event.time,
+                    text: String::new(),
+                });
+                match edit {
+                    TypedEdit::Insert(c) => current.text.push_str(&c),
+                    TypedEdit::Backspace => { current.text.pop(); }
+                }
+                current.last_time = event.time;
+                continue;
+            }
+        }
+
         // Skip standalone modifiers
         let key_name = match key_display_name(event.key_code, event.character.as_deref()) {
             Some(name) => name,
@@ -656,8 +1261,14 @@ pub fn generate_keystrokes(
             continue;
         }
 
+        // A discrete badge ends any typed string in progress
+        flush_typed_run(run.take(), settings, &mut track);
+
         let mod_symbols = modifier_symbols(&event.modifiers);
-        let display_text = format!("{}{}", mod_symbols, key_name);
+        let chord = format!("{}{}", mod_symbols, key_name);
+        let display_text = normalize_chord(&chord)
+            .and_then(|c| labels.get(&c))
+            .map_or(chord, |label| label.to_string());
 
         let kf = KeystrokeKeyframe {
             id: Uuid::new_v4(),
@@ -666,18 +1277,72 @@ pub fn generate_keystrokes(
             duration: settings.display_duration,
             fade_in_duration: settings.fade_in_duration,
             fade_out_duration: settings.fade_out_duration,
-            position: NormalizedPoint::new(0.5, 0.95),
+            position: settings.position,
             easing: EasingCurve::EaseOut,
+            generated: true,
         };
 
         track.add_keyframe(kf);
         last_time = event.time;
     }
+    flush_typed_run(run, settings, &mut track);
 
+    limit_concurrent(&mut track.keyframes, settings.max_concurrent, settings.fade_out_duration, |k| {
+        (k.time, &mut k.duration)
+    });
     track
 }
 
-fn modifier_symbols(mods: &Modifiers) -> String {
+/// Typed string being accumulated by the grouping pass
+struct TypedRun {
+    start_time: f64,
+    last_time: f64,
+    text: String,
+}
+
+pub(crate) enum TypedEdit {
+    Insert(String),
+    Backspace,
+}
+
+/// Map a plain key-down to its effect on a typed string.
+/// Returns None for named keys (Enter, Tab, arrows...) which stay discrete.
+pub(crate) fn typed_edit(key_code: u16, character: Option<&str>) -> Option<TypedEdit> {
+    // Printable characters first: key codes collide across platforms
+    // (e.g. Windows '3' = 51 = macOS Backspace)
+    if let Some(c) = character.filter(|c| !c.is_empty() && !c.chars().any(char::is_control)) {
+        return Some(TypedEdit::Insert(c.to_string()));
+    }
+    match key_code {
+        0x20 => Some(TypedEdit::Insert(" ".to_string())),
+        0x08 | 51 => Some(TypedEdit::Backspace),
+        _ => None,
+    }
+}
+
+/// Emit a typed run as a single overlay lasting from the first keystroke
+/// until `display_duration` after the last one
+fn flush_typed_run(run: Option<TypedRun>, settings: &KeystrokeSettings, track: &mut KeystrokeTrack) {
+    let Some(run) = run else { return };
+    let display_text = run.text.trim().to_string();
+    if display_text.is_empty() {
+        return;
+    }
+
+    track.add_keyframe(KeystrokeKeyframe {
+        id: Uuid::new_v4(),
+        time: run.start_time,
+        display_text,
+        duration: (run.last_time - run.start_time) + settings.display_duration,
+        fade_in_duration: settings.fade_in_duration,
+        fade_out_duration: settings.fade_out_duration,
+        position: settings.position,
+        easing: EasingCurve::EaseOut,
+        generated: true,
+    });
+}
+
+pub(crate) fn modifier_symbols(mods: &Modifiers) -> String {
     let mut s = String::new();
     if mods.control { s.push_str("Ctrl+"); }
     if mods.alt { s.push_str("Alt+"); }
@@ -686,7 +1351,7 @@ fn modifier_symbols(mods: &Modifiers) -> String {
     s
 }
 
-fn key_display_name(key_code: u16, character: Option<&str>) -> Option<String> {
+pub(crate) fn key_display_name(key_code: u16, character: Option<&str>) -> Option<String> {
     // Common named keys (cross-platform key codes vary;
     // these match common virtual key codes)
     let name = match key_code {
@@ -736,10 +1401,62 @@ fn key_display_name(key_code: u16, character: Option<&str>) -> Option<String> {
 // Cursor Generator (simplified — creates keyframes at stops/direction changes)
 // ============================================================================
 
-/// Generate cursor style keyframes from mouse positions
+/// Cursor generator settings
+#[derive(Debug, Clone)]
+pub struct CursorSettings {
+    /// Hide the cursor during typing sessions
+    pub hide_while_typing: bool,
+    /// Shortest typing session that hides the cursor (seconds), so brief
+    /// bursts don't flicker it off and on
+    pub min_hide_duration: f64,
+    /// How long the cursor stays hidden after the last keystroke (seconds)
+    pub restore_delay: f64,
+    /// Pause between keystrokes that ends a typing session (seconds)
+    pub typing_session_timeout: f64,
+}
+
+impl Default for CursorSettings {
+    fn default() -> Self {
+        Self {
+            hide_while_typing: false,
+            min_hide_duration: 1.0,
+            restore_delay: 0.5,
+            typing_session_timeout: TYPING_SESSION_TIMEOUT,
+        }
+    }
+}
+
+/// Spans to hide the cursor in: typing sessions long enough to count,
+/// ended early by a click (the user reached for the mouse)
+fn typing_hide_spans(
+    keyboard_events: &[KeyboardEvent],
+    clicks: &[ClickEvent],
+    settings: &CursorSettings,
+) -> Vec<(f64, f64)> {
+    detect_typing_sessions(keyboard_events, settings.typing_session_timeout)
+        .into_iter()
+        .filter_map(|session| {
+            let mut end = session.end + settings.restore_delay;
+            if let Some(click) = clicks
+                .iter()
+                .filter(|c| c.time > session.start && c.time < end)
+                .map(|c| c.time)
+                .min_by(f64::total_cmp)
+            {
+                end = click;
+            }
+            (end - session.start >= settings.min_hide_duration).then_some((session.start, end))
+        })
+        .collect()
+}
+
+/// Generate cursor style keyframes from mouse positions (and keyboard events
+/// when hiding the cursor while typing)
 pub fn generate_cursor_keyframes(
     positions: &[(f64, NormalizedPoint)],
     clicks: &[ClickEvent],
+    keyboard_events: &[KeyboardEvent],
+    settings: &CursorSettings,
 ) -> CursorTrack {
     let mut track = CursorTrack::new();
     let mut keyframes: Vec<CursorStyleKeyframe> = Vec::new();
@@ -787,6 +1504,37 @@ pub fn generate_cursor_keyframes(
     // Deduplicate very close keyframes
     keyframes.dedup_by(|b, a| (b.time - a.time).abs() < 0.05);
 
+    if settings.hide_while_typing {
+        let spans = typing_hide_spans(keyboard_events, clicks, settings);
+        // Style keyframes hold until the next one, so anything inside a span must hide too
+        for kf in &mut keyframes {
+            if spans.iter().any(|&(start, end)| kf.time >= start && kf.time < end) {
+                kf.visible = false;
+            }
+        }
+        // Hiding and restoring keep the style and scale in effect at that moment,
+        // so a span ending mid-press doesn't snap the cursor back to the default
+        let styled_at = |keyframes: &[CursorStyleKeyframe], time: f64| {
+            let mut kf = CursorStyleKeyframe::new(time);
+            if let Some(prev) = keyframes.iter().rev().find(|k| k.time < time) {
+                kf.style = prev.style;
+                kf.scale = prev.scale;
+            }
+            kf
+        };
+        let markers: Vec<CursorStyleKeyframe> = spans
+            .iter()
+            .flat_map(|&(start, end)| {
+                let mut hide = styled_at(&keyframes, start);
+                hide.visible = false;
+                [hide, styled_at(&keyframes, end)]
+            })
+            .collect();
+        keyframes.extend(markers);
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+    }
+    keyframes.iter_mut().for_each(|kf| kf.generated = true);
+
     track.style_keyframes = if keyframes.is_empty() { None } else { Some(keyframes) };
     track
 }
@@ -814,13 +1562,92 @@ mod tests {
                 modifiers: Modifiers::default(),
             },
         ];
-        let sessions = detect_typing_sessions(&events);
+        let sessions = detect_typing_sessions(&events, TYPING_SESSION_TIMEOUT);
         assert_eq!(sessions.len(), 2);
         assert!((sessions[0].start - 1.0).abs() < 1e-10);
         assert!((sessions[0].end - 1.2).abs() < 1e-10);
         assert!((sessions[1].start - 5.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_typing_session_timeout_merges_sessions() {
+        // A slow typist: 2s pauses between keystrokes
+        let events: Vec<KeyboardEvent> = [1.0, 3.0, 5.0]
+            .iter()
+            .map(|&time| KeyboardEvent {
+                time, event_type: KeyEventType::KeyDown,
+                key_code: 0, character: Some("a".into()),
+                modifiers: Modifiers::default(),
+            })
+            .collect();
+        assert_eq!(detect_typing_sessions(&events, TYPING_SESSION_TIMEOUT).len(), 3);
+
+        let sessions = detect_typing_sessions(&events, 2.5);
+        assert_eq!(sessions.len(), 1);
+        assert!((sessions[0].start - 1.0).abs() < 1e-10);
+        assert!((sessions[0].end - 5.0).abs() < 1e-10);
+
+        let data = MouseData {
+            positions: vec![(0.0, NormalizedPoint::CENTER)],
+            clicks: Vec::new(),
+            keyboard_events: events,
+            drags: Vec::new(),
+            windows: Vec::new(),
+            duration: 10.0,
+        };
+        let typing = |timeout| {
+            collect_activities(&data, timeout)
+                .iter()
+                .filter(|a| a.activity_type == ActivityType::Typing)
+                .count()
+        };
+        // Three lone keystrokes vs one session with a start and an end
+        assert_eq!(typing(TYPING_SESSION_TIMEOUT), 3);
+        assert_eq!(typing(2.5), 2);
+    }
+
+    #[test]
+    fn test_smart_zoom_presets() {
+        assert_eq!(SmartZoomPreset::Balanced.settings(), SmartZoomSettings::default());
+
+        let subtle = SmartZoomPreset::Subtle.settings();
+        let dramatic = SmartZoomPreset::Dramatic.settings();
+        assert!(subtle.max_zoom < 2.5 && dramatic.max_zoom > 2.5);
+        assert!(subtle.default_zoom > 1.0 && subtle.default_zoom < dramatic.default_zoom);
+        assert!(subtle.target_area_coverage > dramatic.target_area_coverage);
+        assert!(subtle.transition_duration > dramatic.transition_duration);
+        // Untouched fields carry over
+        assert_eq!(dramatic.min_zoom, 1.0);
+        assert_eq!(dramatic.session_merge_interval, 3.0);
+    }
+
+    #[test]
+    fn test_camera_spring_overrides_easings() {
+        let spring = CameraSpring { damping_ratio: 1.0, response: 0.3 };
+        let config = SmartZoomConfig::Preset { preset: SmartZoomPreset::Dramatic }.with_spring(Some(spring));
+        let SmartZoomConfig::Custom { settings } = &config else { panic!("expected custom: {config:?}") };
+        assert_eq!(settings.zoom_in_curve(), EasingCurve::spring(1.0, 0.3));
+        assert_eq!(settings.move_curve(), EasingCurve::spring(1.0, 0.3 * MOVE_SPRING_SLOWDOWN));
+        assert_eq!(settings.max_zoom, SmartZoomPreset::Dramatic.settings().max_zoom);
+
+        // Generated zoom-ins use the spring
+        let mouse_data = MouseData {
+            positions: vec![(0.0, NormalizedPoint::CENTER)],
+            clicks: vec![ClickEvent {
+                time: 3.0, position: NormalizedPoint::new(0.3, 0.3), click_type: ClickType::LeftDown, duration: 0.1,
+            }],
+            keyboard_events: Vec::new(),
+            drags: Vec::new(),
+            windows: Vec::new(),
+            duration: 20.0,
+        };
+        let track = generate_smart_zoom(&mouse_data, &[], settings);
+        assert!(track.keyframes.iter().any(|kf| kf.easing == EasingCurve::spring(1.0, 0.3)));
+
+        // Clearing the spring goes back to the preset
+        assert_eq!(config.with_spring(None), SmartZoomConfig::Preset { preset: SmartZoomPreset::Dramatic });
+    }
+
     #[test]
     fn test_cluster_activities() {
         let activities = vec![
@@ -835,6 +1662,44 @@ mod tests {
         assert_eq!(sessions[1].activities.len(), 1);
     }
 
+    #[test]
+    fn test_every_zoom_phase_uses_its_easing() {
+        let settings = SmartZoomSettings {
+            pre_hold_easing: EasingCurve::EaseIn,
+            zoom_in_easing: EasingCurve::EaseInOut,
+            hold_easing: EasingCurve::Linear,
+            zoom_out_easing: EasingCurve::EaseOut,
+            final_easing: EasingCurve::spring(1.0, 0.4),
+            ..Default::default()
+        };
+        let mouse_data = MouseData {
+            positions: vec![(0.0, NormalizedPoint::CENTER)],
+            clicks: vec![ClickEvent {
+                time: 3.0, position: NormalizedPoint::new(0.3, 0.3), click_type: ClickType::LeftDown, duration: 0.1,
+            }],
+            keyboard_events: Vec::new(),
+            drags: Vec::new(),
+            windows: Vec::new(),
+            duration: 20.0,
+        };
+        let track = generate_smart_zoom(&mouse_data, &[], &settings);
+        // Rest at 1x, zoom in, hold, zoom out, end: each keyframe eases the phase after it
+        let easings: Vec<&EasingCurve> = track.keyframes.iter().map(|kf| &kf.easing).collect();
+        assert_eq!(easings, [
+            &EasingCurve::EaseIn,
+            &EasingCurve::EaseInOut,
+            &EasingCurve::Linear,
+            &EasingCurve::EaseOut,
+            &EasingCurve::spring(1.0, 0.4),
+        ]);
+
+        // Without the hold the zoom-out still eases out
+        let short = MouseData { duration: 4.0, ..mouse_data };
+        let track = generate_smart_zoom(&short, &[], &settings);
+        let zoomed = track.keyframes.iter().position(|kf| kf.zoom > settings.min_zoom).unwrap();
+        assert_eq!(track.keyframes[zoomed].easing, EasingCurve::EaseOut);
+    }
+
     #[test]
     fn test_calculate_zoom() {
         let mut session = WorkSession {
@@ -851,6 +1716,169 @@ mod tests {
         assert!((session.zoom - 2.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_focus_follow_pulls_toward_window() {
+        let click = |time| ClickEvent {
+            time, position: NormalizedPoint::new(0.3, 0.3), click_type: ClickType::LeftDown, duration: 0.1,
+        };
+        let mut data = MouseData {
+            positions: vec![(0.0, NormalizedPoint::CENTER)],
+            clicks: vec![click(2.0), click(2.5)],
+            keyboard_events: Vec::new(),
+            drags: Vec::new(),
+            windows: vec![WindowEvent { time: 0.5, bounds: (0.2, 0.2, 0.6, 0.6) }],
+            duration: 10.0,
+        };
+        let zoomed = |data: &MouseData, focus_follow| {
+            let settings = SmartZoomSettings { focus_follow, ..Default::default() };
+            let track = generate_smart_zoom(data, &[], &settings);
+            let kf = track.keyframes.iter().find(|kf| kf.zoom > 1.0).unwrap();
+            (kf.center, kf.zoom)
+        };
+
+        let (plain, _) = zoomed(&data, false);
+        assert!((plain.x - 0.3).abs() < 1e-9);
+        // Toward the window centre (0.5), but the clicks stay in view
+        let (followed, zoom) = zoomed(&data, true);
+        assert!(followed.x > plain.x && followed.x < 0.5, "{followed:?}");
+        assert!(followed.x - 0.5 / zoom <= 0.3 - 0.02);
+
+        // A maximized window is ignored
+        data.windows[0].bounds = (0.0, 0.0, 1.0, 1.0);
+        assert_eq!(zoomed(&data, true).0, plain);
+    }
+
+    #[test]
+    fn test_lead_factor_leads_moving_cursor() {
+        let click = |time, x| ClickEvent {
+            time, position: NormalizedPoint::new(x, 0.5), click_type: ClickType::LeftDown, duration: 0.1,
+        };
+        // Moving right at 0.2/s through the session
+        let data = MouseData {
+            positions: (0..=30).map(|i| (i as f64 * 0.1, NormalizedPoint::new(0.3 + i as f64 * 0.02, 0.5))).collect(),
+            clicks: vec![click(2.0, 0.34), click(2.4, 0.42)],
+            keyboard_events: Vec::new(),
+            drags: Vec::new(),
+            windows: Vec::new(),
+            duration: 10.0,
+        };
+        let center = |lead_factor| {
+            let settings = SmartZoomSettings { lead_factor, ..Default::default() };
+            let track = generate_smart_zoom(&data, &[], &settings);
+            let kf = track.keyframes.iter().find(|kf| kf.zoom > 1.0).unwrap();
+            (kf.center, kf.zoom)
+        };
+
+        let (raw, _) = center(0.0);
+        let (led, zoom) = center(0.5);
+        assert!(led.x > raw.x, "{led:?} vs {raw:?}");
+        assert!((led.y - raw.y).abs() < 1e-9);
+        // Never more than half the viewport ahead, however fast
+        let (far, _) = center(100.0);
+        assert!(far.x - raw.x <= MAX_LEAD_FRACTION * 0.5 / zoom + 1e-9);
+    }
+
+    #[test]
+    fn test_lead_factor_applies_with_focus_follow() {
+        let click = |time, x| ClickEvent {
+            time, position: NormalizedPoint::new(x, 0.3), click_type: ClickType::LeftDown, duration: 0.1,
+        };
+        let data = MouseData {
+            positions: (0..=30).map(|i| (i as f64 * 0.1, NormalizedPoint::new(0.3 + i as f64 * 0.02, 0.3))).collect(),
+            clicks: vec![click(2.0, 0.34), click(2.4, 0.42)],
+            keyboard_events: Vec::new(),
+            drags: Vec::new(),
+            windows: vec![WindowEvent { time: 0.5, bounds: (0.2, 0.2, 0.6, 0.6) }],
+            duration: 10.0,
+        };
+        let center = |lead_factor| {
+            let settings = SmartZoomSettings { focus_follow: true, lead_factor, ..Default::default() };
+            let track = generate_smart_zoom(&data, &[], &settings);
+            let kf = track.keyframes.iter().find(|kf| kf.zoom > 1.0).unwrap();
+            (kf.center, kf.zoom)
+        };
+
+        let (followed, _) = center(0.0);
+        let (led, zoom) = center(0.5);
+        assert!(led.x > followed.x, "{led:?} vs {followed:?}");
+        // The clicks stay in view
+        assert!(led.x - 0.5 / zoom <= 0.34);
+    }
+
+    #[test]
+    fn test_lead_factor_pans_between_sessions_earlier() {
+        let click = |time, x| ClickEvent {
+            time, position: NormalizedPoint::new(x, 0.5), click_type: ClickType::LeftDown, duration: 0.1,
+        };
+        let data = MouseData {
+            positions: vec![(0.0, NormalizedPoint::CENTER)],
+            clicks: vec![click(2.0, 0.2), click(2.3, 0.22), click(5.0, 0.8), click(5.3, 0.82)],
+            keyboard_events: Vec::new(),
+            drags: Vec::new(),
+            windows: Vec::new(),
+            duration: 12.0,
+        };
+        // Arrival at the second session's centre
+        let arrival = |lead_factor| {
+            let settings = SmartZoomSettings { lead_factor, ..Default::default() };
+            let track = generate_smart_zoom(&data, &[], &settings);
+            track.keyframes.iter().find(|kf| kf.zoom > 1.0 && kf.center.x > 0.5).unwrap().time
+        };
+
+        let plain = arrival(0.0);
+        assert!((arrival(0.3) - (plain - 0.3)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_min_session_duration_skips_single_click() {
+        let click = |time, x| ClickEvent {
+            time, position: NormalizedPoint::new(x, 0.3), click_type: ClickType::LeftDown, duration: 0.1,
+        };
+        let data = |clicks| MouseData {
+            positions: vec![(0.0, NormalizedPoint::CENTER)],
+            clicks,
+            keyboard_events: Vec::new(),
+            drags: Vec::new(),
+            windows: Vec::new(),
+            duration: 20.0,
+        };
+        let brief = SmartZoomSettings { min_session_duration: 0.5, ..Default::default() };
+        let zooms = |data: &MouseData, settings: &SmartZoomSettings| {
+            generate_smart_zoom(data, &[], settings).keyframes.iter().filter(|kf| kf.zoom > 1.0).count()
+        };
+
+        // A lone click zooms by default, not with a minimum duration
+        let single = data(vec![click(5.0, 0.3)]);
+        assert!(zooms(&single, &SmartZoomSettings::default()) > 0);
+        assert!(generate_smart_zoom(&single, &[], &brief).keyframes.is_empty());
+        let few = SmartZoomSettings { min_activities_for_zoom: 2, ..Default::default() };
+        assert!(generate_smart_zoom(&single, &[], &few).keyframes.is_empty());
+
+        // A stray click near a real session joins it instead of its own punch-in
+        let near = data(vec![click(5.0, 0.3), click(5.6, 0.32), click(7.0, 0.9)]);
+        let sessions = cluster_activities(&collect_activities(&near, brief.typing_session_timeout), &brief);
+        assert_eq!(sessions.len(), 2);
+        let merged = absorb_brief_sessions(sessions, &brief);
+        assert_eq!(merged.len(), 1);
+        assert_eq!((merged[0].start_time, merged[0].end_time, merged[0].activities.len()), (5.0, 7.0, 3));
+    }
+
+    #[test]
+    fn test_click_at_video_end_does_not_leave_zoom() {
+        let data = MouseData {
+            positions: vec![(0.0, NormalizedPoint::CENTER)],
+            clicks: vec![ClickEvent {
+                time: 9.95, position: NormalizedPoint::new(0.3, 0.3), click_type: ClickType::LeftDown, duration: 0.05,
+            }],
+            keyboard_events: Vec::new(),
+            drags: Vec::new(),
+            windows: Vec::new(),
+            duration: 10.0,
+        };
+        let track = generate_smart_zoom(&data, &[], &SmartZoomSettings::default());
+        assert!(track.keyframes.last().is_none_or(|kf| kf.zoom <= 1.0));
+    }
+
     #[test]
     fn test_generate_ripples() {
         let clicks = vec![
@@ -862,6 +1890,106 @@ mod tests {
         let track = generate_ripples(&clicks, &settings);
         // Second click is within min_interval of first, should be skipped
         assert_eq!(track.keyframe_count(), 2);
+        assert_eq!(track.keyframes[0].max_radius_scale, 1.0);
+
+        let large = RippleSettings { max_radius_scale: 1.5, easing: EasingCurve::EaseOut, ..Default::default() };
+        let track = generate_ripples(&clicks, &large);
+        assert_eq!(track.keyframes[0].max_radius_scale, 1.5);
+        assert_eq!(track.keyframes[0].easing, EasingCurve::EaseOut);
+    }
+
+    #[test]
+    fn test_generate_ripples_skips_excluded_regions() {
+        let click = |time, x, y| ClickEvent {
+            time,
+            position: NormalizedPoint::new(x, y),
+            click_type: ClickType::LeftDown,
+            duration: 0.1,
+        };
+        // A toolbar click, then one in the content below it
+        let clicks = vec![click(1.0, 0.5, 0.05), click(2.0, 0.5, 0.5)];
+        let toolbar = Rect::new(0.0, 0.0, 1.0, 0.1);
+
+        let excluded = generate_ripples(&clicks, &RippleSettings { exclude_regions: vec![toolbar], ..Default::default() });
+        assert_eq!(excluded.keyframe_count(), 1);
+        assert_eq!(excluded.keyframes[0].time, 2.0);
+
+        let only_toolbar = generate_ripples(&clicks, &RippleSettings { include_region: Some(toolbar), ..Default::default() });
+        assert_eq!(only_toolbar.keyframe_count(), 1);
+        assert_eq!(only_toolbar.keyframes[0].time, 1.0);
+
+        // Without regions every click ripples
+        assert_eq!(generate_ripples(&clicks, &RippleSettings::default()).keyframe_count(), 2);
+    }
+
+    #[test]
+    fn test_generate_ripples_max_concurrent() {
+        let clicks: Vec<ClickEvent> = (0..5)
+            .map(|i| ClickEvent {
+                time: 1.0 + i as f64 * 0.12,
+                position: NormalizedPoint::CENTER,
+                click_type: ClickType::LeftDown,
+                duration: 0.05,
+            })
+            .collect();
+        let settings = RippleSettings { max_concurrent: 2, ..Default::default() };
+        let track = generate_ripples(&clicks, &settings);
+        assert_eq!(track.keyframe_count(), 5);
+        // Third click cuts the first short; the last one runs in full
+        assert!((track.keyframes[0].end_time() - (clicks[2].time + RIPPLE_CUTOFF_FADE)).abs() < 1e-9);
+        assert_eq!(track.keyframes[4].duration, settings.duration);
+        // Past each cutoff fade only `max_concurrent` remain
+        assert_eq!(track.active_ripples(clicks[4].time + RIPPLE_CUTOFF_FADE + 0.01).len(), 2);
+
+        let unlimited = generate_ripples(&clicks, &RippleSettings { max_concurrent: 0, ..Default::default() });
+        assert!(unlimited.keyframes.iter().all(|k| k.duration == settings.duration));
+    }
+
+    #[test]
+    fn test_cursor_hidden_while_typing() {
+        let key = |time: f64| KeyboardEvent {
+            time, event_type: KeyEventType::KeyDown,
+            key_code: 0, character: Some("a".into()),
+            modifiers: Modifiers::default(),
+        };
+        // A 2s typing session, then a lone keystroke that must not flicker the cursor
+        let mut events: Vec<KeyboardEvent> = (0..=10).map(|i| key(1.0 + i as f64 * 0.2)).collect();
+        events.push(key(6.0));
+        let settings = CursorSettings { hide_while_typing: true, ..Default::default() };
+
+        let track = generate_cursor_keyframes(&[], &[], &events, &settings);
+        let keyframes = track.style_keyframes.unwrap();
+        let visible_at = |t: f64| keyframes.iter().rev().find(|k| k.time <= t).is_none_or(|k| k.visible);
+        assert!(visible_at(0.5));
+        assert!(!visible_at(1.0) && !visible_at(3.4));
+        assert!(visible_at(3.5));
+        assert!(visible_at(6.2));
+        assert_eq!(keyframes.len(), 2);
+
+        let shown = generate_cursor_keyframes(&[], &[], &events, &CursorSettings::default());
+        assert!(shown.style_keyframes.is_none());
+    }
+
+    #[test]
+    fn test_cursor_restore_keeps_preceding_scale() {
+        let key = |time: f64| KeyboardEvent {
+            time, event_type: KeyEventType::KeyDown,
+            key_code: 0, character: Some("a".into()),
+            modifiers: Modifiers::default(),
+        };
+        // A long press spans the whole typing session, so the restore lands mid-press
+        let click = ClickEvent {
+            time: 0.5, position: NormalizedPoint::new(0.5, 0.5),
+            click_type: ClickType::LeftDown, duration: 5.0,
+        };
+        let events: Vec<KeyboardEvent> = (0..=10).map(|i| key(1.0 + i as f64 * 0.2)).collect();
+        let settings = CursorSettings { hide_while_typing: true, ..Default::default() };
+
+        let track = generate_cursor_keyframes(&[], &[click], &events, &settings);
+        let keyframes = track.style_keyframes.unwrap();
+        let restore = keyframes.iter().find(|k| (k.time - 3.5).abs() < 1e-9).unwrap();
+        assert!(restore.visible);
+        assert_eq!(restore.scale, 2.0);
     }
 
     #[test]
@@ -883,4 +2011,204 @@ mod tests {
         assert_eq!(track.keyframe_count(), 1);
         assert!(track.keyframes[0].display_text.contains("Cmd+"));
     }
+
+    #[test]
+    fn test_generate_keystrokes_uses_position() {
+        let events = vec![key_down(1.0, "c", Modifiers { control: true, ..Default::default() })];
+        let settings = KeystrokeSettings { position: NormalizedPoint::new(0.9, 0.05), ..Default::default() };
+        let track = generate_keystrokes(&events, &settings);
+        assert_eq!(track.keyframes[0].position, NormalizedPoint::new(0.9, 0.05));
+
+        let track = generate_keystrokes(&events, &KeystrokeSettings::default());
+        assert_eq!(track.keyframes[0].position, NormalizedPoint::new(0.5, 0.95));
+    }
+
+    fn key_down(time: f64, character: &str, modifiers: Modifiers) -> KeyboardEvent {
+        KeyboardEvent {
+            time, event_type: KeyEventType::KeyDown,
+            key_code: 0, character: Some(character.into()),
+            modifiers,
+        }
+    }
+
+    #[test]
+    fn test_generate_keystrokes_groups_typing() {
+        let mut events: Vec<KeyboardEvent> = "git commit"
+            .chars()
+            .enumerate()
+            .map(|(i, c)| key_down(1.0 + i as f64 * 0.1, &c.to_string(), Modifiers::default()))
+            .collect();
+        // Shortcut breaks the run and stays a discrete badge
+        events.push(key_down(3.0, "s", Modifiers { control: true, ..Default::default() }));
+        // Pause longer than the session timeout starts a new run
+        events.push(key_down(6.0, "x", Modifiers::default()));
+
+        let settings = KeystrokeSettings { shortcuts_only: false, ..Default::default() };
+        let track = generate_keystrokes(&events, &settings);
+        assert_eq!(track.keyframe_count(), 3);
+        assert_eq!(track.keyframes[0].display_text, "git commit");
+        assert!((track.keyframes[0].time - 1.0).abs() < 1e-10);
+        // Duration extends from first to last keystroke plus display duration
+        assert!((track.keyframes[0].duration - (0.9 + 1.5)).abs() < 1e-10);
+        assert_eq!(track.keyframes[1].display_text, "Ctrl+S");
+        assert_eq!(track.keyframes[2].display_text, "x");
+    }
+
+    #[test]
+    fn test_generate_keystrokes_groups_shifted_typing() {
+        let shift = Modifiers { shift: true, ..Default::default() };
+        let events = vec![
+            key_down(1.0, "H", shift),
+            key_down(1.1, "i", Modifiers::default()),
+            key_down(1.2, "!", shift),
+        ];
+        let settings = KeystrokeSettings { shortcuts_only: false, ..Default::default() };
+        let track = generate_keystrokes(&events, &settings);
+        assert_eq!(track.keyframe_count(), 1);
+        assert_eq!(track.keyframes[0].display_text, "Hi!");
+    }
+
+    #[test]
+    fn test_generate_keystrokes_shortcut_labels() {
+        let ctrl = Modifiers { control: true, ..Default::default() };
+        let ctrl_shift = Modifiers { control: true, shift: true, ..Default::default() };
+        let cmd = Modifiers { command: true, ..Default::default() };
+        let events = vec![
+            key_down(1.0, "v", ctrl),
+            key_down(2.0, "s", cmd),
+            key_down(3.0, "k", ctrl),
+            key_down(4.0, "p", ctrl_shift),
+        ];
+        let mut shortcut_labels = default_shortcut_labels();
+        // User entries match in any case and modifier order
+        shortcut_labels.insert("shift+ctrl+p".into(), "Command Palette".into());
+        let settings = KeystrokeSettings { shortcut_labels, ..Default::default() };
+
+        let track = generate_keystrokes(&events, &settings);
+        let texts: Vec<&str> = track.keyframes.iter().map(|k| k.display_text.as_str()).collect();
+        assert_eq!(texts, ["Paste", "Save", "Ctrl+K", "Command Palette"]);
+
+        assert_eq!(normalize_chord("cmd + Shift + enter").as_deref(), Some("Shift+Cmd+ENTER"));
+        assert_eq!(normalize_chord("Ctrl+"), None);
+    }
+
+    #[test]
+    fn test_generate_keystrokes_grouping_backspace_and_disabled() {
+        let mut events = vec![
+            key_down(1.0, "a", Modifiers::default()),
+            key_down(1.1, "b", Modifiers::default()),
+        ];
+        events.push(KeyboardEvent {
+            time: 1.2, event_type: KeyEventType::KeyDown,
+            key_code: 0x08, character: None,
+            modifiers: Modifiers::default(),
+        });
+        let settings = KeystrokeSettings { shortcuts_only: false, ..Default::default() };
+        let track = generate_keystrokes(&events, &settings);
+        assert_eq!(track.keyframe_count(), 1);
+        assert_eq!(track.keyframes[0].display_text, "a");
+
+        // Without grouping each key (outside min_interval) is its own badge
+        let settings = KeystrokeSettings { shortcuts_only: false, group_typing: false, ..Default::default() };
+        let events = vec![
+            key_down(1.0, "a", Modifiers::default()),
+            key_down(1.5, "b", Modifiers::default()),
+        ];
+        let track = generate_keystrokes(&events, &settings);
+        assert_eq!(track.keyframe_count(), 2);
+        assert_eq!(track.keyframes[0].display_text, "A");
+    }
+
+    #[test]
+    fn test_zoom_for_rect() {
+        let settings = SmartZoomSettings::default();
+        let (zoom, center) = zoom_for_rect((0.1, 0.2, 0.35, 0.2), &settings);
+        assert!((zoom - settings.target_area_coverage / 0.35).abs() < 1e-9);
+        assert!((center.x - 0.275).abs() < 1e-9);
+        assert!((center.y - 0.3).abs() < 1e-9);
+
+        // Tiny rect near the corner: default zoom, center kept inside the frame
+        let (zoom, center) = zoom_for_rect((0.0, 0.0, 0.001, 0.001), &settings);
+        assert_eq!(zoom, settings.default_zoom);
+        assert!(center.x >= 0.5 / zoom - 1e-9);
+    }
+
+    #[test]
+    fn test_zoom_frames_ultrawide_and_portrait_sources() {
+        use crate::core::project::AspectPolicy;
+        // How much of `rect` the zoomed crop shows, per axis (1 = all of it)
+        let shown = |rect: (f64, f64, f64, f64), zoom: f64, center: NormalizedPoint, view: (f64, f64)| {
+            let (x, y, w, h) = rect;
+            let (half_x, half_y) = (0.5 * view.0 / zoom, 0.5 * view.1 / zoom);
+            let overlap = |lo: f64, size: f64, c: f64, half: f64| ((lo + size).min(c + half) - lo.max(c - half)) / size;
+            (overlap(x, w, center.x, half_x), overlap(y, h, center.y, half_y))
+        };
+        let all_shown = |(x, y): (f64, f64)| x > 1.0 - 1e-9 && y > 1.0 - 1e-9;
+        let output_aspect = 1920.0 / 1080.0;
+
+        // 3440x1440 filling 16:9 loses the sides: a strip near the left edge
+        let view = AspectPolicy::Fill.view_extent(3440.0 / 1440.0, output_aspect);
+        assert!((view.0 - output_aspect / (3440.0 / 1440.0)).abs() < 1e-9);
+        assert_eq!(view.1, 1.0);
+        let settings = SmartZoomSettings { view_extent: view, ..Default::default() };
+        let strip = (0.02, 0.3, 0.3, 0.1);
+        let (zoom, center) = zoom_for_rect(strip, &settings);
+        // Covers the target share of what's visible, not of the full width
+        assert!((0.3 * zoom / view.0 - settings.target_area_coverage).abs() < 1e-9);
+        assert!(all_shown(shown(strip, zoom, center, view)));
+        // The crop can reach the left edge
+        assert!((center.x - 0.5 * view.0 / zoom).abs() < 1e-9);
+        // Without the view, the zoom cuts off part of the strip
+        let (flat_zoom, flat_center) = zoom_for_rect(strip, &SmartZoomSettings::default());
+        assert!(shown(strip, flat_zoom, flat_center, view).0 < 1.0);
+
+        // 1080x1920 filling 16:9 keeps about a third of the height: a tall area near the top
+        let view = AspectPolicy::Fill.view_extent(1080.0 / 1920.0, output_aspect);
+        assert_eq!(view.0, 1.0);
+        let settings = SmartZoomSettings { view_extent: view, ..Default::default() };
+        let column = (0.35, 0.02, 0.3, 0.15);
+        let (zoom, center) = zoom_for_rect(column, &settings);
+        assert!(zoom > settings.min_zoom && zoom < 0.7 / 0.3);
+        assert!(all_shown(shown(column, zoom, center, view)));
+        let mut session = WorkSession {
+            start_time: 0.0,
+            end_time: 1.0,
+            activities: Vec::new(),
+            work_area: column,
+            center,
+            zoom: 1.0,
+        };
+        calculate_session_zoom(&mut session, &settings);
+        assert_eq!(session.zoom, zoom);
+
+        // Fit and same-aspect outputs show the whole source
+        assert_eq!(AspectPolicy::Fit.view_extent(3440.0 / 1440.0, output_aspect), FULL_VIEW);
+        assert_eq!(AspectPolicy::Fill.view_extent(output_aspect, output_aspect), FULL_VIEW);
+    }
+
+    #[test]
+    fn test_insert_zoom_region_merges() {
+        let settings = SmartZoomSettings::default();
+        let mut track = TransformTrack::new();
+        track.add_keyframe(TransformKeyframe::new(0.0, 1.0, NormalizedPoint::CENTER, EasingCurve::Linear));
+        track.add_keyframe(TransformKeyframe::new(5.0, 1.0, NormalizedPoint::CENTER, EasingCurve::Linear));
+        track.add_keyframe(TransformKeyframe::new(20.0, 1.0, NormalizedPoint::CENTER, EasingCurve::Linear));
+
+        let region = ZoomRegion {
+            start_time: 4.0,
+            end_time: 8.0,
+            rect: (0.25, 0.25, 0.5, 0.5),
+            easing: EasingCurve::EaseInOut,
+        };
+        insert_zoom_region(&mut track, &region, &settings, 30.0);
+
+        let times: Vec<f64> = track.keyframes.iter().map(|k| k.time).collect();
+        let zoom_in = 4.0 - settings.focusing_duration;
+        let zoom_out = 8.0 + settings.transition_duration;
+        // The 5s keyframe inside the region is replaced
+        assert_eq!(times, vec![0.0, zoom_in, 4.0, 8.0, zoom_out, 20.0]);
+        assert!((track.keyframes[2].zoom - 1.4).abs() < 1e-9);
+        assert_eq!(track.keyframes[3].zoom, track.keyframes[2].zoom);
+        assert_eq!(track.keyframes[4].zoom, 1.0);
+    }
 }
@@ -0,0 +1,138 @@
+//! Where attention went: mouse positions and clicks binned into a grid of
+//! normalized densities, for the editor's heatmap overlay and as a hint for
+//! auto-crop and SmartZoom defaults.
+
+use serde::Serialize;
+
+use super::coordinates::NormalizedPoint;
+use super::input::InputRecording;
+
+/// Weight of a click, in seconds of the cursor resting on a cell
+pub const CLICK_WEIGHT: f64 = 1.0;
+
+/// Longest rest one position sample counts for, so a cursor left alone
+/// during a pause doesn't outweigh everything else
+const MAX_DWELL: f64 = 2.0;
+
+/// Most cells along either side
+pub const MAX_GRID_SIZE: u32 = 256;
+
+/// Activity density per grid cell
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityHeatmap {
+    pub grid_width: u32,
+    pub grid_height: u32,
+    /// Row-major from the top-left cell; the busiest cell is 1 (all 0
+    /// without any input)
+    pub values: Vec<f64>,
+}
+
+impl ActivityHeatmap {
+    /// Bin `recording`'s positions (weighted by how long the cursor stayed,
+    /// up to `MAX_DWELL`) and clicks (`CLICK_WEIGHT` each) between `start`
+    /// and `end` seconds into a `grid_width` x `grid_height` grid
+    pub fn compute(recording: &InputRecording, grid_width: u32, grid_height: u32, start: f64, end: f64) -> Self {
+        let grid_width = grid_width.clamp(1, MAX_GRID_SIZE);
+        let grid_height = grid_height.clamp(1, MAX_GRID_SIZE);
+        let mut heatmap = Self { grid_width, grid_height, values: vec![0.0; (grid_width * grid_height) as usize] };
+        let in_range = |time: f64| time >= start && time <= end;
+
+        let positions = &recording.positions;
+        for (i, sample) in positions.iter().enumerate().filter(|(_, p)| in_range(p.time)) {
+            let next = positions.get(i + 1).map_or(end, |p| p.time.min(end));
+            heatmap.add(sample.position, (next - sample.time).clamp(0.0, MAX_DWELL));
+        }
+        for click in recording.clicks.iter().filter(|c| in_range(c.time)) {
+            heatmap.add(click.position, CLICK_WEIGHT);
+        }
+
+        let max = heatmap.values.iter().copied().fold(0.0, f64::max);
+        if max > 0.0 {
+            heatmap.values.iter_mut().for_each(|v| *v /= max);
+        }
+        heatmap
+    }
+
+    /// Index of the cell holding `point` (clamped into the frame)
+    fn cell(&self, point: NormalizedPoint) -> usize {
+        let point = point.clamped();
+        let x = ((point.x * self.grid_width as f64) as u32).min(self.grid_width - 1);
+        let y = ((point.y * self.grid_height as f64) as u32).min(self.grid_height - 1);
+        (y * self.grid_width + x) as usize
+    }
+
+    fn add(&mut self, point: NormalizedPoint, weight: f64) {
+        let cell = self.cell(point);
+        self.values[cell] += weight;
+    }
+
+    /// Density of the cell holding `point`
+    pub fn value_at(&self, point: NormalizedPoint) -> f64 {
+        self.values[self.cell(point)]
+    }
+
+    /// Normalized (top-left, bottom-right) corners of the box around every
+    /// cell at least `threshold` dense, e.g. an auto-crop around the busy
+    /// area. None without input.
+    pub fn hot_bounds(&self, threshold: f64) -> Option<(NormalizedPoint, NormalizedPoint)> {
+        let threshold = threshold.max(f64::MIN_POSITIVE);
+        let (mut x0, mut y0, mut x1, mut y1) = (u32::MAX, u32::MAX, 0, 0);
+        for (i, _) in self.values.iter().enumerate().filter(|(_, v)| **v >= threshold) {
+            let (x, y) = (i as u32 % self.grid_width, i as u32 / self.grid_width);
+            (x0, y0, x1, y1) = (x0.min(x), y0.min(y), x1.max(x), y1.max(y));
+        }
+        (x0 <= x1).then(|| {
+            let (w, h) = (self.grid_width as f64, self.grid_height as f64);
+            (
+                NormalizedPoint::new(x0 as f64 / w, y0 as f64 / h),
+                NormalizedPoint::new((x1 + 1) as f64 / w, (y1 + 1) as f64 / h),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::input::{MouseButton, MouseClickRecord, MousePositionSample};
+
+    #[test]
+    fn test_heatmap_weights_dwell_and_clicks() {
+        let mut recording = InputRecording::new();
+        let sample = |time, x, y| MousePositionSample { time, position: NormalizedPoint::new(x, y), velocity: 0.0 };
+        // 1s resting top-left, 0.5s bottom-right with two clicks there
+        recording.positions.push(sample(0.0, 0.1, 0.1));
+        recording.positions.push(sample(1.0, 0.9, 0.9));
+        recording.positions.push(sample(1.5, 0.9, 0.1));
+        for time in [1.1, 1.2] {
+            recording.clicks.push(MouseClickRecord {
+                time,
+                position: NormalizedPoint::new(0.9, 0.9),
+                button: MouseButton::Left,
+                duration: 0.1,
+            });
+        }
+
+        let heatmap = ActivityHeatmap::compute(&recording, 2, 2, 0.0, 2.0);
+        assert_eq!((heatmap.grid_width, heatmap.grid_height), (2, 2));
+        assert_eq!(heatmap.values.len(), 4);
+        // Clicks outweigh the longer rest
+        assert_eq!(heatmap.value_at(NormalizedPoint::new(0.9, 0.9)), 1.0);
+        assert!((heatmap.value_at(NormalizedPoint::new(0.1, 0.1)) - 1.0 / 2.5).abs() < 1e-9);
+        // The last sample counts until the end of the range
+        assert!((heatmap.value_at(NormalizedPoint::new(0.9, 0.1)) - 0.5 / 2.5).abs() < 1e-9);
+        assert_eq!(heatmap.value_at(NormalizedPoint::new(0.1, 0.9)), 0.0);
+
+        let (min, max) = heatmap.hot_bounds(0.9).unwrap();
+        assert_eq!((min.x, min.y, max.x, max.y), (0.5, 0.5, 1.0, 1.0));
+
+        // Only the range counts; no input is all zeros
+        let trimmed = ActivityHeatmap::compute(&recording, 2, 2, 0.0, 0.5);
+        assert_eq!(trimmed.value_at(NormalizedPoint::new(0.1, 0.1)), 1.0);
+        let empty = ActivityHeatmap::compute(&InputRecording::new(), 0, 1000, 0.0, 1.0);
+        assert_eq!((empty.grid_width, empty.grid_height), (1, MAX_GRID_SIZE));
+        assert!(empty.values.iter().all(|v| *v == 0.0));
+        assert_eq!(empty.hot_bounds(0.5), None);
+    }
+}
@@ -0,0 +1,239 @@
+//! Idle detection ("dead air"): spans of a recording with no clicks, keys,
+//! scrolls, drags or significant mouse movement, and how to remove them with
+//! speed segments, trims or cuts.
+
+use serde::{Deserialize, Serialize};
+
+use super::generators::{group_typing_times, TYPING_SESSION_TIMEOUT};
+use super::input::{InputRecording, KeyAction};
+use super::timeline::{Cut, SpeedSegment, Timeline};
+
+/// Idle detection settings
+#[derive(Debug, Clone)]
+pub struct IdleSettings {
+    /// Minimum gap without activity to count as idle (seconds)
+    pub inactivity_threshold: f64,
+    /// Mouse speed (normalized units per second) below which movement is ignored
+    pub min_velocity: f64,
+    /// Time kept on each side of an idle span so cuts don't clip actions (seconds)
+    pub padding: f64,
+}
+
+impl Default for IdleSettings {
+    fn default() -> Self {
+        Self {
+            inactivity_threshold: 3.0,
+            min_velocity: 0.05,
+            padding: 0.5,
+        }
+    }
+}
+
+/// Span of source time with no activity
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct IdleSegment {
+    pub start: f64,
+    pub end: f64,
+}
+
+impl IdleSegment {
+    pub fn duration(&self) -> f64 {
+        self.end - self.start
+    }
+}
+
+/// What to do with idle spans
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum IdleAction {
+    /// Play idle spans faster
+    SpeedUp { factor: f64 },
+    /// Trim idle spans at the start/end and cut interior ones out
+    Cut,
+}
+
+/// Find idle spans in `[0, duration]`.
+/// Typing counts as one continuous activity per session, as in the generators.
+pub fn detect_idle_segments(
+    recording: &InputRecording,
+    duration: f64,
+    settings: &IdleSettings,
+) -> Vec<IdleSegment> {
+    let mut active: Vec<(f64, f64)> = Vec::new();
+
+    active.extend(
+        recording
+            .positions
+            .iter()
+            .filter(|p| p.velocity >= settings.min_velocity)
+            .map(|p| (p.time, p.time)),
+    );
+    active.extend(recording.clicks.iter().map(|c| (c.time, c.time + c.duration.max(0.0))));
+    active.extend(recording.scrolls.iter().map(|s| (s.time, s.time)));
+    active.extend(recording.drags.iter().map(|d| (d.start_time, d.end_time)));
+
+    let mut key_times: Vec<f64> = recording
+        .keyboard
+        .iter()
+        .filter(|k| k.event_type == KeyAction::Down)
+        .map(|k| k.time)
+        .collect();
+    key_times.sort_by(f64::total_cmp);
+    active.extend(group_typing_times(&key_times, TYPING_SESSION_TIMEOUT).iter().map(|s| (s.start, s.end)));
+
+    active.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    // Gaps between activity (and before the first / after the last event)
+    let mut gaps = Vec::new();
+    let mut cursor = 0.0_f64;
+    for (start, end) in active {
+        if start > cursor {
+            gaps.push((cursor, start));
+        }
+        cursor = cursor.max(end);
+    }
+    if duration > cursor {
+        gaps.push((cursor, duration));
+    }
+
+    gaps.into_iter()
+        .filter_map(|(start, end)| {
+            // No padding at the recording edges: nothing to protect there
+            let start = if start <= 0.0 { 0.0 } else { start + settings.padding };
+            let end = if end >= duration { duration } else { end - settings.padding };
+            let segment = IdleSegment { start, end: end.min(duration) };
+            (segment.duration() >= settings.inactivity_threshold).then_some(segment)
+        })
+        .collect()
+}
+
+/// Speed segments for idle spans
+pub fn idle_speed_segments(segments: &[IdleSegment], factor: f64) -> Vec<SpeedSegment> {
+    segments
+        .iter()
+        .map(|s| SpeedSegment { start: s.start, end: s.end, factor })
+        .collect()
+}
+
+/// Apply idle spans to a timeline, keeping its existing speed segments and
+/// cuts outside them. `Cut` turns a leading/trailing span into a trim and
+/// cuts interior spans out.
+pub fn apply_idle_action(timeline: &mut Timeline, segments: &[IdleSegment], action: IdleAction) {
+    match action {
+        IdleAction::SpeedUp { factor } => timeline.merge_speed_segments(&idle_speed_segments(segments, factor)),
+        IdleAction::Cut => {
+            for segment in segments {
+                if segment.start <= 0.0 {
+                    timeline.trim_start = timeline.trim_start.max(segment.end);
+                } else if segment.end >= timeline.duration {
+                    timeline.trim_end = Some(timeline.effective_trim_end().min(segment.start));
+                } else {
+                    timeline.cuts.push(Cut { start: segment.start, end: segment.end });
+                }
+            }
+            timeline.cuts.sort_by(|a, b| a.start.total_cmp(&b.start));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::coordinates::NormalizedPoint;
+    use crate::core::input::{KeyboardRecord, ModifierState, MouseButton, MouseClickRecord, MousePositionSample};
+
+    fn click(time: f64) -> MouseClickRecord {
+        MouseClickRecord { time, position: NormalizedPoint::CENTER, button: MouseButton::Left, duration: 0.1 }
+    }
+
+    fn key(time: f64) -> KeyboardRecord {
+        KeyboardRecord {
+            time,
+            event_type: KeyAction::Down,
+            key_code: 0x41,
+            character: Some("a".into()),
+            modifiers: ModifierState::default(),
+        }
+    }
+
+    fn sample(time: f64, velocity: f64) -> MousePositionSample {
+        MousePositionSample { time, position: NormalizedPoint::CENTER, velocity }
+    }
+
+    #[test]
+    fn test_detect_idle_segments() {
+        let recording = InputRecording {
+            // Slow drift at 6s is ignored, real movement at 20s is not
+            positions: vec![sample(6.0, 0.01), sample(20.0, 0.5)],
+            clicks: vec![click(2.0), click(12.0)],
+            // One typing session 13-14s bridges its keystrokes
+            keyboard: vec![key(13.0), key(14.0)],
+            ..Default::default()
+        };
+        let idle = detect_idle_segments(&recording, 30.0, &IdleSettings::default());
+
+        assert_eq!(idle.len(), 3);
+        assert!((idle[0].start - 2.6).abs() < 1e-9 && (idle[0].end - 11.5).abs() < 1e-9);
+        assert!((idle[1].start - 14.5).abs() < 1e-9 && (idle[1].end - 19.5).abs() < 1e-9);
+        // Trailing idle runs to the end, unpadded
+        assert!((idle[2].start - 20.5).abs() < 1e-9 && idle[2].end == 30.0);
+    }
+
+    #[test]
+    fn test_short_gaps_and_leading_idle() {
+        let recording = InputRecording { clicks: vec![click(5.0), click(6.0)], ..Default::default() };
+        let idle = detect_idle_segments(&recording, 6.5, &IdleSettings::default());
+        assert_eq!(idle, vec![IdleSegment { start: 0.0, end: 4.5 }]);
+    }
+
+    #[test]
+    fn test_apply_idle_cut_trims_edges() {
+        let mut timeline = Timeline::new(30.0);
+        let segments = [
+            IdleSegment { start: 0.0, end: 4.0 },
+            IdleSegment { start: 10.0, end: 15.0 },
+            IdleSegment { start: 25.0, end: 30.0 },
+        ];
+        apply_idle_action(&mut timeline, &segments, IdleAction::Cut);
+        assert_eq!(timeline.trim_start, 4.0);
+        assert_eq!(timeline.trim_end, Some(25.0));
+        // The interior span is cut out, not sped up
+        assert!(timeline.speed_segments.is_empty());
+        assert_eq!(timeline.cuts, vec![Cut { start: 10.0, end: 15.0 }]);
+        assert_eq!(timeline.kept_ranges(), vec![(4.0, 10.0), (15.0, 25.0)]);
+        assert!((timeline.output_duration() - 16.0).abs() < 1e-9);
+        assert!((timeline.source_time_at(5.0) - 9.0).abs() < 1e-9);
+        assert!((timeline.source_time_at(7.0) - 16.0).abs() < 1e-9);
+
+        let mut timeline = Timeline::new(30.0);
+        apply_idle_action(&mut timeline, &segments, IdleAction::SpeedUp { factor: 4.0 });
+        assert_eq!(timeline.trim_start, 0.0);
+        assert_eq!(timeline.speed_segments.len(), 3);
+    }
+
+    #[test]
+    fn test_apply_idle_keeps_existing_speed_segments() {
+        let mut timeline = Timeline::new(30.0);
+        // The user's slow-motion 8-12s and speed-up 20-22s
+        timeline.speed_segments = vec![
+            SpeedSegment { start: 8.0, end: 12.0, factor: 0.5 },
+            SpeedSegment { start: 20.0, end: 22.0, factor: 2.0 },
+        ];
+        let idle = [IdleSegment { start: 10.0, end: 15.0 }];
+        apply_idle_action(&mut timeline, &idle, IdleAction::SpeedUp { factor: 4.0 });
+        assert_eq!(
+            timeline.speed_segments,
+            vec![
+                SpeedSegment { start: 8.0, end: 10.0, factor: 0.5 },
+                SpeedSegment { start: 10.0, end: 15.0, factor: 4.0 },
+                SpeedSegment { start: 20.0, end: 22.0, factor: 2.0 },
+            ]
+        );
+
+        // Cutting leaves them (and earlier cuts) alone too
+        timeline.cuts.push(Cut { start: 2.0, end: 3.0 });
+        apply_idle_action(&mut timeline, &[IdleSegment { start: 24.0, end: 27.0 }], IdleAction::Cut);
+        assert_eq!(timeline.speed_segments.len(), 3);
+        assert_eq!(timeline.cuts, vec![Cut { start: 2.0, end: 3.0 }, Cut { start: 24.0, end: 27.0 }]);
+    }
+}
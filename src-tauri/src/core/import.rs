@@ -0,0 +1,99 @@
+//! Importing videos recorded elsewhere: the file is copied into a new
+//! `.lazyrec` package with an empty timeline and no input data, so effects
+//! are added by hand rather than generated.
+
+use std::path::{Path, PathBuf};
+
+use super::input::InputRecording;
+use super::project::{CaptureMeta, MediaAsset, Project, ProjectError, Rect, RenderSettings, Size};
+use super::render::ProbedVideo;
+
+/// Package `video_path` (already probed as `probed`) into a new
+/// `<stem>.lazyrec` in `dir`, numbered when that name is taken. The video is
+/// copied, so the original stays where it was.
+pub fn import_video(
+    video_path: &Path,
+    probed: &ProbedVideo,
+    dir: &Path,
+    render_settings: RenderSettings,
+) -> Result<(Project, PathBuf), ProjectError> {
+    let stem = video_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "Imported".into());
+    let video_name = video_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| ProjectError::NotFound(video_path.display().to_string()))?;
+    let package_dir = free_path(dir, &stem, Project::PACKAGE_EXTENSION);
+
+    let (width, height) = (probed.width as f64, probed.height as f64);
+    let media = MediaAsset {
+        video_relative_path: video_name,
+        mouse_data_relative_path: format!("{stem}_mouse.json"),
+        pixel_size: Size::new(width, height),
+        frame_rate: probed.frame_rate,
+        duration: probed.duration,
+    };
+    // Nothing says how the video maps to points, so a pixel is one. Another
+    // app's recording normally shows its cursor already.
+    let capture_meta = CaptureMeta {
+        cursor_captured: true,
+        ..CaptureMeta::new(Rect::new(0.0, 0.0, width, height), 1.0)
+    };
+    let mut project = Project::new(stem, media, capture_meta);
+    project.render_settings = render_settings;
+
+    // An empty input file keeps the package complete; the generators refuse it
+    std::fs::create_dir_all(package_dir.join("recording"))?;
+    let input_json = InputRecording::new()
+        .to_json()
+        .map_err(|e| ProjectError::Serialization(e.to_string()))?;
+    std::fs::write(project.mouse_data_path(&package_dir), input_json)?;
+    project.save(&package_dir, Some(video_path), None)?;
+    Ok((project, package_dir))
+}
+
+/// `<stem>.<ext>` in `dir`, or `<stem> 2.<ext>`, `<stem> 3.<ext>`… if taken
+pub fn free_path(dir: &Path, stem: &str, ext: &str) -> PathBuf {
+    let first = dir.join(format!("{stem}.{ext}"));
+    if !first.exists() {
+        return first;
+    }
+    (2..)
+        .map(|n| dir.join(format!("{stem} {n}.{ext}")))
+        .find(|path| !path.exists())
+        .unwrap_or(first)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_video_packages_a_copy() {
+        let dir = std::env::temp_dir().join(format!("lazyrec_import_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let video = dir.join("demo.mp4");
+        std::fs::write(&video, b"video").unwrap();
+        let probed = ProbedVideo { width: 1280, height: 720, frame_rate: 30.0, duration: 4.0 };
+
+        let (project, package_dir) = import_video(&video, &probed, &dir, RenderSettings::default()).unwrap();
+        assert_eq!(package_dir, dir.join("demo.lazyrec"));
+        assert!(video.exists());
+        assert_eq!(std::fs::read(project.video_path(&package_dir)).unwrap(), b"video");
+        assert_eq!((project.media.pixel_size.width, project.media.pixel_size.height), (1280.0, 720.0));
+        assert_eq!(project.duration(), 4.0);
+        assert!(project.timeline.is_empty());
+
+        let loaded = Project::load(&package_dir).unwrap();
+        let json = std::fs::read_to_string(loaded.mouse_data_path(&package_dir)).unwrap();
+        assert!(InputRecording::from_json(&json).unwrap().is_empty());
+
+        // A second import doesn't overwrite the first
+        let (_, second) = import_video(&video, &probed, &dir, RenderSettings::default()).unwrap();
+        assert_eq!(second, dir.join("demo 2.lazyrec"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,6 +1,10 @@
 //! Input monitoring abstraction trait.
 //! Captures mouse position, clicks, keyboard events, scroll, and drag events.
 
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
 use serde::{Deserialize, Serialize};
 
 use super::coordinates::NormalizedPoint;
@@ -77,6 +81,14 @@ pub struct DragRecord {
     pub end_position: NormalizedPoint,
 }
 
+/// Foreground window bounds, recorded whenever they change
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowRecord {
+    pub time: f64,
+    /// Bounds as (min_x, min_y, width, height), normalized and clamped to the screen
+    pub bounds: (f64, f64, f64, f64),
+}
+
 /// Complete input recording data
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct InputRecording {
@@ -85,6 +97,9 @@ pub struct InputRecording {
     pub keyboard: Vec<KeyboardRecord>,
     pub scrolls: Vec<ScrollRecord>,
     pub drags: Vec<DragRecord>,
+    /// Active window changes (absent in recordings made before focus follow)
+    #[serde(default)]
+    pub windows: Vec<WindowRecord>,
 }
 
 impl InputRecording {
@@ -92,6 +107,11 @@ impl InputRecording {
         Self::default()
     }
 
+    /// No mouse, click, key or scroll input at all (e.g. an imported video)
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty() && self.clicks.is_empty() && self.keyboard.is_empty() && self.scrolls.is_empty()
+    }
+
     /// Serialize to JSON for saving alongside video
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string_pretty(self)
@@ -101,6 +121,145 @@ impl InputRecording {
     pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
         serde_json::from_str(json)
     }
+
+    /// Append another recording's events with their times shifted by `offset`
+    /// seconds (a segment recorded after this one)
+    pub fn append_shifted(&mut self, other: InputRecording, offset: f64) {
+        self.positions.extend(other.positions.into_iter().map(|mut p| {
+            p.time += offset;
+            p
+        }));
+        self.clicks.extend(other.clicks.into_iter().map(|mut c| {
+            c.time += offset;
+            c
+        }));
+        self.keyboard.extend(other.keyboard.into_iter().map(|mut k| {
+            k.time += offset;
+            k
+        }));
+        self.scrolls.extend(other.scrolls.into_iter().map(|mut s| {
+            s.time += offset;
+            s
+        }));
+        self.drags.extend(other.drags.into_iter().map(|mut d| {
+            d.start_time += offset;
+            d.end_time += offset;
+            d
+        }));
+        self.windows.extend(other.windows.into_iter().map(|mut w| {
+            w.time += offset;
+            w
+        }));
+    }
+
+    /// Shift every event by `offset` seconds (positive = later), e.g. to line
+    /// input up with video frames when the two clocks disagree
+    pub fn shift_times(&mut self, offset: f64) {
+        if offset != 0.0 {
+            let events = std::mem::take(self);
+            self.append_shifted(events, offset);
+        }
+    }
+
+    /// Ignore input before `warmup` seconds (reaching for the mouse after
+    /// starting a recording): earlier clicks, keys, scrolls and drags are
+    /// dropped, and the cursor and active window hold where they were at
+    /// `warmup` instead of moving during it.
+    pub fn skip_warmup(&mut self, warmup: f64) {
+        if warmup <= 0.0 {
+            return;
+        }
+        hold_from(&mut self.positions, warmup, |p| &mut p.time);
+        hold_from(&mut self.windows, warmup, |w| &mut w.time);
+        self.clicks.retain(|c| c.time >= warmup);
+        self.keyboard.retain(|k| k.time >= warmup);
+        self.scrolls.retain(|s| s.time >= warmup);
+        self.drags.retain(|d| d.start_time >= warmup);
+    }
+
+    /// Thin the position samples to shrink the saved file. Keeps the first
+    /// sample in every `1 / target_hz` window (0: no rate floor) plus, when
+    /// `tolerance` > 0, every sample the path can't lose without the
+    /// interpolated cursor straying more than `tolerance` (normalized units)
+    /// from it. Clicks, keys, scrolls, drags and windows are untouched.
+    /// Returns how many samples were removed.
+    pub fn downsample_positions(&mut self, target_hz: f64, tolerance: f64) -> usize {
+        let before = self.positions.len();
+        if before < 3 || (target_hz <= 0.0 && tolerance <= 0.0) {
+            return 0;
+        }
+
+        let mut keep = if tolerance > 0.0 { simplify_path(&self.positions, tolerance) } else { vec![false; before] };
+        if target_hz > 0.0 {
+            let mut last_bucket = None;
+            for (i, sample) in self.positions.iter().enumerate() {
+                let bucket = Some((sample.time * target_hz).floor() as i64);
+                if bucket != last_bucket {
+                    keep[i] = true;
+                    last_bucket = bucket;
+                }
+            }
+        }
+        keep[0] = true;
+        keep[before - 1] = true;
+
+        let mut keep = keep.into_iter();
+        self.positions.retain(|_| keep.next().unwrap_or(true));
+        before - self.positions.len()
+    }
+}
+
+/// Drop time-sorted `samples` before `time`, except the last of them, which
+/// is moved to `time` so the state it recorded still holds from there
+fn hold_from<T>(samples: &mut Vec<T>, time: f64, sample_time: impl Fn(&mut T) -> &mut f64) {
+    let mut before = 0;
+    while before < samples.len() && *sample_time(&mut samples[before]) < time {
+        before += 1;
+    }
+    if before == 0 {
+        return;
+    }
+    samples.drain(..before - 1);
+    *sample_time(&mut samples[0]) = time;
+}
+
+/// Ramer–Douglas–Peucker over a cursor path, measuring each sample against
+/// where linear interpolation between the kept neighbours puts the cursor at
+/// that sample's time (so pauses and speed changes survive, not just turns).
+/// Returns which samples to keep; the endpoints always are.
+fn simplify_path(positions: &[MousePositionSample], tolerance: f64) -> Vec<bool> {
+    let mut keep = vec![false; positions.len()];
+    let Some(last) = positions.len().checked_sub(1) else {
+        return keep;
+    };
+    keep[0] = true;
+    keep[last] = true;
+
+    // Explicit stack: long recordings would recurse too deep
+    let mut spans = vec![(0, last)];
+    while let Some((a, b)) = spans.pop() {
+        if b <= a + 1 {
+            continue;
+        }
+        let (start, end) = (&positions[a], &positions[b]);
+        let span = end.time - start.time;
+        let (worst, distance) = (a + 1..b)
+            .map(|i| {
+                let sample = &positions[i];
+                let t = if span > 0.0 { ((sample.time - start.time) / span).clamp(0.0, 1.0) } else { 0.0 };
+                let x = start.position.x + (end.position.x - start.position.x) * t;
+                let y = start.position.y + (end.position.y - start.position.y) * t;
+                (i, (sample.position.x - x).hypot(sample.position.y - y))
+            })
+            .max_by(|l, r| l.1.total_cmp(&r.1))
+            .unwrap_or((a, 0.0));
+        if distance > tolerance {
+            keep[worst] = true;
+            spans.push((a, worst));
+            spans.push((worst, b));
+        }
+    }
+    keep
 }
 
 /// Input monitoring error types
@@ -116,12 +275,95 @@ pub enum InputError {
     Platform(String),
 }
 
+/// Recording clock shared by the recorder and the input monitor.
+/// Paused time is left out, like the video (which drops frames while
+/// paused), so input timestamps stay on the video's timeline.
+#[derive(Debug, Default)]
+pub struct RecordingClock {
+    paused: AtomicBool,
+    state: Mutex<ClockState>,
+}
+
+#[derive(Debug, Default)]
+struct ClockState {
+    start: Option<Instant>,
+    pause_start: Option<Instant>,
+    total_paused: Duration,
+}
+
+impl RecordingClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start counting from zero (unpaused)
+    pub fn restart(&self) {
+        self.restart_at(Instant::now());
+    }
+
+    /// Stop counting; `elapsed` reads 0 until the next restart
+    pub fn reset(&self) {
+        *self.state.lock().unwrap() = ClockState::default();
+        self.paused.store(false, Ordering::Relaxed);
+    }
+
+    pub fn pause(&self) {
+        self.pause_at(Instant::now());
+    }
+
+    pub fn resume(&self) {
+        self.resume_at(Instant::now());
+    }
+
+    /// Whether input and frames should currently be skipped
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Recording time in seconds, excluding paused time
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed_at(Instant::now())
+    }
+
+    fn restart_at(&self, now: Instant) {
+        *self.state.lock().unwrap() = ClockState { start: Some(now), ..Default::default() };
+        self.paused.store(false, Ordering::Relaxed);
+    }
+
+    fn pause_at(&self, now: Instant) {
+        let mut state = self.state.lock().unwrap();
+        if state.pause_start.is_none() {
+            state.pause_start = Some(now);
+        }
+        self.paused.store(true, Ordering::Relaxed);
+    }
+
+    fn resume_at(&self, now: Instant) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(pause_start) = state.pause_start.take() {
+            state.total_paused += now.saturating_duration_since(pause_start);
+        }
+        self.paused.store(false, Ordering::Relaxed);
+    }
+
+    fn elapsed_at(&self, now: Instant) -> f64 {
+        let state = self.state.lock().unwrap();
+        let Some(start) = state.start else { return 0.0 };
+        // While paused the clock holds at the pause start
+        let end = state.pause_start.unwrap_or(now);
+        end.saturating_duration_since(start)
+            .saturating_sub(state.total_paused)
+            .as_secs_f64()
+    }
+}
+
 /// Input monitor abstraction trait.
 /// Each platform provides its own implementation.
 pub trait InputMonitor: Send {
-    /// Start monitoring all input events.
+    /// Start monitoring all input events, timestamped with `clock`.
+    /// Nothing is recorded while the clock is paused.
     /// Events are collected internally and retrieved via stop_monitoring().
-    fn start_monitoring(&mut self) -> Result<(), InputError>;
+    fn start_monitoring(&mut self, clock: Arc<RecordingClock>) -> Result<(), InputError>;
 
     /// Stop monitoring and return all collected input data.
     fn stop_monitoring(&mut self) -> Result<InputRecording, InputError>;
@@ -142,7 +384,7 @@ impl StubInputMonitor {
 }
 
 impl InputMonitor for StubInputMonitor {
-    fn start_monitoring(&mut self) -> Result<(), InputError> {
+    fn start_monitoring(&mut self, _clock: Arc<RecordingClock>) -> Result<(), InputError> {
         if self.monitoring {
             return Err(InputError::AlreadyMonitoring);
         }
@@ -163,6 +405,27 @@ impl InputMonitor for StubInputMonitor {
     }
 }
 
+/// Default Windows DPI (100% scaling)
+pub const BASE_DPI: u32 = 96;
+
+/// Physical pixel size of a display reported in DPI-scaled logical pixels.
+/// Captured frames are physical, so mouse positions must be normalized
+/// against this size (e.g. 1280x720 logical at 150% / 144 DPI is 1920x1080).
+pub fn physical_screen_size(logical: (f64, f64), dpi: u32) -> (f64, f64) {
+    let scale = if dpi == 0 { 1.0 } else { dpi as f64 / BASE_DPI as f64 };
+    ((logical.0 * scale).round(), (logical.1 * scale).round())
+}
+
+/// Wheel delta of one notch on a classic mouse wheel (Windows `WHEEL_DELTA`)
+pub const WHEEL_DELTA: i16 = 120;
+
+/// Scroll amount in notches for a raw wheel delta, and whether it came from
+/// a high-resolution device. Precision touchpads (and free-spinning wheels)
+/// report fractions of a notch; classic wheels only whole multiples.
+pub fn wheel_scroll(raw: i16) -> (f64, bool) {
+    (raw as f64 / WHEEL_DELTA as f64, raw % WHEEL_DELTA != 0)
+}
+
 /// Windows input monitoring via low-level hooks (SetWindowsHookEx) and GetCursorPos polling.
 ///
 /// Architecture:
@@ -173,17 +436,21 @@ impl InputMonitor for StubInputMonitor {
 #[cfg(target_os = "windows")]
 pub mod windows {
     use super::*;
-    use std::sync::atomic::{AtomicBool, Ordering};
-    use std::sync::{Arc, Mutex};
-    use std::time::Instant;
 
-    use ::windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
-    use ::windows::Win32::Graphics::Gdi::{GetDC, GetDeviceCaps, ReleaseDC, HORZRES, VERTRES};
+    use ::windows::Win32::Foundation::{LPARAM, LRESULT, POINT, RECT, WPARAM};
+    use ::windows::Win32::Graphics::Gdi::{
+        GetDC, GetDeviceCaps, MonitorFromPoint, ReleaseDC, DESKTOPHORZRES, DESKTOPVERTRES,
+        HORZRES, MONITOR_DEFAULTTOPRIMARY, VERTRES,
+    };
+    use ::windows::Win32::UI::HiDpi::{
+        GetDpiForMonitor, SetThreadDpiAwarenessContext, DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2,
+        MDT_EFFECTIVE_DPI,
+    };
     use ::windows::Win32::UI::WindowsAndMessaging::{
-        CallNextHookEx, GetCursorPos, SetWindowsHookExW, UnhookWindowsHookEx,
+        CallNextHookEx, GetCursorPos, GetForegroundWindow, GetWindowRect, SetWindowsHookExW, UnhookWindowsHookEx,
         GetMessageW, PeekMessageW, HHOOK, KBDLLHOOKSTRUCT, MSLLHOOKSTRUCT, MSG,
         PM_NOREMOVE, WH_KEYBOARD_LL, WH_MOUSE_LL, WM_KEYDOWN, WM_KEYUP, WM_LBUTTONDOWN,
-        WM_LBUTTONUP, WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEWHEEL,
+        WM_LBUTTONUP, WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEHWHEEL, WM_MOUSEWHEEL,
         WM_RBUTTONDOWN, WM_RBUTTONUP, WM_SYSKEYDOWN, WM_SYSKEYUP,
     };
     use std::sync::atomic::AtomicU32;
@@ -191,7 +458,7 @@ pub mod windows {
     /// Thread-safe shared state for hook callbacks
     struct HookState {
         recording: Mutex<InputRecording>,
-        start_time: Instant,
+        clock: Arc<RecordingClock>,
         should_stop: AtomicBool,
         screen_width: f64,
         screen_height: f64,
@@ -200,12 +467,32 @@ pub mod windows {
         hook_thread_id: AtomicU32,
     }
 
+    /// Position polls between foreground window samples (~0.5s at 60Hz)
+    const WINDOW_POLL_TICKS: u32 = 30;
+
     // Global state for hook callbacks (Windows hooks require static/global access).
     // Uses Mutex<Option<>> instead of OnceLock so it can be reset between recordings.
     static HOOK_STATE: std::sync::Mutex<Option<Arc<HookState>>> = std::sync::Mutex::new(None);
 
     fn elapsed(state: &HookState) -> f64 {
-        state.start_time.elapsed().as_secs_f64()
+        state.clock.elapsed()
+    }
+
+    /// Normalized bounds of the foreground window, or None when there is none
+    /// or it's off screen (minimized windows sit at -32000)
+    fn foreground_window_bounds(state: &HookState) -> Option<(f64, f64, f64, f64)> {
+        let mut rect = RECT::default();
+        unsafe {
+            let hwnd = GetForegroundWindow();
+            if hwnd.is_invalid() {
+                return None;
+            }
+            GetWindowRect(hwnd, &mut rect).ok()?;
+        }
+        let min = normalize_point(state, rect.left, rect.top);
+        let max = normalize_point(state, rect.right, rect.bottom);
+        let (w, h) = (max.x - min.x, max.y - min.y);
+        (w > 0.0 && h > 0.0).then_some((min.x, min.y, w, h))
     }
 
     fn normalize_point(state: &HookState, x: i32, y: i32) -> NormalizedPoint {
@@ -221,7 +508,7 @@ pub mod windows {
         l_param: LPARAM,
     ) -> LRESULT {
         if n_code >= 0 {
-            if let Some(state) = HOOK_STATE.lock().unwrap().as_ref().cloned() {
+            if let Some(state) = HOOK_STATE.lock().unwrap().as_ref().cloned().filter(|s| !s.clock.is_paused()) {
                 let info = &*(l_param.0 as *const MSLLHOOKSTRUCT);
                 let pos = normalize_point(&state, info.pt.x, info.pt.y);
                 let time = elapsed(&state);
@@ -258,15 +545,17 @@ pub mod windows {
                             }
                         }
                     }
-                    WM_MOUSEWHEEL => {
-                        let delta = (info.mouseData >> 16) as i16 as f64 / 120.0;
+                    WM_MOUSEWHEEL | WM_MOUSEHWHEEL => {
+                        // High word is the signed delta: up for the vertical wheel, right for the horizontal one
+                        let (delta, precise) = wheel_scroll((info.mouseData >> 16) as i16);
+                        let (delta_x, delta_y) = if msg == WM_MOUSEHWHEEL { (delta, 0.0) } else { (0.0, delta) };
                         if let Ok(mut rec) = state.recording.lock() {
                             rec.scrolls.push(ScrollRecord {
                                 time,
                                 position: pos,
-                                delta_x: 0.0,
-                                delta_y: delta,
-                                is_trackpad: false,
+                                delta_x,
+                                delta_y,
+                                is_trackpad: precise,
                             });
                         }
                     }
@@ -283,7 +572,7 @@ pub mod windows {
         l_param: LPARAM,
     ) -> LRESULT {
         if n_code >= 0 {
-            if let Some(state) = HOOK_STATE.lock().unwrap().as_ref().cloned() {
+            if let Some(state) = HOOK_STATE.lock().unwrap().as_ref().cloned().filter(|s| !s.clock.is_paused()) {
                 let info = &*(l_param.0 as *const KBDLLHOOKSTRUCT);
                 let time = elapsed(&state);
                 let msg = w_param.0 as u32;
@@ -331,19 +620,42 @@ pub mod windows {
             }
         }
 
+        /// Primary display size in physical pixels, matching captured frames.
+        /// HORZRES/VERTRES are DPI-scaled for unaware threads; DESKTOPHORZRES is
+        /// always physical, with the monitor DPI as a fallback for scaling.
         fn get_screen_size() -> (f64, f64) {
             unsafe {
                 let hdc = GetDC(None);
-                let w = GetDeviceCaps(hdc, HORZRES) as f64;
-                let h = GetDeviceCaps(hdc, VERTRES) as f64;
+                let logical = (GetDeviceCaps(hdc, HORZRES) as f64, GetDeviceCaps(hdc, VERTRES) as f64);
+                let physical = (GetDeviceCaps(hdc, DESKTOPHORZRES) as f64, GetDeviceCaps(hdc, DESKTOPVERTRES) as f64);
                 let _ = ReleaseDC(None, hdc);
+
+                let (w, h) = if physical.0 > 0.0 && physical.1 > 0.0 {
+                    physical
+                } else {
+                    let monitor = MonitorFromPoint(POINT { x: 0, y: 0 }, MONITOR_DEFAULTTOPRIMARY);
+                    let (mut dpi_x, mut dpi_y) = (BASE_DPI, BASE_DPI);
+                    let _ = GetDpiForMonitor(monitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y);
+                    physical_screen_size(logical, dpi_x)
+                };
+                if (w, h) != logical {
+                    log::info!("Input normalization: {w}x{h} physical ({}x{} logical)", logical.0, logical.1);
+                }
                 (if w > 0.0 { w } else { 1920.0 }, if h > 0.0 { h } else { 1080.0 })
             }
         }
     }
 
+    /// Make the calling thread per-monitor DPI aware so GetCursorPos reports
+    /// physical pixels, like the low-level mouse hook and the captured frames
+    fn enable_physical_coordinates() {
+        unsafe {
+            let _ = SetThreadDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2);
+        }
+    }
+
     impl InputMonitor for WindowsInputMonitor {
-        fn start_monitoring(&mut self) -> Result<(), InputError> {
+        fn start_monitoring(&mut self, clock: Arc<RecordingClock>) -> Result<(), InputError> {
             if self.monitoring {
                 return Err(InputError::AlreadyMonitoring);
             }
@@ -352,7 +664,7 @@ pub mod windows {
 
             let state = Arc::new(HookState {
                 recording: Mutex::new(InputRecording::new()),
-                start_time: Instant::now(),
+                clock,
                 should_stop: AtomicBool::new(false),
                 screen_width: sw,
                 screen_height: sh,
@@ -366,6 +678,7 @@ pub mod windows {
             // Hook thread: installs low-level hooks and runs message loop
             let state_hook = state.clone();
             self.hook_thread = Some(std::thread::spawn(move || {
+                enable_physical_coordinates();
                 unsafe {
                     // Store this thread's ID so stop_monitoring can post WM_QUIT to it
                     let tid = ::windows::Win32::System::Threading::GetCurrentThreadId();
@@ -416,11 +729,15 @@ pub mod windows {
             // Cursor smoothing during export uses interpolation, not higher sample rate.
             let state_poll = state.clone();
             self.poll_thread = Some(std::thread::spawn(move || {
+                enable_physical_coordinates();
                 let interval = std::time::Duration::from_micros(16_667); // ~60Hz
+                let mut tick: u32 = 0;
+                let mut last_window = None;
                 while !state_poll.should_stop.load(Ordering::Relaxed) {
+                    tick = tick.wrapping_add(1);
                     let time = elapsed(&state_poll);
 
-                    let mut point = ::windows::Win32::Foundation::POINT { x: 0, y: 0 };
+                    let mut point = POINT { x: 0, y: 0 };
                     unsafe { let _ = GetCursorPos(&mut point); }
 
                     let pos = normalize_point(&state_poll, point.x, point.y);
@@ -433,12 +750,30 @@ pub mod windows {
                         v
                     };
 
+                    // Keep tracking the position while paused (no velocity spike on
+                    // resume) but record nothing
+                    if state_poll.clock.is_paused() {
+                        std::thread::sleep(interval);
+                        continue;
+                    }
+
+                    // Foreground window at 2Hz, kept only when it moves or changes
+                    let window = if tick.is_multiple_of(WINDOW_POLL_TICKS) {
+                        foreground_window_bounds(&state_poll).filter(|b| last_window != Some(*b))
+                    } else {
+                        None
+                    };
+
                     if let Ok(mut rec) = state_poll.recording.lock() {
                         rec.positions.push(MousePositionSample {
                             time,
                             position: pos,
                             velocity,
                         });
+                        if let Some(bounds) = window {
+                            rec.windows.push(WindowRecord { time, bounds });
+                            last_window = Some(bounds);
+                        }
                     }
 
                     std::thread::sleep(interval);
@@ -540,7 +875,7 @@ pub mod linux {
     }
 
     impl InputMonitor for LinuxInputMonitor {
-        fn start_monitoring(&mut self) -> Result<(), InputError> {
+        fn start_monitoring(&mut self, _clock: Arc<RecordingClock>) -> Result<(), InputError> {
             if self.monitoring {
                 return Err(InputError::AlreadyMonitoring);
             }
@@ -569,6 +904,50 @@ pub mod linux {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_physical_screen_size_scaling() {
+        // 1920x1080 panel at 125% / 150% / 200% Windows scaling
+        assert_eq!(physical_screen_size((1536.0, 864.0), 120), (1920.0, 1080.0));
+        assert_eq!(physical_screen_size((1280.0, 720.0), 144), (1920.0, 1080.0));
+        assert_eq!(physical_screen_size((960.0, 540.0), 192), (1920.0, 1080.0));
+        // 100% and unknown DPI leave the size alone
+        assert_eq!(physical_screen_size((1920.0, 1080.0), BASE_DPI), (1920.0, 1080.0));
+        assert_eq!(physical_screen_size((1920.0, 1080.0), 0), (1920.0, 1080.0));
+    }
+
+    fn sample(time: f64, x: f64, y: f64) -> MousePositionSample {
+        MousePositionSample { time, position: NormalizedPoint::new(x, y), velocity: 0.0 }
+    }
+
+    #[test]
+    fn test_simplify_path_keeps_corners() {
+        // 60 Hz: right along y = 0.2, then down from the corner at sample 63
+        let positions: Vec<_> = (0..=120)
+            .map(|i| {
+                let t = i as f64 / 60.0;
+                if i <= 63 { sample(t, 0.2 + 0.5 * t, 0.2) } else { sample(t, 0.725, 0.2 + 0.5 * (t - 1.05)) }
+            })
+            .collect();
+
+        let keep = simplify_path(&positions, 0.001);
+        let kept: Vec<usize> = (0..keep.len()).filter(|&i| keep[i]).collect();
+        assert_eq!(kept, [0, 63, 120]);
+
+        // Rate floor on top: 10 Hz plus the corner; clicks are left alone
+        let mut rec = InputRecording { positions, ..Default::default() };
+        rec.clicks.push(MouseClickRecord { time: 1.0, position: NormalizedPoint::CENTER, button: MouseButton::Left, duration: 0.1 });
+        let removed = rec.downsample_positions(10.0, 0.001);
+        assert_eq!(rec.positions.len(), 121 - removed);
+        assert_eq!(rec.positions.len(), 22);
+        assert!(rec.positions.iter().any(|p| (p.time - 1.05).abs() < 1e-9));
+        assert_eq!(rec.positions.last().unwrap().time, 2.0);
+        assert_eq!(rec.clicks.len(), 1);
+
+        // A pause on a straight line is a change of speed, so it's kept
+        let paused = [sample(0.0, 0.0, 0.5), sample(1.0, 0.5, 0.5), sample(2.0, 0.5, 0.5), sample(2.5, 1.0, 0.5)];
+        assert_eq!(simplify_path(&paused, 0.01), [true, true, true, true]);
+    }
+
     // InputRecording JSON roundtrip
 
     #[test]
@@ -607,7 +986,7 @@ mod tests {
         rec.scrolls.push(ScrollRecord {
             time: 3.0,
             position: NormalizedPoint::new(0.5, 0.5),
-            delta_x: 0.0,
+            delta_x: 0.25,
             delta_y: -3.0,
             is_trackpad: true,
         });
@@ -617,13 +996,90 @@ mod tests {
             start_position: NormalizedPoint::new(0.1, 0.1),
             end_position: NormalizedPoint::new(0.9, 0.9),
         });
+        rec.windows.push(WindowRecord { time: 0.5, bounds: (0.1, 0.2, 0.5, 0.6) });
         let json = rec.to_json().unwrap();
         let restored = InputRecording::from_json(&json).unwrap();
         assert_eq!(restored.positions.len(), 1);
         assert_eq!(restored.clicks.len(), 1);
         assert_eq!(restored.keyboard.len(), 1);
         assert_eq!(restored.scrolls.len(), 1);
+        assert_eq!((restored.scrolls[0].delta_x, restored.scrolls[0].delta_y), (0.25, -3.0));
+        assert!(restored.scrolls[0].is_trackpad);
         assert_eq!(restored.drags.len(), 1);
+        assert_eq!(restored.windows, rec.windows);
+    }
+
+    #[test]
+    fn test_wheel_scroll_detects_precision_deltas() {
+        assert_eq!(wheel_scroll(120), (1.0, false));
+        assert_eq!(wheel_scroll(-240), (-2.0, false));
+        // A touchpad's fine-grained deltas
+        assert_eq!(wheel_scroll(30), (0.25, true));
+        assert!(wheel_scroll(-7).1);
+    }
+
+    #[test]
+    fn test_append_shifted() {
+        let mut first = InputRecording::new();
+        first.clicks.push(MouseClickRecord {
+            time: 1.0,
+            position: NormalizedPoint::new(0.5, 0.5),
+            button: MouseButton::Left,
+            duration: 0.1,
+        });
+
+        let mut second = InputRecording::new();
+        second.clicks.push(MouseClickRecord {
+            time: 0.5,
+            position: NormalizedPoint::new(0.2, 0.2),
+            button: MouseButton::Right,
+            duration: 0.1,
+        });
+        second.drags.push(DragRecord {
+            start_time: 1.0,
+            end_time: 2.0,
+            start_position: NormalizedPoint::new(0.1, 0.1),
+            end_position: NormalizedPoint::new(0.9, 0.9),
+        });
+
+        first.append_shifted(second, 10.0);
+        assert_eq!(first.clicks.len(), 2);
+        assert_eq!(first.clicks[1].time, 10.5);
+        assert_eq!((first.drags[0].start_time, first.drags[0].end_time), (11.0, 12.0));
+    }
+
+    #[test]
+    fn test_shift_times() {
+        let mut rec = InputRecording::new();
+        rec.positions.push(MousePositionSample { time: 1.0, position: NormalizedPoint::CENTER, velocity: 0.0 });
+        rec.drags.push(DragRecord {
+            start_time: 2.0,
+            end_time: 3.0,
+            start_position: NormalizedPoint::CENTER,
+            end_position: NormalizedPoint::CENTER,
+        });
+        rec.shift_times(-0.05);
+        assert!((rec.positions[0].time - 0.95).abs() < 1e-12);
+        assert!((rec.drags[0].start_time - 1.95).abs() < 1e-12);
+        assert!((rec.drags[0].end_time - 2.95).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_skip_warmup_holds_cursor() {
+        let mut rec = InputRecording::new();
+        for (time, x) in [(0.0, 0.1), (0.4, 0.2), (0.8, 0.3), (1.2, 0.4)] {
+            rec.positions.push(MousePositionSample { time, position: NormalizedPoint::new(x, 0.5), velocity: 0.0 });
+        }
+        for time in [0.5, 1.5] {
+            rec.clicks.push(MouseClickRecord { time, position: NormalizedPoint::CENTER, button: MouseButton::Left, duration: 0.1 });
+        }
+        rec.skip_warmup(1.0);
+
+        // The cursor sits where it was at 1s, then moves on
+        let samples: Vec<_> = rec.positions.iter().map(|p| (p.time, p.position.x)).collect();
+        assert_eq!(samples, vec![(1.0, 0.3), (1.2, 0.4)]);
+        assert_eq!(rec.clicks.len(), 1);
+        assert_eq!(rec.clicks[0].time, 1.5);
     }
 
     #[test]
@@ -633,6 +1089,31 @@ mod tests {
 
     // StubInputMonitor state machine
 
+    #[test]
+    fn test_recording_clock_excludes_pauses() {
+        let clock = RecordingClock::new();
+        assert_eq!(clock.elapsed(), 0.0);
+
+        let t0 = Instant::now();
+        let at = |secs: f64| t0 + Duration::from_secs_f64(secs);
+        clock.restart_at(t0);
+        assert!((clock.elapsed_at(at(2.0)) - 2.0).abs() < 1e-9);
+
+        // Held at the pause start while paused
+        clock.pause_at(at(3.0));
+        assert!(clock.is_paused());
+        assert!((clock.elapsed_at(at(10.0)) - 3.0).abs() < 1e-9);
+
+        // 5s pause subtracted after resuming; a second pause call doesn't reset it
+        clock.pause_at(at(4.0));
+        clock.resume_at(at(8.0));
+        assert!(!clock.is_paused());
+        assert!((clock.elapsed_at(at(9.0)) - 4.0).abs() < 1e-9);
+
+        clock.reset();
+        assert_eq!(clock.elapsed_at(at(9.0)), 0.0);
+    }
+
     #[test]
     fn test_stub_monitor_initial_state() {
         let monitor = StubInputMonitor::new();
@@ -642,7 +1123,7 @@ mod tests {
     #[test]
     fn test_stub_monitor_start_stop() {
         let mut monitor = StubInputMonitor::new();
-        assert!(monitor.start_monitoring().is_ok());
+        assert!(monitor.start_monitoring(Arc::new(RecordingClock::new())).is_ok());
         assert!(monitor.is_monitoring());
         let recording = monitor.stop_monitoring().unwrap();
         assert!(!monitor.is_monitoring());
@@ -652,8 +1133,8 @@ mod tests {
     #[test]
     fn test_stub_monitor_double_start_errors() {
         let mut monitor = StubInputMonitor::new();
-        monitor.start_monitoring().unwrap();
-        match monitor.start_monitoring() {
+        monitor.start_monitoring(Arc::new(RecordingClock::new())).unwrap();
+        match monitor.start_monitoring(Arc::new(RecordingClock::new())) {
             Err(InputError::AlreadyMonitoring) => {}
             other => panic!("Expected AlreadyMonitoring, got {:?}", other),
         }
@@ -18,6 +18,9 @@ pub struct TransformKeyframe {
     pub center: NormalizedPoint,
     /// Interpolation mode to the next keyframe
     pub easing: EasingCurve,
+    /// Produced by a generator (cleared on regeneration) rather than edited by hand
+    #[serde(default)]
+    pub generated: bool,
 }
 
 impl TransformKeyframe {
@@ -28,6 +31,7 @@ impl TransformKeyframe {
             zoom: zoom.max(1.0),
             center: center.clamped(),
             easing,
+            generated: false,
         }
     }
 
@@ -113,6 +117,21 @@ impl RippleColor {
     }
 }
 
+/// Ripple shapes, all driven by the animation progress and intensity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RippleStyle {
+    /// Expanding ring (original look)
+    #[default]
+    Ring,
+    /// Filled circle fading from the center
+    Filled,
+    /// Two concentric expanding rings
+    DoubleRing,
+    /// Small disc that briefly swells and fades
+    Pulse,
+}
+
 /// Ripple effect keyframe
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RippleKeyframe {
@@ -127,6 +146,18 @@ pub struct RippleKeyframe {
     pub duration: f64,
     pub color: RippleColor,
     pub easing: EasingCurve,
+    #[serde(default)]
+    pub style: RippleStyle,
+    /// Size of the fully grown ripple relative to the reference radius (1 = default)
+    #[serde(default = "default_ripple_radius_scale")]
+    pub max_radius_scale: f64,
+    /// Produced by a generator (cleared on regeneration) rather than edited by hand
+    #[serde(default)]
+    pub generated: bool,
+}
+
+pub fn default_ripple_radius_scale() -> f64 {
+    1.0
 }
 
 impl RippleKeyframe {
@@ -139,6 +170,9 @@ impl RippleKeyframe {
             duration: 0.4,
             color: RippleColor::LeftClick,
             easing: EasingCurve::spring_bouncy(),
+            style: RippleStyle::Ring,
+            max_radius_scale: default_ripple_radius_scale(),
+            generated: false,
         }
     }
 
@@ -203,6 +237,9 @@ pub struct CursorStyleKeyframe {
     /// Movement direction (radians, for motion blur)
     pub movement_direction: Option<f64>,
     pub easing: EasingCurve,
+    /// Produced by a generator (cleared on regeneration) rather than edited by hand
+    #[serde(default)]
+    pub generated: bool,
 }
 
 impl CursorStyleKeyframe {
@@ -217,6 +254,7 @@ impl CursorStyleKeyframe {
             velocity: None,
             movement_direction: None,
             easing: EasingCurve::spring_snappy(),
+            generated: false,
         }
     }
 }
@@ -243,6 +281,9 @@ pub struct KeystrokeKeyframe {
     /// Overlay center position (default: bottom-center)
     pub position: NormalizedPoint,
     pub easing: EasingCurve,
+    /// Produced by a generator (cleared on regeneration) rather than edited by hand
+    #[serde(default)]
+    pub generated: bool,
 }
 
 impl KeystrokeKeyframe {
@@ -256,6 +297,7 @@ impl KeystrokeKeyframe {
             fade_out_duration: 0.3,
             position: NormalizedPoint::new(0.5, 0.95),
             easing: EasingCurve::EaseOut,
+            generated: false,
         }
     }
 
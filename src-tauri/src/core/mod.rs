@@ -1,14 +1,27 @@
+pub mod annotations;
 pub mod capture;
+pub mod content;
 pub mod coordinates;
+pub mod cursor_theme;
 pub mod easing;
 pub mod encoder;
 pub mod evaluator;
+pub mod export;
 pub mod generators;
+pub mod heatmap;
+pub mod idle;
+pub mod import;
 pub mod input;
 pub mod keyframe;
 pub mod permissions;
 pub mod project;
 pub mod recorder;
+pub mod recovery;
+pub mod replay;
 pub mod render;
+pub mod settings;
+pub mod sfx;
+pub mod smoothing;
+pub mod style;
 pub mod timeline;
 pub mod track;
@@ -45,6 +45,39 @@ impl PermissionType {
             Self::AudioCapture => "Required to record system or microphone audio",
         }
     }
+
+    /// How a denied permission is granted on this platform
+    pub fn remediation(&self) -> PermissionRemediation {
+        match self {
+            #[cfg(target_os = "linux")]
+            Self::ScreenCapture => PermissionRemediation::AcceptPrompt,
+            #[cfg(target_os = "linux")]
+            Self::InputMonitoring => PermissionRemediation::JoinInputGroup,
+            _ => PermissionRemediation::OpenSystemSettings,
+        }
+    }
+}
+
+/// What the user does to grant a denied permission
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PermissionRemediation {
+    /// Allow the app in the OS privacy settings, then restart it
+    OpenSystemSettings,
+    /// Accept the screen-sharing prompt shown when recording starts
+    AcceptPrompt,
+    /// Join the `input` group (evdev access) and log in again
+    JoinInputGroup,
+}
+
+impl PermissionRemediation {
+    pub fn hint(&self) -> &str {
+        match self {
+            Self::OpenSystemSettings => "allow LazyRec in your system privacy settings, then restart it",
+            Self::AcceptPrompt => "accept the screen sharing prompt when recording starts",
+            Self::JoinInputGroup => "add your user to the 'input' group and log in again",
+        }
+    }
 }
 
 /// Result of checking all permissions
@@ -65,6 +98,23 @@ impl PermissionReport {
                 || self.input_monitoring == PermissionStatus::NotApplicable)
     }
 
+    pub fn status(&self, permission: PermissionType) -> PermissionStatus {
+        match permission {
+            PermissionType::ScreenCapture => self.screen_capture,
+            PermissionType::InputMonitoring => self.input_monitoring,
+            PermissionType::Accessibility => self.accessibility,
+            PermissionType::AudioCapture => self.audio_capture,
+        }
+    }
+
+    /// First permission recording needs that was explicitly denied. Not yet
+    /// requested ones don't count: the OS asks for them when capture starts.
+    pub fn denied_for_recording(&self) -> Option<PermissionType> {
+        [PermissionType::ScreenCapture, PermissionType::InputMonitoring]
+            .into_iter()
+            .find(|&permission| self.status(permission) == PermissionStatus::Denied)
+    }
+
     /// List permissions that need attention
     pub fn missing_permissions(&self) -> Vec<PermissionType> {
         let mut missing = Vec::new();
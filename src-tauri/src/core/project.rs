@@ -3,7 +3,12 @@ use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::coordinates::NormalizedPoint;
+use super::evaluator::EffectScales;
+use super::generators::{default_shortcut_labels, RippleSettings, SmartZoomConfig};
+use super::input::InputRecording;
 use super::timeline::Timeline;
+use super::track::AnyTrack;
 
 /// LazyRec project file.
 /// Contains recorded media and timeline editing data.
@@ -22,8 +27,45 @@ pub struct Project {
     pub timeline: Timeline,
     #[serde(rename = "renderSettings")]
     pub render_settings: RenderSettings,
+    /// User-facing notes (project browser)
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub favorite: bool,
+    /// SmartZoom tuning used when regenerating keyframes
+    #[serde(default, rename = "smartZoom")]
+    pub smart_zoom: SmartZoomConfig,
+    /// Ripple look and timing used when regenerating keyframes
+    #[serde(default, rename = "rippleSettings")]
+    pub ripple_settings: RippleSettings,
+    /// Added to every recorded input time to line it up with the video
+    /// (seconds; negative moves the cursor earlier)
+    #[serde(default, rename = "inputTimeOffset")]
+    pub input_time_offset: f64,
+    /// Input before this many seconds (of video time) is ignored by the
+    /// generators and the cursor, which holds still until then
+    #[serde(default, rename = "warmupSecs")]
+    pub warmup_secs: f64,
+    /// Chord → friendly label shown by generated keystroke overlays
+    #[serde(default = "default_shortcut_labels", rename = "shortcutLabels")]
+    pub shortcut_labels: std::collections::BTreeMap<String, String>,
+    /// Generate typed text as one rolling overlay instead of a badge per key.
+    /// Off for projects saved before it existed, which keep their badges.
+    #[serde(default, rename = "groupTyping")]
+    pub group_typing: bool,
 }
 
+/// Largest accepted `input_time_offset` magnitude (seconds)
+pub const MAX_INPUT_TIME_OFFSET: f64 = 2.0;
+
+/// Longest accepted `warmup_secs` (seconds)
+pub const MAX_WARMUP_SECS: f64 = 10.0;
+
+/// Unsaved edits written beside `project.json` (see `Project::write_recovery`)
+pub const RECOVERY_FILE: &str = "project.recovery.json";
+
 impl Project {
     pub fn new(name: String, media: MediaAsset, capture_meta: CaptureMeta) -> Self {
         let now = chrono_now();
@@ -37,11 +79,41 @@ impl Project {
             capture_meta,
             timeline: Timeline::with_default_tracks(media.duration),
             render_settings: RenderSettings::default(),
+            description: None,
+            tags: Vec::new(),
+            favorite: false,
+            smart_zoom: SmartZoomConfig::default(),
+            ripple_settings: RippleSettings::default(),
+            input_time_offset: 0.0,
+            warmup_secs: 0.0,
+            shortcut_labels: default_shortcut_labels(),
+            group_typing: true,
         }
     }
 
+    /// Duration the timeline, generators and export all work against:
+    /// the media's length (0 if it isn't a valid number)
+    pub fn effective_duration(&self) -> f64 {
+        if self.media.duration.is_finite() { self.media.duration.max(0.0) } else { 0.0 }
+    }
+
+    /// Same as `effective_duration`
     pub fn duration(&self) -> f64 {
-        self.media.duration
+        self.effective_duration()
+    }
+
+    /// Bring the timeline's duration and trims back in line with the media.
+    /// Returns whether the timeline changed.
+    pub fn sync_timeline_duration(&mut self) -> bool {
+        let duration = self.effective_duration();
+        self.timeline.resync_duration(duration)
+    }
+
+    /// Replace the media duration (e.g. after swapping in a different video)
+    /// and resync the timeline to it
+    pub fn set_media_duration(&mut self, duration: f64) {
+        self.media.duration = duration;
+        self.sync_timeline_duration();
     }
 
     pub fn total_frames(&self) -> u64 {
@@ -52,6 +124,70 @@ impl Project {
         self.render_settings.background_enabled
     }
 
+    /// Part of the source's width and height the export shows at 1x (see
+    /// `AspectPolicy::view_extent`). The camera frames work areas and clamps
+    /// its centre against this, so e.g. an ultrawide capture filling a 16:9
+    /// output can still pan to its left and right edges.
+    pub fn view_extent(&self) -> (f64, f64) {
+        let source = self.media.pixel_size;
+        let output = self.render_settings.output_resolution.size(&source);
+        if self.is_window_mode() || source.height <= 0.0 || output.height <= 0.0 {
+            return (1.0, 1.0);
+        }
+        self.render_settings.aspect_policy.view_extent(source.width / source.height, output.width / output.height)
+    }
+
+    /// What a `bake_cursor_only` export renders: the cursor track over the
+    /// plain recording, keeping the edit (trim, speed segments) and output
+    /// format but no zoom, ripples, keystrokes, window canvas or overlays
+    pub fn cursor_only(&self) -> Project {
+        let mut project = self.clone();
+        project.timeline.tracks.retain(|track| matches!(track, AnyTrack::Cursor(_)));
+        let settings = &mut project.render_settings;
+        settings.background_enabled = false;
+        settings.corner_mask = false;
+        settings.overlay_only = false;
+        settings.click_sfx = false;
+        settings.watermark = None;
+        project
+    }
+
+    /// Index of the last frame (frames start at t = 0 and are spaced by 1/fps)
+    fn last_frame(&self) -> u64 {
+        let fps = 1.0 / self.media.frame_duration();
+        ((self.media.duration * fps + FRAME_EPSILON).floor() as u64).saturating_sub(1)
+    }
+
+    /// Frame shown at `time`: the latest frame starting at or before it,
+    /// clamped to the recording
+    pub fn frame_at_time(&self, time: f64) -> u64 {
+        if !time.is_finite() || time <= 0.0 {
+            return 0;
+        }
+        let fps = 1.0 / self.media.frame_duration();
+        ((time * fps + FRAME_EPSILON).floor() as u64).min(self.last_frame())
+    }
+
+    /// Start time of `frame`, clamped to the last frame
+    pub fn time_at_frame(&self, frame: u64) -> f64 {
+        frame.min(self.last_frame()) as f64 * self.media.frame_duration()
+    }
+
+    /// Time of the frame after (or before) the one shown at `time`.
+    /// Always lands on an exact frame time, so repeated stepping doesn't drift.
+    pub fn step_frame(&self, time: f64, forward: bool) -> f64 {
+        let frame = self.frame_at_time(time);
+        let target = if forward {
+            frame + 1
+        } else if time > self.time_at_frame(frame) + FRAME_EPSILON * self.media.frame_duration() {
+            // Mid-frame: step back to the start of the current frame
+            frame
+        } else {
+            frame.saturating_sub(1)
+        };
+        self.time_at_frame(target)
+    }
+
     /// Package extension for project directories
     pub const PACKAGE_EXTENSION: &'static str = "lazyrec";
 
@@ -94,6 +230,9 @@ impl Project {
             }
         }
 
+        // Copy newly picked background/watermark images into the package
+        self.package_images(package_dir)?;
+
         // Update modified timestamp
         self.modified_at = chrono_now();
 
@@ -102,23 +241,77 @@ impl Project {
             .map_err(|e| ProjectError::Serialization(e.to_string()))?;
         let project_path = package_dir.join("project.json");
         std::fs::write(&project_path, project_json)?;
+        // The saved file now holds everything the recovery copy did
+        Self::discard_recovery(package_dir)?;
 
         Ok(package_dir.to_path_buf())
     }
 
     /// Load a project from a `.lazyrec` package directory.
     pub fn load(package_dir: &Path) -> Result<Self, ProjectError> {
-        let project_path = package_dir.join("project.json");
+        Self::load_file(&package_dir.join("project.json"), package_dir)
+    }
+
+    /// Write unsaved edits to `project.recovery.json` beside `project.json`,
+    /// so a crash before the next save can be recovered from
+    pub fn write_recovery(&self, package_dir: &Path) -> Result<(), ProjectError> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| ProjectError::Serialization(e.to_string()))?;
+        std::fs::write(package_dir.join(RECOVERY_FILE), json)?;
+        Ok(())
+    }
+
+    /// Whether the package holds a recovery file written after `project.json`
+    /// was last saved (edits that never reached a save)
+    pub fn has_newer_recovery(package_dir: &Path) -> bool {
+        let modified = |name: &str| std::fs::metadata(package_dir.join(name)).and_then(|m| m.modified()).ok();
+        match (modified(RECOVERY_FILE), modified("project.json")) {
+            (Some(recovery), Some(saved)) => recovery > saved,
+            (Some(_), None) => true,
+            _ => false,
+        }
+    }
+
+    /// Load the package's recovery file instead of `project.json`
+    pub fn load_recovery(package_dir: &Path) -> Result<Self, ProjectError> {
+        Self::load_file(&package_dir.join(RECOVERY_FILE), package_dir)
+    }
+
+    /// Remove the package's recovery file, if any
+    pub fn discard_recovery(package_dir: &Path) -> Result<(), ProjectError> {
+        match std::fs::remove_file(package_dir.join(RECOVERY_FILE)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn load_file(project_path: &Path, package_dir: &Path) -> Result<Self, ProjectError> {
         if !project_path.exists() {
-            return Err(ProjectError::NotFound(
-                format!("project.json not found in {}", package_dir.display()),
-            ));
+            return Err(ProjectError::NotFound(format!(
+                "{} not found in {}",
+                project_path.file_name().unwrap_or_default().to_string_lossy(),
+                package_dir.display(),
+            )));
         }
 
-        let json = std::fs::read_to_string(&project_path)?;
-        let project: Project = serde_json::from_str(&json)
+        let json = std::fs::read_to_string(project_path)?;
+        let mut project: Project = serde_json::from_str(&json)
             .map_err(|e| ProjectError::Serialization(e.to_string()))?;
 
+        if project.sync_timeline_duration() {
+            log::warn!(
+                "Timeline duration in {} didn't match the media; resynced to {:.3}s",
+                package_dir.display(),
+                project.effective_duration(),
+            );
+        }
+        let duration = project.effective_duration();
+        let view = project.view_extent();
+        let report = project.timeline.validate_and_repair(duration, view);
+        if !report.is_clean() {
+            log::warn!("Repaired timeline in {}: {report}", package_dir.display());
+        }
+
         Ok(project)
     }
 
@@ -131,6 +324,97 @@ impl Project {
     pub fn mouse_data_path(&self, package_dir: &Path) -> PathBuf {
         package_dir.join("recording").join(&self.media.mouse_data_relative_path)
     }
+
+    /// Line freshly loaded input up with the video (`input_time_offset`) and
+    /// drop the warmup. The file on disk keeps everything.
+    pub fn align_input(&self, recording: &mut InputRecording) {
+        recording.shift_times(self.input_time_offset);
+        recording.skip_warmup(self.warmup_secs);
+    }
+
+    /// Get the absolute path to the background image, if one is selected
+    pub fn background_image_path(&self, package_dir: &Path) -> Option<PathBuf> {
+        match &self.render_settings.background {
+            Background::Image { relative_path } => Some(package_dir.join(relative_path)),
+            _ => None,
+        }
+    }
+
+    /// Get the absolute path to the watermark image, if one is set
+    pub fn watermark_path(&self, package_dir: &Path) -> Option<PathBuf> {
+        self.render_settings
+            .watermark
+            .as_ref()
+            .map(|w| package_dir.join(&w.relative_path))
+    }
+
+    /// Get the absolute path to the custom cursor theme, if one is selected
+    pub fn cursor_theme_path(&self, package_dir: &Path) -> Option<PathBuf> {
+        match &self.render_settings.cursor_theme {
+            CursorTheme::Custom { relative_path } => Some(package_dir.join(relative_path)),
+            _ => None,
+        }
+    }
+
+    /// Copy a background or watermark image (or a custom cursor theme's
+    /// directory) given as an absolute path into `background/` /
+    /// `watermark/` / `cursors/` and rewrite it as package-relative, so the
+    /// project stays portable.
+    fn package_images(&mut self, package_dir: &Path) -> Result<(), ProjectError> {
+        if let Background::Image { relative_path } = &mut self.render_settings.background {
+            package_image(package_dir, relative_path, "background", "Background image")?;
+        }
+        if let Some(watermark) = &mut self.render_settings.watermark {
+            package_image(package_dir, &mut watermark.relative_path, "watermark", "Watermark image")?;
+        }
+        if let CursorTheme::Custom { relative_path } = &mut self.render_settings.cursor_theme {
+            package_image(package_dir, relative_path, "cursors", "Cursor theme")?;
+        }
+        Ok(())
+    }
+}
+
+/// Copy an image referenced by absolute path into `package_dir/<folder>` and
+/// rewrite `relative_path` to point at the copy. A directory is copied with
+/// the files directly in it. Relative paths are left alone.
+fn package_image(
+    package_dir: &Path,
+    relative_path: &mut String,
+    folder: &str,
+    label: &str,
+) -> Result<(), ProjectError> {
+    let src = PathBuf::from(&*relative_path);
+    if !src.is_absolute() {
+        return Ok(());
+    }
+
+    if let Ok(inside) = src.strip_prefix(package_dir) {
+        *relative_path = inside.to_string_lossy().replace('\\', "/");
+        return Ok(());
+    }
+
+    if !src.exists() {
+        return Err(ProjectError::NotFound(format!("{label} {}", src.display())));
+    }
+    let file_name = src
+        .file_name()
+        .ok_or_else(|| ProjectError::NotFound(format!("{label} {}", src.display())))?;
+    let dir = package_dir.join(folder);
+    std::fs::create_dir_all(&dir)?;
+    if src.is_dir() {
+        let copy = dir.join(file_name);
+        std::fs::create_dir_all(&copy)?;
+        for entry in std::fs::read_dir(&src)? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                std::fs::copy(entry.path(), copy.join(entry.file_name()))?;
+            }
+        }
+    } else {
+        std::fs::copy(&src, dir.join(file_name))?;
+    }
+    *relative_path = format!("{folder}/{}", file_name.to_string_lossy());
+    Ok(())
 }
 
 /// Project I/O errors
@@ -142,8 +426,13 @@ pub enum ProjectError {
     Serialization(String),
     #[error("Not found: {0}")]
     NotFound(String),
+    #[error("Failed to append segment: {0}")]
+    Append(String),
 }
 
+/// Tolerance (in frames) for float error when snapping times to frames
+const FRAME_EPSILON: f64 = 1e-6;
+
 fn chrono_now() -> String {
     // ISO 8601 timestamp
     let now = std::time::SystemTime::now()
@@ -216,11 +505,43 @@ pub struct CaptureMeta {
     /// Scale factor (HiDPI: 2.0, standard: 1.0)
     #[serde(rename = "scaleFactor")]
     pub scale_factor: f64,
+    /// The system cursor is baked into the video, so no cursor is drawn over it
+    #[serde(default = "default_cursor_captured", rename = "cursorCaptured")]
+    pub cursor_captured: bool,
+    /// The recorded window inside the capture, in platform points from the
+    /// capture's top-left (set when a display capture stands in for a window)
+    #[serde(default, rename = "windowRectPt")]
+    pub window_rect_pt: Option<Rect>,
+}
+
+/// Whether the cursor is in a video nothing says about: recordings from
+/// before `cursorCaptured` was stored always had it baked in
+pub const CURSOR_CAPTURED_IF_UNKNOWN: bool = true;
+
+fn default_cursor_captured() -> bool {
+    CURSOR_CAPTURED_IF_UNKNOWN
 }
 
 impl CaptureMeta {
     pub fn new(bounds_pt: Rect, scale_factor: f64) -> Self {
-        Self { bounds_pt, scale_factor }
+        Self { bounds_pt, scale_factor, cursor_captured: false, window_rect_pt: None }
+    }
+
+    /// The recorded window in pixels of a `frame`-sized video, clipped to the
+    /// frame. None without a window rect, or when it covers the whole capture.
+    pub fn window_crop(&self, frame: &Size) -> Option<Rect> {
+        let rect = self.window_rect_pt?;
+        if self.bounds_pt.width <= 0.0 || self.bounds_pt.height <= 0.0 {
+            return None;
+        }
+        let (sx, sy) = (frame.width / self.bounds_pt.width, frame.height / self.bounds_pt.height);
+        let x0 = (rect.x * sx).clamp(0.0, frame.width).round();
+        let y0 = (rect.y * sy).clamp(0.0, frame.height).round();
+        let x1 = ((rect.x + rect.width) * sx).clamp(0.0, frame.width).round();
+        let y1 = ((rect.y + rect.height) * sy).clamp(0.0, frame.height).round();
+        let crop = Rect::new(x0, y0, x1 - x0, y1 - y0);
+        let whole = crop.width >= frame.width && crop.height >= frame.height;
+        (crop.width >= 1.0 && crop.height >= 1.0 && !whole).then_some(crop)
     }
 
     pub fn size_pixel(&self) -> Size {
@@ -251,6 +572,9 @@ impl Rect {
 pub struct RenderSettings {
     #[serde(rename = "outputResolution")]
     pub output_resolution: OutputResolution,
+    /// How the source maps into an output resolution of a different aspect ratio
+    #[serde(default, rename = "aspectPolicy")]
+    pub aspect_policy: AspectPolicy,
     #[serde(rename = "outputFrameRate")]
     pub output_frame_rate: OutputFrameRate,
     pub codec: VideoCodec,
@@ -266,12 +590,154 @@ pub struct RenderSettings {
     pub padding: f64,
     #[serde(rename = "windowInset")]
     pub window_inset: f64,
+    /// Window-mode background fill
+    #[serde(default)]
+    pub background: Background,
+    /// Renderer used for export (falls back to software if the GPU is unavailable)
+    #[serde(default, rename = "renderBackend")]
+    pub render_backend: RenderBackend,
+    /// FFmpeg encoder to force (e.g. "libx264"); None auto-detects hardware
+    #[serde(default, rename = "preferredEncoder")]
+    pub preferred_encoder: Option<String>,
+    /// Software encoder threads (0 = all cores)
+    #[serde(default, rename = "encoderThreads")]
+    pub encoder_threads: usize,
+    /// Encoder speed/size preset (see `encoder::encoder_presets`); None keeps the default
+    #[serde(default, rename = "encoderPreset")]
+    pub encoder_preset: Option<String>,
+    /// Keystroke overlay placement and colors
+    #[serde(default, rename = "keystrokeStyle")]
+    pub keystroke_style: KeystrokeStyle,
+    /// Frame filling for speed segments slower than 1x
+    #[serde(default, rename = "frameInterpolation")]
+    pub frame_interpolation: FrameInterpolation,
+    /// Mix a click/keypress sound into the export audio at each input event
+    #[serde(default, rename = "clickSfx")]
+    pub click_sfx: bool,
+    /// Sound effect volume (0-1)
+    #[serde(default = "default_sfx_volume", rename = "clickSfxVolume")]
+    pub click_sfx_volume: f64,
+    /// Cursor motion trail
+    #[serde(default, rename = "cursorTrail")]
+    pub cursor_trail: CursorTrail,
+    /// How the overlay cursor is drawn
+    #[serde(default, rename = "cursorTheme")]
+    pub cursor_theme: CursorTheme,
+    /// Outside window mode: cut the output's corners to transparent black
+    /// with `corner_radius`, without padding, shadow or background
+    #[serde(default, rename = "cornerMask")]
+    pub corner_mask: bool,
+    /// Generate cursor keyframes that hide the cursor during typing sessions
+    #[serde(default, rename = "hideCursorWhileTyping")]
+    pub hide_cursor_while_typing: bool,
+    /// Export only the effect layers (cursor, ripples, keystrokes) over
+    /// transparency, as VP9/WebM with alpha, for compositing elsewhere
+    #[serde(default, rename = "overlayOnly")]
+    pub overlay_only: bool,
+    /// Window-mode drop shadow color (its alpha scales `shadow_opacity`)
+    #[serde(default = "default_shadow_color", rename = "shadowColor")]
+    pub shadow_color: RgbaColor,
+    /// Stacked shadow layers approximating a gaussian blur (1 = single falloff)
+    #[serde(default = "default_shadow_layers", rename = "shadowLayers")]
+    pub shadow_layers: u32,
+    /// Logo drawn over every frame
+    #[serde(default)]
+    pub watermark: Option<Watermark>,
+    /// Frames between keyframes. Shorter seeks faster in editors and the
+    /// preview; longer makes smaller files.
+    #[serde(default = "default_keyframe_interval", rename = "keyframeInterval")]
+    pub keyframe_interval: u32,
+    /// Closed, fixed-length GOPs so every keyframe is a clean cut/seek point
+    #[serde(default, rename = "closedGop")]
+    pub closed_gop: bool,
+    /// Encode full-range (0-255) instead of limited-range (16-235) YUV
+    #[serde(default, rename = "fullRange")]
+    pub full_range: bool,
+    /// Export just the recording with the smoothed cursor drawn in, skipping
+    /// every other effect (see `Project::cursor_only`)
+    #[serde(default, rename = "bakeCursorOnly")]
+    pub bake_cursor_only: bool,
+    /// 10-bit output reduces banding in smooth gradients; codecs without
+    /// 10-bit support (`VideoCodec::supports_ten_bit`) export 8-bit
+    #[serde(default, rename = "bitDepth")]
+    pub bit_depth: BitDepth,
+    /// Dejitter the recorded cursor path before it is interpolated
+    #[serde(default, rename = "mouseSmoothing")]
+    pub mouse_smoothing: MouseSmoothing,
+    /// Resampling for zoomed content (software renderer)
+    #[serde(default, rename = "transformQuality")]
+    pub transform_quality: TransformQuality,
+    /// Write timeline markers into the video as chapters
+    #[serde(default, rename = "writeChapters")]
+    pub write_chapters: bool,
+    /// Global effect strength multipliers applied over the keyframes (1 = as authored)
+    #[serde(default = "default_effect_scale", rename = "rippleIntensityScale")]
+    pub ripple_intensity_scale: f64,
+    /// Scales how far click ripples grow
+    #[serde(default = "default_effect_scale", rename = "rippleSizeScale")]
+    pub ripple_size_scale: f64,
+    /// Scales how far zooms go past 1x
+    #[serde(default = "default_effect_scale", rename = "zoomScale")]
+    pub zoom_scale: f64,
+    #[serde(default = "default_effect_scale", rename = "cursorScale")]
+    pub cursor_scale: f64,
+    #[serde(default = "default_effect_scale", rename = "keystrokeOpacityScale")]
+    pub keystroke_opacity_scale: f64,
+    /// Window mode frames just the recorded window when the capture has one
+    /// (`CaptureMeta::window_rect_pt`) instead of the whole display
+    #[serde(default = "default_crop_to_window", rename = "cropToWindow")]
+    pub crop_to_window: bool,
+    /// Edge darkening while zoomed in, so the zoom target stands out
+    /// (0 = off, 1 = black corners once fully zoomed)
+    #[serde(default, rename = "vignetteStrength")]
+    pub vignette_strength: f64,
+}
+
+/// Most shadow layers rendered, whatever the setting (cost grows per layer)
+pub const MAX_SHADOW_LAYERS: u32 = 6;
+
+fn default_sfx_volume() -> f64 {
+    0.5
+}
+
+pub(crate) fn default_shadow_color() -> RgbaColor {
+    RgbaColor::new(0.0, 0.0, 0.0, 1.0)
+}
+
+pub(crate) fn default_shadow_layers() -> u32 {
+    3
+}
+
+fn default_keyframe_interval() -> u32 {
+    super::encoder::DEFAULT_KEYFRAME_INTERVAL
+}
+
+fn default_crop_to_window() -> bool {
+    true
+}
+
+pub(crate) fn default_effect_scale() -> f64 {
+    1.0
+}
+
+impl RenderSettings {
+    /// The global effect multipliers, for `FrameEvaluator::scales`
+    pub fn effect_scales(&self) -> EffectScales {
+        EffectScales {
+            ripple_intensity: self.ripple_intensity_scale,
+            ripple_size: self.ripple_size_scale,
+            zoom: self.zoom_scale,
+            cursor: self.cursor_scale,
+            keystroke_opacity: self.keystroke_opacity_scale,
+        }
+    }
 }
 
 impl Default for RenderSettings {
     fn default() -> Self {
         Self {
             output_resolution: OutputResolution::Original,
+            aspect_policy: AspectPolicy::Fit,
             output_frame_rate: OutputFrameRate::Original,
             codec: VideoCodec::H264,
             quality: ExportQuality::High,
@@ -281,10 +747,284 @@ impl Default for RenderSettings {
             shadow_opacity: 0.7,
             padding: 40.0,
             window_inset: 12.0,
+            background: Background::default(),
+            render_backend: RenderBackend::Software,
+            preferred_encoder: None,
+            encoder_threads: 0,
+            encoder_preset: None,
+            keystroke_style: KeystrokeStyle::default(),
+            frame_interpolation: FrameInterpolation::Hold,
+            click_sfx: false,
+            click_sfx_volume: default_sfx_volume(),
+            cursor_trail: CursorTrail::default(),
+            cursor_theme: CursorTheme::default(),
+            corner_mask: false,
+            hide_cursor_while_typing: false,
+            overlay_only: false,
+            shadow_color: default_shadow_color(),
+            shadow_layers: default_shadow_layers(),
+            watermark: None,
+            keyframe_interval: default_keyframe_interval(),
+            closed_gop: false,
+            full_range: false,
+            bake_cursor_only: false,
+            bit_depth: BitDepth::Eight,
+            mouse_smoothing: MouseSmoothing::default(),
+            transform_quality: TransformQuality::Auto,
+            write_chapters: false,
+            ripple_intensity_scale: default_effect_scale(),
+            ripple_size_scale: default_effect_scale(),
+            zoom_scale: default_effect_scale(),
+            cursor_scale: default_effect_scale(),
+            keystroke_opacity_scale: default_effect_scale(),
+            crop_to_window: default_crop_to_window(),
+            vignette_strength: 0.0,
+        }
+    }
+}
+
+/// RGBA color (components 0-1)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RgbaColor {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+    #[serde(default = "default_alpha")]
+    pub a: f64,
+}
+
+fn default_alpha() -> f64 {
+    1.0
+}
+
+impl RgbaColor {
+    pub const fn new(r: f64, g: f64, b: f64, a: f64) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Color as BGRA bytes (frame buffer order)
+    pub fn to_bgra(&self) -> [u8; 4] {
+        let byte = |c: f64| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        [byte(self.b), byte(self.g), byte(self.r), byte(self.a)]
+    }
+}
+
+/// Window-mode background behind the captured content
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Background {
+    #[serde(rename = "solid")]
+    Solid { color: RgbaColor },
+    /// Linear gradient; angle in degrees (0 = left → right, 90 = top → bottom)
+    #[serde(rename = "gradient")]
+    Gradient { start: RgbaColor, end: RgbaColor, angle: f64 },
+    /// Image scaled to cover the frame.
+    /// Package-relative once saved; an absolute path is copied in by `Project::save`.
+    #[serde(rename = "image")]
+    Image {
+        #[serde(rename = "relativePath")]
+        relative_path: String,
+    },
+    /// The recording itself, blurred and scaled to fill the frame: the
+    /// desktop behind the window. Radius in output pixels.
+    #[serde(rename = "blurredSource")]
+    BlurredSource {
+        #[serde(default = "default_blur_radius")]
+        radius: f64,
+    },
+}
+
+fn default_blur_radius() -> f64 {
+    40.0
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Self::Solid { color: RgbaColor::new(0.11, 0.11, 0.13, 1.0) }
+    }
+}
+
+/// Corner of the output frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+/// Logo image composited over the output, fixed on screen
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Watermark {
+    /// Package-relative once saved; an absolute path is copied in by `Project::save`.
+    pub relative_path: String,
+    #[serde(default)]
+    pub corner: Corner,
+    /// Logo width as a fraction of the frame width (shrunk further if it
+    /// wouldn't fit the frame)
+    pub scale: f64,
+    pub opacity: f64,
+}
+
+/// Keystroke overlay placement
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum OverlayPosition {
+    #[default]
+    BottomCenter,
+    TopCenter,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    /// Normalized overlay center
+    Custom { x: f64, y: f64 },
+}
+
+impl OverlayPosition {
+    /// Normalized overlay center. Corners stay clear of the edges;
+    /// the renderer keeps wide overlays inside the frame.
+    pub fn point(&self) -> NormalizedPoint {
+        match *self {
+            Self::BottomCenter => NormalizedPoint::new(0.5, 0.95),
+            Self::TopCenter => NormalizedPoint::new(0.5, 0.05),
+            Self::TopLeft => NormalizedPoint::new(0.1, 0.05),
+            Self::TopRight => NormalizedPoint::new(0.9, 0.05),
+            Self::BottomLeft => NormalizedPoint::new(0.1, 0.95),
+            Self::BottomRight => NormalizedPoint::new(0.9, 0.95),
+            Self::Custom { x, y } => NormalizedPoint::new(x.clamp(0.0, 1.0), y.clamp(0.0, 1.0)),
         }
     }
 }
 
+/// Keystroke overlay appearance
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeystrokeStyle {
+    #[serde(default)]
+    pub position: OverlayPosition,
+    /// Pill fill (alpha included)
+    pub background_color: RgbaColor,
+    pub text_color: RgbaColor,
+    /// Overall overlay opacity, multiplied with the fade
+    pub opacity: f64,
+}
+
+impl Default for KeystrokeStyle {
+    fn default() -> Self {
+        Self {
+            position: OverlayPosition::BottomCenter,
+            background_color: RgbaColor::new(30.0 / 255.0, 30.0 / 255.0, 30.0 / 255.0, 0.75),
+            text_color: RgbaColor::new(1.0, 1.0, 1.0, 230.0 / 255.0),
+            opacity: 1.0,
+        }
+    }
+}
+
+/// Look of the overlay cursor; every theme but `Dot` draws a bitmap per
+/// `CursorStyle` (see `cursor_theme::CursorPack`)
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CursorTheme {
+    /// White dot with a dark ring
+    #[default]
+    Dot,
+    /// White shapes with a black outline, like the desktop's
+    System,
+    /// Bigger yellow shapes that stand out on busy screens
+    LargeYellow,
+    /// Directory of PNGs per style (`CursorPack::load`).
+    /// Package-relative once saved; an absolute path is copied in by `Project::save`.
+    Custom {
+        #[serde(rename = "relativePath")]
+        relative_path: String,
+    },
+}
+
+/// Motion trail drawn behind a fast-moving cursor
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CursorTrail {
+    pub enabled: bool,
+    /// How far back the trail reaches (seconds)
+    pub length: f64,
+    /// Opacity of the newest trail copy; older copies fade out
+    pub fade: f64,
+    pub color: RgbaColor,
+}
+
+impl Default for CursorTrail {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            length: 0.15,
+            fade: 0.5,
+            color: RgbaColor::new(1.0, 1.0, 1.0, 1.0),
+        }
+    }
+}
+
+/// One-euro filter over the recorded cursor path (see `smoothing`)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MouseSmoothing {
+    pub enabled: bool,
+    /// Cutoff frequency at rest (Hz): lower removes more jitter but lags more
+    pub min_cutoff: f64,
+    /// How fast the cutoff rises with cursor speed (per normalized unit/s):
+    /// higher keeps fast moves responsive
+    pub beta: f64,
+}
+
+impl Default for MouseSmoothing {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_cutoff: 1.0,
+            beta: 10.0,
+        }
+    }
+}
+
+/// How slow-motion spans fill output frames between source frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FrameInterpolation {
+    /// Repeat the nearest earlier source frame
+    #[default]
+    Hold,
+    /// Cross-fade between neighbouring source frames
+    Blend,
+}
+
+/// Resampling used by the software renderer's zoom/pan transform
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TransformQuality {
+    /// Nearest-neighbor at low zoom, bilinear once the zoom magnifies visibly
+    #[default]
+    Auto,
+    /// Nearest-neighbor: fastest, can shimmer during slow pans
+    Fast,
+    Bilinear,
+    /// Catmull-Rom bicubic: sharpest at high zoom, slowest
+    Bicubic,
+}
+
+/// Frame renderer backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RenderBackend {
+    /// CPU pixel operations
+    #[default]
+    Software,
+    /// wgpu (requires the `gpu` feature)
+    Gpu,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum OutputResolution {
@@ -303,28 +1043,45 @@ pub enum OutputResolution {
 }
 
 impl OutputResolution {
+    /// Output frame size. Presets are 16:9; a source with another aspect
+    /// ratio is placed according to the `AspectPolicy`.
     pub fn size(&self, source: &Size) -> Size {
         match self {
             Self::Original => *source,
             Self::Custom { width, height } => Size::new(*width as f64, *height as f64),
-            // Standard resolutions: fit to target height, preserve source aspect ratio.
-            // This avoids stretching ultrawide (21:9) or other non-16:9 sources.
-            _ => {
-                let target_h = match self {
-                    Self::Uhd4k => 2160.0,
-                    Self::Qhd1440 => 1440.0,
-                    Self::Fhd1080 => 1080.0,
-                    Self::Hd720 => 720.0,
-                    _ => unreachable!(),
-                };
-                if source.height <= 0.0 {
-                    return Size::new(target_h * 16.0 / 9.0, target_h);
-                }
-                let aspect = source.width / source.height;
-                // Round width to even number (required by most video codecs)
-                let w = ((target_h * aspect) as u32 + 1) & !1;
-                Size::new(w as f64, target_h)
-            }
+            Self::Uhd4k => Size::new(3840.0, 2160.0),
+            Self::Qhd1440 => Size::new(2560.0, 1440.0),
+            Self::Fhd1080 => Size::new(1920.0, 1080.0),
+            Self::Hd720 => Size::new(1280.0, 720.0),
+        }
+    }
+}
+
+/// Mapping of the source into an output frame with a different aspect ratio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AspectPolicy {
+    /// Scale to the exact output size (may distort)
+    Stretch,
+    /// Fit inside the output, letterboxed/pillarboxed with the background
+    #[default]
+    Fit,
+    /// Cover the output, cropping the overflow
+    Fill,
+}
+
+impl AspectPolicy {
+    /// Fractions of the source's width and height shown at 1x when a
+    /// `source_aspect` source goes into an `output_aspect` output: `Fill`
+    /// crops the overflowing axis, the others show all of it
+    pub fn view_extent(self, source_aspect: f64, output_aspect: f64) -> (f64, f64) {
+        if self != Self::Fill || source_aspect <= 0.0 || output_aspect <= 0.0 || (source_aspect - output_aspect).abs() < 1e-3 {
+            return (1.0, 1.0);
+        }
+        if source_aspect > output_aspect {
+            (output_aspect / source_aspect, 1.0)
+        } else {
+            (1.0, source_aspect / output_aspect)
         }
     }
 }
@@ -352,19 +1109,43 @@ impl OutputFrameRate {
 pub enum VideoCodec {
     H264,
     H265,
+    /// Best compression; software encoding (SVT-AV1) is slow
+    Av1,
+    /// WebM with an alpha channel; used for overlay-only exports
+    Vp9,
 }
 
 impl VideoCodec {
     pub fn file_extension(&self) -> &str {
-        "mp4"
+        match self {
+            Self::Vp9 => "webm",
+            _ => "mp4",
+        }
     }
 
     pub fn display_name(&self) -> &str {
         match self {
             Self::H264 => "H.264",
             Self::H265 => "H.265 (HEVC)",
+            Self::Av1 => "AV1",
+            Self::Vp9 => "VP9 (WebM)",
         }
     }
+
+    /// Whether the codec can carry 10-bit 4:2:0 video players commonly
+    /// decode (H.264's High 10 profile mostly can't be played back)
+    pub fn supports_ten_bit(&self) -> bool {
+        !matches!(self, Self::H264)
+    }
+}
+
+/// Bits per color channel of exported video
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BitDepth {
+    #[default]
+    Eight,
+    Ten,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -392,6 +1173,242 @@ impl ExportQuality {
     }
 }
 
+// MARK: - Segments
+
+impl Project {
+    /// Extend the media and timeline by an appended segment. Existing keyframes
+    /// stay where they are; a trim ending at the old end now includes the segment.
+    pub fn extend_duration(&mut self, segment_duration: f64) {
+        let old_duration = self.media.duration;
+        if self.timeline.trim_end.is_some_and(|end| end >= old_duration - FRAME_EPSILON) {
+            self.timeline.trim_end = None;
+        }
+        self.set_media_duration(old_duration + segment_duration.max(0.0));
+    }
+
+    /// Append a recorded segment onto this project's media inside `package_dir`.
+    ///
+    /// `concat(first, second, output)` joins the existing video and the segment
+    /// (e.g. `render::concat_videos`). The segment's input events are shifted to
+    /// start where the project ended. The caller saves the project afterwards.
+    pub fn append_segment(
+        &mut self,
+        package_dir: &Path,
+        segment_video: &Path,
+        segment_input: InputRecording,
+        segment_duration: f64,
+        concat: impl FnOnce(&Path, &Path, &Path) -> Result<(), String>,
+    ) -> Result<(), ProjectError> {
+        let video_path = self.video_path(package_dir);
+        if !video_path.exists() {
+            return Err(ProjectError::NotFound(video_path.display().to_string()));
+        }
+
+        // Join into a temp file first so a failed concat leaves the project intact
+        let joined = video_path.with_extension("appending.mp4");
+        if let Err(e) = concat(&video_path, segment_video, &joined) {
+            let _ = std::fs::remove_file(&joined);
+            return Err(ProjectError::Append(e));
+        }
+        std::fs::rename(&joined, &video_path)?;
+
+        let mouse_path = self.mouse_data_path(package_dir);
+        let mut input = std::fs::read_to_string(&mouse_path)
+            .ok()
+            .and_then(|json| InputRecording::from_json(&json).ok())
+            .unwrap_or_default();
+        input.append_shifted(segment_input, self.media.duration);
+        let json = input.to_json().map_err(|e| ProjectError::Serialization(e.to_string()))?;
+        std::fs::write(&mouse_path, json)?;
+
+        self.extend_duration(segment_duration);
+        Ok(())
+    }
+
+    /// Point the project at a different video (moved, re-encoded, or edited
+    /// outside the app). The file is copied into the package's `recording/`
+    /// unless it's already there, the media is updated with the probed
+    /// `pixel_size`, `frame_rate` and `duration`, and the timeline is resynced.
+    /// The previous `project.json` is kept as `project.json.bak`; the caller
+    /// saves the project afterwards.
+    pub fn relink_video(
+        &mut self,
+        package_dir: &Path,
+        new_video: &Path,
+        pixel_size: Size,
+        frame_rate: f64,
+        duration: f64,
+    ) -> Result<(), ProjectError> {
+        let file_name = new_video
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| ProjectError::NotFound(new_video.display().to_string()))?
+            .to_string();
+        if !new_video.is_file() {
+            return Err(ProjectError::NotFound(new_video.display().to_string()));
+        }
+
+        let project_path = package_dir.join("project.json");
+        if project_path.exists() {
+            std::fs::copy(&project_path, package_dir.join("project.json.bak"))?;
+        }
+
+        let recording_dir = package_dir.join("recording");
+        std::fs::create_dir_all(&recording_dir)?;
+        let dst = recording_dir.join(&file_name);
+        let already_there = std::fs::canonicalize(new_video).ok() == std::fs::canonicalize(&dst).ok();
+        if !already_there {
+            std::fs::copy(new_video, &dst)?;
+        }
+
+        self.media.video_relative_path = file_name;
+        self.media.pixel_size = pixel_size;
+        self.media.frame_rate = frame_rate;
+        self.set_media_duration(duration);
+        Ok(())
+    }
+}
+
+// MARK: - Metadata
+
+/// User-editable project metadata
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectMetadata {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub favorite: bool,
+}
+
+impl Project {
+    pub fn metadata(&self) -> ProjectMetadata {
+        ProjectMetadata {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            tags: self.tags.clone(),
+            favorite: self.favorite,
+        }
+    }
+
+    /// Apply edited metadata. Blank names are ignored, blank descriptions
+    /// cleared, and tags trimmed and de-duplicated (case-insensitive, first wins).
+    pub fn set_metadata(&mut self, metadata: ProjectMetadata) {
+        let name = metadata.name.trim();
+        if !name.is_empty() {
+            self.name = name.to_string();
+        }
+        self.description = metadata
+            .description
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty());
+
+        self.tags.clear();
+        for tag in metadata.tags {
+            let tag = tag.trim();
+            if !tag.is_empty() && !self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                self.tags.push(tag.to_string());
+            }
+        }
+        self.favorite = metadata.favorite;
+    }
+
+    /// `.lazyrec` package directories directly inside `dir`, sorted by path
+    pub fn find_packages(dir: &Path) -> Result<Vec<PathBuf>, ProjectError> {
+        let mut packages: Vec<PathBuf> = std::fs::read_dir(dir)?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.is_dir()
+                    && path.extension().is_some_and(|e| e == Self::PACKAGE_EXTENSION)
+                    && path.join("project.json").exists()
+            })
+            .collect();
+        packages.sort();
+        Ok(packages)
+    }
+}
+
+// MARK: - Asset integrity
+
+/// File a project references inside its package
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AssetKind {
+    Video,
+    MouseData,
+    BackgroundImage,
+    Watermark,
+}
+
+/// Missing or unusable package asset (a warning: the project still loads)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetIssue {
+    pub kind: AssetKind,
+    pub path: PathBuf,
+    pub problem: String,
+}
+
+impl std::fmt::Display for AssetIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} {}: {}", self.kind, self.path.display(), self.problem)
+    }
+}
+
+impl Project {
+    /// Referenced assets that don't exist in the package. Cheap (no decoding),
+    /// so it runs on every load.
+    pub fn missing_assets(&self, package_dir: &Path) -> Vec<AssetIssue> {
+        let mut assets = vec![
+            (AssetKind::Video, self.video_path(package_dir)),
+            (AssetKind::MouseData, self.mouse_data_path(package_dir)),
+        ];
+        if let Some(path) = self.background_image_path(package_dir) {
+            assets.push((AssetKind::BackgroundImage, path));
+        }
+        if let Some(path) = self.watermark_path(package_dir) {
+            assets.push((AssetKind::Watermark, path));
+        }
+        assets
+            .into_iter()
+            .filter(|(_, path)| !path.is_file())
+            .map(|(kind, path)| AssetIssue { kind, path, problem: "missing".into() })
+            .collect()
+    }
+
+    /// Deeper check: assets exist, the video opens (`probe`, e.g. wrapping
+    /// `render::probe_video`) and the mouse data parses.
+    pub fn verify_assets(
+        &self,
+        package_dir: &Path,
+        probe: impl Fn(&Path) -> Result<(), String>,
+    ) -> Vec<AssetIssue> {
+        let mut issues = self.missing_assets(package_dir);
+        let missing = |kind: AssetKind, issues: &[AssetIssue]| issues.iter().any(|i| i.kind == kind);
+
+        if !missing(AssetKind::Video, &issues) {
+            let path = self.video_path(package_dir);
+            if let Err(e) = probe(&path) {
+                issues.push(AssetIssue { kind: AssetKind::Video, path, problem: format!("unreadable: {e}") });
+            }
+        }
+        if !missing(AssetKind::MouseData, &issues) {
+            let path = self.mouse_data_path(package_dir);
+            let parsed = std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|json| InputRecording::from_json(&json).map_err(|e| e.to_string()));
+            if let Err(e) = parsed {
+                issues.push(AssetIssue { kind: AssetKind::MouseData, path, problem: format!("unreadable: {e}") });
+            }
+        }
+        issues
+    }
+}
+
 // MARK: - Mouse Data Format (polyrecorder v4 compatible)
 
 /// Mouse movement event
@@ -532,6 +1549,125 @@ mod tests {
         assert!((media.frame_duration() - 1.0 / 60.0).abs() < 0.001);
     }
 
+    #[test]
+    fn test_frame_time_conversion_boundaries() {
+        let project = Project::new("Frames".into(), test_media(), test_capture_meta());
+        let fps = project.media.frame_rate;
+        let last = project.total_frames() - 1;
+
+        assert_eq!(project.frame_at_time(-1.0), 0);
+        assert_eq!(project.frame_at_time(f64::NAN), 0);
+        assert_eq!(project.frame_at_time(0.0), 0);
+        // Float error just below a frame boundary still snaps to it
+        assert_eq!(project.frame_at_time(7.0 / fps - 1e-12), 7);
+        assert_eq!(project.frame_at_time(7.5 / fps), 7);
+        assert_eq!(project.frame_at_time(project.duration()), last);
+        assert_eq!(project.frame_at_time(project.duration() + 5.0), last);
+        assert_eq!(project.time_at_frame(last + 10), project.time_at_frame(last));
+    }
+
+    #[test]
+    fn test_step_frame_no_drift() {
+        let project = Project::new("Frames".into(), test_media(), test_capture_meta());
+        let last = project.total_frames() - 1;
+
+        // Backward at 0 stays at 0
+        assert_eq!(project.step_frame(0.0, false), 0.0);
+
+        let mut t = 0.0;
+        for _ in 0..(last + 5) {
+            t = project.step_frame(t, true);
+        }
+        // Clamped at the last frame, exactly on its timestamp
+        assert_eq!(t, project.time_at_frame(last));
+        assert_eq!(project.frame_at_time(t), last);
+
+        for _ in 0..last {
+            t = project.step_frame(t, false);
+        }
+        assert_eq!(t, 0.0);
+
+        // Mid-frame steps back to the current frame's start first
+        let mid = project.time_at_frame(10) + project.media.frame_duration() / 2.0;
+        assert_eq!(project.step_frame(mid, false), project.time_at_frame(10));
+        assert_eq!(project.step_frame(mid, true), project.time_at_frame(11));
+    }
+
+    #[test]
+    fn test_extend_duration_keeps_keyframes_and_trim() {
+        let mut project = Project::new("Segments".into(), test_media(), test_capture_meta());
+        project.timeline.trim_start = 2.0;
+        project.timeline.trim_end = Some(30.0);
+        project.extend_duration(10.0);
+
+        assert_eq!(project.duration(), 40.0);
+        assert_eq!(project.timeline.duration, 40.0);
+        // Trim that ended at the old end now runs to the new end
+        assert_eq!(project.timeline.trim_end, None);
+        assert_eq!(project.timeline.trim_start, 2.0);
+
+        // A trim inside the old media is preserved
+        project.timeline.trim_end = Some(20.0);
+        project.extend_duration(5.0);
+        assert_eq!(project.timeline.trim_end, Some(20.0));
+    }
+
+    #[test]
+    fn test_set_metadata_normalizes() {
+        let mut project = Project::new("Demo".into(), test_media(), test_capture_meta());
+        assert!(project.tags.is_empty() && !project.favorite);
+
+        project.set_metadata(ProjectMetadata {
+            name: "  ".into(),
+            description: Some("  ".into()),
+            tags: vec![" tutorial ".into(), "Tutorial".into(), "".into(), "rust".into()],
+            favorite: true,
+        });
+        assert_eq!(project.name, "Demo");
+        assert_eq!(project.description, None);
+        assert_eq!(project.tags, vec!["tutorial".to_string(), "rust".to_string()]);
+        assert!(project.metadata().favorite);
+    }
+
+    #[test]
+    fn test_find_packages() {
+        let dir = std::env::temp_dir().join(format!("lazyrec_browse_{}", uuid::Uuid::new_v4()));
+        for name in ["b.lazyrec", "a.lazyrec"] {
+            std::fs::create_dir_all(dir.join(name)).unwrap();
+            std::fs::write(dir.join(name).join("project.json"), "{}").unwrap();
+        }
+        // No project.json / not a package
+        std::fs::create_dir_all(dir.join("empty.lazyrec")).unwrap();
+        std::fs::write(dir.join("notes.lazyrec"), "").unwrap();
+
+        let packages = Project::find_packages(&dir).unwrap();
+        assert_eq!(packages, vec![dir.join("a.lazyrec"), dir.join("b.lazyrec")]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_missing_and_unreadable_assets() {
+        let dir = std::env::temp_dir().join(format!("lazyrec_assets_{}", uuid::Uuid::new_v4()));
+        let project = Project::new("Assets".into(), test_media(), test_capture_meta());
+        std::fs::create_dir_all(dir.join("recording")).unwrap();
+
+        let kinds = |issues: Vec<AssetIssue>| issues.into_iter().map(|i| (i.kind, i.problem)).collect::<Vec<_>>();
+        assert_eq!(kinds(project.missing_assets(&dir)), vec![
+            (AssetKind::Video, "missing".to_string()),
+            (AssetKind::MouseData, "missing".to_string()),
+        ]);
+
+        std::fs::write(project.video_path(&dir), b"video").unwrap();
+        std::fs::write(project.mouse_data_path(&dir), b"{ truncated").unwrap();
+        assert!(project.missing_assets(&dir).is_empty());
+
+        let issues = project.verify_assets(&dir, |_| Err("no video stream".into()));
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].problem, "unreadable: no video stream");
+        assert_eq!(issues[1].kind, AssetKind::MouseData);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_capture_meta_size_pixel() {
         let meta = test_capture_meta();
@@ -540,6 +1676,21 @@ mod tests {
         assert!((size.height - 1080.0).abs() < 0.001);
     }
 
+    #[test]
+    fn test_capture_meta_window_crop() {
+        let mut meta = test_capture_meta();
+        let frame = Size { width: 1920.0, height: 1080.0 };
+        assert_eq!(meta.window_crop(&frame), None);
+
+        // Points map to video pixels; the part off the capture is clipped
+        meta.window_rect_pt = Some(Rect::new(100.0, -20.0, 400.0, 300.0));
+        assert_eq!(meta.window_crop(&frame), Some(Rect::new(200.0, 0.0, 800.0, 560.0)));
+
+        // A window covering the capture needs no crop
+        meta.window_rect_pt = Some(Rect::new(-5.0, -5.0, 970.0, 550.0));
+        assert_eq!(meta.window_crop(&frame), None);
+    }
+
     #[test]
     fn test_project_save_load_roundtrip() {
         let dir = std::env::temp_dir().join(format!("lazyrec_test_{}", uuid::Uuid::new_v4()));
@@ -556,6 +1707,156 @@ mod tests {
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn test_fill_project_keeps_edge_zoom_through_load() {
+        let dir = std::env::temp_dir().join(format!("lazyrec_test_{}", uuid::Uuid::new_v4()));
+        let media = MediaAsset { pixel_size: Size::new(3440.0, 1440.0), ..test_media() };
+        let mut project = Project::new("Ultrawide".into(), media, test_capture_meta());
+        project.render_settings.output_resolution = OutputResolution::Fhd1080;
+        project.render_settings.aspect_policy = AspectPolicy::Fill;
+        // Framed against the ~3/4 of the width a 16:9 Fill export shows
+        let mut edge = crate::core::keyframe::TransformKeyframe::identity(2.0);
+        edge.zoom = 2.0;
+        edge.center = NormalizedPoint::new(0.2, 0.5);
+        project.timeline.transform_track_mut().unwrap().keyframes = vec![edge];
+
+        project.save(&dir, None, None).unwrap();
+        let loaded = Project::load(&dir).unwrap();
+        let center = loaded.timeline.transform_track().unwrap().keyframes[0].center;
+        assert!((center.x - 0.2).abs() < 1e-9);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_legacy_project_keeps_its_cursor() {
+        let dir = std::env::temp_dir().join(format!("lazyrec_test_{}", uuid::Uuid::new_v4()));
+        Project::new("Legacy".into(), test_media(), test_capture_meta()).save(&dir, None, None).unwrap();
+        // A project.json from before `cursorCaptured` existed
+        let path = dir.join("project.json");
+        let json = std::fs::read_to_string(&path).unwrap();
+        assert!(json.contains("\"cursorCaptured\": false,"));
+        std::fs::write(&path, json.replace("\"cursorCaptured\": false,", "")).unwrap();
+
+        let loaded = Project::load(&dir).unwrap();
+        assert!(loaded.capture_meta.cursor_captured);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_legacy_project_keeps_keystroke_badges() {
+        let dir = std::env::temp_dir().join(format!("lazyrec_test_{}", uuid::Uuid::new_v4()));
+        let mut project = Project::new("Legacy".into(), test_media(), test_capture_meta());
+        assert!(project.group_typing);
+        project.save(&dir, None, None).unwrap();
+        // A project.json from before `groupTyping` existed
+        let path = dir.join("project.json");
+        let json = std::fs::read_to_string(&path).unwrap();
+        assert!(json.contains("\"groupTyping\": true"));
+        std::fs::write(&path, json.replace("\"groupTyping\": true", "\"groupTypingRemoved\": true")).unwrap();
+
+        assert!(!Project::load(&dir).unwrap().group_typing);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_relink_video() {
+        let dir = std::env::temp_dir().join(format!("lazyrec_test_{}", uuid::Uuid::new_v4()));
+        let replacement = std::env::temp_dir().join(format!("lazyrec_relink_{}.mp4", uuid::Uuid::new_v4()));
+        std::fs::write(&replacement, b"edited video").unwrap();
+
+        let mut project = Project::new("Relink".into(), test_media(), test_capture_meta());
+        project.timeline.trim_end = Some(25.0);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("project.json"), b"previous project").unwrap();
+
+        project
+            .relink_video(&dir, &replacement, Size::new(1280.0, 720.0), 30.0, 12.0)
+            .unwrap();
+
+        let video = project.video_path(&dir);
+        assert_eq!(std::fs::read(&video).unwrap(), b"edited video");
+        assert_eq!(project.media.pixel_size, Size::new(1280.0, 720.0));
+        assert_eq!(project.media.frame_rate, 30.0);
+        assert_eq!(project.duration(), 12.0);
+        assert_eq!(project.timeline.duration, 12.0);
+        // The old trim ran past the new video's end
+        assert_eq!(project.timeline.trim_end, None);
+        assert_eq!(std::fs::read(dir.join("project.json.bak")).unwrap(), b"previous project");
+
+        // Relinking the package's own file doesn't copy it onto itself
+        project.relink_video(&dir, &video, Size::new(1280.0, 720.0), 30.0, 12.0).unwrap();
+        assert_eq!(std::fs::read(&video).unwrap(), b"edited video");
+        assert!(project.relink_video(&dir, &dir.join("missing.mp4"), Size::new(1.0, 1.0), 30.0, 1.0).is_err());
+
+        let _ = std::fs::remove_file(&replacement);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_newer_recovery_file() {
+        let dir = std::env::temp_dir().join(format!("lazyrec_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(!Project::has_newer_recovery(&dir));
+
+        let set_modified = |name: &str, secs: u64| {
+            let file = std::fs::File::options().write(true).open(dir.join(name)).unwrap();
+            file.set_modified(std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs)).unwrap();
+        };
+        std::fs::write(dir.join("project.json"), b"saved").unwrap();
+        std::fs::write(dir.join(RECOVERY_FILE), b"edited").unwrap();
+        set_modified("project.json", 2_000);
+        set_modified(RECOVERY_FILE, 1_000);
+        assert!(!Project::has_newer_recovery(&dir), "older than the last save");
+        set_modified(RECOVERY_FILE, 3_000);
+        assert!(Project::has_newer_recovery(&dir));
+
+        Project::discard_recovery(&dir).unwrap();
+        assert!(!Project::has_newer_recovery(&dir));
+        // Nothing to discard is fine
+        Project::discard_recovery(&dir).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_project_save_copies_background_image() {
+        let dir = std::env::temp_dir().join(format!("lazyrec_test_{}", uuid::Uuid::new_v4()));
+        let image = std::env::temp_dir().join(format!("lazyrec_bg_{}.png", uuid::Uuid::new_v4()));
+        std::fs::write(&image, b"not really a png").unwrap();
+
+        let mut project = Project::new("Background".into(), test_media(), test_capture_meta());
+        project.render_settings.background = Background::Image {
+            relative_path: image.to_string_lossy().into_owned(),
+        };
+        project.render_settings.watermark = Some(Watermark {
+            relative_path: image.to_string_lossy().into_owned(),
+            corner: Corner::TopLeft,
+            scale: 0.1,
+            opacity: 0.8,
+        });
+        project.save(&dir, None, None).unwrap();
+
+        let Background::Image { relative_path } = &project.render_settings.background else {
+            panic!("background should still be an image");
+        };
+        assert!(relative_path.starts_with("background/"));
+        let packaged = project.background_image_path(&dir).unwrap();
+        assert!(packaged.exists());
+        assert!(packaged.starts_with(&dir));
+        let watermark = project.render_settings.watermark.as_ref().unwrap();
+        assert!(watermark.relative_path.starts_with("watermark/"));
+        assert!(project.watermark_path(&dir).unwrap().exists());
+
+        // Cleanup
+        let _ = std::fs::remove_file(&image);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_rgba_color_to_bgra() {
+        assert_eq!(RgbaColor::new(1.0, 0.5, 0.0, 1.0).to_bgra(), [0, 128, 255, 255]);
+        assert_eq!(RgbaColor::new(2.0, -1.0, 0.0, 0.0).to_bgra(), [0, 0, 255, 0]);
+    }
+
     #[test]
     fn test_project_load_not_found() {
         let result = Project::load(std::path::Path::new("/nonexistent/path.lazyrec"));
@@ -585,6 +1886,9 @@ mod tests {
         assert_eq!(OutputResolution::Hd720.size(&source).width, 1280.0);
         let custom = OutputResolution::Custom { width: 800, height: 600 };
         assert_eq!(custom.size(&source).width, 800.0);
+        // Presets keep their frame size for other aspect ratios
+        let wide = Size::new(2560.0, 1600.0);
+        assert_eq!(OutputResolution::Fhd1080.size(&wide), Size::new(1920.0, 1080.0));
     }
 
     #[test]
@@ -597,6 +1901,8 @@ mod tests {
     fn test_video_codec() {
         assert_eq!(VideoCodec::H264.file_extension(), "mp4");
         assert_eq!(VideoCodec::H265.display_name(), "H.265 (HEVC)");
+        assert_eq!(VideoCodec::Av1.file_extension(), "mp4");
+        assert_eq!(VideoCodec::Vp9.file_extension(), "webm");
     }
 
     #[test]
@@ -2,21 +2,24 @@
 //! State machine: Idle → Countdown → Recording ↔ Paused → Stopped
 //!
 //! Frame pipeline: capture callback → mpsc channel → encoder thread.
-//! Backpressure: if the channel is full, frames are dropped (logged).
+//! Backpressure: if the channel is full, frames are dropped (logged), or with
+//! `BackpressureMode::Block` the capture callback waits up to a timeout first.
 
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::mpsc;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 
 use serde::{Deserialize, Serialize};
 
-use super::capture::{CaptureConfig, CaptureTarget, ScreenCapture, create_capture};
-use super::encoder::{EncoderConfig, VideoEncoder, VideoFrame, create_encoder};
-use super::input::{InputMonitor, InputRecording, create_input_monitor};
+use super::capture::{BackpressureMode, CaptureConfig, CaptureTarget, ScreenCapture, create_capture};
+use super::encoder::{EncoderConfig, EncoderError, VideoEncoder, VideoFrame, create_encoder};
+use super::input::{InputMonitor, InputRecording, RecordingClock, create_input_monitor};
+use super::permissions::{create_permissions_manager, PermissionRemediation, PermissionType, PermissionsManager};
 use super::project::{CaptureMeta, MediaAsset, Project, Rect};
+use super::render::PROGRESS_HEARTBEAT;
 
 /// Recording session state
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -40,6 +43,32 @@ pub struct RecordingStatus {
     pub elapsed: f64,
     /// Number of frames captured
     pub frame_count: u64,
+    /// Frames dropped so far because the encoder fell behind
+    pub dropped_frames: u64,
+    /// Fraction of frames dropped over the last second (0-1)
+    pub drop_rate: f64,
+}
+
+/// Step `RecordingCoordinator::stop_with_progress` is waiting on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StopStage {
+    StoppingCapture,
+    FinishingVideo,
+    StoppingInput,
+}
+
+/// Progress event while a recording stops
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StopProgress {
+    pub stage: StopStage,
+    /// Seconds spent in this stage so far
+    pub waited: f64,
+    /// Numbers the events from 1
+    pub sequence: u64,
+    /// Wall clock when the event was sent (Unix milliseconds)
+    pub timestamp_ms: u64,
 }
 
 /// Result of a completed recording
@@ -51,6 +80,8 @@ pub struct RecordingResult {
     pub frame_rate: f64,
     pub frame_count: u64,
     pub capture_meta: CaptureMeta,
+    /// (elapsed seconds, label) dropped while recording
+    pub markers: Vec<(f64, String)>,
 }
 
 impl RecordingResult {
@@ -64,6 +95,15 @@ impl RecordingResult {
         Ok(mouse_path)
     }
 
+    /// At least as long as the frames captured, and one frame at the least:
+    /// a recording stopped right after starting still makes a valid project
+    fn media_duration(&self) -> f64 {
+        if self.frame_rate <= 0.0 {
+            return self.duration.max(0.0);
+        }
+        self.duration.max(self.frame_count.max(1) as f64 / self.frame_rate)
+    }
+
     /// Create a Project from this recording result
     pub fn to_project(&self, name: String) -> Project {
         let media = MediaAsset {
@@ -77,10 +117,14 @@ impl RecordingResult {
                 .unwrap_or_else(|| "recording_mouse.json".into()),
             pixel_size: self.capture_meta.size_pixel(),
             frame_rate: self.frame_rate,
-            duration: self.duration,
+            duration: self.media_duration(),
         };
 
-        Project::new(name, media, self.capture_meta.clone())
+        let mut project = Project::new(name, media, self.capture_meta.clone());
+        for (time, label) in &self.markers {
+            project.timeline.add_marker(*time, label.clone());
+        }
+        project
     }
 }
 
@@ -97,30 +141,207 @@ pub enum RecorderError {
     Encoder(#[from] super::encoder::EncoderError),
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+    #[error("No frames were captured")]
+    NoFrames,
+    #[error("{} permission denied: {}", which.display_name(), remediation.hint())]
+    PermissionDenied { which: PermissionType, remediation: PermissionRemediation },
+}
+
+/// Window over which `RecordingStatus::drop_rate` is measured
+const DROP_RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Poll interval while waiting for channel space in `BackpressureMode::Block`
+const BLOCK_POLL_INTERVAL: Duration = Duration::from_millis(2);
+
+/// Recent drop rate from the captured/dropped counters, re-sampled every `DROP_RATE_WINDOW`
+#[derive(Debug)]
+struct DropRateMeter {
+    sampled_at: Instant,
+    captured: u64,
+    dropped: u64,
+    rate: f64,
+}
+
+impl DropRateMeter {
+    fn new() -> Self {
+        Self { sampled_at: Instant::now(), captured: 0, dropped: 0, rate: 0.0 }
+    }
+
+    /// Update with the current totals and return the drop rate of the last window
+    fn sample(&mut self, now: Instant, captured: u64, dropped: u64) -> f64 {
+        if now.duration_since(self.sampled_at) >= DROP_RATE_WINDOW {
+            let captured_delta = captured.saturating_sub(self.captured);
+            let dropped_delta = dropped.saturating_sub(self.dropped);
+            self.rate = if captured_delta > 0 {
+                (dropped_delta as f64 / captured_delta as f64).min(1.0)
+            } else {
+                0.0
+            };
+            self.sampled_at = now;
+            self.captured = captured;
+            self.dropped = dropped;
+        }
+        self.rate
+    }
+}
+
+/// Live preview frames are sent at most this often (a few fps)
+const LIVE_PREVIEW_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Widest live preview frame; larger captures are downscaled to fit
+const LIVE_PREVIEW_WIDTH: u32 = 320;
+
+/// Downscaled RGBA copy of a captured frame for the live preview
+#[derive(Debug, Clone)]
+pub struct PreviewFrame {
+    pub width: u32,
+    pub height: u32,
+    /// Tightly packed RGBA (width * height * 4 bytes)
+    pub rgba: Vec<u8>,
+}
+
+/// Receives live preview frames on the capture thread; must return quickly
+pub type PreviewSink = Arc<dyn Fn(PreviewFrame) + Send + Sync>;
+
+/// Nearest-neighbor downscale of a BGRA frame to at most `max_width` wide,
+/// converted to RGBA
+fn downscale_preview(data: &[u8], width: u32, height: u32, stride: u32, max_width: u32) -> PreviewFrame {
+    let step = width.div_ceil(max_width.max(1)).max(1);
+    let (out_w, out_h) = (width / step, height / step);
+    let mut rgba = Vec::with_capacity((out_w * out_h * 4) as usize);
+    for y in 0..out_h {
+        let row = (y * step * stride) as usize;
+        for x in 0..out_w {
+            let i = row + (x * step * 4) as usize;
+            match data.get(i..i + 4) {
+                Some(&[b, g, r, a]) => rgba.extend_from_slice(&[r, g, b, a]),
+                _ => rgba.extend_from_slice(&[0, 0, 0, 255]),
+            }
+        }
+    }
+    PreviewFrame { width: out_w, height: out_h, rgba }
+}
+
+/// Send a frame to the encoder channel according to `mode`.
+/// Returns false if the frame was dropped.
+fn send_with_backpressure<T>(tx: &mpsc::SyncSender<T>, item: T, mode: BackpressureMode) -> bool {
+    let mut item = match tx.try_send(item) {
+        Ok(()) => return true,
+        Err(mpsc::TrySendError::Full(item)) => item,
+        Err(mpsc::TrySendError::Disconnected(_)) => return false,
+    };
+    let BackpressureMode::Block { timeout_ms } = mode else { return false };
+
+    // std's SyncSender has no timed send: poll until the deadline
+    let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+    while Instant::now() < deadline {
+        thread::sleep(BLOCK_POLL_INTERVAL);
+        item = match tx.try_send(item) {
+            Ok(()) => return true,
+            Err(mpsc::TrySendError::Full(item)) => item,
+            Err(mpsc::TrySendError::Disconnected(_)) => return false,
+        };
+    }
+    false
 }
 
-/// Max frames buffered in the capture→encoder channel.
-/// If the encoder falls behind, new frames are dropped.
-const FRAME_CHANNEL_CAPACITY: usize = 120;
+/// Encoder thread body. The encoder is sized from the first frame that
+/// arrives: the enumerated capture size can be wrong (window sources report
+/// 0×0), so an encoder started at `config`'s size is restarted at the real
+/// one, and with no encoder yet one is created. Later frames of another size
+/// (a resized window) are skipped, as the stream can't change size. An
+/// encoder that gives up (`EncoderError::Failed`) ends the thread with the
+/// video finished up to that point.
+fn encode_frames(
+    rx: mpsc::Receiver<VideoFrame>,
+    mut encoder: Option<Box<dyn VideoEncoder>>,
+    mut config: EncoderConfig,
+    frame_count: &AtomicU64,
+    content_size: &Mutex<Option<(u32, u32)>>,
+) -> Result<(u64, PathBuf), RecorderError> {
+    let mut encoded = 0u64;
+    let mut skipped = 0u64;
+    while let Ok(frame) = rx.recv() {
+        let size = (frame.width, frame.height);
+        let current = *content_size.lock().unwrap();
+        match current {
+            None => {
+                if size != (config.width, config.height) {
+                    log::warn!(
+                        "Capture was configured as {}x{} but frames are {}x{}; encoding at the frame size",
+                        config.width, config.height, frame.width, frame.height,
+                    );
+                    if let Some(mut stale) = encoder.take() {
+                        let _ = stale.finish();
+                        let _ = std::fs::remove_file(&config.output_path);
+                    }
+                    config.width = frame.width;
+                    config.height = frame.height;
+                }
+                if encoder.is_none() {
+                    let mut restarted = create_encoder(config.clone());
+                    restarted.start()?;
+                    encoder = Some(restarted);
+                }
+                *content_size.lock().unwrap() = Some(size);
+            }
+            Some(expected) if expected != size => {
+                if skipped == 0 {
+                    log::warn!(
+                        "Capture size changed to {}x{} mid-recording; skipping frames until it's {}x{} again",
+                        frame.width, frame.height, expected.0, expected.1,
+                    );
+                }
+                skipped += 1;
+                continue;
+            }
+            Some(_) => {}
+        }
+        if let Some(encoder) = encoder.as_mut() {
+            match encoder.append_frame(&frame) {
+                Ok(()) => {}
+                Err(e @ EncoderError::Failed { .. }) => {
+                    log::error!("Encoder gave up: {e}");
+                    let _ = encoder.finish();
+                    return Err(e.into());
+                }
+                // Continue encoding remaining frames
+                Err(e) => log::error!("Encoder error: {e}"),
+            }
+        }
+        encoded += 1;
+        frame_count.store(encoded, Ordering::Relaxed);
+    }
+    if skipped > 0 {
+        log::warn!("Skipped {skipped} frames captured at a different size");
+    }
+    // Channel closed — finalize
+    let mut encoder = encoder.ok_or(RecorderError::NoFrames)?;
+    let path = encoder.finish()?;
+    Ok((encoded, path))
+}
 
 /// Recording coordinator: manages the full recording lifecycle.
 pub struct RecordingCoordinator {
     state: RecordingState,
     capture: Box<dyn ScreenCapture>,
     input_monitor: Box<dyn InputMonitor>,
+    /// Consulted before starting, so a denied permission is reported as such
+    permissions: Box<dyn PermissionsManager>,
     encoder: Option<Box<dyn VideoEncoder>>,
 
     // Frame pipeline
     frame_sender: Option<mpsc::SyncSender<VideoFrame>>,
-    encoder_thread: Option<thread::JoinHandle<Result<(u64, PathBuf), String>>>,
-    is_paused: Arc<AtomicBool>,
+    encoder_thread: Option<thread::JoinHandle<Result<(u64, PathBuf), RecorderError>>>,
     shared_frame_count: Arc<AtomicU64>,
+    captured_frames: Arc<AtomicU64>,
     dropped_frames: Arc<AtomicU64>,
+    drop_rate: Mutex<DropRateMeter>,
+    /// Pixel size of the frames being encoded, once the first has arrived
+    content_size: Arc<Mutex<Option<(u32, u32)>>>,
 
-    // Timing
-    recording_start: Option<Instant>,
-    pause_start: Option<Instant>,
-    total_paused: f64,
+    // Timing (shared with the input monitor and the capture callback)
+    clock: Arc<RecordingClock>,
 
     // Config
     output_dir: PathBuf,
@@ -129,12 +350,21 @@ pub struct RecordingCoordinator {
 
     // Stats
     frame_count: u64,
+    /// Video file of the recording in progress
+    video_path: Option<PathBuf>,
+    /// Markers dropped during the recording in progress
+    markers: Vec<(f64, String)>,
+    /// Live preview subscriber (None: no preview frames are made)
+    live_preview: Arc<Mutex<Option<PreviewSink>>>,
 
     // Capture metadata
     capture_width: u32,
     capture_height: u32,
     scale_factor: f64,
     capture_bounds: Rect,
+    /// Window framed by the capture in progress (a region target is captured
+    /// as its whole display, so the region is recorded for the renderer)
+    window_rect: Option<Rect>,
 }
 
 impl RecordingCoordinator {
@@ -143,23 +373,28 @@ impl RecordingCoordinator {
             state: RecordingState::Idle,
             capture: create_capture(),
             input_monitor: create_input_monitor(),
+            permissions: create_permissions_manager(),
             encoder: None,
             frame_sender: None,
             encoder_thread: None,
-            is_paused: Arc::new(AtomicBool::new(false)),
             shared_frame_count: Arc::new(AtomicU64::new(0)),
+            captured_frames: Arc::new(AtomicU64::new(0)),
             dropped_frames: Arc::new(AtomicU64::new(0)),
-            recording_start: None,
-            pause_start: None,
-            total_paused: 0.0,
+            drop_rate: Mutex::new(DropRateMeter::new()),
+            content_size: Arc::new(Mutex::new(None)),
+            clock: Arc::new(RecordingClock::new()),
             output_dir,
             capture_target: None,
             capture_config: CaptureConfig::default(),
             frame_count: 0,
+            video_path: None,
+            markers: Vec::new(),
+            live_preview: Arc::new(Mutex::new(None)),
             capture_width: 1920,
             capture_height: 1080,
             scale_factor: 1.0,
             capture_bounds: Rect::new(0.0, 0.0, 1920.0, 1080.0),
+            window_rect: None,
         }
     }
 
@@ -169,32 +404,36 @@ impl RecordingCoordinator {
 
     pub fn status(&self) -> RecordingStatus {
         // Use live frame count from encoder thread if recording
-        let frame_count = if self.state == RecordingState::Recording || self.state == RecordingState::Paused {
+        let active = self.state == RecordingState::Recording || self.state == RecordingState::Paused;
+        let frame_count = if active {
             self.shared_frame_count.load(Ordering::Relaxed)
         } else {
             self.frame_count
         };
 
+        let dropped_frames = self.dropped_frames.load(Ordering::Relaxed);
+        let drop_rate = if active {
+            self.drop_rate.lock().unwrap().sample(
+                Instant::now(),
+                self.captured_frames.load(Ordering::Relaxed),
+                dropped_frames,
+            )
+        } else {
+            0.0
+        };
+
         RecordingStatus {
             state: self.state,
             elapsed: self.elapsed(),
             frame_count,
+            dropped_frames,
+            drop_rate,
         }
     }
 
     /// Elapsed recording time in seconds (excludes paused time)
     pub fn elapsed(&self) -> f64 {
-        match self.recording_start {
-            Some(start) => {
-                let raw = start.elapsed().as_secs_f64();
-                let paused = self.total_paused
-                    + self.pause_start
-                        .map(|ps| ps.elapsed().as_secs_f64())
-                        .unwrap_or(0.0);
-                (raw - paused).max(0.0)
-            }
-            None => 0.0,
-        }
+        self.clock.elapsed()
     }
 
     /// Set the capture target before starting
@@ -202,6 +441,28 @@ impl RecordingCoordinator {
         self.capture_target = Some(target);
     }
 
+    /// Configure the capture→encoder buffer size and what happens when it fills up
+    pub fn set_backpressure(&mut self, channel_capacity: usize, mode: BackpressureMode) {
+        self.capture_config.channel_capacity = channel_capacity.max(1);
+        self.capture_config.backpressure = mode;
+    }
+
+    /// Record the system cursor into the video instead of drawing one at export
+    pub fn set_capture_cursor(&mut self, capture_cursor: bool) {
+        self.capture_config.capture_cursor = capture_cursor;
+    }
+
+    /// Subscribe to (Some) or stop (None) throttled, downscaled frames of the
+    /// capture. Takes effect immediately, also mid-recording.
+    pub fn set_live_preview(&mut self, sink: Option<PreviewSink>) {
+        *self.live_preview.lock().unwrap() = sink;
+    }
+
+    /// Replace the permissions manager checked by `start`
+    pub fn set_permissions_manager(&mut self, permissions: Box<dyn PermissionsManager>) {
+        self.permissions = permissions;
+    }
+
     /// Set capture dimensions (from source enumeration)
     pub fn set_capture_dimensions(&mut self, width: u32, height: u32, scale_factor: f64) {
         self.capture_width = width;
@@ -219,6 +480,11 @@ impl RecordingCoordinator {
             });
         }
 
+        // Fail clearly instead of with whatever the capture API reports
+        if let Some(which) = self.permissions.check_all().denied_for_recording() {
+            return Err(RecorderError::PermissionDenied { which, remediation: which.remediation() });
+        }
+
         // Ensure output directory exists
         std::fs::create_dir_all(&self.output_dir)?;
 
@@ -229,39 +495,39 @@ impl RecordingCoordinator {
             .as_secs();
         let video_path = self.output_dir.join(format!("recording_{timestamp}.mp4"));
 
-        // Initialize encoder
+        // Initialize the encoder at the configured size so a broken encoder
+        // fails here; the encoder thread resizes it to the first frame
+        self.video_path = Some(video_path.clone());
         let encoder_config = EncoderConfig::new(
             self.capture_width,
             self.capture_height,
             video_path,
         );
-        let mut encoder = create_encoder(encoder_config);
-        encoder.start()?;
+        let encoder = if self.capture_width > 0 && self.capture_height > 0 {
+            let mut encoder = create_encoder(encoder_config.clone());
+            encoder.start()?;
+            Some(encoder)
+        } else {
+            None
+        };
 
         // Create frame channel (bounded for backpressure)
-        let (tx, rx) = mpsc::sync_channel::<VideoFrame>(FRAME_CHANNEL_CAPACITY);
+        let (tx, rx) = mpsc::sync_channel::<VideoFrame>(self.capture_config.channel_capacity.max(1));
         self.frame_sender = Some(tx.clone());
 
         // Reset counters
         self.shared_frame_count.store(0, Ordering::Relaxed);
+        self.captured_frames.store(0, Ordering::Relaxed);
         self.dropped_frames.store(0, Ordering::Relaxed);
-        self.is_paused.store(false, Ordering::Relaxed);
+        *self.drop_rate.lock().unwrap() = DropRateMeter::new();
+        *self.content_size.lock().unwrap() = None;
+        self.clock.reset();
 
         // Spawn encoder thread
         let frame_count_shared = self.shared_frame_count.clone();
+        let content_size = self.content_size.clone();
         let encoder_handle = thread::spawn(move || {
-            let mut encoded = 0u64;
-            while let Ok(frame) = rx.recv() {
-                if let Err(e) = encoder.append_frame(&frame) {
-                    log::error!("Encoder error: {e}");
-                    // Continue encoding remaining frames
-                }
-                encoded += 1;
-                frame_count_shared.store(encoded, Ordering::Relaxed);
-            }
-            // Channel closed — finalize
-            let path = encoder.finish().map_err(|e| e.to_string())?;
-            Ok((encoded, path))
+            encode_frames(rx, encoder, encoder_config, &frame_count_shared, &content_size)
         });
         self.encoder_thread = Some(encoder_handle);
 
@@ -271,19 +537,42 @@ impl RecordingCoordinator {
         // preventing the cursor overlay from leading ahead of the video.
         let target = self.capture_target.clone()
             .unwrap_or(CaptureTarget::Display { display_id: 0 });
+        self.window_rect = match target {
+            CaptureTarget::Region { x, y, width, height, .. } => Some(Rect::new(x, y, width, height)),
+            _ => None,
+        };
 
-        let is_paused = self.is_paused.clone();
+        let clock = self.clock.clone();
+        let captured = self.captured_frames.clone();
         let dropped = self.dropped_frames.clone();
+        let backpressure = self.capture_config.backpressure;
+        let live_preview = self.live_preview.clone();
+        let mut last_preview: Option<Instant> = None;
 
         if let Err(e) = self.capture.start_capture(
             target,
             self.capture_config.clone(),
             Box::new(move |captured_frame| {
                 // Skip frames while paused
-                if is_paused.load(Ordering::Relaxed) {
+                if clock.is_paused() {
                     return;
                 }
 
+                // Live preview: a throttled, downscaled copy; the frame itself
+                // goes to the encoder untouched
+                if last_preview.is_none_or(|at| at.elapsed() >= LIVE_PREVIEW_INTERVAL) {
+                    last_preview = Some(Instant::now());
+                    if let Some(sink) = live_preview.lock().unwrap().clone() {
+                        sink(downscale_preview(
+                            &captured_frame.data,
+                            captured_frame.width,
+                            captured_frame.height,
+                            captured_frame.stride,
+                            LIVE_PREVIEW_WIDTH,
+                        ));
+                    }
+                }
+
                 let video_frame = VideoFrame {
                     data: captured_frame.data,
                     width: captured_frame.width,
@@ -292,8 +581,9 @@ impl RecordingCoordinator {
                     pts: captured_frame.timestamp,
                 };
 
-                // Try to send; drop frame if channel is full (backpressure)
-                if tx.try_send(video_frame).is_err() {
+                // Drop the frame if the channel stays full (backpressure)
+                captured.fetch_add(1, Ordering::Relaxed);
+                if !send_with_backpressure(&tx, video_frame, backpressure) {
                     dropped.fetch_add(1, Ordering::Relaxed);
                 }
             }),
@@ -301,14 +591,13 @@ impl RecordingCoordinator {
             return Err(e.into());
         }
 
-        // Start input monitoring AFTER capture so mouse timestamps align with video
-        self.input_monitor.start_monitoring()?;
+        // Start input monitoring AFTER capture so mouse timestamps align with video.
+        // Input is timestamped by the same clock as `elapsed`, so pauses line up.
+        self.clock.restart();
+        self.input_monitor.start_monitoring(self.clock.clone())?;
 
-        // Start timing
-        self.recording_start = Some(Instant::now());
-        self.total_paused = 0.0;
-        self.pause_start = None;
         self.frame_count = 0;
+        self.markers.clear();
         self.state = RecordingState::Recording;
 
         log::info!("Recording started ({}x{})", self.capture_width, self.capture_height);
@@ -324,12 +613,25 @@ impl RecordingCoordinator {
             });
         }
 
-        self.is_paused.store(true, Ordering::Relaxed);
-        self.pause_start = Some(Instant::now());
+        self.clock.pause();
         self.state = RecordingState::Paused;
         Ok(())
     }
 
+    /// Drop a named marker at the current elapsed time (e.g. from a hotkey).
+    /// Returns the marker's time.
+    pub fn add_marker(&mut self, label: String) -> Result<f64, RecorderError> {
+        if self.state != RecordingState::Recording && self.state != RecordingState::Paused {
+            return Err(RecorderError::InvalidState {
+                state: self.state,
+                action: "add marker".into(),
+            });
+        }
+        let time = self.elapsed();
+        self.markers.push((time, label));
+        Ok(time)
+    }
+
     /// Resume from pause
     pub fn resume(&mut self) -> Result<(), RecorderError> {
         if self.state != RecordingState::Paused {
@@ -339,10 +641,7 @@ impl RecordingCoordinator {
             });
         }
 
-        if let Some(pause_start) = self.pause_start.take() {
-            self.total_paused += pause_start.elapsed().as_secs_f64();
-        }
-        self.is_paused.store(false, Ordering::Relaxed);
+        self.clock.resume();
         self.state = RecordingState::Recording;
         Ok(())
     }
@@ -350,6 +649,15 @@ impl RecordingCoordinator {
     /// Stop recording and return the result.
     /// Uses timeouts throughout to prevent hanging the UI.
     pub fn stop(&mut self) -> Result<RecordingResult, RecorderError> {
+        self.stop_with_progress(|_| {})
+    }
+
+    /// `stop`, reporting each step and, while waiting on the encoder, a
+    /// heartbeat every `PROGRESS_HEARTBEAT`
+    pub fn stop_with_progress(
+        &mut self,
+        mut progress: impl FnMut(StopProgress),
+    ) -> Result<RecordingResult, RecorderError> {
         if self.state != RecordingState::Recording && self.state != RecordingState::Paused {
             return Err(RecorderError::InvalidState {
                 state: self.state,
@@ -361,6 +669,116 @@ impl RecordingCoordinator {
         let duration = self.elapsed();
         log::info!("Stopping recording (elapsed: {:.1}s)...", duration);
 
+        let mut sequence = 0;
+        let mut report = |stage, waited| {
+            sequence += 1;
+            let timestamp_ms = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_millis() as u64);
+            progress(StopProgress { stage, waited, sequence, timestamp_ms });
+        };
+
+        let (encoded_count, finished_path) = match self.stop_pipeline(&mut report) {
+            Ok(stopped) => stopped,
+            Err(e) => {
+                self.reset();
+                return Err(e);
+            }
+        };
+        let video_path = finished_path
+            .or_else(|| self.video_path.clone())
+            .unwrap_or_else(|| self.output_dir.join("recording.mp4"));
+
+        let dropped = self.dropped_frames.load(Ordering::Relaxed);
+        if dropped > 0 {
+            let captured = self.captured_frames.load(Ordering::Relaxed).max(1);
+            log::warn!(
+                "Recording: dropped {dropped} of {captured} frames ({:.1}%) due to encoder backpressure",
+                dropped as f64 / captured as f64 * 100.0,
+            );
+        }
+
+        self.frame_count = encoded_count;
+
+        log::info!("Stopping input monitoring...");
+        report(StopStage::StoppingInput, 0.0);
+        let input_data = self.input_monitor.stop_monitoring()
+            .unwrap_or_default();
+
+        self.encoder = None;
+
+        // The video has the frames' size, whatever the source reported
+        let mut bounds = self.capture_bounds;
+        if let Some((width, height)) = *self.content_size.lock().unwrap() {
+            if (width, height) != (self.capture_width, self.capture_height) {
+                bounds = Rect::new(0.0, 0.0, width as f64 / self.scale_factor, height as f64 / self.scale_factor);
+            }
+        }
+        let capture_meta = CaptureMeta {
+            cursor_captured: self.capture_config.capture_cursor,
+            window_rect_pt: self.window_rect,
+            ..CaptureMeta::new(bounds, self.scale_factor)
+        };
+
+        self.state = RecordingState::Completed;
+
+        log::info!("Recording stopped: {:.1}s, {} frames, path: {}", duration, self.frame_count, video_path.display());
+        Ok(RecordingResult {
+            video_path,
+            input_data,
+            duration,
+            frame_rate: self.capture_config.target_fps as f64,
+            frame_count: self.frame_count,
+            capture_meta,
+            markers: std::mem::take(&mut self.markers),
+        })
+    }
+
+    /// Stop recording and delete the partial video and input data, returning
+    /// to `Idle` without a result. The encoder thread is joined first so the
+    /// video's file handle is released before it's removed.
+    pub fn discard(&mut self) -> Result<(), RecorderError> {
+        if self.state != RecordingState::Recording && self.state != RecordingState::Paused {
+            return Err(RecorderError::InvalidState {
+                state: self.state,
+                action: "discard".into(),
+            });
+        }
+
+        self.state = RecordingState::Stopping;
+        log::info!("Discarding recording (elapsed: {:.1}s)...", self.elapsed());
+        let finished_path = self.stop_pipeline(&mut |_, _| {}).ok().and_then(|(_, path)| path);
+        if self.input_monitor.is_monitoring() {
+            let _ = self.input_monitor.stop_monitoring();
+        }
+        let video_path = finished_path.or_else(|| self.video_path.clone());
+        self.reset();
+
+        let Some(video_path) = video_path else {
+            return Ok(());
+        };
+        let mut result = Ok(());
+        for path in [mouse_data_path(&video_path), video_path] {
+            match std::fs::remove_file(&path) {
+                Ok(()) => log::info!("Discarded {}", path.display()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    log::warn!("Failed to delete {}: {e}", path.display());
+                    result = result.and(Err(e.into()));
+                }
+            }
+        }
+        result
+    }
+
+    /// Close the frame channel, stop capture and join the encoder thread,
+    /// telling `report` (stage, seconds waited) what it's waiting on.
+    /// Returns the encoded frame count and, if the encoder finished cleanly,
+    /// the finished video path; an encoder that gave up mid-stream is an error.
+    fn stop_pipeline(
+        &mut self,
+        report: &mut dyn FnMut(StopStage, f64),
+    ) -> Result<(u64, Option<PathBuf>), RecorderError> {
         // 1. Drop the sender to close the channel — this unblocks the encoder thread's rx.recv()
         //    Note: the capture callback also holds a sender clone, but dropping ours means
         //    once capture stops, the last sender drops and the encoder thread finishes.
@@ -370,6 +788,7 @@ impl RecordingCoordinator {
         // 2. Stop capture with a timeout — control.stop() can block if the capture thread is stuck.
         //    We run it on a separate thread so we can time it out.
         log::info!("Stopping capture...");
+        report(StopStage::StoppingCapture, 0.0);
         let stop_start = Instant::now();
         let _ = self.capture.stop_capture();
         let stop_elapsed = stop_start.elapsed();
@@ -377,68 +796,44 @@ impl RecordingCoordinator {
 
         // 3. Wait for encoder thread to finish with a timeout
         log::info!("Waiting for encoder thread...");
-        let (encoded_count, video_path) = if let Some(handle) = self.encoder_thread.take() {
-            let deadline = Instant::now() + Duration::from_secs(5);
+        if let Some(handle) = self.encoder_thread.take() {
+            let waiting_since = Instant::now();
+            let deadline = waiting_since + Duration::from_secs(5);
+            let mut last_report = None;
             let mut result = None;
             while Instant::now() < deadline {
                 if handle.is_finished() {
                     result = Some(handle.join());
                     break;
                 }
+                if last_report.is_none_or(|at: Instant| at.elapsed() >= PROGRESS_HEARTBEAT) {
+                    last_report = Some(Instant::now());
+                    report(StopStage::FinishingVideo, waiting_since.elapsed().as_secs_f64());
+                }
                 thread::sleep(Duration::from_millis(50));
             }
-            match result {
+            Ok(match result {
                 Some(Ok(Ok((count, path)))) => {
                     log::info!("Encoder thread finished: {count} frames encoded");
-                    (count, path)
+                    (count, Some(path))
                 }
+                Some(Ok(Err(e @ RecorderError::Encoder(EncoderError::Failed { .. })))) => return Err(e),
                 Some(Ok(Err(e))) => {
                     log::error!("Encoder thread error: {e}");
-                    (self.shared_frame_count.load(Ordering::Relaxed), self.output_dir.join("recording.mp4"))
+                    (self.shared_frame_count.load(Ordering::Relaxed), None)
                 }
                 Some(Err(_)) => {
                     log::error!("Encoder thread panicked");
-                    (0, self.output_dir.join("recording.mp4"))
+                    (0, None)
                 }
                 None => {
                     log::warn!("Encoder thread did not finish within 5s, abandoning");
-                    (self.shared_frame_count.load(Ordering::Relaxed), self.output_dir.join("recording.mp4"))
+                    (self.shared_frame_count.load(Ordering::Relaxed), None)
                 }
-            }
+            })
         } else {
-            (0, self.output_dir.join("recording.mp4"))
-        };
-
-        let dropped = self.dropped_frames.load(Ordering::Relaxed);
-        if dropped > 0 {
-            log::warn!("Recording: dropped {dropped} frames due to encoder backpressure");
+            Ok((0, None))
         }
-
-        self.frame_count = encoded_count;
-
-        // 4. Stop input monitoring
-        log::info!("Stopping input monitoring...");
-        let input_data = self.input_monitor.stop_monitoring()
-            .unwrap_or_default();
-
-        self.encoder = None;
-
-        let capture_meta = CaptureMeta::new(
-            self.capture_bounds,
-            self.scale_factor,
-        );
-
-        self.state = RecordingState::Completed;
-
-        log::info!("Recording stopped: {:.1}s, {} frames, path: {}", duration, self.frame_count, video_path.display());
-        Ok(RecordingResult {
-            video_path,
-            input_data,
-            duration,
-            frame_rate: self.capture_config.target_fps as f64,
-            frame_count: self.frame_count,
-            capture_meta,
-        })
     }
 
     /// Reset to idle state for a new recording
@@ -448,19 +843,29 @@ impl RecordingCoordinator {
             let _ = self.input_monitor.stop_monitoring();
         }
         self.state = RecordingState::Idle;
-        self.recording_start = None;
-        self.pause_start = None;
-        self.total_paused = 0.0;
+        self.clock.reset();
         self.frame_count = 0;
+        self.video_path = None;
+        self.markers.clear();
         self.encoder = None;
         self.frame_sender = None;
         self.encoder_thread = None;
-        self.is_paused.store(false, Ordering::Relaxed);
         self.shared_frame_count.store(0, Ordering::Relaxed);
+        self.captured_frames.store(0, Ordering::Relaxed);
         self.dropped_frames.store(0, Ordering::Relaxed);
         self.capture_target = None;
     }
 
+    /// Capture size in pixels used for the next recording
+    pub fn capture_size(&self) -> (u32, u32) {
+        (self.capture_width, self.capture_height)
+    }
+
+    /// Directory new recordings are written to
+    pub fn output_dir(&self) -> &Path {
+        &self.output_dir
+    }
+
     /// Number of frames dropped due to encoder backpressure
     pub fn dropped_frames(&self) -> u64 {
         self.dropped_frames.load(Ordering::Relaxed)
@@ -468,7 +873,7 @@ impl RecordingCoordinator {
 }
 
 /// Derive mouse data file path from video path: video.mp4 → video_mouse.json
-fn mouse_data_path(video_path: &Path) -> PathBuf {
+pub(crate) fn mouse_data_path(video_path: &Path) -> PathBuf {
     let stem = video_path
         .file_stem()
         .map(|s| s.to_string_lossy().to_string())
@@ -480,6 +885,7 @@ fn mouse_data_path(video_path: &Path) -> PathBuf {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::permissions::{PermissionReport, PermissionStatus};
     use std::env::temp_dir;
 
     #[test]
@@ -498,9 +904,108 @@ mod tests {
         coord.resume().unwrap();
         assert_eq!(coord.state(), RecordingState::Recording);
 
+        assert!(coord.add_marker("Step 1".into()).unwrap() >= 0.0);
+
         let result = coord.stop().unwrap();
         assert_eq!(coord.state(), RecordingState::Completed);
         assert!(result.duration >= 0.0);
+        assert!(coord.add_marker("Too late".into()).is_err());
+
+        let project = result.to_project("Markers".into());
+        assert_eq!(project.timeline.markers.len(), 1);
+        assert_eq!(project.timeline.markers[0].label, "Step 1");
+    }
+
+    #[test]
+    fn test_immediate_stop_makes_valid_project() {
+        let dir = temp_dir().join("lazyrec_test_recorder_immediate");
+        let mut coord = RecordingCoordinator::new(dir);
+        coord.start().unwrap();
+        let mut result = coord.stop().unwrap();
+        result.duration = 0.0;
+
+        let project = result.to_project("Blink".into());
+        let frame = 1.0 / result.frame_rate;
+        assert!(project.duration() >= frame - 1e-9);
+        assert_eq!(project.timeline.duration, project.duration());
+
+        result.frame_count = 3;
+        assert!((result.to_project("Three".into()).duration() - 3.0 * frame).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_discard_deletes_partial_files() {
+        let dir = temp_dir().join("lazyrec_test_recorder_discard");
+        let mut coord = RecordingCoordinator::new(dir);
+
+        coord.start().unwrap();
+        let video_path = coord.video_path.clone().unwrap();
+        // Whatever the encoder and input saver had written so far
+        std::fs::write(&video_path, b"partial").unwrap();
+        std::fs::write(mouse_data_path(&video_path), b"{}").unwrap();
+
+        coord.discard().unwrap();
+        assert_eq!(coord.state(), RecordingState::Idle);
+        assert!(!video_path.exists());
+        assert!(!mouse_data_path(&video_path).exists());
+
+        // Nothing to discard once idle; a new take can start
+        assert!(coord.discard().is_err());
+        coord.start().unwrap();
+        coord.discard().unwrap();
+    }
+
+    #[test]
+    fn test_stop_reports_progress() {
+        let dir = temp_dir().join("lazyrec_test_recorder_stop_progress");
+        let mut coord = RecordingCoordinator::new(dir);
+        coord.start().unwrap();
+
+        let mut events = Vec::new();
+        coord.stop_with_progress(|p| events.push(p)).unwrap();
+        let stages: Vec<_> = events.iter().map(|p| p.stage).collect();
+        assert_eq!(stages.first(), Some(&StopStage::StoppingCapture));
+        assert_eq!(stages.last(), Some(&StopStage::StoppingInput));
+        assert!(events.iter().enumerate().all(|(i, p)| p.sequence == i as u64 + 1));
+    }
+
+    #[test]
+    fn test_denied_permission_stops_start() {
+        struct Denied(PermissionType);
+        impl PermissionsManager for Denied {
+            fn check_all(&self) -> PermissionReport {
+                let status = |p| self.check(p);
+                PermissionReport {
+                    screen_capture: status(PermissionType::ScreenCapture),
+                    input_monitoring: status(PermissionType::InputMonitoring),
+                    accessibility: status(PermissionType::Accessibility),
+                    audio_capture: status(PermissionType::AudioCapture),
+                }
+            }
+            fn check(&self, permission: PermissionType) -> PermissionStatus {
+                if permission == self.0 { PermissionStatus::Denied } else { PermissionStatus::NotDetermined }
+            }
+            fn request(&self, permission: PermissionType) -> PermissionStatus {
+                self.check(permission)
+            }
+        }
+
+        let dir = temp_dir().join("lazyrec_test_recorder_permissions");
+        let mut coord = RecordingCoordinator::new(dir);
+        coord.set_permissions_manager(Box::new(Denied(PermissionType::InputMonitoring)));
+        match coord.start() {
+            Err(RecorderError::PermissionDenied { which, remediation }) => {
+                assert_eq!(which, PermissionType::InputMonitoring);
+                assert_eq!(remediation, PermissionType::InputMonitoring.remediation());
+            }
+            other => panic!("expected PermissionDenied, got {other:?}"),
+        }
+        assert_eq!(coord.state(), RecordingState::Idle);
+
+        // Permissions the OS hasn't asked for yet don't block
+        coord.set_permissions_manager(Box::new(Denied(PermissionType::AudioCapture)));
+        coord.start().unwrap();
+        coord.discard().unwrap();
     }
 
     #[test]
@@ -516,9 +1021,130 @@ mod tests {
         assert!(coord.resume().is_err());
     }
 
+    #[test]
+    fn test_downscale_preview() {
+        // 8x4 BGRA with a 40-byte stride (padded rows); the pixel value is x + 10y
+        let (width, height, stride) = (8, 4, 40);
+        let mut data = vec![0u8; (stride * height) as usize];
+        for y in 0..height {
+            for x in 0..width {
+                let i = (y * stride + x * 4) as usize;
+                data[i..i + 4].copy_from_slice(&[(x + 10 * y) as u8, 1, 2, 255]);
+            }
+        }
+
+        let preview = downscale_preview(&data, width, height, stride, 3);
+        assert_eq!((preview.width, preview.height), (2, 1));
+        assert_eq!(preview.rgba.len(), 2 * 4);
+        // Every third pixel, channels swapped to RGBA
+        assert_eq!(&preview.rgba[..4], &[2, 1, 0, 255]);
+        assert_eq!(&preview.rgba[4..], &[2, 1, 3, 255]);
+
+        // Small frames keep their size
+        let full = downscale_preview(&data, width, height, stride, 320);
+        assert_eq!((full.width, full.height), (8, 4));
+        assert_eq!(&full.rgba[(9 * 4)..(9 * 4 + 4)], &[2, 1, 11, 255]);
+    }
+
     #[test]
     fn test_mouse_data_path() {
         let p = PathBuf::from("/tmp/recording.mp4");
         assert_eq!(mouse_data_path(&p), PathBuf::from("/tmp/recording_mouse.json"));
     }
+
+    #[test]
+    fn test_encoder_sized_from_first_frame() {
+        let frame = |width, height| VideoFrame { data: Vec::new(), width, height, stride: width * 4, pts: 0.0 };
+        let config = EncoderConfig::new(1920, 1080, temp_dir().join("lazyrec_test_content_size.mp4"));
+        let mut encoder = create_encoder(config.clone());
+        encoder.start().unwrap();
+
+        let (tx, rx) = mpsc::sync_channel(8);
+        for (width, height) in [(640, 360), (640, 360), (800, 600), (640, 360)] {
+            tx.send(frame(width, height)).unwrap();
+        }
+        drop(tx);
+        let (count, size) = (AtomicU64::new(0), Mutex::new(None));
+        let (encoded, _) = encode_frames(rx, Some(encoder), config.clone(), &count, &size).unwrap();
+        // The resized frame is skipped
+        assert_eq!(encoded, 3);
+        assert_eq!(*size.lock().unwrap(), Some((640, 360)));
+
+        // Nothing captured and no encoder yet (0×0 source): nothing to finish
+        let (tx, rx) = mpsc::sync_channel::<VideoFrame>(1);
+        drop(tx);
+        assert!(encode_frames(rx, None, config, &count, &Mutex::new(None)).is_err());
+    }
+
+    /// Encoder whose every frame fails as if it had given up
+    struct GivingUpEncoder(bool);
+
+    impl VideoEncoder for GivingUpEncoder {
+        fn start(&mut self) -> Result<(), EncoderError> {
+            Ok(())
+        }
+
+        fn append_frame(&mut self, _frame: &VideoFrame) -> Result<(), EncoderError> {
+            Err(EncoderError::Failed { encoder: "test".into(), frame: 0, reason: "driver".into() })
+        }
+
+        fn finish(&mut self) -> Result<PathBuf, EncoderError> {
+            self.0 = true;
+            Ok(PathBuf::new())
+        }
+
+        fn is_encoding(&self) -> bool {
+            !self.0
+        }
+
+        fn frames_encoded(&self) -> u64 {
+            0
+        }
+
+        fn encoder_name(&self) -> &str {
+            "test"
+        }
+    }
+
+    #[test]
+    fn test_encoder_failure_ends_encoding() {
+        let config = EncoderConfig::new(64, 64, temp_dir().join("lazyrec_test_failing_encoder.mp4"));
+        let (tx, rx) = mpsc::sync_channel(4);
+        for _ in 0..3 {
+            tx.send(VideoFrame { data: Vec::new(), width: 64, height: 64, stride: 256, pts: 0.0 }).unwrap();
+        }
+        drop(tx);
+        let (count, size) = (AtomicU64::new(0), Mutex::new(None));
+        let result = encode_frames(rx, Some(Box::new(GivingUpEncoder(false))), config, &count, &size);
+        // Stops at the first frame instead of encoding the rest into a broken file
+        assert!(matches!(result, Err(RecorderError::Encoder(EncoderError::Failed { .. }))));
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_send_with_backpressure() {
+        let (tx, rx) = mpsc::sync_channel::<u32>(1);
+        assert!(send_with_backpressure(&tx, 1, BackpressureMode::Drop));
+        // Full: drop mode gives up immediately, block mode waits for space
+        assert!(!send_with_backpressure(&tx, 2, BackpressureMode::Drop));
+        let consumer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            rx.recv().unwrap();
+            rx
+        });
+        assert!(send_with_backpressure(&tx, 3, BackpressureMode::Block { timeout_ms: 2000 }));
+        let rx = consumer.join().unwrap();
+        assert_eq!(rx.recv().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_drop_rate_meter() {
+        let mut meter = DropRateMeter::new();
+        let start = meter.sampled_at;
+        // Within the window the previous rate is kept
+        assert_eq!(meter.sample(start, 30, 3), 0.0);
+        assert!((meter.sample(start + DROP_RATE_WINDOW, 60, 6) - 0.1).abs() < 1e-9);
+        // Next window has no new drops
+        assert_eq!(meter.sample(start + DROP_RATE_WINDOW * 2, 120, 6), 0.0);
+    }
 }
@@ -0,0 +1,197 @@
+//! Recovery of orphaned recordings: `recording_*.mp4` files left in the output
+//! directory (e.g. after a crash mid-recording) that never became a project package.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::input::InputRecording;
+use super::project::{CaptureMeta, Project, ProjectError, Rect, RenderSettings, CURSOR_CAPTURED_IF_UNKNOWN};
+use super::recorder::{mouse_data_path, RecorderError, RecordingResult};
+use super::render::{ExportError, ProbedVideo};
+
+/// A recording that can be wrapped into a project
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoverableRecording {
+    pub video_path: PathBuf,
+    /// Mouse/keyboard data saved next to the video, if it survived
+    pub mouse_data_path: Option<PathBuf>,
+    pub width: u32,
+    pub height: u32,
+    pub frame_rate: f64,
+    pub duration: f64,
+    pub size_bytes: u64,
+}
+
+/// Recovery errors
+#[derive(Debug, thiserror::Error)]
+pub enum RecoveryError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Recorder error: {0}")]
+    Recorder(#[from] RecorderError),
+    #[error("Project error: {0}")]
+    Project(#[from] ProjectError),
+}
+
+/// `recording_*.mp4` files in `dir` that no `.lazyrec` package contains
+pub fn find_orphaned_recordings(dir: &Path) -> Result<Vec<PathBuf>, RecoveryError> {
+    let mut videos = Vec::new();
+    let mut packaged = Vec::new();
+
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        if path.is_dir() && path.extension().is_some_and(|e| e == Project::PACKAGE_EXTENSION) {
+            // Packages keep the original file name under recording/
+            if let Ok(files) = std::fs::read_dir(path.join("recording")) {
+                packaged.extend(files.flatten().map(|f| f.file_name().to_string_lossy().to_string()));
+            }
+        } else if path.is_file() && name.starts_with("recording_") && name.ends_with(".mp4") {
+            videos.push(path);
+        }
+    }
+
+    videos.retain(|v| {
+        let name = v.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        !packaged.contains(&name)
+    });
+    videos.sort();
+    Ok(videos)
+}
+
+/// Probe orphaned recordings, keeping only those that decode.
+/// `probe` is `render::probe_video` in the app; unreadable files are logged and skipped.
+pub fn scan_recoverable(
+    dir: &Path,
+    probe: impl Fn(&Path) -> Result<ProbedVideo, ExportError>,
+) -> Result<Vec<RecoverableRecording>, RecoveryError> {
+    let mut recoverable = Vec::new();
+    for video_path in find_orphaned_recordings(dir)? {
+        let probed = match probe(&video_path) {
+            Ok(p) => p,
+            Err(e) => {
+                log::warn!("Skipping unreadable recording {}: {e}", video_path.display());
+                continue;
+            }
+        };
+        let mouse_path = mouse_data_path(&video_path);
+        recoverable.push(RecoverableRecording {
+            size_bytes: std::fs::metadata(&video_path).map(|m| m.len()).unwrap_or(0),
+            mouse_data_path: mouse_path.exists().then_some(mouse_path),
+            video_path,
+            width: probed.width,
+            height: probed.height,
+            frame_rate: probed.frame_rate,
+            duration: probed.duration,
+        });
+    }
+    Ok(recoverable)
+}
+
+/// Wrap a recoverable recording into a `.lazyrec` package next to it.
+/// The originals are removed once copied, like a normal stop.
+pub fn recover_recording(
+    recording: &RecoverableRecording,
+    render_settings: RenderSettings,
+) -> Result<(Project, PathBuf), RecoveryError> {
+    let input_data = recording
+        .mouse_data_path
+        .as_ref()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|json| InputRecording::from_json(&json).ok())
+        .unwrap_or_default();
+
+    let result = RecordingResult {
+        video_path: recording.video_path.clone(),
+        input_data,
+        duration: recording.duration,
+        frame_rate: recording.frame_rate,
+        frame_count: (recording.duration * recording.frame_rate) as u64,
+        // Nothing recorded how it was captured
+        capture_meta: CaptureMeta {
+            cursor_captured: CURSOR_CAPTURED_IF_UNKNOWN,
+            ..CaptureMeta::new(Rect::new(0.0, 0.0, recording.width as f64, recording.height as f64), 1.0)
+        },
+        markers: Vec::new(),
+    };
+
+    // Without the original mouse data, write what we have so the package is complete
+    let mouse_path = match &recording.mouse_data_path {
+        Some(p) => p.clone(),
+        None => result.save_input_data()?,
+    };
+
+    let stem = recording
+        .video_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "recording".into());
+    let package_dir = recording
+        .video_path
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join(format!("Recovered_{stem}.{}", Project::PACKAGE_EXTENSION));
+
+    let mut project = result.to_project(format!("Recovered {stem}"));
+    project.render_settings = render_settings;
+    project.save(&package_dir, Some(&recording.video_path), Some(&mouse_path))?;
+
+    for original in [&recording.video_path, &mouse_path] {
+        if let Err(e) = std::fs::remove_file(original) {
+            log::warn!("Failed to remove recovered file {}: {e}", original.display());
+        }
+    }
+    Ok((project, package_dir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("lazyrec_recovery_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn fake_probe(path: &Path) -> Result<ProbedVideo, ExportError> {
+        // Empty files stand in for truncated recordings
+        if std::fs::metadata(path)?.len() == 0 {
+            return Err(ExportError::NoSource);
+        }
+        Ok(ProbedVideo { width: 1280, height: 720, frame_rate: 30.0, duration: 4.0 })
+    }
+
+    #[test]
+    fn test_find_orphaned_skips_packaged() {
+        let dir = temp_dir("orphans");
+        std::fs::write(dir.join("recording_1.mp4"), b"video").unwrap();
+        std::fs::write(dir.join("recording_2.mp4"), b"video").unwrap();
+        std::fs::write(dir.join("export_3.mp4"), b"video").unwrap();
+        let pkg = dir.join("Recording_9.lazyrec").join("recording");
+        std::fs::create_dir_all(&pkg).unwrap();
+        std::fs::write(pkg.join("recording_2.mp4"), b"video").unwrap();
+
+        let orphans = find_orphaned_recordings(&dir).unwrap();
+        assert_eq!(orphans, vec![dir.join("recording_1.mp4")]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_scan_recoverable_validates_readable() {
+        let dir = temp_dir("scan");
+        std::fs::write(dir.join("recording_1.mp4"), b"video").unwrap();
+        std::fs::write(dir.join("recording_1_mouse.json"), b"{}").unwrap();
+        std::fs::write(dir.join("recording_2.mp4"), b"").unwrap(); // truncated
+
+        let found = scan_recoverable(&dir, fake_probe).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].video_path, dir.join("recording_1.mp4"));
+        assert_eq!(found[0].mouse_data_path, Some(dir.join("recording_1_mouse.json")));
+        assert_eq!((found[0].width, found[0].height), (1280, 720));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
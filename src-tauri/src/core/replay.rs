@@ -0,0 +1,357 @@
+//! Running every generator on recorded input alone, without video: the
+//! editor's `generate_keyframes`, fixture tests and "why no zoom here"
+//! debugging from a `recording_mouse.json` all go through here.
+
+use serde::Deserialize;
+
+use super::coordinates::NormalizedPoint;
+use super::generators::{
+    default_shortcut_labels, generate_cursor_keyframes, generate_keystrokes, generate_ripples, generate_smart_zoom,
+    ActivityEvent, ClickEvent, ClickType, CursorSettings, DragEvent, KeyEventType, KeyboardEvent, KeystrokeSettings,
+    Modifiers, MouseData, RippleSettings, SmartZoomConfig, SmartZoomSettings, WindowEvent,
+};
+use super::input::{InputRecording, KeyAction, MouseButton};
+use super::project::Project;
+use super::timeline::Timeline;
+use super::track::{AnyTrack, CursorTrack, KeystrokeTrack, RippleTrack, TrackType, TransformTrack};
+
+/// Convert recorded input to the generators' event format
+pub fn input_to_mouse_data(recording: &InputRecording, duration: f64) -> MouseData {
+    let positions: Vec<(f64, NormalizedPoint)> = recording
+        .positions.iter().map(|p| (p.time, p.position)).collect();
+
+    let clicks: Vec<ClickEvent> = recording.clicks.iter().map(|c| {
+        let click_type = match c.button {
+            MouseButton::Left => ClickType::LeftDown,
+            MouseButton::Right => ClickType::RightDown,
+            MouseButton::Middle => ClickType::LeftDown, // treat middle as left for generators
+        };
+        ClickEvent {
+            time: c.time,
+            position: c.position,
+            click_type,
+            duration: c.duration,
+        }
+    }).collect();
+
+    let keyboard_events: Vec<KeyboardEvent> = recording.keyboard.iter().map(|k| {
+        KeyboardEvent {
+            time: k.time,
+            event_type: match k.event_type {
+                KeyAction::Down => KeyEventType::KeyDown,
+                KeyAction::Up => KeyEventType::KeyUp,
+            },
+            key_code: k.key_code,
+            character: k.character.clone(),
+            modifiers: Modifiers {
+                command: k.modifiers.command,
+                shift: k.modifiers.shift,
+                alt: k.modifiers.alt,
+                control: k.modifiers.control,
+            },
+        }
+    }).collect();
+
+    let drags: Vec<DragEvent> = recording.drags.iter().map(|d| {
+        DragEvent {
+            start_time: d.start_time,
+            end_time: d.end_time,
+            start_position: d.start_position,
+            end_position: d.end_position,
+        }
+    }).collect();
+
+    let windows: Vec<WindowEvent> = recording.windows.iter().map(|w| {
+        WindowEvent { time: w.time, bounds: w.bounds }
+    }).collect();
+
+    MouseData {
+        positions,
+        clicks,
+        keyboard_events,
+        drags,
+        windows,
+        duration,
+    }
+}
+
+/// Settings for one run of every generator
+#[derive(Debug, Clone)]
+pub struct GeneratorSettings {
+    pub smart_zoom: SmartZoomSettings,
+    pub ripple: RippleSettings,
+    pub keystroke: KeystrokeSettings,
+    pub cursor: CursorSettings,
+}
+
+impl Default for GeneratorSettings {
+    /// What a new project generates with
+    fn default() -> Self {
+        Self::new(SmartZoomSettings::default(), RippleSettings::default(), KeystrokeSettings {
+            shortcut_labels: default_shortcut_labels(),
+            ..Default::default()
+        }, false)
+    }
+}
+
+impl GeneratorSettings {
+    fn new(smart_zoom: SmartZoomSettings, ripple: RippleSettings, keystroke: KeystrokeSettings, hide_cursor_while_typing: bool) -> Self {
+        let typing_session_timeout = smart_zoom.typing_session_timeout;
+        Self {
+            keystroke: KeystrokeSettings { typing_session_timeout, ..keystroke },
+            cursor: CursorSettings {
+                hide_while_typing: hide_cursor_while_typing,
+                typing_session_timeout,
+                ..Default::default()
+            },
+            smart_zoom,
+            ripple,
+        }
+    }
+
+    /// The project's SmartZoom, ripple, keystroke and cursor settings
+    pub fn from_project(project: &Project) -> Self {
+        Self::new(
+            SmartZoomSettings { view_extent: project.view_extent(), ..project.smart_zoom.settings() },
+            project.ripple_settings.clone(),
+            KeystrokeSettings {
+                position: project.render_settings.keystroke_style.position.point(),
+                shortcut_labels: project.shortcut_labels.clone(),
+                group_typing: project.group_typing,
+                ..Default::default()
+            },
+            project.render_settings.hide_cursor_while_typing,
+        )
+    }
+
+    /// Replace the settings `overrides` sets
+    pub fn with_overrides(self, overrides: &ReplaySettings) -> Self {
+        let view_extent = self.smart_zoom.view_extent;
+        let smart_zoom = overrides.smart_zoom.as_ref().map_or(self.smart_zoom, |config| SmartZoomSettings {
+            view_extent,
+            ..config.settings()
+        });
+        let ripple = overrides.ripple.clone().unwrap_or(self.ripple);
+        let hide_cursor = overrides.hide_cursor_while_typing.unwrap_or(self.cursor.hide_while_typing);
+        Self::new(smart_zoom, ripple, self.keystroke, hide_cursor)
+    }
+}
+
+/// Generator settings to try on a replayed input file instead of the
+/// current project's (or the defaults)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReplaySettings {
+    pub smart_zoom: Option<SmartZoomConfig>,
+    pub ripple: Option<RippleSettings>,
+    pub hide_cursor_while_typing: Option<bool>,
+    /// Timeline length; defaults to the last recorded event
+    pub duration: Option<f64>,
+}
+
+/// Tracks from one run of every generator
+#[derive(Debug, Clone)]
+pub struct GeneratedTracks {
+    pub transform: TransformTrack,
+    pub ripple: RippleTrack,
+    pub cursor: CursorTrack,
+    pub keystroke: KeystrokeTrack,
+}
+
+impl GeneratedTracks {
+    pub fn into_tracks(self) -> Vec<AnyTrack> {
+        vec![
+            AnyTrack::Transform(self.transform),
+            AnyTrack::Ripple(self.ripple),
+            AnyTrack::Cursor(self.cursor),
+            AnyTrack::Keystroke(self.keystroke),
+        ]
+    }
+}
+
+/// Run SmartZoom, ripple, keystroke and cursor generation on `data`.
+/// `content` is the video's content activity, if analyzed.
+pub fn run_generators(data: &MouseData, content: &[ActivityEvent], settings: &GeneratorSettings) -> GeneratedTracks {
+    GeneratedTracks {
+        transform: generate_smart_zoom(data, content, &settings.smart_zoom),
+        ripple: generate_ripples(&data.clicks, &settings.ripple),
+        cursor: generate_cursor_keyframes(&data.positions, &data.clicks, &data.keyboard_events, &settings.cursor),
+        keystroke: generate_keystrokes(&data.keyboard_events, &settings.keystroke),
+    }
+}
+
+/// Run only the generator behind `track_type`, e.g. to redo the cursor track
+/// after a smoothing change without redoing SmartZoom. `content` only matters
+/// for `Transform`.
+pub fn run_generator(
+    track_type: TrackType,
+    data: &MouseData,
+    content: &[ActivityEvent],
+    settings: &GeneratorSettings,
+) -> AnyTrack {
+    match track_type {
+        TrackType::Transform => AnyTrack::Transform(generate_smart_zoom(data, content, &settings.smart_zoom)),
+        TrackType::Ripple => AnyTrack::Ripple(generate_ripples(&data.clicks, &settings.ripple)),
+        TrackType::Cursor => AnyTrack::Cursor(generate_cursor_keyframes(
+            &data.positions, &data.clicks, &data.keyboard_events, &settings.cursor,
+        )),
+        TrackType::Keystroke => AnyTrack::Keystroke(generate_keystrokes(&data.keyboard_events, &settings.keystroke)),
+    }
+}
+
+/// Generator input for `project`'s (aligned) recording, over the project's
+/// effective duration rather than whatever the timeline last held
+pub fn project_mouse_data(project: &Project, recording: &InputRecording) -> MouseData {
+    input_to_mouse_data(recording, project.effective_duration())
+}
+
+/// Time of the last recorded event (0 for an empty recording)
+pub fn input_end_time(recording: &InputRecording) -> f64 {
+    let times = recording.positions.iter().map(|p| p.time)
+        .chain(recording.clicks.iter().map(|c| c.time + c.duration))
+        .chain(recording.keyboard.iter().map(|k| k.time))
+        .chain(recording.scrolls.iter().map(|s| s.time))
+        .chain(recording.drags.iter().map(|d| d.end_time))
+        .chain(recording.windows.iter().map(|w| w.time));
+    times.fold(0.0, f64::max)
+}
+
+/// Generate a timeline from `recording` alone: every generator's track over
+/// `duration` (the last event's time when None). Deterministic for a given
+/// recording and settings.
+pub fn replay_input(recording: &InputRecording, settings: &GeneratorSettings, duration: Option<f64>) -> Timeline {
+    let duration = duration.filter(|d| d.is_finite() && *d > 0.0).unwrap_or_else(|| input_end_time(recording));
+    let data = input_to_mouse_data(recording, duration);
+    let mut timeline = Timeline::new(duration);
+    for track in run_generators(&data, &[], settings).into_tracks() {
+        timeline.add_track(track);
+    }
+    timeline
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::input::{KeyboardRecord, ModifierState, MouseClickRecord, MousePositionSample};
+
+    fn recording() -> InputRecording {
+        let mut recording = InputRecording::new();
+        for i in 0..=40 {
+            let t = i as f64 * 0.1;
+            let x = if t < 2.0 { 0.2 } else { 0.8 };
+            recording.positions.push(MousePositionSample { time: t, position: NormalizedPoint::new(x, 0.3), velocity: 0.0 });
+        }
+        for time in [0.5, 0.8, 1.1] {
+            recording.clicks.push(MouseClickRecord {
+                time,
+                position: NormalizedPoint::new(0.2, 0.3),
+                button: MouseButton::Left,
+                duration: 0.1,
+            });
+        }
+        recording.keyboard.push(KeyboardRecord {
+            time: 2.5,
+            event_type: KeyAction::Down,
+            key_code: 9,
+            character: Some("v".into()),
+            modifiers: ModifierState { control: true, ..Default::default() },
+        });
+        recording
+    }
+
+    #[test]
+    fn test_replay_input_runs_every_generator() {
+        let recording = recording();
+        assert_eq!(input_end_time(&recording), 4.0);
+
+        let settings = GeneratorSettings::default();
+        let timeline = replay_input(&recording, &settings, None);
+        assert_eq!(timeline.duration, 4.0);
+        assert_eq!(timeline.tracks.len(), 4);
+        assert_eq!(timeline.ripple_track().unwrap().keyframe_count(), 3);
+        assert!(timeline.keystroke_track().unwrap().keyframe_count() > 0);
+        assert!(timeline.transform_tracks().next().unwrap().keyframe_count() > 0);
+
+        // Same input, same settings: same keyframes
+        let again = replay_input(&recording, &settings, Some(4.0));
+        let times = |t: &Timeline| -> Vec<f64> {
+            t.transform_tracks().next().unwrap().keyframes.iter().map(|k| k.time).collect()
+        };
+        assert_eq!(times(&timeline), times(&again));
+
+        // Overrides replace just their part
+        let spaced = RippleSettings { min_interval: 0.4, ..Default::default() };
+        let overrides = ReplaySettings { ripple: Some(spaced), ..Default::default() };
+        let quiet = replay_input(&recording, &settings.with_overrides(&overrides), None);
+        assert_eq!(quiet.ripple_track().unwrap().keyframe_count(), 2);
+        assert_eq!(times(&quiet), times(&timeline));
+    }
+
+    #[test]
+    fn test_run_generator_matches_full_run() {
+        let recording = recording();
+        let data = input_to_mouse_data(&recording, 4.0);
+        let settings = GeneratorSettings::default();
+        let all = run_generators(&data, &[], &settings).into_tracks();
+
+        for full in all {
+            let single = run_generator(full.track_type(), &data, &[], &settings);
+            assert_eq!(single.track_type(), full.track_type());
+            assert_eq!(single.keyframe_count(), full.keyframe_count());
+        }
+    }
+
+    #[test]
+    fn test_replay_empty_and_one_frame_input() {
+        let settings = GeneratorSettings::default();
+        let empty = replay_input(&InputRecording::new(), &settings, None);
+        assert_eq!(empty.duration, 0.0);
+        assert!(empty.tracks.iter().all(|t| t.keyframe_count() == 0));
+
+        // One frame with a click in it: whatever is generated stays inside it
+        let mut recording = InputRecording::new();
+        recording.positions.push(MousePositionSample { time: 0.0, position: NormalizedPoint::CENTER, velocity: 0.0 });
+        recording.clicks.push(MouseClickRecord {
+            time: 0.0,
+            position: NormalizedPoint::CENTER,
+            button: MouseButton::Left,
+            duration: 0.0,
+        });
+        let frame = 1.0 / 30.0;
+        let timeline = replay_input(&recording, &settings, Some(frame));
+        assert_eq!(timeline.duration, frame);
+        for track in timeline.transform_tracks() {
+            assert!(track.keyframes.iter().all(|k| k.time.is_finite() && (0.0..=frame).contains(&k.time)));
+            assert!(track.keyframes.iter().all(|k| k.zoom.is_finite()));
+        }
+    }
+
+    #[test]
+    fn test_generators_use_reconciled_duration() {
+        use crate::core::project::{CaptureMeta, MediaAsset, Rect, Size};
+
+        let media = MediaAsset {
+            video_relative_path: "test.mp4".into(),
+            mouse_data_relative_path: "test_mouse.json".into(),
+            pixel_size: Size::new(32.0, 24.0),
+            frame_rate: 10.0,
+            duration: 4.0,
+        };
+        let mut project = Project::new("Resync".into(), media, CaptureMeta::new(Rect::new(0.0, 0.0, 32.0, 24.0), 1.0));
+        // A shorter video was swapped in after the timeline was built
+        project.media.duration = 2.0;
+        assert_eq!(project.timeline.duration, 4.0);
+
+        let data = project_mouse_data(&project, &recording());
+        assert_eq!(data.duration, 2.0);
+        let tracks = run_generators(&data, &[], &GeneratorSettings::from_project(&project));
+        assert!(tracks.transform.keyframes.iter().all(|k| k.time <= 2.0));
+        // The stale duration would have zoomed on input past the video's end
+        let stale_data = input_to_mouse_data(&recording(), 4.0);
+        let stale = run_generators(&stale_data, &[], &GeneratorSettings::from_project(&project));
+        assert!(stale.transform.keyframes.iter().any(|k| k.time > 2.0));
+
+        project.sync_timeline_duration();
+        assert_eq!(project.timeline.duration, data.duration);
+    }
+}
@@ -0,0 +1,323 @@
+//! App-wide settings persisted between launches (last capture target, the
+//! render settings applied to new recordings, user export presets). Separate
+//! from per-project `RenderSettings`, which live in each project package.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::capture::{BackpressureMode, CaptureTarget};
+use super::project::{Background, CursorTheme, ExportQuality, OutputFrameRate, OutputResolution, RenderSettings, VideoCodec};
+
+const SETTINGS_FILE_NAME: &str = "settings.json";
+
+/// Persisted app settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSettings {
+    /// Capture target selected last time
+    #[serde(default)]
+    pub last_capture_target: Option<CaptureTarget>,
+    /// Pixel size of the last capture target, if known
+    #[serde(default)]
+    pub capture_width: Option<u32>,
+    #[serde(default)]
+    pub capture_height: Option<u32>,
+    /// Render settings applied to new recordings
+    #[serde(default)]
+    pub default_render_settings: RenderSettings,
+    /// Capture→encoder buffer size in frames (None uses the default)
+    #[serde(default)]
+    pub frame_channel_capacity: Option<usize>,
+    /// What to do when the encoder can't keep up
+    #[serde(default)]
+    pub backpressure: BackpressureMode,
+    /// Record the system cursor into the video instead of drawing one at
+    /// export. Stored per project at capture time.
+    #[serde(default)]
+    pub use_captured_cursor: bool,
+    /// User-defined export presets (a built-in's id overrides that built-in)
+    #[serde(default)]
+    pub export_presets: Vec<ExportPreset>,
+}
+
+/// What an export writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    /// A video file in the codec's container
+    #[default]
+    Video,
+    /// Numbered PNG frames
+    ImageSequence,
+}
+
+/// Named bundle of output settings applied before an export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportPreset {
+    pub id: String,
+    pub name: String,
+    pub resolution: OutputResolution,
+    pub frame_rate: OutputFrameRate,
+    pub codec: VideoCodec,
+    pub quality: ExportQuality,
+    #[serde(default)]
+    pub format: ExportFormat,
+    /// Shipped with the app (set when listing; never stored)
+    #[serde(default, skip_deserializing)]
+    pub built_in: bool,
+}
+
+impl ExportPreset {
+    /// Presets shipped with the app
+    pub fn built_ins() -> Vec<ExportPreset> {
+        let preset = |id: &str, name: &str, resolution, fps: Option<u32>, codec, quality, format| ExportPreset {
+            id: id.into(),
+            name: name.into(),
+            resolution,
+            frame_rate: fps.map_or(OutputFrameRate::Original, |fps| OutputFrameRate::Fixed { fps }),
+            codec,
+            quality,
+            format,
+            built_in: true,
+        };
+        vec![
+            preset("youtube-1080p60", "YouTube 1080p60", OutputResolution::Fhd1080, Some(60), VideoCodec::H264, ExportQuality::High, ExportFormat::Video),
+            preset("youtube-4k", "YouTube 4K", OutputResolution::Uhd4k, Some(60), VideoCodec::H264, ExportQuality::High, ExportFormat::Video),
+            preset("social-720p", "Social 720p30", OutputResolution::Hd720, Some(30), VideoCodec::H264, ExportQuality::Medium, ExportFormat::Video),
+            preset("archive", "Archive (original, HEVC)", OutputResolution::Original, None, VideoCodec::H265, ExportQuality::Original, ExportFormat::Video),
+            preset("png-sequence", "PNG sequence", OutputResolution::Original, None, VideoCodec::H264, ExportQuality::Original, ExportFormat::ImageSequence),
+        ]
+    }
+
+    /// Set the project's output settings from this preset
+    pub fn apply(&self, settings: &mut RenderSettings) {
+        settings.output_resolution = self.resolution.clone();
+        settings.output_frame_rate = self.frame_rate.clone();
+        settings.codec = self.codec;
+        settings.quality = self.quality;
+    }
+}
+
+/// Lowercase, dash-separated id from a preset name ("My 1080p!" -> "my-1080p")
+fn preset_id(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Settings store errors
+#[derive(Debug, thiserror::Error)]
+pub enum SettingsError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Serialization error: {0}")]
+    Serialization(String),
+}
+
+impl AppSettings {
+    /// Default location: `<config dir>/LazyRec/settings.json`
+    pub fn default_path() -> PathBuf {
+        dirs::config_dir()
+            .unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join(".config"))
+            .join("LazyRec")
+            .join(SETTINGS_FILE_NAME)
+    }
+
+    /// Load settings, falling back to defaults if the file is missing or unreadable
+    pub fn load(path: &Path) -> Self {
+        let json = match std::fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Self::default(),
+            Err(e) => {
+                log::warn!("Failed to read settings {}: {e}; using defaults", path.display());
+                return Self::default();
+            }
+        };
+        match serde_json::from_str(&json) {
+            Ok(settings) => settings,
+            Err(e) => {
+                log::warn!("Corrupt settings {}: {e}; using defaults", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    /// Write settings, creating the config directory if needed
+    pub fn save(&self, path: &Path) -> Result<(), SettingsError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| SettingsError::Serialization(e.to_string()))?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Remember `settings` as the default for new recordings.
+    /// Image backgrounds, watermarks and custom cursor themes are
+    /// project-relative, so they are not carried over.
+    pub fn remember_render_settings(&mut self, settings: &RenderSettings) {
+        let mut settings = settings.clone();
+        if matches!(settings.background, Background::Image { .. }) {
+            settings.background = Background::default();
+        }
+        if matches!(settings.cursor_theme, CursorTheme::Custom { .. }) {
+            settings.cursor_theme = CursorTheme::default();
+        }
+        settings.watermark = None;
+        self.default_render_settings = settings;
+    }
+
+    /// Built-in presets followed by the user's, in saved order. A user preset
+    /// with a built-in's id takes that built-in's place.
+    pub fn export_presets(&self) -> Vec<ExportPreset> {
+        let mut presets = ExportPreset::built_ins();
+        for user in &self.export_presets {
+            let user = ExportPreset { built_in: false, ..user.clone() };
+            match presets.iter_mut().find(|p| p.id == user.id) {
+                Some(existing) => *existing = user,
+                None => presets.push(user),
+            }
+        }
+        presets
+    }
+
+    pub fn export_preset(&self, id: &str) -> Option<ExportPreset> {
+        self.export_presets().into_iter().find(|p| p.id == id)
+    }
+
+    /// Add or replace a user preset (matched by id; a blank id comes from the
+    /// name). Returns the stored preset.
+    pub fn save_export_preset(&mut self, preset: ExportPreset) -> Result<ExportPreset, String> {
+        let name = preset.name.trim().to_string();
+        if name.is_empty() {
+            return Err("Preset name is required".into());
+        }
+        let id = match preset.id.trim() {
+            "" => preset_id(&name),
+            id => id.to_string(),
+        };
+        if id.is_empty() {
+            return Err(format!("Can't make a preset id from \"{name}\""));
+        }
+        let preset = ExportPreset { id, name, built_in: false, ..preset };
+        match self.export_presets.iter_mut().find(|p| p.id == preset.id) {
+            Some(existing) => *existing = preset.clone(),
+            None => self.export_presets.push(preset.clone()),
+        }
+        Ok(preset)
+    }
+
+    /// Remove a user preset. A built-in it overrode comes back.
+    /// Returns whether anything was removed.
+    pub fn remove_export_preset(&mut self, id: &str) -> bool {
+        let before = self.export_presets.len();
+        self.export_presets.retain(|p| p.id != id);
+        self.export_presets.len() != before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("lazyrec_settings_{name}_{}", std::process::id()))
+            .join(SETTINGS_FILE_NAME)
+    }
+
+    #[test]
+    fn test_load_missing_or_corrupt_uses_defaults() {
+        let path = temp_path("missing");
+        let settings = AppSettings::load(&path);
+        assert!(settings.last_capture_target.is_none());
+
+        let path = temp_path("corrupt");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "{ not json").unwrap();
+        let settings = AppSettings::load(&path);
+        assert!(settings.last_capture_target.is_none());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_save_load_roundtrip() {
+        let path = temp_path("roundtrip");
+        let mut settings = AppSettings {
+            last_capture_target: Some(CaptureTarget::Display { display_id: 2 }),
+            capture_width: Some(2560),
+            capture_height: Some(1440),
+            ..Default::default()
+        };
+        settings.default_render_settings.padding = 64.0;
+        settings.save(&path).unwrap();
+
+        let loaded = AppSettings::load(&path);
+        assert!(matches!(loaded.last_capture_target, Some(CaptureTarget::Display { display_id: 2 })));
+        assert_eq!(loaded.capture_width, Some(2560));
+        assert_eq!(loaded.default_render_settings.padding, 64.0);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_export_presets_merge_and_apply() {
+        let mut settings = AppSettings::default();
+        let built_in_count = ExportPreset::built_ins().len();
+        assert_eq!(settings.export_presets().len(), built_in_count);
+
+        let twitter = ExportPreset {
+            id: String::new(),
+            name: " Twitter 720p ".into(),
+            resolution: OutputResolution::Hd720,
+            frame_rate: OutputFrameRate::Fixed { fps: 30 },
+            codec: VideoCodec::H264,
+            quality: ExportQuality::Medium,
+            format: ExportFormat::Video,
+            built_in: true,
+        };
+        let saved = settings.save_export_preset(twitter.clone()).unwrap();
+        assert_eq!(saved.id, "twitter-720p");
+        assert_eq!(saved.name, "Twitter 720p");
+        assert!(!saved.built_in);
+
+        // Overriding a built-in keeps its place in the list; saving again replaces
+        let mut youtube = settings.export_preset("youtube-1080p60").unwrap();
+        youtube.quality = ExportQuality::Original;
+        settings.save_export_preset(youtube).unwrap();
+        settings.save_export_preset(ExportPreset { quality: ExportQuality::Low, ..saved }).unwrap();
+        let presets = settings.export_presets();
+        assert_eq!(presets.len(), built_in_count + 1);
+        assert_eq!(presets[0].quality, ExportQuality::Original);
+        assert!(!presets[0].built_in && presets[1].built_in);
+        assert_eq!(presets.last().unwrap().quality, ExportQuality::Low);
+
+        assert!(settings.remove_export_preset("youtube-1080p60"));
+        assert_eq!(settings.export_preset("youtube-1080p60").unwrap().quality, ExportQuality::High);
+        assert!(settings.save_export_preset(ExportPreset { name: "  ".into(), ..twitter.clone() }).is_err());
+
+        let mut render = RenderSettings::default();
+        settings.export_preset("twitter-720p").unwrap().apply(&mut render);
+        assert_eq!(render.output_resolution, OutputResolution::Hd720);
+        assert_eq!(render.output_frame_rate, OutputFrameRate::Fixed { fps: 30 });
+        assert_eq!(render.quality, ExportQuality::Low);
+    }
+
+    #[test]
+    fn test_remember_render_settings_drops_image_background() {
+        let mut render = RenderSettings {
+            background: Background::Image { relative_path: "background/bg.png".into() },
+            ..Default::default()
+        };
+        render.corner_radius = 12.0;
+
+        let mut settings = AppSettings::default();
+        settings.remember_render_settings(&render);
+        assert!(matches!(settings.default_render_settings.background, Background::Solid { .. }));
+        assert_eq!(settings.default_render_settings.corner_radius, 12.0);
+    }
+}
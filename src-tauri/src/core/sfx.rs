@@ -0,0 +1,214 @@
+//! Sound effects: short synthesized click/keypress samples laid onto a mono
+//! PCM track at input event times (the audio counterpart of the ripple generator).
+
+use super::input::{InputRecording, KeyAction};
+
+/// Sample rate of generated sound-effect tracks (Hz)
+pub const SFX_SAMPLE_RATE: u32 = 48_000;
+
+/// Peak level of a single effect at volume 1.0. Samples are normalized to this
+/// so the volume setting is independent of how loud the synthesis comes out.
+const SFX_PEAK: f32 = 0.8;
+
+/// Sound played for an input event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SfxKind {
+    Click,
+    Key,
+}
+
+impl SfxKind {
+    /// (length in seconds, tone frequency in Hz, relative level)
+    fn shape(self) -> (f64, f64, f32) {
+        match self {
+            Self::Click => (0.012, 2000.0, 1.0),
+            Self::Key => (0.008, 3200.0, 0.6),
+        }
+    }
+}
+
+/// Sound effect at a point in time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SfxEvent {
+    pub time: f64,
+    pub kind: SfxKind,
+}
+
+/// Synthesize an effect: a decaying tone mixed with a noise transient,
+/// normalized to `SFX_PEAK * level`
+pub fn synthesize(kind: SfxKind, sample_rate: u32) -> Vec<f32> {
+    let (length, frequency, level) = kind.shape();
+    let count = (length * sample_rate as f64).round().max(1.0) as usize;
+
+    // Deterministic noise (LCG) so exports are reproducible
+    let mut seed: u32 = 0x1234_5678;
+    let mut samples: Vec<f32> = (0..count)
+        .map(|i| {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            let noise = (seed >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0;
+            let t = i as f64 / sample_rate as f64;
+            let tone = (2.0 * std::f64::consts::PI * frequency * t).sin() as f32;
+            let envelope = (-(i as f32) / count as f32 * 6.0).exp();
+            (tone * 0.7 + noise * 0.3) * envelope
+        })
+        .collect();
+
+    let peak = samples.iter().fold(0.0_f32, |m, s| m.max(s.abs()));
+    if peak > 0.0 {
+        let gain = SFX_PEAK * level / peak;
+        samples.iter_mut().for_each(|s| *s *= gain);
+    }
+    samples
+}
+
+/// Click and key-down events from recorded input, sorted by time
+pub fn sfx_events(recording: &InputRecording) -> Vec<SfxEvent> {
+    let mut events: Vec<SfxEvent> = recording
+        .clicks
+        .iter()
+        .map(|c| SfxEvent { time: c.time, kind: SfxKind::Click })
+        .chain(
+            recording
+                .keyboard
+                .iter()
+                .filter(|k| k.event_type == KeyAction::Down)
+                .map(|k| SfxEvent { time: k.time, kind: SfxKind::Key }),
+        )
+        .collect();
+    events.sort_by(|a, b| a.time.total_cmp(&b.time));
+    events
+}
+
+/// Effects laid onto a silent mono track, rendered a range at a time so an
+/// export can encode its audio alongside the video instead of holding it all
+pub struct SfxMixer {
+    /// (start sample, kind), in event order
+    starts: Vec<(usize, SfxKind)>,
+    click: Vec<f32>,
+    key: Vec<f32>,
+    volume: f32,
+    len: usize,
+    sample_rate: u32,
+}
+
+impl std::fmt::Debug for SfxMixer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SfxMixer")
+            .field("events", &self.starts.len())
+            .field("samples", &self.len)
+            .field("sample_rate", &self.sample_rate)
+            .finish()
+    }
+}
+
+impl SfxMixer {
+    /// Mixer for a track of `duration` seconds
+    pub fn new(events: &[SfxEvent], duration: f64, sample_rate: u32, volume: f64) -> Self {
+        let len = (duration.max(0.0) * sample_rate as f64).round() as usize;
+        let volume = volume.clamp(0.0, 1.0) as f32;
+        let starts = if volume == 0.0 {
+            Vec::new()
+        } else {
+            events
+                .iter()
+                .filter(|e| e.time.is_finite() && e.time >= 0.0)
+                .map(|e| ((e.time * sample_rate as f64).round() as usize, e.kind))
+                .collect()
+        };
+        Self {
+            starts,
+            click: synthesize(SfxKind::Click, sample_rate),
+            key: synthesize(SfxKind::Key, sample_rate),
+            volume,
+            len,
+            sample_rate,
+        }
+    }
+
+    /// Samples in the whole track
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Samples `start..end` of the track (clipped to its length).
+    /// Overlapping effects add up and are clipped to [-1, 1].
+    pub fn mix(&self, start: usize, end: usize) -> Vec<f32> {
+        let end = end.min(self.len);
+        let mut chunk = vec![0.0_f32; end.saturating_sub(start)];
+        for &(at, kind) in &self.starts {
+            let sample = match kind {
+                SfxKind::Click => &self.click,
+                SfxKind::Key => &self.key,
+            };
+            if at >= end || at + sample.len() <= start {
+                continue;
+            }
+            // Offset of the chunk into the effect, or of the effect into the chunk
+            let (skip_src, skip_dst) = if at < start { (start - at, 0) } else { (0, at - start) };
+            for (dst, src) in chunk.iter_mut().skip(skip_dst).zip(&sample[skip_src..]) {
+                *dst = (*dst + src * self.volume).clamp(-1.0, 1.0);
+            }
+        }
+        chunk
+    }
+}
+
+/// Mix effects onto a silent mono track of `duration` seconds
+pub fn mix_sfx_track(events: &[SfxEvent], duration: f64, sample_rate: u32, volume: f64) -> Vec<f32> {
+    let mixer = SfxMixer::new(events, duration, sample_rate, volume);
+    mixer.mix(0, mixer.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_synthesize_normalized() {
+        let click = synthesize(SfxKind::Click, SFX_SAMPLE_RATE);
+        assert_eq!(click.len(), 576);
+        let peak = click.iter().fold(0.0_f32, |m, s| m.max(s.abs()));
+        assert!((peak - SFX_PEAK).abs() < 1e-5);
+        // Decays towards the end
+        assert!(click[click.len() - 1].abs() < peak * 0.1);
+    }
+
+    #[test]
+    fn test_mix_sfx_track_places_events() {
+        let events = [
+            SfxEvent { time: 0.5, kind: SfxKind::Click },
+            SfxEvent { time: 0.999, kind: SfxKind::Key }, // runs past the end
+        ];
+        let track = mix_sfx_track(&events, 1.0, 1000, 1.0);
+        assert_eq!(track.len(), 1000);
+        assert!(track[..500].iter().all(|s| *s == 0.0));
+        assert!(track[500..512].iter().any(|s| s.abs() > 0.1));
+        assert!(track[520..999].iter().all(|s| *s == 0.0));
+
+        let silent = mix_sfx_track(&events, 1.0, 1000, 0.0);
+        assert!(silent.iter().all(|s| *s == 0.0));
+    }
+
+    #[test]
+    fn test_mixer_chunks_match_whole_track() {
+        let events = [
+            SfxEvent { time: 0.1, kind: SfxKind::Click },
+            SfxEvent { time: 0.105, kind: SfxKind::Key }, // overlaps the click
+            SfxEvent { time: 0.4, kind: SfxKind::Key },
+        ];
+        let whole = mix_sfx_track(&events, 0.5, 1000, 0.7);
+        let mixer = SfxMixer::new(&events, 0.5, 1000, 0.7);
+        // Chunk edges fall inside the effects
+        let chunked: Vec<f32> = (0..mixer.len()).step_by(7).flat_map(|i| mixer.mix(i, i + 7)).collect();
+        assert_eq!(chunked, whole);
+        assert_eq!(mixer.mix(480, 600).len(), 20);
+    }
+}
@@ -0,0 +1,112 @@
+//! Mouse path smoothing: a one-euro filter over recorded cursor samples.
+//! Slow movement is smoothed heavily (removing sensor jitter) while the cutoff
+//! rises with speed, so fast moves stay responsive instead of lagging.
+
+use super::coordinates::NormalizedPoint;
+use super::evaluator::MousePosition;
+use super::project::MouseSmoothing;
+
+/// Cutoff of the speed estimate that drives the adaptive cutoff (Hz)
+const DERIVATIVE_CUTOFF: f64 = 1.0;
+
+/// Exponential smoothing factor for a low-pass with `cutoff` Hz over `dt` seconds
+fn smoothing_factor(dt: f64, cutoff: f64) -> f64 {
+    let tau = 1.0 / (2.0 * std::f64::consts::PI * cutoff.max(1e-3));
+    1.0 / (1.0 + tau / dt)
+}
+
+/// 2D one-euro filter. The cutoff follows the speed magnitude so both axes
+/// are filtered alike.
+struct OneEuroFilter {
+    min_cutoff: f64,
+    beta: f64,
+    /// (time, filtered point, filtered velocity)
+    last: Option<(f64, NormalizedPoint, (f64, f64))>,
+}
+
+impl OneEuroFilter {
+    fn new(settings: &MouseSmoothing) -> Self {
+        Self { min_cutoff: settings.min_cutoff, beta: settings.beta.max(0.0), last: None }
+    }
+
+    fn filter(&mut self, time: f64, point: NormalizedPoint) -> NormalizedPoint {
+        let Some((last_time, last, (vx, vy))) = self.last else {
+            self.last = Some((time, point, (0.0, 0.0)));
+            return point;
+        };
+        let dt = time - last_time;
+
+        let a_d = smoothing_factor(dt, DERIVATIVE_CUTOFF);
+        let vx = a_d * (point.x - last.x) / dt + (1.0 - a_d) * vx;
+        let vy = a_d * (point.y - last.y) / dt + (1.0 - a_d) * vy;
+
+        let cutoff = self.min_cutoff + self.beta * vx.hypot(vy);
+        let a = smoothing_factor(dt, cutoff);
+        let filtered = NormalizedPoint::new(
+            a * point.x + (1.0 - a) * last.x,
+            a * point.y + (1.0 - a) * last.y,
+        );
+        self.last = Some((time, filtered, (vx, vy)));
+        filtered
+    }
+}
+
+/// Smooth a cursor path. Samples that don't advance in time (duplicates from
+/// the poller) or aren't finite are dropped first. Disabled settings return
+/// the path unchanged.
+pub fn smooth_positions(positions: &[MousePosition], settings: &MouseSmoothing) -> Vec<MousePosition> {
+    if !settings.enabled {
+        return positions.to_vec();
+    }
+
+    let mut filter = OneEuroFilter::new(settings);
+    let mut last_time = f64::NEG_INFINITY;
+    let mut smoothed = Vec::with_capacity(positions.len());
+    for sample in positions {
+        let finite = sample.time.is_finite() && sample.position.x.is_finite() && sample.position.y.is_finite();
+        if !finite || sample.time <= last_time {
+            continue;
+        }
+        last_time = sample.time;
+        smoothed.push(MousePosition {
+            time: sample.time,
+            position: filter.filter(sample.time, sample.position),
+        });
+    }
+    smoothed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_noisy_line_becomes_monotonic() {
+        // Slow left-to-right drag at 60 Hz with ±0.002 jitter on both axes
+        let positions: Vec<MousePosition> = (0..180)
+            .map(|i| {
+                let time = i as f64 / 60.0;
+                let noise = if i % 2 == 0 { 0.002 } else { -0.002 };
+                MousePosition { time, position: NormalizedPoint::new(0.2 + 0.06 * time + noise, 0.5 - noise) }
+            })
+            .collect();
+        // A duplicate sample from the poller
+        let mut with_duplicate = positions.clone();
+        with_duplicate.insert(91, positions[90].clone());
+
+        let settings = MouseSmoothing { enabled: true, ..Default::default() };
+        let smoothed = smooth_positions(&with_duplicate, &settings);
+        assert_eq!(smoothed.len(), positions.len());
+
+        // The raw path doubles back every other sample; the smoothed one doesn't (after warm-up)
+        assert!(positions.windows(2).any(|w| w[1].position.x < w[0].position.x));
+        assert!(smoothed[30..].windows(2).all(|w| w[1].position.x > w[0].position.x));
+        let jitter = |path: &[MousePosition]| {
+            path[30..].iter().map(|p| (p.position.y - 0.5).abs()).fold(0.0, f64::max)
+        };
+        assert!(jitter(&smoothed) < jitter(&positions) / 4.0);
+
+        let off = smooth_positions(&positions, &MouseSmoothing::default());
+        assert_eq!(off[7].position, positions[7].position);
+    }
+}
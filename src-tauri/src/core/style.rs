@@ -0,0 +1,251 @@
+//! Style presets: a project's effect look (SmartZoom tuning, ripples,
+//! keystroke theme, cursor and window styling) saved as a small JSON file to
+//! reuse in other projects or share. Importing replaces only the sections
+//! the file contains.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::generators::{RippleSettings, SmartZoomConfig};
+use super::project::{Background, CursorTrail, KeystrokeStyle, Project, RenderSettings, RgbaColor};
+
+/// Current style file format; files from newer versions are refused
+pub const STYLE_PRESET_VERSION: u32 = 1;
+
+/// Style file errors
+#[derive(Debug, thiserror::Error)]
+pub enum StyleError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Serialization error: {0}")]
+    Serialization(String),
+    #[error("Style file version {0} is newer than this app supports ({STYLE_PRESET_VERSION})")]
+    UnsupportedVersion(u32),
+}
+
+/// Versioned bundle of style settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StylePreset {
+    pub version: u32,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub smart_zoom: Option<SmartZoomConfig>,
+    #[serde(default)]
+    pub ripple: Option<RippleSettings>,
+    #[serde(default)]
+    pub keystrokes: Option<KeystrokeTheme>,
+    #[serde(default)]
+    pub render: Option<StyleRenderSettings>,
+}
+
+/// Keystroke overlay look and the labels shown for shortcuts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeystrokeTheme {
+    pub style: KeystrokeStyle,
+    #[serde(default)]
+    pub shortcut_labels: BTreeMap<String, String>,
+}
+
+/// The `RenderSettings` that make up a look, leaving out output format and
+/// encoder choices
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StyleRenderSettings {
+    // Defaults as in `RenderSettings`, for files written before a field existed
+    #[serde(default)]
+    pub cursor_trail: CursorTrail,
+    #[serde(default = "super::project::default_effect_scale")]
+    pub cursor_scale: f64,
+    #[serde(default = "super::project::default_effect_scale")]
+    pub ripple_intensity_scale: f64,
+    #[serde(default = "super::project::default_effect_scale")]
+    pub ripple_size_scale: f64,
+    #[serde(default = "super::project::default_effect_scale")]
+    pub zoom_scale: f64,
+    #[serde(default = "super::project::default_effect_scale")]
+    pub keystroke_opacity_scale: f64,
+    pub corner_radius: f64,
+    pub padding: f64,
+    pub shadow_radius: f64,
+    pub shadow_opacity: f64,
+    #[serde(default = "super::project::default_shadow_color")]
+    pub shadow_color: RgbaColor,
+    #[serde(default = "super::project::default_shadow_layers")]
+    pub shadow_layers: u32,
+    /// None for image backgrounds, which stay with their package
+    #[serde(default)]
+    pub background: Option<Background>,
+}
+
+impl StyleRenderSettings {
+    pub fn from_settings(settings: &RenderSettings) -> Self {
+        Self {
+            cursor_trail: settings.cursor_trail,
+            cursor_scale: settings.cursor_scale,
+            ripple_intensity_scale: settings.ripple_intensity_scale,
+            ripple_size_scale: settings.ripple_size_scale,
+            zoom_scale: settings.zoom_scale,
+            keystroke_opacity_scale: settings.keystroke_opacity_scale,
+            corner_radius: settings.corner_radius,
+            padding: settings.padding,
+            shadow_radius: settings.shadow_radius,
+            shadow_opacity: settings.shadow_opacity,
+            shadow_color: settings.shadow_color,
+            shadow_layers: settings.shadow_layers,
+            background: match &settings.background {
+                Background::Image { .. } => None,
+                background => Some(background.clone()),
+            },
+        }
+    }
+
+    pub fn apply(&self, settings: &mut RenderSettings) {
+        settings.cursor_trail = self.cursor_trail;
+        settings.cursor_scale = self.cursor_scale;
+        settings.ripple_intensity_scale = self.ripple_intensity_scale;
+        settings.ripple_size_scale = self.ripple_size_scale;
+        settings.zoom_scale = self.zoom_scale;
+        settings.keystroke_opacity_scale = self.keystroke_opacity_scale;
+        settings.corner_radius = self.corner_radius;
+        settings.padding = self.padding;
+        settings.shadow_radius = self.shadow_radius;
+        settings.shadow_opacity = self.shadow_opacity;
+        settings.shadow_color = self.shadow_color;
+        settings.shadow_layers = self.shadow_layers;
+        if let Some(background) = &self.background {
+            settings.background = background.clone();
+        }
+    }
+}
+
+impl StylePreset {
+    /// Every section, taken from `project`
+    pub fn from_project(name: String, project: &Project) -> Self {
+        Self {
+            version: STYLE_PRESET_VERSION,
+            name,
+            smart_zoom: Some(project.smart_zoom.clone()),
+            ripple: Some(project.ripple_settings.clone()),
+            keystrokes: Some(KeystrokeTheme {
+                style: project.render_settings.keystroke_style,
+                shortcut_labels: project.shortcut_labels.clone(),
+            }),
+            render: Some(StyleRenderSettings::from_settings(&project.render_settings)),
+        }
+    }
+
+    /// Replace the project's settings for each section present. Existing
+    /// keyframes keep their look until they are regenerated.
+    pub fn apply(&self, project: &mut Project) {
+        if let Some(smart_zoom) = &self.smart_zoom {
+            project.smart_zoom = smart_zoom.clone();
+        }
+        if let Some(ripple) = &self.ripple {
+            project.ripple_settings = ripple.clone();
+        }
+        if let Some(keystrokes) = &self.keystrokes {
+            project.render_settings.keystroke_style = keystrokes.style;
+            project.shortcut_labels = keystrokes.shortcut_labels.clone();
+        }
+        if let Some(render) = &self.render {
+            render.apply(&mut project.render_settings);
+        }
+    }
+
+    pub fn check_version(&self) -> Result<(), StyleError> {
+        if self.version > STYLE_PRESET_VERSION {
+            return Err(StyleError::UnsupportedVersion(self.version));
+        }
+        Ok(())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), StyleError> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| StyleError::Serialization(e.to_string()))?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, StyleError> {
+        let json = std::fs::read_to_string(path)?;
+        let preset: Self = serde_json::from_str(&json).map_err(|e| StyleError::Serialization(e.to_string()))?;
+        preset.check_version()?;
+        Ok(preset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::generators::SmartZoomPreset;
+    use crate::core::keyframe::RippleColor;
+    use crate::core::project::{CaptureMeta, MediaAsset, Rect, Size};
+
+    fn project() -> Project {
+        let media = MediaAsset {
+            video_relative_path: "recording.mp4".into(),
+            mouse_data_relative_path: "recording_mouse.json".into(),
+            pixel_size: Size::new(64.0, 36.0),
+            frame_rate: 10.0,
+            duration: 1.0,
+        };
+        Project::new("Style".into(), media, CaptureMeta::new(Rect::new(0.0, 0.0, 64.0, 36.0), 1.0))
+    }
+
+    #[test]
+    fn test_style_preset_applies_present_sections() {
+        let mut source = project();
+        source.smart_zoom = SmartZoomConfig::Preset { preset: SmartZoomPreset::Subtle };
+        source.ripple_settings.color = RippleColor::RightClick;
+        source.render_settings.keystroke_style.opacity = 0.5;
+        source.render_settings.corner_radius = 24.0;
+        source.render_settings.background = Background::Image { relative_path: "background.png".into() };
+        source.render_settings.codec = crate::core::project::VideoCodec::H265;
+
+        let preset = StylePreset::from_project("Brand".into(), &source);
+        assert_eq!(preset.render.as_ref().unwrap().background, None);
+
+        let mut target = project();
+        let background = target.render_settings.background.clone();
+        preset.apply(&mut target);
+        assert_eq!(target.smart_zoom, source.smart_zoom);
+        assert_eq!(target.ripple_settings.color, RippleColor::RightClick);
+        assert_eq!(target.render_settings.keystroke_style.opacity, 0.5);
+        assert_eq!(target.render_settings.corner_radius, 24.0);
+        // Packaged images and output settings aren't part of a style
+        assert_eq!(target.render_settings.background, background);
+        assert_ne!(target.render_settings.codec, source.render_settings.codec);
+
+        // Only the sections in the file are replaced
+        let partial = StylePreset { ripple: None, keystrokes: None, render: None, ..preset.clone() };
+        let mut untouched = project();
+        partial.apply(&mut untouched);
+        assert_eq!(untouched.ripple_settings, RippleSettings::default());
+        assert_eq!(untouched.smart_zoom, source.smart_zoom);
+
+        assert!(preset.check_version().is_ok());
+        let newer = StylePreset { version: STYLE_PRESET_VERSION + 1, ..preset };
+        assert!(matches!(newer.check_version(), Err(StyleError::UnsupportedVersion(_))));
+    }
+
+    #[test]
+    fn test_style_file_without_newer_render_fields_loads() {
+        let json = r#"{
+            "version": 1,
+            "render": { "cornerRadius": 12.0, "padding": 40.0, "shadowRadius": 20.0, "shadowOpacity": 0.4 }
+        }"#;
+        let preset: StylePreset = serde_json::from_str(json).unwrap();
+        let render = preset.render.unwrap();
+        let defaults = StyleRenderSettings::from_settings(&RenderSettings::default());
+        assert_eq!(render.corner_radius, 12.0);
+        assert_eq!(render.cursor_trail, defaults.cursor_trail);
+        assert_eq!(render.zoom_scale, defaults.zoom_scale);
+        assert_eq!(render.shadow_color, defaults.shadow_color);
+        assert_eq!(render.shadow_layers, defaults.shadow_layers);
+        assert_eq!(render.background, None);
+    }
+}
@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::coordinates::NormalizedPoint;
+use super::evaluator::clamp_center;
+use super::keyframe::*;
 use super::track::*;
 
 /// Timeline contains multiple tracks, each holding keyframes
@@ -15,8 +18,64 @@ pub struct Timeline {
     /// Trim end time (None uses duration)
     #[serde(default, rename = "trimEnd")]
     pub trim_end: Option<f64>,
+    /// Time remap: playback speed over spans of source time (1.0 elsewhere)
+    #[serde(default, rename = "speedSegments")]
+    pub speed_segments: Vec<SpeedSegment>,
+    /// Spans of source time left out of the export (e.g. idle time)
+    #[serde(default)]
+    pub cuts: Vec<Cut>,
+    /// Named points in time order, written as chapters on export
+    #[serde(default)]
+    pub markers: Vec<Marker>,
 }
 
+/// Named point in source time (a chapter start)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Marker {
+    pub id: Uuid,
+    pub time: f64,
+    pub label: String,
+}
+
+/// Playback speed over a span of source time (0.5 = half speed, 2.0 = double)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpeedSegment {
+    pub start: f64,
+    pub end: f64,
+    pub factor: f64,
+}
+
+/// Span of source time removed from the output
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Cut {
+    pub start: f64,
+    pub end: f64,
+}
+
+/// Slowest / fastest allowed speed factor
+pub const MIN_SPEED_FACTOR: f64 = 0.1;
+pub const MAX_SPEED_FACTOR: f64 = 16.0;
+
+/// How `Timeline::apply_generated` combines generated tracks with existing ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum GenerateMode {
+    /// Discard all tracks and use the generated ones
+    #[default]
+    Replace,
+    /// Keep manual keyframes, replace generated ones, skip generated keyframes
+    /// that collide with manual ones
+    Merge,
+    /// Regenerate one track type only, leaving the others untouched
+    RegenerateTrack {
+        #[serde(rename = "trackType")]
+        track_type: TrackType,
+    },
+}
+
+/// Generated keyframes this close to a manual one are dropped when merging (seconds)
+pub const MERGE_COLLISION_TOLERANCE: f64 = 0.1;
+
 impl Timeline {
     pub fn new(duration: f64) -> Self {
         Self {
@@ -24,6 +83,9 @@ impl Timeline {
             duration,
             trim_start: 0.0,
             trim_end: None,
+            speed_segments: Vec::new(),
+            cuts: Vec::new(),
+            markers: Vec::new(),
         }
     }
 
@@ -39,7 +101,29 @@ impl Timeline {
             duration,
             trim_start: 0.0,
             trim_end: None,
+            speed_segments: Vec::new(),
+            cuts: Vec::new(),
+            markers: Vec::new(),
+        }
+    }
+
+    /// Match the timeline to the media's duration. Trims and markers are kept
+    /// inside the new range; a trim end at or past the end is dropped.
+    /// Returns whether anything changed.
+    pub fn resync_duration(&mut self, duration: f64) -> bool {
+        let before = (self.duration, self.trim_start, self.trim_end);
+        self.duration = duration;
+        self.trim_start = self.trim_start.clamp(0.0, duration);
+        self.trim_end = self
+            .trim_end
+            .filter(|&end| end < duration)
+            .map(|end| end.max(self.trim_start));
+        let mut moved_marker = false;
+        for marker in self.markers.iter_mut().filter(|m| m.time > duration) {
+            marker.time = duration.max(0.0);
+            moved_marker = true;
         }
+        moved_marker || before != (self.duration, self.trim_start, self.trim_end)
     }
 
     // Trim properties
@@ -64,6 +148,174 @@ impl Timeline {
         time >= self.effective_trim_start() && time <= self.effective_trim_end()
     }
 
+    // Time remap
+
+    /// Whether any span plays at other than normal speed
+    pub fn has_speed_segments(&self) -> bool {
+        self.speed_spans().iter().any(|s| s.factor != 1.0)
+    }
+
+    /// Whether a cut removes time inside the trim range
+    pub fn has_cuts(&self) -> bool {
+        self.kept_ranges().len() > 1
+    }
+
+    /// Whether output time differs from source time anywhere: trims, cuts or
+    /// speed segments
+    pub fn is_remapped(&self) -> bool {
+        self.is_trimmed() || self.has_cuts() || self.has_speed_segments()
+    }
+
+    /// Source time that reaches the output, in order: the trim range minus cuts
+    pub fn kept_ranges(&self) -> Vec<(f64, f64)> {
+        let (start, end) = (self.effective_trim_start(), self.effective_trim_end());
+        let mut cuts: Vec<(f64, f64)> = self
+            .cuts
+            .iter()
+            .filter(|c| c.start.is_finite() && c.end.is_finite())
+            .map(|c| (c.start.max(start), c.end.min(end)))
+            .filter(|(s, e)| e > s)
+            .collect();
+        cuts.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut ranges = Vec::with_capacity(cuts.len() + 1);
+        let mut cursor = start;
+        for (cut_start, cut_end) in cuts {
+            if cut_start > cursor {
+                ranges.push((cursor, cut_start));
+            }
+            cursor = cursor.max(cut_end);
+        }
+        if end > cursor {
+            ranges.push((cursor, end));
+        }
+        ranges
+    }
+
+    /// Spans of the kept source time (`kept_ranges`) in order: speed segments
+    /// clipped to it (overlaps dropped, factors clamped), gaps at normal speed
+    pub fn speed_spans(&self) -> Vec<SpeedSegment> {
+        let kept = self.kept_ranges();
+        self.full_speed_spans()
+            .into_iter()
+            .flat_map(|span| {
+                kept.iter().filter_map(move |&(start, end)| {
+                    let (start, end) = (span.start.max(start), span.end.min(end));
+                    (end > start).then_some(SpeedSegment { start, end, factor: span.factor })
+                })
+            })
+            .collect()
+    }
+
+    /// `speed_spans` over all of `[0, duration]`, ignoring trims and cuts
+    fn full_speed_spans(&self) -> Vec<SpeedSegment> {
+        let mut segments: Vec<SpeedSegment> = self
+            .speed_segments
+            .iter()
+            .filter(|s| s.start.is_finite() && s.end.is_finite() && s.factor.is_finite())
+            .map(|s| SpeedSegment {
+                start: s.start.clamp(0.0, self.duration),
+                end: s.end.clamp(0.0, self.duration),
+                factor: s.factor.clamp(MIN_SPEED_FACTOR, MAX_SPEED_FACTOR),
+            })
+            .filter(|s| s.end > s.start)
+            .collect();
+        segments.sort_by(|a, b| a.start.total_cmp(&b.start));
+
+        let mut spans = Vec::with_capacity(segments.len() * 2 + 1);
+        let mut cursor = 0.0;
+        for segment in segments {
+            if segment.start < cursor {
+                continue;
+            }
+            if segment.start > cursor {
+                spans.push(SpeedSegment { start: cursor, end: segment.start, factor: 1.0 });
+            }
+            spans.push(segment);
+            cursor = segment.end;
+        }
+        if cursor < self.duration {
+            spans.push(SpeedSegment { start: cursor, end: self.duration, factor: 1.0 });
+        }
+        spans
+    }
+
+    /// Add `segments` over the existing speed segments: where they overlap,
+    /// the new ones win and the existing ones keep the rest of their span
+    pub fn merge_speed_segments(&mut self, segments: &[SpeedSegment]) {
+        let mut merged: Vec<SpeedSegment> = Vec::with_capacity(self.speed_segments.len() + segments.len());
+        for existing in &self.speed_segments {
+            let mut pieces = vec![*existing];
+            for new in segments {
+                pieces = pieces
+                    .into_iter()
+                    .flat_map(|p| {
+                        [
+                            SpeedSegment { end: p.end.min(new.start), ..p },
+                            SpeedSegment { start: p.start.max(new.end), ..p },
+                        ]
+                    })
+                    .filter(|p| p.end > p.start)
+                    .collect();
+            }
+            merged.extend(pieces);
+        }
+        merged.extend_from_slice(segments);
+        merged.sort_by(|a, b| a.start.total_cmp(&b.start));
+        self.speed_segments = merged;
+    }
+
+    /// Length of the remapped output (seconds)
+    pub fn output_duration(&self) -> f64 {
+        self.speed_spans().iter().map(|s| (s.end - s.start) / s.factor).sum()
+    }
+
+    /// Source time shown at `output_time` of the remapped output
+    pub fn source_time_at(&self, output_time: f64) -> f64 {
+        let spans = self.speed_spans();
+        let mut output_start = 0.0;
+        for span in &spans {
+            let output_len = (span.end - span.start) / span.factor;
+            if output_time < output_start + output_len {
+                return span.start + (output_time - output_start).max(0.0) * span.factor;
+            }
+            output_start += output_len;
+        }
+        spans.last().map_or(self.effective_trim_end(), |s| s.end)
+    }
+
+    /// Output time at which `source_time` is shown (inverse of `source_time_at`).
+    /// Trimmed or cut time maps to where the output resumes.
+    pub fn output_time_at(&self, source_time: f64) -> f64 {
+        let mut output_start = 0.0;
+        for span in self.speed_spans() {
+            if source_time < span.end {
+                return output_start + (source_time - span.start).max(0.0) / span.factor;
+            }
+            output_start += (span.end - span.start) / span.factor;
+        }
+        output_start
+    }
+
+    // Markers
+
+    /// Add a marker at `time` (clamped to the timeline), keeping markers in
+    /// time order. Returns the new marker's id.
+    pub fn add_marker(&mut self, time: f64, label: String) -> Uuid {
+        let time = time.clamp(0.0, self.duration.max(0.0));
+        let id = Uuid::new_v4();
+        let index = self.markers.partition_point(|m| m.time <= time);
+        self.markers.insert(index, Marker { id, time, label });
+        id
+    }
+
+    /// Remove a marker. Returns whether it existed.
+    pub fn remove_marker(&mut self, id: Uuid) -> bool {
+        let before = self.markers.len();
+        self.markers.retain(|m| m.id != id);
+        self.markers.len() != before
+    }
+
     // Track access
 
     pub fn transform_track(&self) -> Option<&TransformTrack> {
@@ -72,6 +324,14 @@ impl Timeline {
         })
     }
 
+    /// All transform tracks, bottom layer first. The first is the base camera
+    /// (the one SmartZoom generates); later ones nest inside it.
+    pub fn transform_tracks(&self) -> impl Iterator<Item = &TransformTrack> {
+        self.tracks.iter().filter_map(|t| {
+            if let AnyTrack::Transform(track) = t { Some(track) } else { None }
+        })
+    }
+
     pub fn transform_track_mut(&mut self) -> Option<&mut TransformTrack> {
         self.tracks.iter_mut().find_map(|t| {
             if let AnyTrack::Transform(track) = t { Some(track) } else { None }
@@ -122,12 +382,39 @@ impl Timeline {
         self.tracks.iter().find(|t| t.id() == id)
     }
 
+    pub fn track_mut(&mut self, id: Uuid) -> Option<&mut AnyTrack> {
+        self.tracks.iter_mut().find(|t| t.id() == id)
+    }
+
     pub fn update_track(&mut self, track: AnyTrack) {
         if let Some(idx) = self.tracks.iter().position(|t| t.id() == track.id()) {
             self.tracks[idx] = track;
         }
     }
 
+    /// Combine freshly generated tracks with the existing ones
+    pub fn apply_generated(&mut self, generated: Vec<AnyTrack>, mode: GenerateMode) {
+        if mode == GenerateMode::Replace {
+            self.tracks = generated;
+            return;
+        }
+        for track in generated {
+            if let GenerateMode::RegenerateTrack { track_type } = mode {
+                if track.track_type() != track_type {
+                    continue;
+                }
+            }
+            let existing = self.tracks.iter_mut().find(|t| t.track_type() == track.track_type());
+            match (existing, mode) {
+                (Some(existing), GenerateMode::Merge) => {
+                    existing.merge_keyframes(track, MERGE_COLLISION_TOLERANCE)
+                }
+                (Some(existing), _) => existing.replace_keyframes(track),
+                (None, _) => self.tracks.push(track),
+            }
+        }
+    }
+
     // Keyframe query
 
     pub fn total_keyframe_count(&self) -> usize {
@@ -141,6 +428,165 @@ impl Timeline {
     pub fn is_valid(&self) -> bool {
         self.duration > 0.0
     }
+
+    // Validation
+
+    /// Repair out-of-range keyframes (e.g. from hand-edited or migrated projects).
+    /// Clamps times to `[0, duration]`, enforces zoom >= 1.0, clamps positions,
+    /// re-sorts keyframes and drops exact duplicates. Zoom centres are clamped
+    /// against `view`, the part of the source the export shows at 1x (see
+    /// `Project::view_extent`), so a Fill export keeps its edge framings.
+    pub fn validate_and_repair(&mut self, duration: f64, view: (f64, f64)) -> TimelineRepairReport {
+        let duration = if duration.is_finite() { duration.max(0.0) } else { 0.0 };
+        let mut report = TimelineRepairReport::default();
+
+        for track in &mut self.tracks {
+            match track {
+                AnyTrack::Transform(t) => {
+                    for kf in &mut t.keyframes {
+                        repair_time(&mut kf.time, duration, &mut report);
+                        if !kf.zoom.is_finite() || kf.zoom < 1.0 {
+                            kf.zoom = 1.0;
+                            report.clamped_zooms += 1;
+                        }
+                        // Same clamping as generate_zoom_keyframes: keep the crop inside the frame
+                        let center = clamp_center(
+                            NormalizedPoint::new(repair_coordinate(kf.center.x), repair_coordinate(kf.center.y)),
+                            kf.zoom,
+                            view,
+                        );
+                        repair_position(&mut kf.center, center, &mut report);
+                    }
+                    repair_order(&mut t.keyframes, |k| k.time, |a, b| {
+                        *a == TransformKeyframe { id: a.id, ..b.clone() }
+                    }, &mut report);
+                }
+                AnyTrack::Ripple(t) => {
+                    for kf in &mut t.keyframes {
+                        repair_time(&mut kf.time, duration, &mut report);
+                        let position = clamped_position(kf.position);
+                        repair_position(&mut kf.position, position, &mut report);
+                    }
+                    repair_order(&mut t.keyframes, |k| k.time, |a, b| {
+                        *a == RippleKeyframe { id: a.id, ..b.clone() }
+                    }, &mut report);
+                }
+                AnyTrack::Cursor(t) => {
+                    if let Some(keyframes) = &mut t.style_keyframes {
+                        for kf in keyframes.iter_mut() {
+                            repair_time(&mut kf.time, duration, &mut report);
+                            if let Some(pos) = &mut kf.position {
+                                let position = clamped_position(*pos);
+                                repair_position(pos, position, &mut report);
+                            }
+                        }
+                        repair_order(keyframes, |k| k.time, |a, b| {
+                            *a == CursorStyleKeyframe { id: a.id, ..b.clone() }
+                        }, &mut report);
+                    }
+                }
+                AnyTrack::Keystroke(t) => {
+                    for kf in &mut t.keyframes {
+                        repair_time(&mut kf.time, duration, &mut report);
+                        let position = clamped_position(kf.position);
+                        repair_position(&mut kf.position, position, &mut report);
+                    }
+                    repair_order(&mut t.keyframes, |k| k.time, |a, b| {
+                        *a == KeystrokeKeyframe { id: a.id, ..b.clone() }
+                    }, &mut report);
+                }
+            }
+        }
+
+        report
+    }
+}
+
+/// Summary of what `Timeline::validate_and_repair` changed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TimelineRepairReport {
+    /// Keyframe times moved into `[0, duration]`
+    pub clamped_times: usize,
+    /// Transform keyframes with zoom raised to 1.0
+    pub clamped_zooms: usize,
+    /// Centers/positions moved back inside the frame
+    pub clamped_positions: usize,
+    /// Tracks whose keyframes were out of order
+    pub resorted_tracks: usize,
+    /// Exact duplicate keyframes removed
+    pub removed_duplicates: usize,
+}
+
+impl TimelineRepairReport {
+    pub fn is_clean(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl std::fmt::Display for TimelineRepairReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} times clamped, {} zooms clamped, {} positions clamped, {} tracks re-sorted, {} duplicates removed",
+            self.clamped_times,
+            self.clamped_zooms,
+            self.clamped_positions,
+            self.resorted_tracks,
+            self.removed_duplicates,
+        )
+    }
+}
+
+fn repair_time(time: &mut f64, duration: f64, report: &mut TimelineRepairReport) {
+    let repaired = if time.is_finite() { time.clamp(0.0, duration) } else { 0.0 };
+    if repaired != *time {
+        *time = repaired;
+        report.clamped_times += 1;
+    }
+}
+
+fn repair_coordinate(value: f64) -> f64 {
+    if value.is_finite() { value.clamp(0.0, 1.0) } else { 0.5 }
+}
+
+fn clamped_position(point: NormalizedPoint) -> NormalizedPoint {
+    NormalizedPoint::new(repair_coordinate(point.x), repair_coordinate(point.y))
+}
+
+fn repair_position(point: &mut NormalizedPoint, repaired: NormalizedPoint, report: &mut TimelineRepairReport) {
+    if repaired != *point {
+        *point = repaired;
+        report.clamped_positions += 1;
+    }
+}
+
+/// Sort keyframes by time and drop exact duplicates (same values, any id)
+fn repair_order<T>(
+    keyframes: &mut Vec<T>,
+    time: impl Fn(&T) -> f64,
+    same: impl Fn(&T, &T) -> bool,
+    report: &mut TimelineRepairReport,
+) {
+    if keyframes.windows(2).any(|w| time(&w[0]) > time(&w[1])) {
+        keyframes.sort_by(|a, b| time(a).total_cmp(&time(b)));
+        report.resorted_tracks += 1;
+    }
+
+    let mut kept: Vec<T> = Vec::with_capacity(keyframes.len());
+    for kf in keyframes.drain(..) {
+        // Duplicates share a time, so only the tail of `kept` needs checking
+        let is_duplicate = kept
+            .iter()
+            .rev()
+            .take_while(|k| time(k) == time(&kf))
+            .any(|k| same(k, &kf));
+        if is_duplicate {
+            report.removed_duplicates += 1;
+        } else {
+            kept.push(kf);
+        }
+    }
+    *keyframes = kept;
 }
 
 impl Default for Timeline {
@@ -152,6 +598,7 @@ impl Default for Timeline {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::evaluator::FULL_VIEW;
 
     #[test]
     fn test_new_timeline() {
@@ -172,6 +619,31 @@ mod tests {
         assert_eq!(tl.tracks[3].track_type(), TrackType::Keystroke);
     }
 
+    #[test]
+    fn test_apply_generated_regenerate_track() {
+        let mut tl = Timeline::with_default_tracks(30.0);
+        let transform_id = tl.tracks[0].id();
+        let ripple_id = tl.tracks[1].id();
+        tl.transform_track_mut().unwrap().add_keyframe(TransformKeyframe::identity(1.0));
+
+        let mut ripples = RippleTrack::new();
+        ripples.add_keyframe(RippleKeyframe::new(2.0, NormalizedPoint::CENTER));
+        let mut zoom = TransformTrack::new();
+        zoom.add_keyframe(TransformKeyframe::identity(5.0));
+        let generated = vec![AnyTrack::Transform(zoom), AnyTrack::Ripple(ripples)];
+
+        tl.apply_generated(generated.clone(), GenerateMode::RegenerateTrack { track_type: TrackType::Ripple });
+        // Transform track untouched, ripple keyframes replaced in place
+        assert_eq!(tl.tracks[0].id(), transform_id);
+        assert_eq!(tl.transform_track().unwrap().keyframes[0].time, 1.0);
+        assert_eq!(tl.tracks[1].id(), ripple_id);
+        assert_eq!(tl.ripple_track().unwrap().keyframe_count(), 1);
+
+        tl.apply_generated(generated, GenerateMode::Replace);
+        assert_eq!(tl.tracks.len(), 2);
+        assert_eq!(tl.transform_track().unwrap().keyframes[0].time, 5.0);
+    }
+
     #[test]
     fn test_effective_trim_start_clamped() {
         let mut tl = Timeline::new(10.0);
@@ -218,6 +690,61 @@ mod tests {
         assert!(!tl.is_time_in_trim_range(9.0));
     }
 
+    #[test]
+    fn test_speed_remap() {
+        let mut tl = Timeline::new(10.0);
+        assert!(!tl.has_speed_segments());
+        assert_eq!(tl.output_duration(), 10.0);
+        assert_eq!(tl.source_time_at(3.0), 3.0);
+
+        // 2-4s at half speed, 6-10s at double speed
+        tl.speed_segments = vec![
+            SpeedSegment { start: 6.0, end: 12.0, factor: 2.0 },
+            SpeedSegment { start: 2.0, end: 4.0, factor: 0.5 },
+        ];
+        assert!(tl.has_speed_segments());
+        assert!((tl.output_duration() - (2.0 + 4.0 + 2.0 + 2.0)).abs() < 1e-9);
+
+        assert!((tl.source_time_at(1.0) - 1.0).abs() < 1e-9);
+        assert!((tl.source_time_at(4.0) - 3.0).abs() < 1e-9);
+        assert!((tl.source_time_at(7.0) - 5.0).abs() < 1e-9);
+        assert!((tl.source_time_at(9.0) - 8.0).abs() < 1e-9);
+        assert_eq!(tl.source_time_at(20.0), 10.0);
+
+        for t in [0.5, 3.0, 5.5, 9.0] {
+            assert!((tl.source_time_at(tl.output_time_at(t)) - t).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_speed_spans_drop_overlaps() {
+        let mut tl = Timeline::new(10.0);
+        tl.speed_segments = vec![
+            SpeedSegment { start: 1.0, end: 5.0, factor: 0.0 },
+            SpeedSegment { start: 3.0, end: 6.0, factor: 2.0 },
+        ];
+        let spans = tl.speed_spans();
+        assert_eq!(spans.len(), 3);
+        // Factor clamped, overlapping segment ignored
+        assert_eq!(spans[1], SpeedSegment { start: 1.0, end: 5.0, factor: MIN_SPEED_FACTOR });
+        assert_eq!(spans[2].factor, 1.0);
+    }
+
+    #[test]
+    fn test_markers_stay_sorted() {
+        let mut tl = Timeline::new(10.0);
+        let late = tl.add_marker(8.0, "Outro".into());
+        tl.add_marker(2.0, "Intro".into());
+        tl.add_marker(50.0, "Past the end".into());
+        let labels: Vec<&str> = tl.markers.iter().map(|m| m.label.as_str()).collect();
+        assert_eq!(labels, ["Intro", "Outro", "Past the end"]);
+        assert_eq!(tl.markers[2].time, 10.0);
+
+        assert!(tl.remove_marker(late));
+        assert!(!tl.remove_marker(late));
+        assert_eq!(tl.markers.len(), 2);
+    }
+
     #[test]
     fn test_track_access_typed() {
         let tl = Timeline::with_default_tracks(10.0);
@@ -269,6 +796,67 @@ mod tests {
         assert!(tl.tracks.is_empty());
     }
 
+    #[test]
+    fn test_validate_and_repair_corrupt_timeline() {
+        let mut tl = Timeline::with_default_tracks(10.0);
+        {
+            let transform = tl.transform_track_mut().unwrap();
+            let mut beyond = TransformKeyframe::identity(15.0);
+            beyond.zoom = 0.5;
+            let mut negative = TransformKeyframe::identity(-2.0);
+            negative.center = NormalizedPoint::new(1.5, -0.5);
+            negative.zoom = 2.0;
+            let dup = TransformKeyframe::identity(3.0);
+            let mut dup_copy = dup.clone();
+            dup_copy.id = Uuid::new_v4();
+            // Pushed directly to bypass add_keyframe's sorting
+            transform.keyframes = vec![beyond, negative, dup, dup_copy];
+        }
+        let mut ripple = RippleKeyframe::new(f64::NAN, NormalizedPoint::CENTER);
+        ripple.position = NormalizedPoint::new(2.0, 0.5);
+        tl.ripple_track_mut().unwrap().keyframes.push(ripple);
+
+        let report = tl.validate_and_repair(10.0, FULL_VIEW);
+        assert!(!report.is_clean());
+        assert_eq!(report.clamped_times, 3);
+        assert_eq!(report.clamped_zooms, 1);
+        assert_eq!(report.clamped_positions, 2);
+        assert_eq!(report.resorted_tracks, 1);
+        assert_eq!(report.removed_duplicates, 1);
+
+        let kfs = &tl.transform_track().unwrap().keyframes;
+        assert_eq!(kfs.len(), 3);
+        assert_eq!(kfs[0].time, 0.0);
+        // Center clamped to the half-crop range for zoom 2.0
+        assert_eq!(kfs[0].center, NormalizedPoint::new(0.75, 0.25));
+        assert_eq!(kfs[1].time, 3.0);
+        assert_eq!(kfs[2].time, 10.0);
+        assert_eq!(kfs[2].zoom, 1.0);
+        let ripple = &tl.ripple_track().unwrap().keyframes[0];
+        assert_eq!(ripple.time, 0.0);
+        assert_eq!(ripple.position.x, 1.0);
+
+        // Second pass has nothing left to fix
+        assert!(tl.validate_and_repair(10.0, FULL_VIEW).is_clean());
+    }
+
+    #[test]
+    fn test_validate_and_repair_honors_view_extent() {
+        let mut tl = Timeline::with_default_tracks(10.0);
+        let mut edge = TransformKeyframe::identity(1.0);
+        edge.zoom = 2.0;
+        edge.center = NormalizedPoint::new(0.2, 0.5);
+        tl.transform_track_mut().unwrap().keyframes = vec![edge];
+
+        // Showing 3/4 of the width at 1x, a 2x crop may centre as low as 0.1875
+        assert!(tl.validate_and_repair(10.0, (0.75, 1.0)).is_clean());
+        assert_eq!(tl.transform_track().unwrap().keyframes[0].center.x, 0.2);
+
+        // The whole frame at 1x pulls it back to 0.25
+        tl.validate_and_repair(10.0, FULL_VIEW);
+        assert_eq!(tl.transform_track().unwrap().keyframes[0].center.x, 0.25);
+    }
+
     #[test]
     fn test_serde_roundtrip() {
         let tl = Timeline::with_default_tracks(15.0);
@@ -3,6 +3,9 @@ use uuid::Uuid;
 
 use super::keyframe::*;
 
+/// Keyframes closer than this (seconds) after an edit count as one
+pub const KEYFRAME_COLLISION_TOLERANCE: f64 = 0.001;
+
 /// Track type identifier
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -54,10 +57,11 @@ impl TransformTrack {
         self.keyframes.retain(|k| k.id != id);
     }
 
-    /// Update keyframe (re-sort after)
+    /// Update keyframe (re-sort after). The edit makes it a manual keyframe,
+    /// so a Merge generate keeps it.
     pub fn update_keyframe(&mut self, keyframe: TransformKeyframe) {
         if let Some(idx) = self.keyframes.iter().position(|k| k.id == keyframe.id) {
-            self.keyframes[idx] = keyframe;
+            self.keyframes[idx] = TransformKeyframe { generated: false, ..keyframe };
             self.keyframes.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap());
         }
     }
@@ -289,6 +293,177 @@ impl AnyTrack {
             Self::Keystroke(t) => t.keyframe_count(),
         }
     }
+
+    /// Replace this track's keyframes with `other`'s, keeping id, name and
+    /// settings. Does nothing if the track types differ.
+    pub fn replace_keyframes(&mut self, other: AnyTrack) {
+        match (self, other) {
+            (Self::Transform(t), Self::Transform(o)) => t.keyframes = o.keyframes,
+            (Self::Ripple(t), Self::Ripple(o)) => t.keyframes = o.keyframes,
+            (Self::Cursor(t), Self::Cursor(o)) => t.style_keyframes = o.style_keyframes,
+            (Self::Keystroke(t), Self::Keystroke(o)) => t.keyframes = o.keyframes,
+            _ => {}
+        }
+    }
+
+    /// Drop this track's generated keyframes and add `other`'s, skipping any
+    /// within `tolerance` seconds of a remaining manual keyframe.
+    /// Does nothing if the track types differ.
+    pub fn merge_keyframes(&mut self, other: AnyTrack, tolerance: f64) {
+        match (self, other) {
+            (Self::Transform(t), Self::Transform(o)) => {
+                merge_generated(&mut t.keyframes, o.keyframes, |k| (k.time, k.generated), tolerance)
+            }
+            (Self::Ripple(t), Self::Ripple(o)) => {
+                merge_generated(&mut t.keyframes, o.keyframes, |k| (k.time, k.generated), tolerance)
+            }
+            (Self::Cursor(t), Self::Cursor(o)) => {
+                let mut keyframes = t.style_keyframes.take().unwrap_or_default();
+                merge_generated(
+                    &mut keyframes,
+                    o.style_keyframes.unwrap_or_default(),
+                    |k| (k.time, k.generated),
+                    tolerance,
+                );
+                t.style_keyframes = if keyframes.is_empty() { None } else { Some(keyframes) };
+            }
+            (Self::Keystroke(t), Self::Keystroke(o)) => {
+                merge_generated(&mut t.keyframes, o.keyframes, |k| (k.time, k.generated), tolerance)
+            }
+            _ => {}
+        }
+    }
+
+    /// Move every keyframe at or after `from_time` by `delta` seconds, clamped
+    /// to `[0, duration]`. Where keyframes end up on the same time, a moved one
+    /// beats one that stayed put, and among moved ones the least-clamped wins.
+    /// Moved keyframes count as manual from then on. Returns how many moved.
+    pub fn shift_keyframes(&mut self, from_time: f64, delta: f64, duration: f64) -> usize {
+        match self {
+            Self::Transform(t) => {
+                shift_times(&mut t.keyframes, from_time, delta, duration, |k| (&mut k.time, &mut k.generated))
+            }
+            Self::Ripple(t) => {
+                shift_times(&mut t.keyframes, from_time, delta, duration, |k| (&mut k.time, &mut k.generated))
+            }
+            Self::Cursor(t) => {
+                let Some(keyframes) = t.style_keyframes.as_mut() else { return 0 };
+                shift_times(keyframes, from_time, delta, duration, |k| (&mut k.time, &mut k.generated))
+            }
+            Self::Keystroke(t) => {
+                shift_times(&mut t.keyframes, from_time, delta, duration, |k| (&mut k.time, &mut k.generated))
+            }
+        }
+    }
+
+    /// Copy keyframe `id` to `time` (clamped to `[0, duration]`) as a manual
+    /// keyframe with a new id, replacing any keyframe already at that time.
+    /// Returns the copy's id, or None if `id` isn't on this track.
+    pub fn duplicate_keyframe(&mut self, id: Uuid, time: f64, duration: f64) -> Option<Uuid> {
+        match self {
+            Self::Transform(t) => {
+                duplicate_at(&mut t.keyframes, id, time, duration, |k| (&mut k.id, &mut k.time, &mut k.generated))
+            }
+            Self::Ripple(t) => {
+                duplicate_at(&mut t.keyframes, id, time, duration, |k| (&mut k.id, &mut k.time, &mut k.generated))
+            }
+            Self::Cursor(t) => {
+                let keyframes = t.style_keyframes.as_mut()?;
+                duplicate_at(keyframes, id, time, duration, |k| (&mut k.id, &mut k.time, &mut k.generated))
+            }
+            Self::Keystroke(t) => {
+                duplicate_at(&mut t.keyframes, id, time, duration, |k| (&mut k.id, &mut k.time, &mut k.generated))
+            }
+        }
+    }
+}
+
+/// Stable sort by the time `time` points at
+fn sort_by_time<K>(keyframes: &mut Vec<K>, time: impl Fn(&mut K) -> &mut f64) {
+    let mut timed: Vec<(f64, K)> = keyframes.drain(..).map(|mut k| (*time(&mut k), k)).collect();
+    timed.sort_by(|a, b| a.0.total_cmp(&b.0));
+    keyframes.extend(timed.into_iter().map(|(_, k)| k));
+}
+
+/// See `AnyTrack::shift_keyframes`
+fn shift_times<K>(
+    keyframes: &mut Vec<K>,
+    from_time: f64,
+    delta: f64,
+    duration: f64,
+    fields: impl Fn(&mut K) -> (&mut f64, &mut bool),
+) -> usize {
+    let duration = duration.max(0.0);
+    // (time, keyframe, moved, how far clamping pulled it off its target)
+    let mut shifted: Vec<(f64, K, bool, f64)> = keyframes
+        .drain(..)
+        .map(|mut k| {
+            let (t, generated) = fields(&mut k);
+            if *t < from_time - KEYFRAME_COLLISION_TOLERANCE {
+                return (*t, k, false, 0.0);
+            }
+            let target = *t + delta;
+            *t = target.clamp(0.0, duration);
+            *generated = false;
+            let (new_time, clamped) = (*t, (target - *t).abs());
+            (new_time, k, true, clamped)
+        })
+        .collect();
+    shifted.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let moved = shifted.iter().filter(|s| s.2).count();
+
+    // One keyframe per cluster of colliding times
+    let mut kept: Vec<(f64, K, bool, f64)> = Vec::with_capacity(shifted.len());
+    for entry in shifted {
+        match kept.last_mut() {
+            Some(last) if entry.0 - last.0 <= KEYFRAME_COLLISION_TOLERANCE => {
+                let better = (entry.2 && !last.2) || (entry.2 == last.2 && entry.3 < last.3);
+                if better {
+                    *last = entry;
+                }
+            }
+            _ => kept.push(entry),
+        }
+    }
+    keyframes.extend(kept.into_iter().map(|(_, k, _, _)| k));
+    moved
+}
+
+/// See `AnyTrack::duplicate_keyframe`. `fields` exposes (id, time, generated).
+fn duplicate_at<K: Clone>(
+    keyframes: &mut Vec<K>,
+    id: Uuid,
+    time: f64,
+    duration: f64,
+    fields: impl Fn(&mut K) -> (&mut Uuid, &mut f64, &mut bool),
+) -> Option<Uuid> {
+    let index = keyframes.iter_mut().position(|k| *fields(k).0 == id)?;
+    let mut copy = keyframes[index].clone();
+    let new_id = Uuid::new_v4();
+    let time = time.clamp(0.0, duration.max(0.0));
+    let (copy_id, copy_time, generated) = fields(&mut copy);
+    (*copy_id, *copy_time, *generated) = (new_id, time, false);
+
+    keyframes.retain_mut(|k| (*fields(k).1 - time).abs() > KEYFRAME_COLLISION_TOLERANCE);
+    keyframes.push(copy);
+    sort_by_time(keyframes, |k| fields(k).1);
+    Some(new_id)
+}
+
+/// Keep manual keyframes, replace generated ones with non-colliding `generated`, re-sort
+fn merge_generated<K>(
+    keyframes: &mut Vec<K>,
+    generated: Vec<K>,
+    info: impl Fn(&K) -> (f64, bool),
+    tolerance: f64,
+) {
+    keyframes.retain(|k| !info(k).1);
+    let manual: Vec<f64> = keyframes.iter().map(|k| info(k).0).collect();
+    keyframes.extend(generated.into_iter().filter(|k| {
+        let time = info(k).0;
+        manual.iter().all(|m| (m - time).abs() > tolerance)
+    }));
+    keyframes.sort_by(|a, b| info(a).0.total_cmp(&info(b).0));
 }
 
 #[cfg(test)]
@@ -397,6 +572,98 @@ mod tests {
         assert_eq!(any.keyframe_count(), 0);
     }
 
+    #[test]
+    fn test_merge_keyframes_keeps_manual() {
+        let mut manual = RippleKeyframe::new(2.0, NormalizedPoint::CENTER);
+        manual.intensity = 0.3;
+        let mut stale = RippleKeyframe::new(5.0, NormalizedPoint::CENTER);
+        stale.generated = true;
+        let mut track = RippleTrack::new();
+        track.add_keyframe(manual.clone());
+        track.add_keyframe(stale);
+
+        let mut fresh = RippleTrack::new();
+        for t in [1.0, 2.05, 3.0] {
+            let mut kf = RippleKeyframe::new(t, NormalizedPoint::CENTER);
+            kf.generated = true;
+            fresh.add_keyframe(kf);
+        }
+
+        let mut any = AnyTrack::Ripple(track);
+        any.merge_keyframes(AnyTrack::Ripple(fresh), 0.1);
+        let AnyTrack::Ripple(merged) = any else { unreachable!() };
+        // Stale 5.0 dropped, 2.05 collides with the manual keyframe
+        let times: Vec<f64> = merged.keyframes.iter().map(|k| k.time).collect();
+        assert_eq!(times, vec![1.0, 2.0, 3.0]);
+        assert_eq!(merged.keyframes[1], manual);
+    }
+
+    #[test]
+    fn test_shift_and_duplicate_keyframes() {
+        let ripple_times = |any: &AnyTrack| -> Vec<f64> {
+            let AnyTrack::Ripple(t) = any else { unreachable!() };
+            t.keyframes.iter().map(|k| k.time).collect()
+        };
+        let mut track = RippleTrack::new();
+        for t in [1.0, 2.0, 4.0, 6.0] {
+            track.add_keyframe(RippleKeyframe::new(t, NormalizedPoint::CENTER));
+        }
+        let original_first = track.keyframes[0].id;
+        let mut any = AnyTrack::Ripple(track);
+
+        // Close a 2s gap after 3.0: 4.0 lands on the unmoved 2.0 and replaces it
+        assert_eq!(any.shift_keyframes(3.0, -2.0, 10.0), 2);
+        assert_eq!(ripple_times(&any), vec![1.0, 2.0, 4.0]);
+
+        // Shifting past the end clamps; of the two that pile up at 8.0, the
+        // one clamped least (from 2.0) stays
+        let AnyTrack::Ripple(t) = &any else { unreachable!() };
+        let from_two = t.keyframes[1].id;
+        assert_eq!(any.shift_keyframes(2.0, 6.5, 8.0), 2);
+        assert_eq!(ripple_times(&any), vec![1.0, 8.0]);
+        let AnyTrack::Ripple(t) = &any else { unreachable!() };
+        assert_eq!(t.keyframes[1].id, from_two);
+
+        // A copy at a free time is added; one on an existing keyframe replaces it
+        any.duplicate_keyframe(original_first, 4.0, 8.0).unwrap();
+        let copy = any.duplicate_keyframe(original_first, 9.0, 8.0).unwrap();
+        assert_eq!(ripple_times(&any), vec![1.0, 4.0, 8.0]);
+        let AnyTrack::Ripple(t) = &any else { unreachable!() };
+        assert_eq!(t.keyframes[2].id, copy);
+        assert!(!t.keyframes[2].generated);
+
+        assert!(any.duplicate_keyframe(Uuid::new_v4(), 1.0, 8.0).is_none());
+        assert_eq!(AnyTrack::Cursor(CursorTrack::new()).shift_keyframes(0.0, 1.0, 5.0), 0);
+    }
+
+    #[test]
+    fn test_edited_keyframes_survive_merge() {
+        let generated = |t: f64| {
+            let mut kf = TransformKeyframe::new(t, 1.5, NormalizedPoint::CENTER, EasingCurve::Linear);
+            kf.generated = true;
+            kf
+        };
+        let mut track = TransformTrack::new();
+        for t in [1.0, 3.0, 5.0] {
+            track.add_keyframe(generated(t));
+        }
+        // Tune the zoom of the first, drag the last two later
+        let mut tuned = track.keyframes[0].clone();
+        tuned.zoom = 2.5;
+        track.update_keyframe(tuned);
+        let mut any = AnyTrack::Transform(track);
+        assert_eq!(any.shift_keyframes(3.0, 1.0, 10.0), 2);
+
+        let mut fresh = TransformTrack::new();
+        for t in [2.0, 8.0] {
+            fresh.add_keyframe(generated(t));
+        }
+        any.merge_keyframes(AnyTrack::Transform(fresh), 0.1);
+        let AnyTrack::Transform(merged) = any else { unreachable!() };
+        let kfs: Vec<(f64, f64)> = merged.keyframes.iter().map(|k| (k.time, k.zoom)).collect();
+        assert_eq!(kfs, vec![(1.0, 2.5), (2.0, 1.5), (4.0, 1.5), (6.0, 1.5), (8.0, 1.5)]);
+    }
+
     #[test]
     fn test_any_track_serde_roundtrip() {
         let mut track = RippleTrack::new();
@@ -0,0 +1,230 @@
+//! Errors returned by Tauri commands: a category the frontend can act on
+//! plus a human-readable message, mapped from the core error types.
+
+use std::fmt::Display;
+
+use serde::Serialize;
+
+use crate::core::capture::CaptureError;
+use crate::core::encoder::EncoderError;
+use crate::core::permissions::{PermissionRemediation, PermissionType};
+use crate::core::project::ProjectError;
+use crate::core::recorder::RecorderError;
+use crate::core::recovery::RecoveryError;
+use crate::core::render::ExportError;
+use crate::core::settings::SettingsError;
+use crate::core::style::StyleError;
+
+/// Category of a command failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorKind {
+    /// The command needs a loaded project
+    NoProject,
+    /// A file or directory doesn't exist
+    FileMissing,
+    /// The OS refused access (file permissions, screen recording)
+    PermissionDenied,
+    /// An argument was out of range or malformed
+    InvalidInput,
+    /// A file exists but couldn't be parsed (project, mouse data)
+    InvalidData,
+    /// Encoding or decoding failed
+    Encoder,
+    /// Screen capture failed
+    Capture,
+    /// The operation was cancelled by the user
+    Cancelled,
+    /// Any other I/O failure
+    Io,
+}
+
+/// Error returned to the frontend as `{ kind, message, permission }`
+#[derive(Debug, Clone, PartialEq, Serialize, thiserror::Error)]
+#[error("{message}")]
+pub struct CommandError {
+    pub kind: ErrorKind,
+    pub message: String,
+    /// The permission a `PermissionDenied` recording start needs, so the
+    /// frontend can point at the right setting
+    pub permission: Option<PermissionDetail>,
+}
+
+/// Which permission was denied and how the user grants it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PermissionDetail {
+    pub which: PermissionType,
+    pub remediation: PermissionRemediation,
+}
+
+pub type CommandResult<T> = Result<T, CommandError>;
+
+impl CommandError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self { kind, message: message.into(), permission: None }
+    }
+
+    pub fn no_project() -> Self {
+        Self::new(ErrorKind::NoProject, "No project loaded. Record or open a project first.")
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InvalidInput, message)
+    }
+
+    /// Prefix the message with what was being done, keeping the kind
+    pub fn context(mut self, what: impl Display) -> Self {
+        self.message = format!("{what}: {}", self.message);
+        self
+    }
+}
+
+impl From<std::io::Error> for CommandError {
+    fn from(e: std::io::Error) -> Self {
+        let kind = match e.kind() {
+            std::io::ErrorKind::NotFound => ErrorKind::FileMissing,
+            std::io::ErrorKind::PermissionDenied => ErrorKind::PermissionDenied,
+            _ => ErrorKind::Io,
+        };
+        Self::new(kind, e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for CommandError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::new(ErrorKind::InvalidData, e.to_string())
+    }
+}
+
+impl From<ProjectError> for CommandError {
+    fn from(e: ProjectError) -> Self {
+        match e {
+            ProjectError::Io(io) => io.into(),
+            ProjectError::NotFound(_) => Self::new(ErrorKind::FileMissing, e.to_string()),
+            ProjectError::Serialization(_) => Self::new(ErrorKind::InvalidData, e.to_string()),
+            ProjectError::Append(_) => Self::new(ErrorKind::Io, e.to_string()),
+        }
+    }
+}
+
+impl From<EncoderError> for CommandError {
+    fn from(e: EncoderError) -> Self {
+        match e {
+            EncoderError::Io(io) => io.into(),
+            EncoderError::InvalidConfig(_) => Self::invalid_input(e.to_string()),
+            _ => Self::new(ErrorKind::Encoder, e.to_string()),
+        }
+    }
+}
+
+impl From<ExportError> for CommandError {
+    fn from(e: ExportError) -> Self {
+        match e {
+            ExportError::Encoder(encoder) => encoder.into(),
+            ExportError::Io(io) => io.into(),
+            ExportError::NoSource => Self::new(ErrorKind::FileMissing, e.to_string()),
+            ExportError::Cancelled => Self::new(ErrorKind::Cancelled, e.to_string()),
+            ExportError::NoFrames => Self::invalid_input(e.to_string()),
+        }
+    }
+}
+
+impl From<CaptureError> for CommandError {
+    fn from(e: CaptureError) -> Self {
+        let kind = match e {
+            CaptureError::PermissionDenied => ErrorKind::PermissionDenied,
+            _ => ErrorKind::Capture,
+        };
+        Self::new(kind, e.to_string())
+    }
+}
+
+impl From<RecorderError> for CommandError {
+    fn from(e: RecorderError) -> Self {
+        match e {
+            RecorderError::Capture(capture) => capture.into(),
+            RecorderError::Encoder(encoder) => encoder.into(),
+            RecorderError::Io(io) => io.into(),
+            RecorderError::PermissionDenied { which, remediation } => Self {
+                permission: Some(PermissionDetail { which, remediation }),
+                ..Self::new(ErrorKind::PermissionDenied, e.to_string())
+            },
+            RecorderError::InvalidState { .. } => Self::invalid_input(e.to_string()),
+            RecorderError::Input(_) | RecorderError::NoFrames => Self::new(ErrorKind::Capture, e.to_string()),
+        }
+    }
+}
+
+impl From<RecoveryError> for CommandError {
+    fn from(e: RecoveryError) -> Self {
+        match e {
+            RecoveryError::Io(io) => io.into(),
+            RecoveryError::Recorder(recorder) => recorder.into(),
+            RecoveryError::Project(project) => project.into(),
+        }
+    }
+}
+
+impl From<SettingsError> for CommandError {
+    fn from(e: SettingsError) -> Self {
+        match e {
+            SettingsError::Io(io) => io.into(),
+            SettingsError::Serialization(_) => Self::new(ErrorKind::InvalidData, e.to_string()),
+        }
+    }
+}
+
+impl From<StyleError> for CommandError {
+    fn from(e: StyleError) -> Self {
+        match e {
+            StyleError::Io(io) => io.into(),
+            StyleError::Serialization(_) | StyleError::UnsupportedVersion(_) => {
+                Self::new(ErrorKind::InvalidData, e.to_string())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_kinds_from_core_errors() {
+        let missing = std::io::Error::new(std::io::ErrorKind::NotFound, "gone");
+        assert_eq!(CommandError::from(ProjectError::Io(missing)).kind, ErrorKind::FileMissing);
+        assert_eq!(
+            CommandError::from(ProjectError::NotFound("project.json".into())).kind,
+            ErrorKind::FileMissing,
+        );
+
+        let denied = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "no");
+        let err = CommandError::from(ExportError::Encoder(EncoderError::Io(denied)));
+        assert_eq!(err.kind, ErrorKind::PermissionDenied);
+        assert_eq!(
+            CommandError::from(ExportError::Encoder(EncoderError::Ffmpeg("x".into()))).kind,
+            ErrorKind::Encoder,
+        );
+        assert_eq!(CommandError::from(ExportError::Cancelled).kind, ErrorKind::Cancelled);
+        assert_eq!(CommandError::from(CaptureError::PermissionDenied).kind, ErrorKind::PermissionDenied);
+
+        let denied = RecorderError::PermissionDenied {
+            which: PermissionType::ScreenCapture,
+            remediation: PermissionRemediation::OpenSystemSettings,
+        };
+        let err = CommandError::from(denied);
+        assert_eq!(err.kind, ErrorKind::PermissionDenied);
+        assert_eq!(
+            err.permission,
+            Some(PermissionDetail {
+                which: PermissionType::ScreenCapture,
+                remediation: PermissionRemediation::OpenSystemSettings,
+            }),
+        );
+        assert_eq!(CommandError::from(CaptureError::PermissionDenied).permission, None);
+
+        let err = CommandError::from(CaptureError::TargetNotFound).context("Start recording");
+        assert_eq!(err.kind, ErrorKind::Capture);
+        assert!(err.message.starts_with("Start recording: "));
+    }
+}
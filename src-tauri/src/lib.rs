@@ -1,4 +1,5 @@
 pub mod core;
+mod error;
 
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
@@ -6,24 +7,171 @@ use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Emitter, Manager, State};
 
 use core::capture::create_capture;
-use core::permissions::{create_permissions_manager, PermissionReport};
+use core::export::{input_to_evaluator_clicks, input_to_evaluator_positions, load_input_recording};
+use core::permissions::{create_permissions_manager, PermissionReport, PermissionType};
 use core::capture::CaptureSource;
+use core::encoder::bgra_to_rgba;
 use core::project::Project;
 use core::recorder::{RecordingCoordinator, RecordingStatus};
 use core::render::{ExportProgress, FrameBuffer};
+use core::settings::AppSettings;
+use error::{CommandError, CommandResult, ErrorKind};
 
 struct AppState {
     recorder: Arc<Mutex<RecordingCoordinator>>,
     export_progress: Arc<Mutex<Option<ExportProgress>>>,
-    /// Currently loaded project (set after recording or opening a project)
-    current_project: Mutex<Option<LoadedProject>>,
+    /// Set by `cancel_export` to stop the running export
+    export_cancel: Arc<std::sync::atomic::AtomicBool>,
+    /// Currently loaded project (set after recording or opening a project),
+    /// shared with the auto-save thread
+    current_project: Arc<Mutex<Option<LoadedProject>>>,
+    /// Persisted app settings and where they are stored
+    settings: Mutex<AppSettings>,
+    settings_path: PathBuf,
+    /// Package the in-progress recording is appended to (set by `start_append_recording`)
+    append_target: Mutex<Option<PathBuf>>,
+    /// Mouse data for preview rendering, kept between scrubs
+    preview_input: Mutex<Option<Arc<PreviewInput>>>,
+    /// Images and cursor pack for preview rendering, kept between scrubs
+    preview_assets: Mutex<PreviewAssets>,
+    /// Last `compute_activity_heatmap` result
+    heatmap: Mutex<Option<CachedHeatmap>>,
 }
 
+/// Evaluator input parsed from a project's mouse data file
+struct PreviewInput {
+    mouse_path: PathBuf,
+    modified: Option<std::time::SystemTime>,
+    time_offset: f64,
+    warmup_secs: f64,
+    smoothing: core::project::MouseSmoothing,
+    positions: Vec<core::evaluator::MousePosition>,
+    clicks: Vec<core::evaluator::MouseClick>,
+}
+
+/// Preview assets decoded from the project's files
+#[derive(Default)]
+struct PreviewAssets {
+    background: Option<CachedAsset<Option<Arc<FrameBuffer>>>>,
+    watermark: Option<CachedAsset<Option<Arc<FrameBuffer>>>>,
+    cursor_pack: Option<CachedAsset<Arc<core::cursor_theme::CursorPack>>>,
+}
+
+/// Something loaded from `path`, and the file's modification time then
+struct CachedAsset<T> {
+    path: PathBuf,
+    modified: Option<std::time::SystemTime>,
+    value: T,
+}
+
+/// The value cached in `slot` if it was loaded from `path` as it is now,
+/// else `load(path)` (which replaces it)
+fn cached_asset<T: Clone>(
+    slot: &mut Option<CachedAsset<T>>,
+    path: &std::path::Path,
+    load: impl FnOnce(&std::path::Path) -> T,
+) -> T {
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    if let Some(asset) = slot.as_ref().filter(|a| a.path == path && a.modified == modified) {
+        return asset.value.clone();
+    }
+    let value = load(path);
+    *slot = Some(CachedAsset { path: path.to_path_buf(), modified, value: value.clone() });
+    value
+}
+
+/// A heatmap and what it was computed from
+struct CachedHeatmap {
+    mouse_path: PathBuf,
+    modified: Option<std::time::SystemTime>,
+    time_offset: f64,
+    warmup_secs: f64,
+    range: (f64, f64),
+    heatmap: core::heatmap::ActivityHeatmap,
+}
+
+impl AppState {
+    /// Apply a change to the app settings and persist it (failures are logged)
+    fn update_settings(&self, f: impl FnOnce(&mut AppSettings)) {
+        let mut settings = self.settings.lock().unwrap();
+        f(&mut settings);
+        if let Err(e) = settings.save(&self.settings_path) {
+            log::warn!("Failed to save app settings: {e}");
+        }
+    }
+
+    /// Save the current project's unsaved edits now instead of waiting for
+    /// the auto-save, before something reads the package from disk
+    fn flush_project(&self) {
+        if let Some(loaded) = self.current_project.lock().unwrap().as_mut() {
+            loaded.flush();
+        }
+    }
+
+    /// Make `loaded` the current project, saving the edits of the one it replaces
+    fn set_project(&self, loaded: LoadedProject) {
+        let mut current = self.current_project.lock().unwrap();
+        if let Some(previous) = current.as_mut() {
+            previous.flush();
+        }
+        *current = Some(loaded);
+    }
+}
+
+/// How often the auto-save thread writes edited projects to disk
+const AUTOSAVE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3);
+
 /// A project loaded in the editor, with its package directory path.
+///
+/// Editing commands only `mark_dirty`; the auto-save thread (or an explicit
+/// save) writes `project.json`, so rapid edits don't each rewrite the package.
 #[derive(Clone)]
 struct LoadedProject {
     project: Project,
     package_dir: PathBuf,
+    /// Edited since `project.json` was last written
+    dirty: bool,
+}
+
+impl LoadedProject {
+    fn new(project: Project, package_dir: PathBuf) -> Self {
+        Self { project, package_dir, dirty: false }
+    }
+
+    /// Record an edit: the recovery file is rewritten now, `project.json` on
+    /// the next auto-save
+    fn mark_dirty(&mut self) {
+        self.dirty = true;
+        if let Err(e) = self.project.write_recovery(&self.package_dir) {
+            log::warn!("Failed to write recovery file for {}: {e}", self.package_dir.display());
+        }
+    }
+
+    fn save(&mut self) -> Result<(), core::project::ProjectError> {
+        self.project.save(&self.package_dir, None, None)?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Save if there are unsaved edits (failures are logged and retried on
+    /// the next flush)
+    fn flush(&mut self) {
+        if self.dirty {
+            if let Err(e) = self.save() {
+                log::warn!("Auto-save of {} failed: {e}", self.package_dir.display());
+            }
+        }
+    }
+}
+
+/// Write the current project's unsaved edits every `AUTOSAVE_INTERVAL`
+fn spawn_autosave(current_project: Arc<Mutex<Option<LoadedProject>>>) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(AUTOSAVE_INTERVAL);
+        if let Some(loaded) = current_project.lock().unwrap().as_mut() {
+            loaded.flush();
+        }
+    });
 }
 
 /// Serializable project info returned to the frontend
@@ -36,6 +184,30 @@ struct ProjectInfo {
     width: f64,
     height: f64,
     package_path: String,
+    /// Referenced files missing from the package (the project still opens)
+    missing_assets: Vec<core::project::AssetIssue>,
+    input_time_offset: f64,
+    warmup_secs: f64,
+    /// The package has unsaved edits from a session that ended before they
+    /// were saved (see `resolve_recovery`)
+    recovery_available: bool,
+}
+
+impl ProjectInfo {
+    fn new(project: &Project, package_dir: &std::path::Path) -> Self {
+        Self {
+            name: project.name.clone(),
+            duration: project.duration(),
+            frame_rate: project.media.frame_rate,
+            width: project.media.pixel_size.width,
+            height: project.media.pixel_size.height,
+            package_path: package_dir.display().to_string(),
+            missing_assets: project.missing_assets(package_dir),
+            input_time_offset: project.input_time_offset,
+            warmup_secs: project.warmup_secs,
+            recovery_available: Project::has_newer_recovery(package_dir),
+        }
+    }
 }
 
 #[tauri::command]
@@ -44,10 +216,13 @@ fn check_permissions() -> PermissionReport {
     manager.check_all()
 }
 
+/// Capturable displays and windows. Falls back to the primary display when
+/// enumeration fails; errors only without permission or with nothing to capture.
 #[tauri::command]
-fn list_capture_sources() -> Result<Vec<CaptureSource>, String> {
+fn list_capture_sources() -> CommandResult<Vec<CaptureSource>> {
     let capture = create_capture();
-    capture.enumerate_sources().map_err(|e| e.to_string())
+    let permission = create_permissions_manager().check(PermissionType::ScreenCapture);
+    Ok(core::capture::list_sources(capture.as_ref(), permission)?)
 }
 
 #[tauri::command]
@@ -62,60 +237,201 @@ fn set_capture_target(
     width: Option<u32>,
     height: Option<u32>,
     state: State<AppState>,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     let mut recorder = state.recorder.lock().unwrap();
-    recorder.set_target(target);
+    recorder.set_target(target.clone());
     if let (Some(w), Some(h)) = (width, height) {
         if w > 0 && h > 0 {
             recorder.set_capture_dimensions(w, h, 1.0);
         }
     }
+    drop(recorder);
+
+    state.update_settings(|settings| {
+        settings.last_capture_target = Some(target);
+        settings.capture_width = width.filter(|w| *w > 0);
+        settings.capture_height = height.filter(|h| *h > 0);
+    });
     Ok(())
 }
 
+/// Record `source` (a display or window from `list_capture_sources`) at its size
+#[tauri::command]
+fn set_capture_source(source: core::capture::CaptureSource, state: State<AppState>) -> CommandResult<()> {
+    let (width, height) = (Some(source.width), Some(source.height));
+    set_capture_target(source.target(), width, height, state)
+}
+
+/// Get the persisted app settings (last capture target, defaults for new recordings).
 #[tauri::command]
-fn start_recording(state: State<AppState>) -> Result<(), String> {
+fn get_app_settings(state: State<AppState>) -> AppSettings {
+    state.settings.lock().unwrap().clone()
+}
+
+/// Replace the persisted app settings and apply the capture target to the recorder.
+#[tauri::command]
+fn set_app_settings(settings: AppSettings, state: State<AppState>) -> CommandResult<()> {
+    apply_capture_settings(&mut state.recorder.lock().unwrap(), &settings);
+    settings.save(&state.settings_path)?;
+    *state.settings.lock().unwrap() = settings;
+    Ok(())
+}
+
+/// Built-in export presets followed by the user's.
+#[tauri::command]
+fn list_export_presets(state: State<AppState>) -> Vec<core::settings::ExportPreset> {
+    state.settings.lock().unwrap().export_presets()
+}
+
+/// Add or replace a user export preset. Returns it with its final id.
+#[tauri::command]
+fn save_export_preset(
+    preset: core::settings::ExportPreset,
+    state: State<AppState>,
+) -> CommandResult<core::settings::ExportPreset> {
+    let mut settings = state.settings.lock().unwrap();
+    let saved = settings.save_export_preset(preset).map_err(CommandError::invalid_input)?;
+    settings.save(&state.settings_path)?;
+    Ok(saved)
+}
+
+/// Remove a user export preset (a built-in it overrode comes back).
+#[tauri::command]
+fn delete_export_preset(id: String, state: State<AppState>) -> CommandResult<()> {
+    let mut settings = state.settings.lock().unwrap();
+    if !settings.remove_export_preset(&id) {
+        return Err(CommandError::invalid_input(format!("No user preset \"{id}\"")));
+    }
+    settings.save(&state.settings_path).map_err(CommandError::from)
+}
+
+/// Point the recorder at the remembered capture target, if any,
+/// and apply the backpressure tuning and cursor capture mode
+fn apply_capture_settings(recorder: &mut RecordingCoordinator, settings: &AppSettings) {
+    recorder.set_backpressure(
+        settings.frame_channel_capacity.unwrap_or(core::capture::DEFAULT_FRAME_CHANNEL_CAPACITY),
+        settings.backpressure,
+    );
+    recorder.set_capture_cursor(settings.use_captured_cursor);
+    if let Some(target) = &settings.last_capture_target {
+        recorder.set_target(target.clone());
+        if let (Some(w), Some(h)) = (settings.capture_width, settings.capture_height) {
+            recorder.set_capture_dimensions(w, h, 1.0);
+        }
+    }
+}
+
+#[tauri::command]
+fn start_recording(state: State<AppState>) -> CommandResult<()> {
     log::info!("Starting recording...");
+    let capture_cursor = state.settings.lock().unwrap().use_captured_cursor;
     let mut recorder = state.recorder.lock().unwrap();
+    // An appended take may have switched the cursor mode to match its project
+    recorder.set_capture_cursor(capture_cursor);
     recorder.start().map_err(|e| {
         log::error!("Failed to start recording: {e}");
-        e.to_string()
-    })
+        CommandError::from(e)
+    })?;
+    *state.append_target.lock().unwrap() = None;
+    Ok(())
 }
 
+/// Record another take and append it to an existing project when stopped.
+/// The capture size must match the project's video so the streams can be joined.
 #[tauri::command]
-fn pause_recording(state: State<AppState>) -> Result<(), String> {
+fn start_append_recording(package_dir: String, state: State<AppState>) -> CommandResult<()> {
+    let package_dir = PathBuf::from(&package_dir);
+    let project = Project::load(&package_dir)?;
+
     let mut recorder = state.recorder.lock().unwrap();
-    recorder.pause().map_err(|e| e.to_string())
+    let (width, height) = recorder.capture_size();
+    let media_size = project.media.pixel_size;
+    if width as f64 != media_size.width || height as f64 != media_size.height {
+        return Err(CommandError::invalid_input(format!(
+            "Capture size {width}x{height} doesn't match the project's {}x{}",
+            media_size.width, media_size.height,
+        )));
+    }
+    // Takes share one cursor mode, or the drawn cursor would come and go
+    recorder.set_capture_cursor(project.capture_meta.cursor_captured);
+
+    log::info!("Starting recording to append to {}", package_dir.display());
+    recorder.start().map_err(|e| {
+        log::error!("Failed to start recording: {e}");
+        CommandError::from(e)
+    })?;
+    *state.append_target.lock().unwrap() = Some(package_dir);
+    Ok(())
 }
 
+/// Start or stop sending `recording-preview` events (a few downscaled frames
+/// a second, as `FrameData`) while recording.
 #[tauri::command]
-fn resume_recording(state: State<AppState>) -> Result<(), String> {
+fn set_live_preview(enabled: bool, app: AppHandle, state: State<AppState>) {
+    use base64::Engine;
+
+    let sink: Option<core::recorder::PreviewSink> = enabled.then(|| {
+        Arc::new(move |frame: core::recorder::PreviewFrame| {
+            let _ = app.emit("recording-preview", FrameData {
+                width: frame.width,
+                height: frame.height,
+                rgba_base64: base64::engine::general_purpose::STANDARD.encode(&frame.rgba),
+            });
+        }) as core::recorder::PreviewSink
+    });
+    state.recorder.lock().unwrap().set_live_preview(sink);
+}
+
+#[tauri::command]
+fn pause_recording(state: State<AppState>) -> CommandResult<()> {
+    let mut recorder = state.recorder.lock().unwrap();
+    recorder.pause().map_err(CommandError::from)
+}
+
+#[tauri::command]
+fn resume_recording(state: State<AppState>) -> CommandResult<()> {
     let mut recorder = state.recorder.lock().unwrap();
-    recorder.resume().map_err(|e| e.to_string())
+    recorder.resume().map_err(CommandError::from)
+}
+
+/// Drop a marker at the current recording time (bound to a hotkey in the UI).
+/// It becomes a timeline marker when the recording is saved. Returns its time.
+#[tauri::command]
+fn add_recording_marker(label: Option<String>, state: State<AppState>) -> CommandResult<f64> {
+    let mut recorder = state.recorder.lock().unwrap();
+    let label = label.map(|l| l.trim().to_string()).filter(|l| !l.is_empty());
+    recorder.add_marker(label.unwrap_or_else(|| "Marker".into())).map_err(CommandError::from)
 }
 
 /// Stop recording asynchronously. Returns immediately, emits "recording-stopped" event
 /// with the project info when done, or "recording-stop-error" with the error message.
+/// "recording-stop-progress" events report what the stop is waiting on meanwhile.
 #[tauri::command]
-fn stop_recording(app: AppHandle, state: State<AppState>) -> Result<(), String> {
+fn stop_recording(app: AppHandle, state: State<AppState>) -> CommandResult<()> {
     // Verify we can stop (check state without blocking for long)
     {
         let recorder = state.recorder.lock().unwrap();
         let s = recorder.state();
         if s != core::recorder::RecordingState::Recording && s != core::recorder::RecordingState::Paused {
-            return Err(format!("Cannot stop: recorder is {:?}", s));
+            return Err(CommandError::invalid_input(format!("Cannot stop: recorder is {:?}", s)));
         }
     }
 
+    // An append reads the package from disk
+    state.flush_project();
+
     // Clone what we need for the background thread
     let recorder_clone = state.recorder.clone();
+    let default_render_settings = state.settings.lock().unwrap().default_render_settings.clone();
+    let append_to = state.append_target.lock().unwrap().take();
     let app_for_thread = app.clone();
 
     std::thread::spawn(move || {
         let stop_result = {
             let mut recorder = recorder_clone.lock().unwrap();
-            let result = recorder.stop();
+            let result = recorder.stop_with_progress(|progress| {
+                let _ = app_for_thread.emit("recording-stop-progress", &progress);
+            });
             if result.is_ok() {
                 recorder.reset();
             }
@@ -124,64 +440,25 @@ fn stop_recording(app: AppHandle, state: State<AppState>) -> Result<(), String>
 
         match stop_result {
             Ok(result) => {
-                // Save input data alongside video
-                let mouse_path = match result.save_input_data() {
+                let packaged = match &append_to {
+                    Some(package_dir) => append_recording_segment(&result, package_dir),
+                    None => package_new_recording(&result, default_render_settings),
+                };
+                let (project, package_dir) = match packaged {
                     Ok(p) => p,
                     Err(e) => {
-                        log::error!("Failed to save input data: {e}");
+                        log::error!("Failed to save project: {e}");
                         let _ = app_for_thread.emit("recording-stop-error", e.to_string());
                         return;
                     }
                 };
 
-                // Create and save project package
-                let timestamp = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs();
-                let package_name = format!("Recording_{timestamp}.lazyrec");
-                let package_dir = result.video_path.parent()
-                    .unwrap_or(std::path::Path::new("."))
-                    .join(&package_name);
-
-                let mut project = result.to_project(format!("Recording {timestamp}"));
-                if let Err(e) = project.save(
-                    &package_dir,
-                    Some(&result.video_path),
-                    Some(&mouse_path),
-                ) {
-                    log::error!("Failed to save project: {e}");
-                    let _ = app_for_thread.emit("recording-stop-error", e.to_string());
-                    return;
-                }
-
-                // Clean up original files now that they're copied into the package
-                if let Err(e) = std::fs::remove_file(&result.video_path) {
-                    log::warn!("Failed to remove original video: {e}");
-                }
-                if let Err(e) = std::fs::remove_file(&mouse_path) {
-                    log::warn!("Failed to remove original mouse data: {e}");
-                }
-
-                let info = ProjectInfo {
-                    name: project.name.clone(),
-                    duration: project.duration(),
-                    frame_rate: project.media.frame_rate,
-                    width: project.media.pixel_size.width,
-                    height: project.media.pixel_size.height,
-                    package_path: package_dir.display().to_string(),
-                };
+                let info = ProjectInfo::new(&project, &package_dir);
 
                 // Store as current project — need to access AppState
                 // We use the app handle to get the managed state
                 let app_state: tauri::State<AppState> = app_for_thread.state();
-                {
-                    let mut current = app_state.current_project.lock().unwrap();
-                    *current = Some(LoadedProject {
-                        project,
-                        package_dir,
-                    });
-                }
+                app_state.set_project(LoadedProject::new(project, package_dir));
 
                 let _ = app_for_thread.emit("recording-stopped", &info);
             }
@@ -195,101 +472,158 @@ fn stop_recording(app: AppHandle, state: State<AppState>) -> Result<(), String>
     Ok(())
 }
 
-/// Start export on a background thread. Progress is streamed via "export-progress" events.
-/// Returns immediately with "ok" or an error if no project is loaded.
+/// Abort the recording without creating a project: stops capture and input,
+/// then deletes the partial video. Runs in the background like `stop_recording`
+/// and emits "recording-discarded" when done or "recording-stop-error" on failure.
 #[tauri::command]
-fn start_export(app: AppHandle, state: State<AppState>) -> Result<String, String> {
-    use core::render::{ExportEngine, create_video_source_from_file};
+fn discard_recording(app: AppHandle, state: State<AppState>) -> CommandResult<()> {
+    {
+        let recorder = state.recorder.lock().unwrap();
+        let s = recorder.state();
+        if s != core::recorder::RecordingState::Recording && s != core::recorder::RecordingState::Paused {
+            return Err(CommandError::invalid_input(format!("Cannot discard: recorder is {:?}", s)));
+        }
+    }
+    *state.append_target.lock().unwrap() = None;
 
-    let current = state.current_project.lock().unwrap();
-    let loaded = current.as_ref().ok_or("No project loaded. Record or open a project first.")?;
+    let recorder_clone = state.recorder.clone();
+    std::thread::spawn(move || {
+        let result = recorder_clone.lock().unwrap().discard();
+        match result {
+            Ok(()) => {
+                let _ = app.emit("recording-discarded", ());
+            }
+            Err(e) => {
+                log::error!("Discard recording failed: {e}");
+                let _ = app.emit("recording-stop-error", e.to_string());
+            }
+        }
+    });
 
-    // Clone everything we need before dropping the lock
-    let project = loaded.project.clone();
-    let package_dir = loaded.package_dir.clone();
-    drop(current);
+    Ok(())
+}
 
-    let progress_state = state.export_progress.clone();
+/// Package a finished recording as a new `.lazyrec` project next to the video.
+/// The original files are removed once copied into the package.
+fn package_new_recording(
+    result: &core::recorder::RecordingResult,
+    render_settings: core::project::RenderSettings,
+) -> CommandResult<(Project, PathBuf)> {
+    // Save input data alongside video
+    let mouse_path = result.save_input_data()?;
+
+    // Create and save project package
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let package_name = format!("Recording_{timestamp}.lazyrec");
+    let package_dir = result.video_path.parent()
+        .unwrap_or(std::path::Path::new("."))
+        .join(&package_name);
+
+    let mut project = result.to_project(format!("Recording {timestamp}"));
+    project.render_settings = render_settings;
+    project.save(
+        &package_dir,
+        Some(&result.video_path),
+        Some(&mouse_path),
+    )?;
+
+    // Clean up original files now that they're copied into the package
+    if let Err(e) = std::fs::remove_file(&result.video_path) {
+        log::warn!("Failed to remove original video: {e}");
+    }
+    if let Err(e) = std::fs::remove_file(&mouse_path) {
+        log::warn!("Failed to remove original mouse data: {e}");
+    }
+    Ok((project, package_dir))
+}
 
-    std::thread::spawn(move || {
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
-        let output_dir = package_dir.parent()
-            .unwrap_or(std::path::Path::new("."));
-        let output_path = output_dir.join(format!("export_{timestamp}.mp4"));
+/// Append a finished recording as a new segment of the project in `package_dir`.
+/// The segment files are removed once joined; on failure they stay recoverable.
+fn append_recording_segment(
+    result: &core::recorder::RecordingResult,
+    package_dir: &std::path::Path,
+) -> CommandResult<(Project, PathBuf)> {
+    let mouse_path = result.save_input_data()?;
+    let mut project = Project::load(package_dir)?;
+    let segment_start = project.media.duration;
+    project.append_segment(
+        package_dir,
+        &result.video_path,
+        result.input_data.clone(),
+        result.duration,
+        |first, second, output| {
+            core::render::concat_videos(first, second, output).map_err(|e| e.to_string())
+        },
+    )?;
+    for (time, label) in &result.markers {
+        project.timeline.add_marker(segment_start + time, label.clone());
+    }
+    project.save(package_dir, None, None)?;
+    log::info!(
+        "Appended {:.1}s segment to {} (now {:.1}s)",
+        result.duration, package_dir.display(), project.duration(),
+    );
 
-        let video_path = project.video_path(&package_dir);
-        log::info!(
-            "Export: video_path={}, exists={}, package_dir={}",
-            video_path.display(),
-            video_path.exists(),
-            package_dir.display(),
-        );
+    if let Err(e) = std::fs::remove_file(&result.video_path) {
+        log::warn!("Failed to remove segment video: {e}");
+    }
+    if let Err(e) = std::fs::remove_file(&mouse_path) {
+        log::warn!("Failed to remove segment mouse data: {e}");
+    }
+    Ok((project, package_dir.to_path_buf()))
+}
 
-        // Fast path: if timeline has no effects, just copy the recording file.
-        // No need to decode/re-encode every frame when nothing changes.
-        let kf_count = project.timeline.total_keyframe_count();
-        log::info!("Export: timeline has {} keyframes, is_empty={}", kf_count, project.timeline.is_empty());
-        if project.timeline.is_empty() {
-            log::info!("No effects — fast-copying recording to {}", output_path.display());
-            let ps = progress_state.clone();
-            if let Ok(mut p) = ps.lock() {
-                *p = Some(core::render::ExportProgress {
-                    current_frame: 0,
-                    total_frames: 1,
-                    progress: 0.5,
-                    eta_seconds: 0.0,
-                    state: core::render::ExportState::Rendering,
-                });
-            }
-            match std::fs::copy(&video_path, &output_path) {
-                Ok(_) => {
-                    let size = std::fs::metadata(&output_path).map(|m| m.len()).unwrap_or(0);
-                    let size_mb = size as f64 / (1024.0 * 1024.0);
-                    let msg = format!("Export complete: {} ({:.1} MB)", output_path.display(), size_mb);
-                    log::info!("{msg}");
-                    let _ = app.emit("export-complete", &msg);
-                }
-                Err(e) => {
-                    log::error!("Fast copy failed: {e}");
-                    let _ = app.emit("export-error", &e.to_string());
-                }
-            }
-            return;
-        }
+/// Export the project in the background. With `image_sequence` the frames are
+/// written as numbered PNGs (default directory: `export_<time>_frames` next to
+/// where the video would go) instead of a video.
+#[tauri::command]
+fn start_export(
+    image_sequence: Option<core::encoder::ImageSequenceOptions>,
+    preset_id: Option<String>,
+    app: AppHandle,
+    state: State<AppState>,
+) -> CommandResult<String> {
+    use core::export::{export_project, ExportTarget};
+
+    let preset = match preset_id {
+        Some(id) => Some(
+            state.settings.lock().unwrap().export_preset(&id)
+                .ok_or_else(|| CommandError::invalid_input(format!("Unknown export preset \"{id}\"")))?,
+        ),
+        None => None,
+    };
 
-        let source = create_video_source_from_file(
-            &video_path,
-            project.media.pixel_size.width as u32,
-            project.media.pixel_size.height as u32,
-            project.duration(),
-            project.media.frame_rate,
-        );
+    let mut current = state.current_project.lock().unwrap();
+    let loaded = current.as_mut().ok_or_else(CommandError::no_project)?;
+
+    // A preset rewrites the project's output settings (and sticks, like any
+    // other render settings change)
+    let mut image_sequence = image_sequence;
+    if let Some(preset) = &preset {
+        preset.apply(&mut loaded.project.render_settings);
+        loaded.save()?;
+        if preset.format == core::settings::ExportFormat::ImageSequence && image_sequence.is_none() {
+            image_sequence = Some(core::encoder::ImageSequenceOptions::default());
+        }
+    }
 
-        let mouse_positions = {
-            let mouse_path = project.mouse_data_path(&package_dir);
-            if mouse_path.exists() {
-                let json = std::fs::read_to_string(&mouse_path).unwrap_or_default();
-                core::input::InputRecording::from_json(&json)
-                    .map(|r| input_to_evaluator_positions(&r))
-                    .unwrap_or_default()
-            } else {
-                vec![]
-            }
-        };
+    // Clone everything we need before dropping the lock
+    let project = loaded.project.clone();
+    let package_dir = loaded.package_dir.clone();
+    drop(current);
 
-        let mut engine = ExportEngine::from_project(
-            &project,
-            source,
-            mouse_positions,
-            output_path,
-        );
+    let progress_state = state.export_progress.clone();
+    let cancel = state.export_cancel.clone();
+    cancel.store(false, std::sync::atomic::Ordering::Relaxed);
 
+    std::thread::spawn(move || {
+        let target = ExportTarget::beside_package(&project, &package_dir, image_sequence);
         let app_handle = app.clone();
         let ps = progress_state.clone();
-        let result = engine.export(move |progress| {
+        let result = export_project(&project, &package_dir, &target, Some(cancel), move |progress| {
             if let Ok(mut p) = ps.lock() {
                 *p = Some(progress.clone());
             }
@@ -298,6 +632,11 @@ fn start_export(app: AppHandle, state: State<AppState>) -> Result<String, String
 
         // Emit final result event
         match result {
+            Ok(path) if target.image_sequence.is_some() => {
+                let frames = std::fs::read_dir(&path).map(|d| d.count()).unwrap_or(0);
+                let msg = format!("Export complete: {} ({frames} frames)", path.display());
+                let _ = app.emit("export-complete", &msg);
+            }
             Ok(path) => {
                 let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
                 let size_mb = size as f64 / (1024.0 * 1024.0);
@@ -305,52 +644,509 @@ fn start_export(app: AppHandle, state: State<AppState>) -> Result<String, String
                 let _ = app.emit("export-complete", &msg);
             }
             Err(e) => {
-                let _ = app.emit("export-error", &e.to_string());
+                let _ = app.emit("export-error", &e.to_string());
+            }
+        }
+    });
+
+    Ok("Export started".into())
+}
+
+/// Decode an exported video end to end on a background thread and check its
+/// frame count and duration against the export log beside it, so a truncated
+/// or corrupt file is caught. Streams `verify-progress` and ends with
+/// `verify-complete` (the problems found, rather than failing on them) or
+/// `verify-error`.
+#[tauri::command]
+fn verify_export(app: AppHandle, path: String) -> CommandResult<()> {
+    let output = PathBuf::from(&path);
+    if !output.is_file() {
+        return Err(CommandError::new(ErrorKind::FileMissing, format!("{path} does not exist")));
+    }
+    std::thread::spawn(move || {
+        let app_handle = app.clone();
+        let result = core::render::verify_export(&output, move |progress| {
+            let _ = app_handle.emit("verify-progress", &progress);
+        });
+        match result {
+            Ok(result) => {
+                let _ = app.emit("verify-complete", &result);
+            }
+            Err(e) => {
+                let _ = app.emit("verify-error", &format!("Can't read {path}: {e}"));
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Re-encode an existing video to roughly `target_size_mb` megabytes, next to
+/// the input as `<name>_compressed.<ext>` (numbered when taken), keeping its
+/// audio. Returns the chosen bit rate (with a warning if the target is
+/// unachievable) and streams progress through `compress-progress`, ending
+/// with `compress-complete` or `compress-error`.
+#[tauri::command]
+fn compress_video(
+    app: AppHandle,
+    input_path: String,
+    target_size_mb: f64,
+    codec: Option<core::project::VideoCodec>,
+) -> CommandResult<core::render::CompressPlan> {
+    use core::render::{CompressPlan, probe_video};
+
+    if !target_size_mb.is_finite() || target_size_mb <= 0.0 {
+        return Err(CommandError::invalid_input("Target size must be a positive number of megabytes"));
+    }
+    let input = PathBuf::from(&input_path);
+    if !input.exists() {
+        return Err(CommandError::new(ErrorKind::FileMissing, format!("{input_path} does not exist")));
+    }
+    let video = probe_video(&input).map_err(|e| CommandError::from(e).context(format!("Can't read {input_path}")))?;
+    let audio_bytes = core::render::audio_stream_bytes(&input);
+    let plan = CompressPlan::new(&video, (target_size_mb * 1024.0 * 1024.0) as u64, audio_bytes);
+    if let Some(warning) = &plan.warning {
+        log::warn!("Compress {input_path}: {warning}");
+    }
+
+    let stem = input.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| "video".into());
+    let codec = codec.unwrap_or(core::project::VideoCodec::H264);
+    let dir = input.parent().unwrap_or(std::path::Path::new("."));
+    let output = core::import::free_path(dir, &format!("{stem}_compressed"), codec.file_extension());
+    let job = plan.clone();
+    std::thread::spawn(move || {
+        let app_handle = app.clone();
+        let result = core::render::compress_video(&input, output, &job, codec, move |progress| {
+            let _ = app_handle.emit("compress-progress", &progress);
+        });
+        match result {
+            Ok(path) => {
+                let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                let msg = format!(
+                    "Compressed to {} ({:.1} MB)",
+                    path.display(),
+                    size as f64 / (1024.0 * 1024.0),
+                );
+                let _ = app.emit("compress-complete", &msg);
+            }
+            Err(e) => {
+                let _ = app.emit("compress-error", &e.to_string());
+            }
+        }
+    });
+
+    Ok(plan)
+}
+
+#[tauri::command]
+fn get_export_progress(state: State<AppState>) -> Option<ExportProgress> {
+    state.export_progress.lock().unwrap().clone()
+}
+
+/// Ask the running export to stop; it ends with an "export-error" event
+/// once the current frame is done. A no-op when nothing is exporting.
+#[tauri::command]
+fn cancel_export(state: State<AppState>) {
+    log::info!("Export cancel requested");
+    state.export_cancel.store(true, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Save the current project to its package directory.
+#[tauri::command]
+fn save_project(state: State<AppState>) -> CommandResult<String> {
+    let mut current = state.current_project.lock().unwrap();
+    let loaded = current.as_mut().ok_or_else(CommandError::no_project)?;
+
+    loaded.save()?;
+
+    Ok(format!("Project saved: {}", loaded.package_dir.display()))
+}
+
+/// Load a project from a `.lazyrec` package directory path.
+#[tauri::command]
+fn load_project(path: String, state: State<AppState>) -> CommandResult<ProjectInfo> {
+    let package_dir = PathBuf::from(&path);
+    state.flush_project();
+    let project = Project::load(&package_dir)?;
+
+    let info = ProjectInfo::new(&project, &package_dir);
+    for issue in &info.missing_assets {
+        log::warn!("Project {}: {issue}", package_dir.display());
+    }
+
+    state.set_project(LoadedProject::new(project, package_dir));
+    Ok(info)
+}
+
+/// Answer the `recoveryAvailable` offer for the current project: `restore`
+/// replaces it with the unsaved edits from the recovery file (saved on the
+/// next auto-save), otherwise the recovery file is discarded.
+#[tauri::command]
+fn resolve_recovery(restore: bool, state: State<AppState>) -> CommandResult<ProjectInfo> {
+    let mut current = state.current_project.lock().unwrap();
+    let loaded = current.as_mut().ok_or_else(CommandError::no_project)?;
+
+    if restore {
+        loaded.project = Project::load_recovery(&loaded.package_dir)?;
+        loaded.dirty = true;
+        log::info!("Restored unsaved edits for {}", loaded.package_dir.display());
+    } else {
+        Project::discard_recovery(&loaded.package_dir)?;
+    }
+    *state.preview_input.lock().unwrap() = None;
+    let mut info = ProjectInfo::new(&loaded.project, &loaded.package_dir);
+    info.recovery_available = false;
+    Ok(info)
+}
+
+/// Point a project at a new video file (after it was moved, replaced or edited
+/// outside the app). The file must decode; its size, frame rate and duration
+/// replace the project's, and the old project.json is kept as a backup.
+/// Reloads the project if it's the one open in the editor.
+#[tauri::command]
+fn relink_media(package_dir: String, new_video_path: String, state: State<AppState>) -> CommandResult<ProjectInfo> {
+    let package_dir = PathBuf::from(&package_dir);
+    let new_video = PathBuf::from(&new_video_path);
+    state.flush_project();
+    let video = core::render::probe_video(&new_video)
+        .map_err(|e| CommandError::from(e).context(format!("Can't read {new_video_path}")))?;
+
+    let mut project = Project::load(&package_dir)?;
+    project.relink_video(
+        &package_dir,
+        &new_video,
+        core::project::Size::new(video.width as f64, video.height as f64),
+        video.frame_rate,
+        video.duration,
+    )?;
+    project.save(&package_dir, None, None)?;
+    log::info!(
+        "Relinked {} to {} ({}x{} @ {:.2} fps, {:.2}s)",
+        package_dir.display(), new_video.display(), video.width, video.height, video.frame_rate, video.duration,
+    );
+
+    let info = ProjectInfo::new(&project, &package_dir);
+    let mut current = state.current_project.lock().unwrap();
+    if current.as_ref().is_some_and(|loaded| loaded.package_dir == package_dir) {
+        *current = Some(LoadedProject::new(project, package_dir));
+        *state.preview_input.lock().unwrap() = None;
+    }
+    Ok(info)
+}
+
+/// Check the current project's assets: present, video decodable, mouse data parseable.
+/// Returns the problems found (empty when the package is healthy).
+#[tauri::command]
+fn verify_project(state: State<AppState>) -> CommandResult<Vec<core::project::AssetIssue>> {
+    let current = state.current_project.lock().unwrap();
+    let loaded = current.as_ref().ok_or_else(CommandError::no_project)?;
+    let (project, package_dir) = (loaded.project.clone(), loaded.package_dir.clone());
+    // Release lock before decoding
+    drop(current);
+
+    Ok(project.verify_assets(&package_dir, |path| {
+        if cfg!(feature = "ffmpeg") {
+            core::render::probe_video(path).map(|_| ()).map_err(|e| e.to_string())
+        } else {
+            Ok(())
+        }
+    }))
+}
+
+/// Get the current project's name, description, tags and favorite flag.
+#[tauri::command]
+fn get_project_metadata(state: State<AppState>) -> CommandResult<core::project::ProjectMetadata> {
+    let current = state.current_project.lock().unwrap();
+    let loaded = current.as_ref().ok_or_else(CommandError::no_project)?;
+    Ok(loaded.project.metadata())
+}
+
+/// Update the current project's metadata and save it.
+#[tauri::command]
+fn set_project_metadata(
+    metadata: core::project::ProjectMetadata,
+    state: State<AppState>,
+) -> CommandResult<core::project::ProjectMetadata> {
+    let mut current = state.current_project.lock().unwrap();
+    let loaded = current.as_mut().ok_or_else(CommandError::no_project)?;
+    loaded.project.set_metadata(metadata);
+    loaded.mark_dirty();
+    Ok(loaded.project.metadata())
+}
+
+/// Shift all recorded input by `offset` seconds relative to the video
+/// (positive = later) to fix a cursor that leads or lags. Returns the stored value.
+#[tauri::command]
+fn set_input_time_offset(offset: f64, state: State<AppState>) -> CommandResult<f64> {
+    use core::project::MAX_INPUT_TIME_OFFSET;
+
+    if !offset.is_finite() {
+        return Err(CommandError::invalid_input("Invalid input time offset"));
+    }
+    let mut current = state.current_project.lock().unwrap();
+    let loaded = current.as_mut().ok_or_else(CommandError::no_project)?;
+    loaded.project.input_time_offset = offset.clamp(-MAX_INPUT_TIME_OFFSET, MAX_INPUT_TIME_OFFSET);
+    loaded.mark_dirty();
+    Ok(loaded.project.input_time_offset)
+}
+
+/// Ignore input before `secs` seconds of video (setup motion after pressing
+/// record), and with `trim_video` also trim the video to start there at the
+/// earliest. Takes effect on the next generate. Returns the stored value.
+#[tauri::command]
+fn set_input_warmup(secs: f64, trim_video: Option<bool>, state: State<AppState>) -> CommandResult<f64> {
+    use core::project::MAX_WARMUP_SECS;
+
+    if !secs.is_finite() {
+        return Err(CommandError::invalid_input("Invalid warmup"));
+    }
+    let mut current = state.current_project.lock().unwrap();
+    let loaded = current.as_mut().ok_or_else(CommandError::no_project)?;
+    let project = &mut loaded.project;
+    project.warmup_secs = secs.clamp(0.0, MAX_WARMUP_SECS.min(project.duration()));
+    if trim_video.unwrap_or(false) {
+        project.timeline.trim_start = project.timeline.trim_start.max(project.warmup_secs);
+    }
+    loaded.mark_dirty();
+    Ok(loaded.project.warmup_secs)
+}
+
+/// Get the chord → label table used for keystroke overlays.
+#[tauri::command]
+fn get_shortcut_labels(state: State<AppState>) -> CommandResult<std::collections::BTreeMap<String, String>> {
+    let current = state.current_project.lock().unwrap();
+    let loaded = current.as_ref().ok_or_else(CommandError::no_project)?;
+    Ok(loaded.project.shortcut_labels.clone())
+}
+
+/// Replace the project's shortcut labels (e.g. {"Ctrl+Shift+P": "Command Palette"})
+/// and save it. Applies the next time keyframes are generated.
+#[tauri::command]
+fn set_shortcut_labels(
+    labels: std::collections::BTreeMap<String, String>,
+    state: State<AppState>,
+) -> CommandResult<()> {
+    if let Some(chord) = labels.keys().find(|c| core::generators::normalize_chord(c).is_none()) {
+        return Err(CommandError::invalid_input(format!("Invalid shortcut \"{chord}\": it needs a key, e.g. Ctrl+S")));
+    }
+    let mut current = state.current_project.lock().unwrap();
+    let loaded = current.as_mut().ok_or_else(CommandError::no_project)?;
+    loaded.project.shortcut_labels = labels;
+    loaded.mark_dirty();
+    Ok(())
+}
+
+/// Get the SmartZoom configuration used by `generate_keyframes`.
+#[tauri::command]
+fn get_smart_zoom_settings(state: State<AppState>) -> CommandResult<core::generators::SmartZoomConfig> {
+    let current = state.current_project.lock().unwrap();
+    let loaded = current.as_ref().ok_or_else(CommandError::no_project)?;
+    Ok(loaded.project.smart_zoom.clone())
+}
+
+/// Set (or clear, with None) the SmartZoom camera spring on the project and
+/// save it. Takes effect on the next generate. Returns the stored config.
+#[tauri::command]
+fn set_camera_spring(
+    spring: Option<core::generators::CameraSpring>,
+    state: State<AppState>,
+) -> CommandResult<core::generators::SmartZoomConfig> {
+    if let Some(spring) = &spring {
+        if !(spring.damping_ratio.is_finite() && spring.response.is_finite() && spring.response > 0.0) {
+            return Err(CommandError::invalid_input("Spring damping and response must be positive numbers"));
+        }
+    }
+    let mut current = state.current_project.lock().unwrap();
+    let loaded = current.as_mut().ok_or_else(CommandError::no_project)?;
+    loaded.project.smart_zoom = loaded.project.smart_zoom.with_spring(spring);
+    loaded.mark_dirty();
+    Ok(loaded.project.smart_zoom.clone())
+}
+
+/// Store a SmartZoom preset or custom values on the project and save it.
+#[tauri::command]
+fn set_smart_zoom_settings(
+    config: core::generators::SmartZoomConfig,
+    state: State<AppState>,
+) -> CommandResult<()> {
+    if let core::generators::SmartZoomConfig::Custom { settings } = &config {
+        if settings.min_zoom < 1.0 || settings.max_zoom < settings.min_zoom {
+            return Err(CommandError::invalid_input(format!(
+                "Invalid zoom range {:.2}..{:.2}",
+                settings.min_zoom, settings.max_zoom,
+            )));
+        }
+        if settings.typing_session_timeout.is_nan() || settings.typing_session_timeout <= 0.0 {
+            return Err(CommandError::invalid_input("Typing session timeout must be positive"));
+        }
+    }
+    let mut current = state.current_project.lock().unwrap();
+    let loaded = current.as_mut().ok_or_else(CommandError::no_project)?;
+    loaded.project.smart_zoom = config;
+    loaded.mark_dirty();
+    Ok(())
+}
+
+/// Save the current project's effect style (SmartZoom, ripples, keystroke
+/// theme, cursor and window look) to a style file at `path`.
+#[tauri::command]
+fn export_style(path: String, name: Option<String>, state: State<AppState>) -> CommandResult<()> {
+    let current = state.current_project.lock().unwrap();
+    let loaded = current.as_ref().ok_or_else(CommandError::no_project)?;
+    let name = name.unwrap_or_else(|| loaded.project.name.clone());
+    core::style::StylePreset::from_project(name, &loaded.project)
+        .save(std::path::Path::new(&path))
+        .map_err(CommandError::from)
+}
+
+/// Apply a style file to the current project and save it. With `regenerate`,
+/// generated keyframes are rebuilt (manual ones kept) so they take on the style.
+#[tauri::command]
+fn import_style(
+    path: String,
+    regenerate: Option<bool>,
+    state: State<AppState>,
+) -> CommandResult<core::style::StylePreset> {
+    let preset = core::style::StylePreset::load(std::path::Path::new(&path))?;
+    {
+        let mut current = state.current_project.lock().unwrap();
+        let loaded = current.as_mut().ok_or_else(CommandError::no_project)?;
+        preset.apply(&mut loaded.project);
+        loaded.mark_dirty();
+    }
+    log::info!("Applied style \"{}\" from {path}", preset.name);
+    if regenerate.unwrap_or(false) {
+        generate_keyframes(Some(core::timeline::GenerateMode::Merge), state)?;
+    }
+    Ok(preset)
+}
+
+/// Thumbnail size bound for the project browser (pixels)
+const THUMBNAIL_WIDTH: u32 = 320;
+
+/// Project browser entry
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProjectSummary {
+    package_path: String,
+    #[serde(flatten)]
+    metadata: core::project::ProjectMetadata,
+    duration: f64,
+    created_at: String,
+    modified_at: String,
+    /// First frame of the recording, if the video is present
+    thumbnail: Option<FrameData>,
+}
+
+/// Scan a directory for `.lazyrec` packages and return their metadata and thumbnails.
+/// Packages that fail to load are logged and skipped.
+#[tauri::command]
+fn list_projects(dir: String) -> CommandResult<Vec<ProjectSummary>> {
+    use base64::Engine;
+
+    let packages = Project::find_packages(std::path::Path::new(&dir))?;
+    let mut summaries = Vec::with_capacity(packages.len());
+    for package_dir in packages {
+        let project = match Project::load(&package_dir) {
+            Ok(p) => p,
+            Err(e) => {
+                log::warn!("Skipping unreadable project {}: {e}", package_dir.display());
+                continue;
             }
-        }
-    });
+        };
 
-    Ok("Export started".into())
+        let video_path = project.video_path(&package_dir);
+        let thumbnail = video_path.exists().then(|| {
+            let mut source = core::render::create_video_source_from_file(
+                &video_path,
+                project.media.pixel_size.width as u32,
+                project.media.pixel_size.height as u32,
+                project.duration(),
+                project.media.frame_rate,
+            );
+            source.read_frame(0.0).ok().map(|frame| {
+                let frame = frame.fit_within(THUMBNAIL_WIDTH, THUMBNAIL_WIDTH);
+                let rgba = bgra_to_rgba(&frame.data, frame.width, frame.height, frame.stride);
+                FrameData {
+                    width: frame.width,
+                    height: frame.height,
+                    rgba_base64: base64::engine::general_purpose::STANDARD.encode(rgba),
+                }
+            })
+        }).flatten();
+
+        summaries.push(ProjectSummary {
+            package_path: package_dir.display().to_string(),
+            metadata: project.metadata(),
+            duration: project.duration(),
+            created_at: project.created_at.clone(),
+            modified_at: project.modified_at.clone(),
+            thumbnail,
+        });
+    }
+    Ok(summaries)
 }
 
+/// List orphaned `recording_*.mp4` files (e.g. left by a crash) that decode
+/// but were never packaged. Defaults to the recorder's output directory.
 #[tauri::command]
-fn get_export_progress(state: State<AppState>) -> Option<ExportProgress> {
-    state.export_progress.lock().unwrap().clone()
+fn scan_recoverable(
+    dir: Option<String>,
+    state: State<AppState>,
+) -> CommandResult<Vec<core::recovery::RecoverableRecording>> {
+    let dir = {
+        let recorder = state.recorder.lock().unwrap();
+        // The active recording's file is not orphaned
+        if recorder.state() != core::recorder::RecordingState::Idle {
+            return Err(CommandError::invalid_input("Cannot scan while recording"));
+        }
+        dir.map(PathBuf::from).unwrap_or_else(|| recorder.output_dir().to_path_buf())
+    };
+
+    core::recovery::scan_recoverable(&dir, core::render::probe_video).map_err(CommandError::from)
 }
 
-/// Save the current project to its package directory.
+/// Wrap an orphaned recording into a project package and open it.
 #[tauri::command]
-fn save_project(state: State<AppState>) -> Result<String, String> {
-    let mut current = state.current_project.lock().unwrap();
-    let loaded = current.as_mut().ok_or("No project loaded")?;
+fn recover_recording(video_path: String, state: State<AppState>) -> CommandResult<ProjectInfo> {
+    let video_path = PathBuf::from(&video_path);
+    let dir = video_path.parent().ok_or_else(|| CommandError::invalid_input("Invalid recording path"))?;
+    let recording = core::recovery::scan_recoverable(dir, core::render::probe_video)?
+        .into_iter()
+        .find(|r| r.video_path == video_path)
+        .ok_or_else(|| CommandError::invalid_input("Recording is not recoverable"))?;
 
-    loaded.project.save(&loaded.package_dir, None, None)
-        .map_err(|e| e.to_string())?;
+    let render_settings = state.settings.lock().unwrap().default_render_settings.clone();
+    let (project, package_dir) = core::recovery::recover_recording(&recording, render_settings)?;
+    log::info!("Recovered {} into {}", video_path.display(), package_dir.display());
 
-    Ok(format!("Project saved: {}", loaded.package_dir.display()))
+    let info = ProjectInfo::new(&project, &package_dir);
+
+    state.set_project(LoadedProject::new(project, package_dir));
+    Ok(info)
 }
 
-/// Load a project from a `.lazyrec` package directory path.
+/// Copy a video recorded elsewhere into a new project package in the
+/// recordings folder and open it. It has no input data, so the timeline
+/// starts empty and effects are added by hand.
 #[tauri::command]
-fn load_project(path: String, state: State<AppState>) -> Result<ProjectInfo, String> {
-    let package_dir = PathBuf::from(&path);
-    let project = Project::load(&package_dir).map_err(|e| e.to_string())?;
-
-    let info = ProjectInfo {
-        name: project.name.clone(),
-        duration: project.duration(),
-        frame_rate: project.media.frame_rate,
-        width: project.media.pixel_size.width,
-        height: project.media.pixel_size.height,
-        package_path: package_dir.display().to_string(),
-    };
+fn import_video(path: String, state: State<AppState>) -> CommandResult<ProjectInfo> {
+    let video_path = PathBuf::from(&path);
+    if !video_path.is_file() {
+        return Err(CommandError::new(ErrorKind::FileMissing, format!("{path} does not exist")));
+    }
+    let video = core::render::probe_video(&video_path)
+        .map_err(|e| CommandError::from(e).context(format!("Can't read {path}")))?;
 
-    let mut current = state.current_project.lock().unwrap();
-    *current = Some(LoadedProject {
-        project,
-        package_dir,
-    });
+    let dir = state.recorder.lock().unwrap().output_dir().to_path_buf();
+    let render_settings = state.settings.lock().unwrap().default_render_settings.clone();
+    let (project, package_dir) = core::import::import_video(&video_path, &video, &dir, render_settings)?;
+    log::info!(
+        "Imported {} into {} ({}x{} @ {:.2} fps, {:.2}s)",
+        video_path.display(), package_dir.display(), video.width, video.height, video.frame_rate, video.duration,
+    );
 
+    let info = ProjectInfo::new(&project, &package_dir);
+    state.set_project(LoadedProject::new(project, package_dir));
     Ok(info)
 }
 
@@ -366,18 +1162,18 @@ struct MousePositionData {
 /// Load mouse data from the current project.
 /// Returns mouse positions as normalized (0-1) coordinates.
 #[tauri::command]
-fn load_mouse_data(state: State<AppState>) -> Result<Vec<MousePositionData>, String> {
+fn load_mouse_data(state: State<AppState>) -> CommandResult<Vec<MousePositionData>> {
     let current = state.current_project.lock().unwrap();
-    let loaded = current.as_ref().ok_or("No project loaded")?;
+    let loaded = current.as_ref().ok_or_else(CommandError::no_project)?;
 
     let mouse_path = loaded.project.mouse_data_path(&loaded.package_dir);
     if !mouse_path.exists() {
         return Ok(vec![]);
     }
 
-    let json = std::fs::read_to_string(&mouse_path).map_err(|e| e.to_string())?;
-    let recording = core::input::InputRecording::from_json(&json)
-        .map_err(|e| e.to_string())?;
+    let json = std::fs::read_to_string(&mouse_path)?;
+    let mut recording = core::input::InputRecording::from_json(&json)?;
+    loaded.project.align_input(&mut recording);
 
     let positions: Vec<MousePositionData> = recording.positions.iter().map(|p| {
         MousePositionData {
@@ -390,72 +1186,48 @@ fn load_mouse_data(state: State<AppState>) -> Result<Vec<MousePositionData>, Str
     Ok(positions)
 }
 
-/// Convert InputRecording to generator MouseData format
-fn input_to_mouse_data(recording: &core::input::InputRecording, duration: f64) -> core::generators::MouseData {
-    use core::generators::*;
-    use core::input::MouseButton;
-
-    let positions: Vec<(f64, core::coordinates::NormalizedPoint)> = recording
-        .positions.iter().map(|p| (p.time, p.position)).collect();
-
-    let clicks: Vec<ClickEvent> = recording.clicks.iter().map(|c| {
-        let click_type = match c.button {
-            MouseButton::Left => ClickType::LeftDown,
-            MouseButton::Right => ClickType::RightDown,
-            MouseButton::Middle => ClickType::LeftDown, // treat middle as left for generators
-        };
-        ClickEvent {
-            time: c.time,
-            position: c.position,
-            click_type,
-            duration: c.duration,
-        }
-    }).collect();
-
-    let keyboard_events: Vec<KeyboardEvent> = recording.keyboard.iter().map(|k| {
-        KeyboardEvent {
-            time: k.time,
-            event_type: match k.event_type {
-                core::input::KeyAction::Down => KeyEventType::KeyDown,
-                core::input::KeyAction::Up => KeyEventType::KeyUp,
-            },
-            key_code: k.key_code,
-            character: k.character.clone(),
-            modifiers: Modifiers {
-                command: k.modifiers.command,
-                shift: k.modifiers.shift,
-                alt: k.modifiers.alt,
-                control: k.modifiers.control,
-            },
-        }
-    }).collect();
-
-    let drags: Vec<DragEvent> = recording.drags.iter().map(|d| {
-        DragEvent {
-            start_time: d.start_time,
-            end_time: d.end_time,
-            start_position: d.start_position,
-            end_position: d.end_position,
-        }
-    }).collect();
+/// Size savings reported by `compact_input_data`
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InputCompaction {
+    positions_before: usize,
+    positions_after: usize,
+    bytes_before: u64,
+    bytes_after: u64,
+}
 
-    MouseData {
-        positions,
-        clicks,
-        keyboard_events,
-        drags,
-        duration,
+/// Thin the project's recorded mouse positions to at most `target_hz` samples
+/// a second, keeping turns and pauses that would move the cursor more than
+/// `tolerance` (normalized, default 0.002) and every click, key and scroll.
+/// Rewrites the mouse data file; this can't be undone.
+#[tauri::command]
+fn compact_input_data(
+    target_hz: f64,
+    tolerance: Option<f64>,
+    state: State<AppState>,
+) -> CommandResult<InputCompaction> {
+    if !target_hz.is_finite() || target_hz < 0.0 {
+        return Err(CommandError::invalid_input(format!("Invalid target rate: {target_hz}")));
     }
-}
+    let current = state.current_project.lock().unwrap();
+    let loaded = current.as_ref().ok_or_else(CommandError::no_project)?;
 
-/// Convert InputRecording positions to evaluator MousePosition format
-fn input_to_evaluator_positions(recording: &core::input::InputRecording) -> Vec<core::evaluator::MousePosition> {
-    recording.positions.iter().map(|p| {
-        core::evaluator::MousePosition {
-            time: p.time,
-            position: p.position,
-        }
-    }).collect()
+    let mouse_path = loaded.project.mouse_data_path(&loaded.package_dir);
+    let json = std::fs::read_to_string(&mouse_path)?;
+    let mut recording = core::input::InputRecording::from_json(&json)?;
+
+    let positions_before = recording.positions.len();
+    recording.downsample_positions(target_hz, tolerance.unwrap_or(0.002).max(0.0));
+    let compacted = recording.to_json()?;
+    std::fs::write(&mouse_path, &compacted)?;
+
+    log::info!("Compacted mouse data: {positions_before} -> {} samples", recording.positions.len());
+    Ok(InputCompaction {
+        positions_before,
+        positions_after: recording.positions.len(),
+        bytes_before: json.len() as u64,
+        bytes_after: compacted.len() as u64,
+    })
 }
 
 /// Generated keyframes result returned to the frontend
@@ -469,40 +1241,91 @@ struct GeneratedKeyframes {
     total: usize,
 }
 
-/// Run auto-generators on the current project's mouse/keyboard data.
-/// Replaces the project's timeline tracks with generated keyframes.
-#[tauri::command]
-fn generate_keyframes(state: State<AppState>) -> Result<GeneratedKeyframes, String> {
-    use core::generators::*;
-    use core::track::AnyTrack;
-
-    let mut current = state.current_project.lock().unwrap();
-    let loaded = current.as_mut().ok_or("No project loaded")?;
+/// The current project's input as generator events, its content activity
+/// (when SmartZoom analyzes content and `analyze_content` is set) and its
+/// generator settings, with the id of the project they were read from.
+/// Content analysis decodes the whole video, so it runs with the project
+/// unlocked; callers re-lock and check the id before applying the result.
+fn generator_input(
+    state: &AppState,
+    analyze_content: bool,
+) -> CommandResult<(uuid::Uuid, core::generators::MouseData, Vec<core::generators::ActivityEvent>, core::replay::GeneratorSettings)> {
+    let (project_id, mouse_data, settings, content_source) = {
+        let current = state.current_project.lock().unwrap();
+        let loaded = current.as_ref().ok_or_else(CommandError::no_project)?;
+        let mouse_path = loaded.project.mouse_data_path(&loaded.package_dir);
+        let mut recording = if mouse_path.exists() {
+            let json = std::fs::read_to_string(&mouse_path)?;
+            core::input::InputRecording::from_json(&json)?
+        } else {
+            return Err(CommandError::new(ErrorKind::FileMissing, "No mouse data found in project"));
+        };
+        if recording.is_empty() {
+            return Err(CommandError::invalid_input(
+                "This project has no recorded input (e.g. an imported video); add keyframes by hand",
+            ));
+        }
+        loaded.project.align_input(&mut recording);
+
+        let duration = loaded.project.duration();
+        let mouse_data = core::replay::project_mouse_data(&loaded.project, &recording);
+        let settings = core::replay::GeneratorSettings::from_project(&loaded.project);
+        let project = &loaded.project;
+        let content_source = (analyze_content && settings.smart_zoom.analyze_content).then(|| (
+            project.video_path(&loaded.package_dir),
+            (project.media.pixel_size.width as u32, project.media.pixel_size.height as u32),
+            duration,
+            project.media.frame_rate,
+        ));
+        (project.id, mouse_data, settings, content_source)
+    };
 
-    // Load mouse data
-    let mouse_path = loaded.project.mouse_data_path(&loaded.package_dir);
-    let recording = if mouse_path.exists() {
-        let json = std::fs::read_to_string(&mouse_path).map_err(|e| e.to_string())?;
-        core::input::InputRecording::from_json(&json).map_err(|e| e.to_string())?
-    } else {
-        return Err("No mouse data found in project".into());
+    let content_activities = match content_source {
+        Some((video_path, (width, height), duration, frame_rate)) if video_path.exists() => {
+            let mut source = core::render::create_video_source_from_file(
+                &video_path, width, height, duration, frame_rate,
+            );
+            let activities = core::content::analyze_content(source.as_mut())?;
+            log::info!("Content analysis: {} activities", activities.len());
+            activities
+        }
+        Some((video_path, ..)) => {
+            log::warn!("Content analysis skipped: {} not found", video_path.display());
+            Vec::new()
+        }
+        None => Vec::new(),
     };
 
-    let duration = loaded.project.duration();
-    let mouse_data = input_to_mouse_data(&recording, duration);
+    Ok((project_id, mouse_data, content_activities, settings))
+}
+
+/// The current project if it's still the one `project_id` names, for
+/// applying what was generated from it
+fn project_for_generated(
+    current: &mut Option<LoadedProject>,
+    project_id: uuid::Uuid,
+) -> CommandResult<&mut LoadedProject> {
+    current
+        .as_mut()
+        .filter(|loaded| loaded.project.id == project_id)
+        .ok_or_else(|| CommandError::invalid_input("The project changed while generating keyframes"))
+}
 
-    // Run generators
-    let zoom_settings = SmartZoomSettings::default();
-    let ripple_settings = RippleSettings::default();
-    let keystroke_settings = KeystrokeSettings::default();
+/// Run auto-generators on the current project's mouse/keyboard data.
+/// `mode` (default `Replace`) controls whether existing tracks and manual
+/// keyframes are discarded, merged with, or left alone except for one track type.
+#[tauri::command(async)]
+fn generate_keyframes(
+    mode: Option<core::timeline::GenerateMode>,
+    state: State<AppState>,
+) -> CommandResult<GeneratedKeyframes> {
+    let (project_id, mouse_data, content_activities, settings) = generator_input(&state, true)?;
 
-    let transform_track = generate_smart_zoom(&mouse_data, &zoom_settings);
-    let ripple_track = generate_ripples(&mouse_data.clicks, &ripple_settings);
-    let keystroke_track = generate_keystrokes(&mouse_data.keyboard_events, &keystroke_settings);
-    let cursor_track = generate_cursor_keyframes(&mouse_data.positions, &mouse_data.clicks);
+    let generated = core::replay::run_generators(&mouse_data, &content_activities, &settings);
+    let cursor_count = generated.cursor.style_keyframes.as_ref().map_or(0, |v| v.len());
 
     // Log zoom keyframe details
-    for (i, kf) in transform_track.keyframes.iter().enumerate() {
+    for (i, kf) in generated.transform.keyframes.iter().enumerate() {
         log::info!(
             "  Zoom keyframe {}: t={:.2}s zoom={:.2}x center=({:.3},{:.3})",
             i, kf.time, kf.zoom, kf.center.x, kf.center.y,
@@ -510,10 +1333,10 @@ fn generate_keyframes(state: State<AppState>) -> Result<GeneratedKeyframes, Stri
     }
     log::info!(
         "Generated keyframes: transform={}, ripple={}, cursor={}, keystroke={} (from {} clicks, {} keyboard events, {} drags, {} positions over {:.1}s)",
-        transform_track.keyframe_count(),
-        ripple_track.keyframe_count(),
-        cursor_track.style_keyframes.as_ref().map_or(0, |v| v.len()),
-        keystroke_track.keyframe_count(),
+        generated.transform.keyframe_count(),
+        generated.ripple.keyframe_count(),
+        cursor_count,
+        generated.keystroke.keyframe_count(),
         mouse_data.clicks.len(),
         mouse_data.keyboard_events.len(),
         mouse_data.drags.len(),
@@ -522,31 +1345,223 @@ fn generate_keyframes(state: State<AppState>) -> Result<GeneratedKeyframes, Stri
     );
 
     let result = GeneratedKeyframes {
-        transform_count: transform_track.keyframe_count(),
-        ripple_count: ripple_track.keyframe_count(),
-        cursor_count: cursor_track.style_keyframes.as_ref().map_or(0, |v| v.len()),
-        keystroke_count: keystroke_track.keyframe_count(),
-        total: transform_track.keyframe_count()
-            + ripple_track.keyframe_count()
-            + cursor_track.style_keyframes.as_ref().map_or(0, |v| v.len())
-            + keystroke_track.keyframe_count(),
+        transform_count: generated.transform.keyframe_count(),
+        ripple_count: generated.ripple.keyframe_count(),
+        cursor_count,
+        keystroke_count: generated.keystroke.keyframe_count(),
+        total: generated.transform.keyframe_count()
+            + generated.ripple.keyframe_count()
+            + cursor_count
+            + generated.keystroke.keyframe_count(),
     };
 
-    // Replace tracks in the project timeline
-    loaded.project.timeline.tracks = vec![
-        AnyTrack::Transform(transform_track),
-        AnyTrack::Ripple(ripple_track),
-        AnyTrack::Cursor(cursor_track),
-        AnyTrack::Keystroke(keystroke_track),
-    ];
+    // Replace or merge tracks in the project timeline
+    let mut current = state.current_project.lock().unwrap();
+    let loaded = project_for_generated(&mut current, project_id)?;
+    loaded.project.timeline.apply_generated(generated.into_tracks(), mode.unwrap_or_default());
 
-    // Auto-save the updated project
-    loaded.project.save(&loaded.package_dir, None, None)
-        .map_err(|e| e.to_string())?;
+    loaded.mark_dirty();
 
     Ok(result)
 }
 
+/// Re-run only the generator for `track_type` and swap that track's keyframes,
+/// e.g. after changing cursor smoothing. The other tracks, and the replaced
+/// track's name and enabled state, are left as they are. Content analysis
+/// only runs for the transform track. Returns the new keyframe count.
+#[tauri::command(async)]
+fn regenerate_track(track_type: core::track::TrackType, state: State<AppState>) -> CommandResult<usize> {
+    let analyze = track_type == core::track::TrackType::Transform;
+    let (project_id, mouse_data, content_activities, settings) = generator_input(&state, analyze)?;
+    let track = core::replay::run_generator(track_type, &mouse_data, &content_activities, &settings);
+    let count = track.keyframe_count();
+    log::info!("Regenerated {track_type:?} track: {count} keyframes");
+
+    let mode = core::timeline::GenerateMode::RegenerateTrack { track_type };
+    let mut current = state.current_project.lock().unwrap();
+    let loaded = project_for_generated(&mut current, project_id)?;
+    loaded.project.timeline.apply_generated(vec![track], mode);
+    loaded.mark_dirty();
+    Ok(count)
+}
+
+/// Zoom the camera to a normalized rect drawn on the preview for a time range.
+/// Inserts zoom-in/hold/zoom-out keyframes into the transform track (or the
+/// camera layer `track_id`) and saves.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+fn add_zoom_region(
+    start_time: f64,
+    end_time: f64,
+    rect_x: f64,
+    rect_y: f64,
+    rect_w: f64,
+    rect_h: f64,
+    easing: Option<core::easing::EasingCurve>,
+    track_id: Option<uuid::Uuid>,
+    state: State<AppState>,
+) -> CommandResult<usize> {
+    use core::generators::{insert_zoom_region, ZoomRegion};
+    use core::track::{AnyTrack, TransformTrack};
+
+    if !(start_time.is_finite() && end_time.is_finite()) || end_time < start_time {
+        return Err(CommandError::invalid_input("Invalid zoom region time range"));
+    }
+
+    let mut current = state.current_project.lock().unwrap();
+    let loaded = current.as_mut().ok_or_else(CommandError::no_project)?;
+    let duration = loaded.project.duration();
+
+    let settings = core::generators::SmartZoomSettings {
+        view_extent: loaded.project.view_extent(),
+        ..loaded.project.smart_zoom.settings()
+    };
+    let region = ZoomRegion {
+        start_time,
+        end_time,
+        rect: (rect_x, rect_y, rect_w, rect_h),
+        easing: easing.unwrap_or_else(|| settings.zoom_in_curve()),
+    };
+
+    let timeline = &mut loaded.project.timeline;
+    let track = match track_id {
+        Some(id) => match timeline.track_mut(id) {
+            Some(AnyTrack::Transform(track)) => track,
+            Some(_) => return Err(CommandError::invalid_input("Not a transform track")),
+            None => return Err(CommandError::invalid_input("Track not found")),
+        },
+        None => {
+            if timeline.transform_track().is_none() {
+                timeline.add_track(AnyTrack::Transform(TransformTrack::new()));
+            }
+            timeline.transform_track_mut().ok_or_else(|| CommandError::invalid_input("No transform track"))?
+        }
+    };
+    insert_zoom_region(track, &region, &settings, duration);
+    let count = track.keyframe_count();
+
+    loaded.mark_dirty();
+    Ok(count)
+}
+
+/// List the timeline's markers in time order.
+#[tauri::command]
+fn list_markers(state: State<AppState>) -> CommandResult<Vec<core::timeline::Marker>> {
+    let current = state.current_project.lock().unwrap();
+    let loaded = current.as_ref().ok_or_else(CommandError::no_project)?;
+    Ok(loaded.project.timeline.markers.clone())
+}
+
+/// Add a named marker (a chapter start on export) and save. Blank labels
+/// are numbered. Returns the markers in time order.
+#[tauri::command]
+fn add_marker(time: f64, label: String, state: State<AppState>) -> CommandResult<Vec<core::timeline::Marker>> {
+    if !time.is_finite() {
+        return Err(CommandError::invalid_input("Invalid marker time"));
+    }
+    let mut current = state.current_project.lock().unwrap();
+    let loaded = current.as_mut().ok_or_else(CommandError::no_project)?;
+    let timeline = &mut loaded.project.timeline;
+    let label = match label.trim() {
+        "" => format!("Marker {}", timeline.markers.len() + 1),
+        label => label.to_string(),
+    };
+    timeline.add_marker(time, label);
+
+    loaded.mark_dirty();
+    Ok(loaded.project.timeline.markers.clone())
+}
+
+/// Remove a marker and save. Returns the remaining markers.
+#[tauri::command]
+fn remove_marker(marker_id: uuid::Uuid, state: State<AppState>) -> CommandResult<Vec<core::timeline::Marker>> {
+    let mut current = state.current_project.lock().unwrap();
+    let loaded = current.as_mut().ok_or_else(CommandError::no_project)?;
+    if !loaded.project.timeline.remove_marker(marker_id) {
+        return Err(CommandError::invalid_input("Marker not found"));
+    }
+
+    loaded.mark_dirty();
+    Ok(loaded.project.timeline.markers.clone())
+}
+
+/// Add an empty camera layer above the existing transform tracks. Its zooms
+/// multiply with the ones below and its centers are read inside their view,
+/// so a punch-in can sit on top of a slow establishing zoom. Returns its id.
+#[tauri::command]
+fn add_transform_track(name: Option<String>, state: State<AppState>) -> CommandResult<String> {
+    use core::track::{AnyTrack, TransformTrack};
+
+    let mut current = state.current_project.lock().unwrap();
+    let loaded = current.as_mut().ok_or_else(CommandError::no_project)?;
+    let timeline = &mut loaded.project.timeline;
+    let layer = timeline.transform_tracks().count();
+    let mut track = TransformTrack::new();
+    match name {
+        Some(name) => track.name = name,
+        None if layer > 0 => track.name = format!("Transform {}", layer + 1),
+        None => {}
+    }
+    let id = track.id;
+    timeline.add_track(AnyTrack::Transform(track));
+
+    loaded.mark_dirty();
+    Ok(id.to_string())
+}
+
+/// Move every keyframe of a track at or after `from_time` by `delta_secs`
+/// (e.g. after trimming the start of a clip). Returns how many moved.
+#[tauri::command]
+fn shift_keyframes(
+    track_id: uuid::Uuid,
+    from_time: f64,
+    delta_secs: f64,
+    state: State<AppState>,
+) -> CommandResult<usize> {
+    if !from_time.is_finite() || !delta_secs.is_finite() {
+        return Err(CommandError::invalid_input("Invalid shift"));
+    }
+    let mut current = state.current_project.lock().unwrap();
+    let loaded = current.as_mut().ok_or_else(CommandError::no_project)?;
+    let duration = loaded.project.duration();
+    let track = loaded
+        .project
+        .timeline
+        .track_mut(track_id)
+        .ok_or_else(|| CommandError::invalid_input("Track not found"))?;
+    let moved = track.shift_keyframes(from_time, delta_secs, duration);
+
+    loaded.mark_dirty();
+    Ok(moved)
+}
+
+/// Copy a keyframe to `new_time` on the same track. Returns the copy's id.
+#[tauri::command]
+fn duplicate_keyframe(
+    track_id: uuid::Uuid,
+    keyframe_id: uuid::Uuid,
+    new_time: f64,
+    state: State<AppState>,
+) -> CommandResult<String> {
+    if !new_time.is_finite() {
+        return Err(CommandError::invalid_input("Invalid keyframe time"));
+    }
+    let mut current = state.current_project.lock().unwrap();
+    let loaded = current.as_mut().ok_or_else(CommandError::no_project)?;
+    let duration = loaded.project.duration();
+    let track = loaded
+        .project
+        .timeline
+        .track_mut(track_id)
+        .ok_or_else(|| CommandError::invalid_input("Track not found"))?;
+    let copy = track
+        .duplicate_keyframe(keyframe_id, new_time, duration)
+        .ok_or_else(|| CommandError::invalid_input("Keyframe not found"))?;
+
+    loaded.mark_dirty();
+    Ok(copy.to_string())
+}
+
 /// Serializable timeline data for the frontend
 #[derive(serde::Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -566,14 +1581,117 @@ struct TimelineTrackData {
     keyframes: Vec<serde_json::Value>,
 }
 
-/// Get the current project's timeline as serialized tracks + keyframes.
+/// Most samples `dump_evaluated_timeline` returns (e.g. 10 minutes at 1/60s steps)
+const MAX_EVALUATED_SAMPLES: usize = 36_000;
+
+/// Evaluate the current timeline every `step_secs` over the whole duration
+/// (for debugging effect state without rendering pixels).
 #[tauri::command]
-fn get_timeline(state: State<AppState>) -> Result<TimelineData, String> {
-    use core::track::AnyTrack;
+fn dump_evaluated_timeline(
+    step_secs: f64,
+    state: State<AppState>,
+) -> CommandResult<Vec<core::evaluator::EvaluatedFrameState>> {
+    if !step_secs.is_finite() || step_secs <= 0.0 {
+        return Err(CommandError::invalid_input("Step must be a positive number of seconds"));
+    }
+
+    let current = state.current_project.lock().unwrap();
+    let loaded = current.as_ref().ok_or_else(CommandError::no_project)?;
+    let project = &loaded.project;
+
+    let duration = project.duration();
+    let sample_count = (duration / step_secs).floor() as usize + 1;
+    if sample_count > MAX_EVALUATED_SAMPLES {
+        return Err(CommandError::invalid_input(format!(
+            "Step {step_secs}s gives {sample_count} samples (max {MAX_EVALUATED_SAMPLES}); use a larger step"
+        )));
+    }
+
+    let recording = load_input_recording(project, &loaded.package_dir);
+    let smoothing = &project.render_settings.mouse_smoothing;
+    let mouse_positions = recording
+        .as_ref()
+        .map(|r| input_to_evaluator_positions(r, smoothing))
+        .unwrap_or_default();
+    let mouse_clicks = recording.as_ref().map(input_to_evaluator_clicks).unwrap_or_default();
+
+    let evaluator = core::evaluator::FrameEvaluator::for_project(project);
+    let samples = (0..sample_count)
+        .map(|i| (i as f64 * step_secs).min(duration))
+        .map(|time| evaluator.evaluate(&project.timeline, time, &mouse_positions, &mouse_clicks))
+        .collect();
+    Ok(samples)
+}
 
+/// Most steps `sample_transform_curve` takes between two keyframes
+const MAX_CURVE_STEPS: usize = 1000;
+
+/// Zoom and center at `steps + 1` evenly spaced times between two keyframes
+/// of one transform track, through the real easing (and any keyframes in
+/// between), so the editor can draw the camera's motion curve
+#[tauri::command]
+fn sample_transform_curve(
+    from_kf_id: uuid::Uuid,
+    to_kf_id: uuid::Uuid,
+    steps: usize,
+    state: State<AppState>,
+) -> CommandResult<Vec<core::evaluator::TransformCurveSample>> {
+    if steps == 0 || steps > MAX_CURVE_STEPS {
+        return Err(CommandError::invalid_input(format!("Steps must be between 1 and {MAX_CURVE_STEPS}")));
+    }
+    let current = state.current_project.lock().unwrap();
+    let loaded = current.as_ref().ok_or_else(CommandError::no_project)?;
+    let project = &loaded.project;
+
+    let keyframe_time = |track: &core::track::TransformTrack, id| track.keyframes.iter().find(|k| k.id == id).map(|k| k.time);
+    let (track, from_time, to_time) = project
+        .timeline
+        .transform_tracks()
+        .find_map(|track| Some((track, keyframe_time(track, from_kf_id)?, keyframe_time(track, to_kf_id)?)))
+        .ok_or_else(|| CommandError::invalid_input("Both keyframes must be on the same transform track"))?;
+
+    let evaluator = core::evaluator::FrameEvaluator::for_project(project);
+    Ok(evaluator.sample_transform_curve(track, from_time.min(to_time), from_time.max(to_time), steps))
+}
+
+/// Get the current project's timeline as serialized tracks + keyframes.
+#[tauri::command]
+fn get_timeline(state: State<AppState>) -> CommandResult<TimelineData> {
     let current = state.current_project.lock().unwrap();
-    let loaded = current.as_ref().ok_or("No project loaded")?;
-    let timeline = &loaded.project.timeline;
+    let loaded = current.as_ref().ok_or_else(CommandError::no_project)?;
+    Ok(timeline_data(&loaded.project.timeline))
+}
+
+/// Run every generator on an input JSON file (a `recording_mouse.json` or a
+/// synthetic fixture) without video or touching the project, and return the
+/// tracks in `get_timeline`'s format. Uses the current project's generator
+/// settings (defaults without one), with `settings` overriding parts.
+#[tauri::command]
+fn generate_from_input_file(
+    input_path: String,
+    settings: Option<core::replay::ReplaySettings>,
+    state: State<AppState>,
+) -> CommandResult<TimelineData> {
+    let json = std::fs::read_to_string(&input_path)
+        .map_err(|e| CommandError::from(e).context(format!("Can't read {input_path}")))?;
+    let recording = core::input::InputRecording::from_json(&json)?;
+
+    let base = match state.current_project.lock().unwrap().as_ref() {
+        Some(loaded) => core::replay::GeneratorSettings::from_project(&loaded.project),
+        None => core::replay::GeneratorSettings::default(),
+    };
+    let settings = settings.unwrap_or_default();
+    let timeline = core::replay::replay_input(&recording, &base.with_overrides(&settings), settings.duration);
+    log::info!(
+        "Replayed {input_path}: {} keyframes over {:.1}s",
+        timeline.total_keyframe_count(), timeline.duration,
+    );
+    Ok(timeline_data(&timeline))
+}
+
+/// Serialize tracks + keyframes for the frontend
+fn timeline_data(timeline: &core::timeline::Timeline) -> TimelineData {
+    use core::track::AnyTrack;
 
     let tracks: Vec<TimelineTrackData> = timeline.tracks.iter().map(|track| {
         match track {
@@ -585,6 +1703,7 @@ fn get_timeline(state: State<AppState>) -> Result<TimelineData, String> {
                     serde_json::json!({
                         "id": kf.id.to_string(),
                         "time": kf.time,
+                        "generated": kf.generated,
                         "zoom": kf.zoom,
                         "centerX": kf.center.x,
                         "centerY": kf.center.y,
@@ -605,9 +1724,12 @@ fn get_timeline(state: State<AppState>) -> Result<TimelineData, String> {
                     serde_json::json!({
                         "id": kf.id.to_string(),
                         "time": kf.time,
+                        "generated": kf.generated,
                         "intensity": kf.intensity,
                         "rippleDuration": kf.duration,
                         "color": color_str,
+                        "style": kf.style,
+                        "maxRadiusScale": kf.max_radius_scale,
                     })
                 }).collect(),
             },
@@ -620,6 +1742,7 @@ fn get_timeline(state: State<AppState>) -> Result<TimelineData, String> {
                         serde_json::json!({
                             "id": kf.id.to_string(),
                             "time": kf.time,
+                            "generated": kf.generated,
                             "scale": kf.scale,
                             "visible": kf.visible,
                             "style": format!("{:?}", kf.style).to_lowercase(),
@@ -635,6 +1758,7 @@ fn get_timeline(state: State<AppState>) -> Result<TimelineData, String> {
                     serde_json::json!({
                         "id": kf.id.to_string(),
                         "time": kf.time,
+                        "generated": kf.generated,
                         "text": kf.display_text,
                         "displayDuration": kf.duration,
                     })
@@ -643,48 +1767,151 @@ fn get_timeline(state: State<AppState>) -> Result<TimelineData, String> {
         }
     }).collect();
 
-    Ok(TimelineData {
+    TimelineData {
         duration: timeline.duration,
         tracks,
-    })
+    }
+}
+
+/// Step one frame forward/backward from `current_time`.
+/// Returns the exact frame time, clamped to the recording.
+#[tauri::command]
+fn step_frame(forward: bool, current_time: f64, state: State<AppState>) -> CommandResult<f64> {
+    let current = state.current_project.lock().unwrap();
+    let loaded = current.as_ref().ok_or_else(CommandError::no_project)?;
+    Ok(loaded.project.step_frame(current_time, forward))
 }
 
 /// Get the currently loaded project info.
 #[tauri::command]
 fn get_current_project(state: State<AppState>) -> Option<ProjectInfo> {
     let current = state.current_project.lock().unwrap();
-    current.as_ref().map(|loaded| ProjectInfo {
-        name: loaded.project.name.clone(),
-        duration: loaded.project.duration(),
-        frame_rate: loaded.project.media.frame_rate,
-        width: loaded.project.media.pixel_size.width,
-        height: loaded.project.media.pixel_size.height,
-        package_path: loaded.package_dir.display().to_string(),
+    current.as_ref().map(|loaded| {
+        let mut info = ProjectInfo::new(&loaded.project, &loaded.package_dir);
+        // This session's own edits aren't something to recover
+        info.recovery_available &= !loaded.dirty;
+        info
     })
 }
 
 /// Get the current project's render settings.
 #[tauri::command]
-fn get_render_settings(state: State<AppState>) -> Result<core::project::RenderSettings, String> {
+fn get_render_settings(state: State<AppState>) -> CommandResult<core::project::RenderSettings> {
     let current = state.current_project.lock().unwrap();
-    let loaded = current.as_ref().ok_or("No project loaded")?;
+    let loaded = current.as_ref().ok_or_else(CommandError::no_project)?;
     Ok(loaded.project.render_settings.clone())
 }
 
+/// Replace the current project's speed segments (slow-motion / speed ramps).
+#[tauri::command]
+fn set_speed_segments(segments: Vec<core::timeline::SpeedSegment>, state: State<AppState>) -> CommandResult<f64> {
+    let mut current = state.current_project.lock().unwrap();
+    let loaded = current.as_mut().ok_or_else(CommandError::no_project)?;
+    loaded.project.timeline.speed_segments = segments;
+    loaded.mark_dirty();
+    Ok(loaded.project.timeline.output_duration())
+}
+
+/// Idle spans (no clicks, keys or significant movement) in the current recording,
+/// for the user to approve before `apply_idle_cuts`.
+#[tauri::command]
+fn suggest_cuts(
+    inactivity_threshold: Option<f64>,
+    state: State<AppState>,
+) -> CommandResult<Vec<core::idle::IdleSegment>> {
+    let current = state.current_project.lock().unwrap();
+    let loaded = current.as_ref().ok_or_else(CommandError::no_project)?;
+    let recording = load_input_recording(&loaded.project, &loaded.package_dir)
+        .ok_or_else(|| CommandError::new(ErrorKind::FileMissing, "No input data for this recording"))?;
+
+    let mut settings = core::idle::IdleSettings::default();
+    if let Some(threshold) = inactivity_threshold {
+        settings.inactivity_threshold = threshold.max(0.5);
+    }
+    Ok(core::idle::detect_idle_segments(&recording, loaded.project.duration(), &settings))
+}
+
+/// Where the mouse spent the (trimmed) recording: positions and clicks
+/// binned into a `grid_w` x `grid_h` grid of densities (busiest cell 1),
+/// clicks weighted above movement. Cached until the mouse data, its time
+/// alignment or the trim changes.
+#[tauri::command]
+fn compute_activity_heatmap(
+    grid_w: u32,
+    grid_h: u32,
+    state: State<AppState>,
+) -> CommandResult<core::heatmap::ActivityHeatmap> {
+    use core::heatmap::{ActivityHeatmap, MAX_GRID_SIZE};
+
+    if grid_w == 0 || grid_h == 0 || grid_w > MAX_GRID_SIZE || grid_h > MAX_GRID_SIZE {
+        return Err(CommandError::invalid_input(format!("Grid sides must be between 1 and {MAX_GRID_SIZE} cells")));
+    }
+    let current = state.current_project.lock().unwrap();
+    let loaded = current.as_ref().ok_or_else(CommandError::no_project)?;
+    let project = &loaded.project;
+
+    let mouse_path = project.mouse_data_path(&loaded.package_dir);
+    let modified = std::fs::metadata(&mouse_path).and_then(|m| m.modified()).ok();
+    let range = (project.timeline.effective_trim_start(), project.timeline.effective_trim_end());
+    let mut cached = state.heatmap.lock().unwrap();
+    if let Some(hit) = cached.as_ref().filter(|c| {
+        c.mouse_path == mouse_path
+            && c.modified == modified
+            && c.time_offset == project.input_time_offset
+            && c.warmup_secs == project.warmup_secs
+            && c.range == range
+            && (c.heatmap.grid_width, c.heatmap.grid_height) == (grid_w, grid_h)
+    }) {
+        return Ok(hit.heatmap.clone());
+    }
+
+    let json = std::fs::read_to_string(&mouse_path)
+        .map_err(|e| CommandError::from(e).context("No input data for this recording"))?;
+    let mut recording = core::input::InputRecording::from_json(&json)?;
+    project.align_input(&mut recording);
+    let heatmap = ActivityHeatmap::compute(&recording, grid_w, grid_h, range.0, range.1);
+    *cached = Some(CachedHeatmap {
+        mouse_path,
+        modified,
+        time_offset: project.input_time_offset,
+        warmup_secs: project.warmup_secs,
+        range,
+        heatmap: heatmap.clone(),
+    });
+    Ok(heatmap)
+}
+
+/// Speed up or cut the approved idle spans. Returns the new output duration.
+#[tauri::command]
+fn apply_idle_cuts(
+    segments: Vec<core::idle::IdleSegment>,
+    action: core::idle::IdleAction,
+    state: State<AppState>,
+) -> CommandResult<f64> {
+    let mut current = state.current_project.lock().unwrap();
+    let loaded = current.as_mut().ok_or_else(CommandError::no_project)?;
+    core::idle::apply_idle_action(&mut loaded.project.timeline, &segments, action);
+    loaded.mark_dirty();
+    Ok(loaded.project.timeline.output_duration())
+}
+
 /// Update the current project's render settings.
 #[tauri::command]
-fn update_render_settings(settings: core::project::RenderSettings, state: State<AppState>) -> Result<(), String> {
+fn update_render_settings(settings: core::project::RenderSettings, state: State<AppState>) -> CommandResult<()> {
     let mut current = state.current_project.lock().unwrap();
-    let loaded = current.as_mut().ok_or("No project loaded")?;
+    let loaded = current.as_mut().ok_or_else(CommandError::no_project)?;
     loaded.project.render_settings = settings;
-    loaded.project.save(&loaded.package_dir, None, None)
-        .map_err(|e| e.to_string())?;
+    loaded.mark_dirty();
+    let saved = loaded.project.render_settings.clone();
+    drop(current);
+
+    state.update_settings(|app_settings| app_settings.remember_render_settings(&saved));
     Ok(())
 }
 
 /// Frame data returned to the frontend for preview rendering.
 /// Contains base64-encoded RGBA pixel data and dimensions.
-#[derive(serde::Serialize)]
+#[derive(Clone, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 struct FrameData {
     width: u32,
@@ -695,37 +1922,81 @@ struct FrameData {
 
 /// Extract a video frame at the given time for preview.
 /// Uses the loaded project's video file (via FFmpeg when available, else stub).
+/// Timeline effects (zoom, ripples, cursor, keystrokes) are rendered as in the
+/// export, and the frame is downscaled to fit
+/// `max_width` x `max_height` (aspect preserved) to keep the IPC payload small.
+/// `guides` are drawn on top of the scaled frame and never exported.
 /// Throttled by the frontend to avoid excessive calls during scrubbing.
 #[tauri::command]
-fn extract_preview_frame(time: f64, state: State<AppState>) -> Result<FrameData, String> {
+fn extract_preview_frame(
+    time: f64,
+    max_width: Option<u32>,
+    max_height: Option<u32>,
+    guides: Option<core::render::PreviewGuides>,
+    state: State<AppState>,
+) -> CommandResult<FrameData> {
     use core::render::create_video_source_from_file;
     use base64::Engine;
 
     let current = state.current_project.lock().unwrap();
     let loaded = current.as_ref();
 
-    let mut source = if let Some(loaded) = loaded {
+    let (mut source, project) = if let Some(loaded) = loaded {
         let video_path = loaded.project.video_path(&loaded.package_dir);
-        create_video_source_from_file(
+        let source = create_video_source_from_file(
             &video_path,
             loaded.project.media.pixel_size.width as u32,
             loaded.project.media.pixel_size.height as u32,
             loaded.project.duration(),
             loaded.project.media.frame_rate,
-        )
+        );
+        (source, Some((loaded.project.clone(), loaded.package_dir.clone())))
     } else {
         // No project loaded — use stub
         use core::render::create_video_source;
-        create_video_source(640, 360, 30.0, 30.0)
+        (create_video_source(640, 360, 30.0, 30.0), None)
     };
 
     // Release lock before potentially slow frame read
     drop(current);
 
-    let frame = source.read_frame(time).map_err(|e| e.to_string())?;
+    let mut frame = source.read_frame(time)?;
+
+    // Render effects exactly like the export does
+    if let Some((project, package_dir)) = &project {
+        let input = preview_input(&state, project, package_dir);
+        let evaluator = core::evaluator::FrameEvaluator::for_project(project);
+        let frame_state = evaluator.evaluate(&project.timeline, time, &input.positions, &input.clicks);
+
+        let mut ctx = core::render::RenderContext::from_project(project);
+        let load_image = |path: &std::path::Path| core::render::load_background_image(path).map(Arc::new);
+        let mut assets = state.preview_assets.lock().unwrap();
+        if project.is_window_mode() {
+            ctx.background_image = project
+                .background_image_path(package_dir)
+                .and_then(|path| cached_asset(&mut assets.background, &path, load_image));
+        }
+        ctx.watermark_image = project
+            .watermark_path(package_dir)
+            .and_then(|path| cached_asset(&mut assets.watermark, &path, load_image));
+        if let Some(dir) = project.cursor_theme_path(package_dir) {
+            ctx.cursor_pack = Some(cached_asset(
+                &mut assets.cursor_pack,
+                &dir,
+                core::cursor_theme::CursorPack::load_or_system,
+            ));
+        }
+        drop(assets);
+        frame = core::render::SoftwareRenderer::new(ctx).render_frame(&frame, &frame_state);
+    }
+
+    let mut frame = frame.fit_within(max_width.unwrap_or(u32::MAX), max_height.unwrap_or(u32::MAX));
+    if let Some(guides) = guides.filter(|g| !g.is_empty()) {
+        core::render::draw_guides(&mut frame, &guides);
+    }
 
     // Convert BGRA → RGBA for HTML Canvas ImageData
-    let rgba = bgra_to_rgba(&frame);
+    let rgba = bgra_to_rgba(&frame.data, frame.width, frame.height, frame.stride);
 
     let rgba_base64 = base64::engine::general_purpose::STANDARD.encode(&rgba);
 
@@ -736,16 +2007,205 @@ fn extract_preview_frame(time: f64, state: State<AppState>) -> Result<FrameData,
     })
 }
 
-/// Convert BGRA pixel data to RGBA for use with HTML Canvas ImageData
-fn bgra_to_rgba(frame: &FrameBuffer) -> Vec<u8> {
-    let mut rgba = vec![0u8; frame.data.len()];
-    for (src, dst) in frame.data.chunks_exact(4).zip(rgba.chunks_exact_mut(4)) {
-        dst[0] = src[2]; // R
-        dst[1] = src[1]; // G
-        dst[2] = src[0]; // B
-        dst[3] = src[3]; // A
+/// Mouse positions/clicks for previewing `project`, re-read only when the
+/// mouse data file or how it is shifted/smoothed changes (e.g. another project
+/// loaded or a take appended)
+fn preview_input(state: &AppState, project: &Project, package_dir: &std::path::Path) -> Arc<PreviewInput> {
+    let mouse_path = project.mouse_data_path(package_dir);
+    let modified = std::fs::metadata(&mouse_path).and_then(|m| m.modified()).ok();
+
+    let mut cached = state.preview_input.lock().unwrap();
+    let time_offset = project.input_time_offset;
+    let warmup_secs = project.warmup_secs;
+    let smoothing = project.render_settings.mouse_smoothing;
+    if let Some(input) = cached.as_ref().filter(|i| {
+        i.mouse_path == mouse_path
+            && i.modified == modified
+            && i.time_offset == time_offset
+            && i.warmup_secs == warmup_secs
+            && i.smoothing == smoothing
+    }) {
+        return input.clone();
+    }
+
+    let recording = load_input_recording(project, package_dir);
+    let input = Arc::new(PreviewInput {
+        mouse_path,
+        modified,
+        time_offset,
+        warmup_secs,
+        smoothing,
+        positions: recording
+            .as_ref()
+            .map(|r| input_to_evaluator_positions(r, &smoothing))
+            .unwrap_or_default(),
+        clicks: recording.as_ref().map(input_to_evaluator_clicks).unwrap_or_default(),
+    });
+    *cached = Some(input.clone());
+    input
+}
+
+/// Run a synthetic render benchmark and return per-stage timings.
+/// Uses a stub source and a representative timeline; no project files are touched.
+#[tauri::command]
+fn benchmark_render(
+    width: Option<u32>,
+    height: Option<u32>,
+    frames: Option<u64>,
+) -> CommandResult<core::render::RenderBenchmark> {
+    let width = width.unwrap_or(1920).clamp(16, 7680);
+    let height = height.unwrap_or(1080).clamp(16, 4320);
+    let frames = frames.unwrap_or(120).clamp(1, 3600);
+
+    let bench = core::render::benchmark_render(width, height, frames)?;
+    log::info!(
+        "Render benchmark {}x{} x{} frames: decode {:.1}ms, evaluate {:.1}ms, render {:.1}ms, encode {:.1}ms — {:.1} fps",
+        bench.width, bench.height, bench.frames,
+        bench.decode_ms, bench.evaluate_ms, bench.render_ms, bench.encode_ms, bench.fps,
+    );
+    Ok(bench)
+}
+
+/// Expected output of exporting the current project
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportEstimate {
+    width: u32,
+    height: u32,
+    frame_rate: f64,
+    /// Output duration after speed changes (seconds)
+    duration: f64,
+    total_frames: u64,
+    /// Approximate file size from the quality bit rate
+    size_bytes: u64,
+    /// Seconds between keyframes: the longest seek a player or editor decodes through
+    keyframe_spacing: f64,
+    /// Things the user should know before starting (e.g. slow encoders)
+    warnings: Vec<String>,
+}
+
+/// Estimate the size and frame count of an export with the current settings.
+#[tauri::command]
+fn estimate_export(state: State<AppState>) -> CommandResult<ExportEstimate> {
+    use core::project::VideoCodec;
+
+    let current = state.current_project.lock().unwrap();
+    let loaded = current.as_ref().ok_or_else(CommandError::no_project)?;
+    let project = &loaded.project;
+    let settings = &project.render_settings;
+
+    let ctx = core::render::RenderContext::from_project(project);
+    let (width, height) = (ctx.output_size.width, ctx.output_size.height);
+    let duration = project.timeline.output_duration();
+    let bit_rate = settings.quality.bit_rate(width, height);
+
+    let mut warnings = Vec::new();
+    if settings.overlay_only && settings.click_sfx {
+        warnings.push("Overlay-only exports have no audio: click sounds are left out".into());
+    }
+    // Short GOPs seek fast but cost size; long ones shrink the file but make scrubbing slow
+    let keyframe_interval = settings.keyframe_interval.clamp(1, core::encoder::MAX_KEYFRAME_INTERVAL);
+    let keyframe_spacing = keyframe_interval as f64 / ctx.frame_rate.max(1.0);
+    if keyframe_spacing < 0.5 {
+        warnings.push(format!(
+            "Keyframes every {keyframe_interval} frames: seeking is instant but the file will be noticeably larger",
+        ));
+    } else if keyframe_spacing > 5.0 {
+        warnings.push(format!(
+            "Keyframes every {keyframe_spacing:.1}s: smaller file, but seeking and scrubbing in editors will be slow",
+        ));
     }
-    rgba
+    if settings.codec == VideoCodec::Av1 && !settings.overlay_only {
+        let hardware = core::encoder::list_encoders().into_iter().any(|e| {
+            e.codec == VideoCodec::Av1
+                && e.hardware
+                && e.available
+                && settings.preferred_encoder.as_deref().is_none_or(|p| p == e.name)
+        });
+        if !hardware {
+            warnings.push(
+                "No hardware AV1 encoder found: software AV1 (SVT-AV1) is several times slower than H.264/H.265".into(),
+            );
+        }
+    }
+
+    Ok(ExportEstimate {
+        width: width as u32,
+        height: height as u32,
+        frame_rate: ctx.frame_rate,
+        duration,
+        total_frames: (duration * ctx.frame_rate).ceil() as u64,
+        size_bytes: (bit_rate as f64 * duration / 8.0) as u64,
+        keyframe_spacing,
+        warnings,
+    })
+}
+
+/// List FFmpeg encoders and whether each one works on this machine.
+/// Any available name can be set as `preferredEncoder` in render settings.
+#[tauri::command]
+fn list_encoders() -> Vec<core::encoder::EncoderInfo> {
+    let encoders = core::encoder::list_encoders();
+    let available: Vec<&str> = encoders.iter()
+        .filter(|e| e.available)
+        .map(|e| e.name.as_str())
+        .collect();
+    log::info!("Available encoders: {available:?}");
+    encoders
+}
+
+/// Write a caption sidecar (SRT or WebVTT) describing clicks and keystrokes.
+/// It sits next to the most recent export (same file stem) and returns its path.
+#[tauri::command]
+fn export_annotations(
+    state: State<AppState>,
+    format: core::annotations::AnnotationFormat,
+) -> CommandResult<String> {
+    let current = state.current_project.lock().unwrap();
+    let loaded = current.as_ref().ok_or_else(CommandError::no_project)?;
+
+    let mouse_path = loaded.project.mouse_data_path(&loaded.package_dir);
+    let json = std::fs::read_to_string(&mouse_path)
+        .map_err(|e| CommandError::from(e).context("No input data for this recording"))?;
+    let mut recording = core::input::InputRecording::from_json(&json)?;
+    loaded.project.align_input(&mut recording);
+
+    let data = core::replay::project_mouse_data(&loaded.project, &recording);
+    let annotations = core::annotations::collect_annotations(&data);
+    let text = core::annotations::format_annotations(&annotations, format);
+
+    let output_dir = loaded.package_dir.parent()
+        .unwrap_or(std::path::Path::new("."));
+    let stem = latest_export_stem(output_dir).unwrap_or_else(|| loaded.project.name.clone());
+    let output_path = output_dir.join(format!("{stem}.{}", format.file_extension()));
+    std::fs::write(&output_path, text)?;
+
+    log::info!("Wrote {} annotations to {}", annotations.len(), output_path.display());
+    Ok(output_path.to_string_lossy().to_string())
+}
+
+/// File stem of the newest finished export (`export_*.mp4`, `.webm` or
+/// `.gif`) in a directory
+fn latest_export_stem(dir: &std::path::Path) -> Option<String> {
+    const EXPORT_EXTENSIONS: [&str; 3] = ["mp4", "webm", "gif"];
+    std::fs::read_dir(dir).ok()?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let extension = path.extension()?.to_string_lossy().to_lowercase();
+            if !EXPORT_EXTENSIONS.contains(&extension.as_str()) {
+                return None;
+            }
+            let stem = path.file_stem()?.to_string_lossy().to_string();
+            // `.part` files are left behind by an export that crashed
+            if !stem.starts_with("export_") || stem.ends_with(".part") {
+                return None;
+            }
+            let modified = entry.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, stem))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, stem)| stem)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -754,6 +2214,13 @@ pub fn run() {
         .unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join("Videos"))
         .join("LazyRec");
 
+    let settings_path = AppSettings::default_path();
+    let settings = AppSettings::load(&settings_path);
+    let mut recorder = RecordingCoordinator::new(output_dir);
+    apply_capture_settings(&mut recorder, &settings);
+    let current_project = Arc::new(Mutex::new(None));
+    spawn_autosave(current_project.clone());
+
     tauri::Builder::default()
         .plugin(tauri_plugin_log::Builder::new()
             .targets([
@@ -767,31 +2234,97 @@ pub fn run() {
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
         .manage(AppState {
-            recorder: Arc::new(Mutex::new(RecordingCoordinator::new(output_dir))),
+            recorder: Arc::new(Mutex::new(recorder)),
             export_progress: Arc::new(Mutex::new(None)),
-            current_project: Mutex::new(None),
+            export_cancel: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            current_project,
+            settings: Mutex::new(settings),
+            settings_path,
+            append_target: Mutex::new(None),
+            preview_input: Mutex::new(None),
+            preview_assets: Mutex::new(PreviewAssets::default()),
+            heatmap: Mutex::new(None),
         })
         .invoke_handler(tauri::generate_handler![
             check_permissions,
             list_capture_sources,
             set_capture_target,
+            set_capture_source,
+            get_app_settings,
+            set_app_settings,
+            list_export_presets,
+            save_export_preset,
+            delete_export_preset,
             get_recording_status,
             start_recording,
+            start_append_recording,
+            set_live_preview,
             pause_recording,
             resume_recording,
+            add_recording_marker,
             stop_recording,
+            discard_recording,
             start_export,
             get_export_progress,
+            cancel_export,
             extract_preview_frame,
+            step_frame,
+            benchmark_render,
+            list_encoders,
+            estimate_export,
+            compress_video,
+            export_annotations,
             save_project,
             load_project,
+            resolve_recovery,
+            get_project_metadata,
+            set_project_metadata,
+            set_input_time_offset,
+            set_input_warmup,
+            get_shortcut_labels,
+            set_shortcut_labels,
+            get_smart_zoom_settings,
+            set_smart_zoom_settings,
+            export_style,
+            import_style,
+            set_camera_spring,
+            list_projects,
+            verify_project,
+            relink_media,
             get_current_project,
             get_timeline,
+            generate_from_input_file,
+            add_zoom_region,
+            list_markers,
+            add_marker,
+            remove_marker,
+            add_transform_track,
+            shift_keyframes,
+            duplicate_keyframe,
+            dump_evaluated_timeline,
+            sample_transform_curve,
+            scan_recoverable,
+            recover_recording,
+            import_video,
+            verify_export,
             load_mouse_data,
+            compact_input_data,
             generate_keyframes,
+            regenerate_track,
             get_render_settings,
             update_render_settings,
+            set_speed_segments,
+            suggest_cuts,
+            compute_activity_heatmap,
+            apply_idle_cuts,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // The auto-save only runs every few seconds, so save whatever
+            // was edited since then before the process goes away
+            if let tauri::RunEvent::ExitRequested { .. } | tauri::RunEvent::Exit = event {
+                app.state::<AppState>().flush_project();
+            }
+        });
 }
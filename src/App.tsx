@@ -7,6 +7,8 @@ type Screen = "welcome" | "recording" | "post-recording" | "editor";
 
 function App() {
   const [screen, setScreen] = useState<Screen>("welcome");
+  // Package the next recording is appended to ("Add Take" in the editor)
+  const [appendTo, setAppendTo] = useState<string | null>(null);
 
   return (
     <main className="app">
@@ -18,8 +20,9 @@ function App() {
       )}
       {screen === "recording" && (
         <RecordingScreen
-          onBack={() => setScreen("welcome")}
-          onRecordingComplete={() => setScreen("post-recording")}
+          appendTo={appendTo}
+          onBack={() => { setAppendTo(null); setScreen("welcome"); }}
+          onRecordingComplete={() => { setAppendTo(null); setScreen("post-recording"); }}
         />
       )}
       {screen === "post-recording" && (
@@ -30,7 +33,10 @@ function App() {
         />
       )}
       {screen === "editor" && (
-        <EditorScreen onBack={() => setScreen("welcome")} />
+        <EditorScreen
+          onBack={() => setScreen("welcome")}
+          onAddTake={(packagePath) => { setAppendTo(packagePath); setScreen("recording"); }}
+        />
       )}
     </main>
   );
@@ -40,6 +46,16 @@ function App() {
 // Welcome Screen
 // =============================================================================
 
+interface RecoverableRecording {
+  videoPath: string;
+  mouseDataPath: string | null;
+  width: number;
+  height: number;
+  frameRate: number;
+  duration: number;
+  sizeBytes: number;
+}
+
 function WelcomeScreen({
   onStartRecording,
   onOpenEditor,
@@ -53,6 +69,7 @@ function WelcomeScreen({
   const [downloadProgress, setDownloadProgress] = useState(0);
   const [updateChecking, setUpdateChecking] = useState(false);
   const [updateMessage, setUpdateMessage] = useState<string | null>(null);
+  const [recoverable, setRecoverable] = useState<RecoverableRecording[]>([]);
 
   const checkForUpdates = async () => {
     setUpdateChecking(true);
@@ -72,8 +89,33 @@ function WelcomeScreen({
 
   useEffect(() => {
     checkForUpdates();
+    invoke<RecoverableRecording[]>("scan_recoverable", {})
+      .then(setRecoverable)
+      .catch((err) => console.error("Recovery scan failed:", err));
   }, []);
 
+  const handleRecover = async (recording: RecoverableRecording) => {
+    try {
+      await invoke("recover_recording", { videoPath: recording.videoPath });
+      onOpenEditor();
+    } catch (err) {
+      console.error("Recovery failed:", err);
+      setRecoverable((list) => list.filter((r) => r.videoPath !== recording.videoPath));
+    }
+  };
+
+  const handleImportVideo = async () => {
+    const path = window.prompt("Path of the video to import (.mp4, .mov):", "");
+    if (!path?.trim()) return;
+    try {
+      await invoke("import_video", { path: path.trim() });
+      onOpenEditor();
+    } catch (err) {
+      console.error("Import failed:", err);
+      alert(`Import failed: ${errorMessage(err)}`);
+    }
+  };
+
   const handleUpdate = async () => {
     if (!update) return;
     setUpdateStatus("downloading");
@@ -134,7 +176,7 @@ function WelcomeScreen({
           title="Open Video"
           description="Edit existing video"
           color="#4a90d9"
-          onClick={onOpenEditor}
+          onClick={handleImportVideo}
         />
         <ActionCard
           icon="📁"
@@ -156,6 +198,16 @@ function WelcomeScreen({
         <span className="drop-hint">.mp4, .mov, .lazyrec</span>
       </div>
 
+      {recoverable.length > 0 && (
+        <div className="update-banner">
+          <span>
+            Unsaved recording found ({recoverable[0].width}x{recoverable[0].height},{" "}
+            {recoverable[0].duration.toFixed(1)}s)
+          </span>
+          <button className="update-btn" onClick={() => handleRecover(recoverable[0])}>Recover</button>
+        </div>
+      )}
+
       {update ? (
         <div className="update-banner">
           {updateStatus === "idle" && (
@@ -197,31 +249,41 @@ interface RecordingStatusData {
   state: string;
   elapsed: number;
   frameCount: number;
+  droppedFrames: number;
+  dropRate: number;
 }
 
+/// Drop rate above which the recording screen warns about choppy output
+const DROP_RATE_WARNING = 0.05;
+
 interface CaptureSourceInfo {
   id: string;
   name: string;
-  sourceType: "display";
+  source_type: "display" | "window";
   width: number;
   height: number;
 }
 
 function RecordingScreen({
+  appendTo,
   onBack,
   onRecordingComplete,
 }: {
+  // Existing project package to append this take to
+  appendTo: string | null;
   onBack: () => void;
   onRecordingComplete: () => void;
 }) {
   const [state, setState] = useState<RecordingState>("idle");
   const [elapsed, setElapsed] = useState(0);
   const [frameCount, setFrameCount] = useState(0);
+  const [dropRate, setDropRate] = useState(0);
   const [countdown, setCountdown] = useState(3);
   const [error, setError] = useState<string | null>(null);
   const [sources, setSources] = useState<CaptureSourceInfo[]>([]);
   const [selectedSourceId, setSelectedSourceId] = useState<string>("");
   const pollRef = useRef<number | null>(null);
+  const livePreviewRef = useRef<HTMLCanvasElement | null>(null);
 
   // Load capture sources on mount
   useEffect(() => {
@@ -230,7 +292,12 @@ function RecordingScreen({
         const { invoke } = await import("@tauri-apps/api/core");
         const srcs = await invoke<CaptureSourceInfo[]>("list_capture_sources");
         setSources(srcs);
-        if (srcs.length > 0) setSelectedSourceId(srcs[0].id);
+        // Restore the last-used display if it is still connected
+        const settings = await invoke<{ lastCaptureTarget?: { type: string; displayId?: number; display_id?: number } | null }>("get_app_settings");
+        const last = settings.lastCaptureTarget;
+        const lastId = last?.type === "display" ? `display-${last.displayId ?? last.display_id}` : null;
+        if (lastId && srcs.some(s => s.id === lastId)) setSelectedSourceId(lastId);
+        else if (srcs.length > 0) setSelectedSourceId(srcs[0].id);
       } catch {
         // Fallback — sources remain empty, backend will use default
       }
@@ -246,6 +313,7 @@ function RecordingScreen({
           const status = await invoke<RecordingStatusData>("get_recording_status");
           setElapsed(Math.floor(status.elapsed));
           setFrameCount(status.frameCount);
+          setDropRate(status.dropRate);
         } catch {
           // Silently ignore poll errors
         }
@@ -261,6 +329,42 @@ function RecordingScreen({
     return () => { if (pollRef.current) clearInterval(pollRef.current); };
   }, [state]);
 
+  // Live preview: the backend only makes preview frames while subscribed
+  const isCapturing = state === "recording" || state === "paused";
+  useEffect(() => {
+    if (!isCapturing) return;
+    // Kept so cleanup can wait for a subscription still being set up
+    const subscription = (async () => {
+      const { invoke } = await import("@tauri-apps/api/core");
+      const { listen } = await import("@tauri-apps/api/event");
+      const unlisten = await listen<{ width: number; height: number; rgbaBase64: string }>("recording-preview", (event) => {
+        const canvas = livePreviewRef.current;
+        const ctx = canvas?.getContext("2d");
+        if (!canvas || !ctx) return;
+        const frame = event.payload;
+        const binary = atob(frame.rgbaBase64);
+        const bytes = new Uint8ClampedArray(binary.length);
+        for (let i = 0; i < binary.length; i++) {
+          bytes[i] = binary.charCodeAt(i);
+        }
+        canvas.width = frame.width;
+        canvas.height = frame.height;
+        ctx.putImageData(new ImageData(bytes, frame.width, frame.height), 0, 0);
+      });
+      await invoke("set_live_preview", { enabled: true }).catch(() => {});
+      return unlisten;
+    })().catch(() => undefined); // No preview available
+    return () => {
+      subscription
+        .then(async (unlisten) => {
+          unlisten?.();
+          const { invoke } = await import("@tauri-apps/api/core");
+          await invoke("set_live_preview", { enabled: false });
+        })
+        .catch(() => {});
+    };
+  }, [isCapturing]);
+
   const startCountdown = useCallback(() => {
     setState("countdown");
     setCountdown(3);
@@ -276,30 +380,27 @@ function RecordingScreen({
           if (selectedSourceId) {
             const source = sources.find(s => s.id === selectedSourceId);
             if (source) {
-              // Parse display index from source id (e.g., "display-1" → 1)
-              const idxStr = source.id.replace("display-", "");
-              const displayId = parseInt(idxStr, 10) || 0;
-              const target = { type: "display" as const, displayId };
-              await invoke("set_capture_target", {
-                target,
-                width: source.width || null,
-                height: source.height || null,
-              });
+              // The backend maps it to a display or window target
+              await invoke("set_capture_source", { source });
             }
           }
-          await invoke("start_recording");
+          if (appendTo) {
+            await invoke("start_append_recording", { packageDir: appendTo });
+          } else {
+            await invoke("start_recording");
+          }
           setState("recording");
           setElapsed(0);
           setFrameCount(0);
         } catch (err) {
-          setError(String(err));
+          setError(errorMessage(err));
           setState("idle");
         }
       } else {
         setCountdown(count);
       }
     }, 1000);
-  }, [sources, selectedSourceId]);
+  }, [sources, selectedSourceId, appendTo]);
 
   const togglePause = async () => {
     try {
@@ -317,13 +418,22 @@ function RecordingScreen({
   };
 
   const [isStopping, setIsStopping] = useState(false);
+  // What the stop is waiting on, from "recording-stop-progress"
+  const [stopStage, setStopStage] = useState<string | null>(null);
 
   // Listen for async stop events
   useEffect(() => {
     let unlisten1: (() => void) | null = null;
     let unlisten2: (() => void) | null = null;
+    let unlisten3: (() => void) | null = null;
+    let unlisten4: (() => void) | null = null;
     (async () => {
       const { listen } = await import("@tauri-apps/api/event");
+      unlisten4 = await listen<{ stage: string; waited: number }>("recording-stop-progress", (event) => {
+        const { stage, waited } = event.payload;
+        const label = stage === "finishingVideo" ? "Finishing video" : stage === "stoppingInput" ? "Saving input" : "Stopping capture";
+        setStopStage(waited >= 1 ? `${label} (${Math.floor(waited)}s)...` : `${label}...`);
+      }) as unknown as () => void;
       unlisten1 = await listen("recording-stopped", () => {
         setIsStopping(false);
         setState("idle");
@@ -336,15 +446,24 @@ function RecordingScreen({
         setError(String(event.payload));
         setState("idle");
       }) as unknown as () => void;
+      unlisten3 = await listen("recording-discarded", () => {
+        setIsStopping(false);
+        setState("idle");
+        setElapsed(0);
+        setFrameCount(0);
+      }) as unknown as () => void;
     })();
     return () => {
       unlisten1?.();
       unlisten2?.();
+      unlisten3?.();
+      unlisten4?.();
     };
   }, [onRecordingComplete]);
 
   const stopRecording = async () => {
     setIsStopping(true);
+    setStopStage(null);
     try {
       const { invoke } = await import("@tauri-apps/api/core");
       await invoke("stop_recording");
@@ -356,6 +475,39 @@ function RecordingScreen({
     }
   };
 
+  const discardRecording = async () => {
+    if (!window.confirm("Discard this take? The recording will be deleted.")) return;
+    setIsStopping(true);
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      await invoke("discard_recording");
+      // Wait for the "recording-discarded" event
+    } catch (err) {
+      setIsStopping(false);
+      setError(String(err));
+      setState("idle");
+    }
+  };
+
+  const addMarker = useCallback(async () => {
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      await invoke("add_recording_marker", { label: null });
+    } catch (err) {
+      setError(String(err));
+    }
+  }, []);
+
+  // M drops a chapter marker while recording
+  useEffect(() => {
+    if (state !== "recording" && state !== "paused") return;
+    const handleKey = (e: KeyboardEvent) => {
+      if (e.key === "m" && !e.ctrlKey && !e.metaKey && !e.altKey) addMarker();
+    };
+    window.addEventListener("keydown", handleKey);
+    return () => window.removeEventListener("keydown", handleKey);
+  }, [state, addMarker]);
+
   const formatTime = (seconds: number) => {
     const m = Math.floor(seconds / 60).toString().padStart(2, "0");
     const s = (seconds % 60).toString().padStart(2, "0");
@@ -413,15 +565,27 @@ function RecordingScreen({
               <span className="rec-label">{state === "paused" ? "PAUSED" : "REC"}</span>
             </div>
 
+            <canvas ref={livePreviewRef} className="recording-live-preview" />
             <div className="recording-timer">{formatTime(elapsed)}</div>
             <div className="recording-frame-count">{frameCount} frames</div>
+            {dropRate > DROP_RATE_WARNING && (
+              <div className="recording-frame-count">
+                ⚠ Dropping {Math.round(dropRate * 100)}% of frames — encoder can't keep up
+              </div>
+            )}
 
             <div className="recording-controls">
               <button className="control-btn" onClick={togglePause}>
                 {state === "paused" ? "▶ Resume" : "⏸ Pause"}
               </button>
+              <button className="control-btn" onClick={addMarker} title="Drop a chapter marker (M)">
+                ⚑ Marker
+              </button>
               <button className="control-btn stop" onClick={stopRecording} disabled={isStopping}>
-                {isStopping ? "Stopping..." : "■ Stop"}
+                {isStopping ? stopStage ?? "Stopping..." : "■ Stop"}
+              </button>
+              <button className="control-btn" onClick={discardRecording} disabled={isStopping}>
+                ✕ Discard
               </button>
             </div>
           </div>
@@ -498,7 +662,7 @@ function PostRecordingScreen({
       await invoke("start_export");
     } catch (err) {
       setStatus("error");
-      setMessage(`Error: ${err}`);
+      setMessage(`Error: ${errorMessage(err)}`);
     }
   };
 
@@ -581,6 +745,7 @@ interface Track {
 interface Keyframe {
   id: string;
   time: number;
+  generated?: boolean;
   [key: string]: unknown;
 }
 
@@ -592,23 +757,44 @@ interface ExportProgress {
   progress: number;
   etaSeconds: number;
   state: string;
+  sequence?: number;
+  timestampMs?: number;
 }
 
+/** Seconds without an export progress event before offering to cancel */
+const EXPORT_STALL_SECS = 15;
+
 interface MousePositionData {
   time: number;
   x: number;
   y: number;
 }
 
-function EditorScreen({ onBack }: { onBack: () => void }) {
+function EditorScreen({
+  onBack,
+  onAddTake,
+}: {
+  onBack: () => void;
+  onAddTake: (packagePath: string) => void;
+}) {
   const [playheadTime, setPlayheadTime] = useState(0);
   const [isPlaying, setIsPlaying] = useState(false);
   const [duration, setDuration] = useState(30);
   const [exportProgress, setExportProgress] = useState<ExportProgress | null>(null);
   const [isExporting, setIsExporting] = useState(false);
+  // When the last export progress event arrived, and whether the export looks stuck
+  const lastExportEventAt = useRef(0);
+  const [exportStalled, setExportStalled] = useState(false);
   const [isGenerating, setIsGenerating] = useState(false);
   const [timelineZoom, setTimelineZoom] = useState(1);
   const [mousePositions, setMousePositions] = useState<MousePositionData[]>([]);
+  const [missingAssets, setMissingAssets] = useState<{ kind: string; path: string; problem: string }[]>([]);
+  // "custom" when the project stores hand-tuned values
+  const [zoomPreset, setZoomPreset] = useState<string>("balanced");
+  const [cameraSpring, setCameraSpring] = useState<string>("default");
+  // Export preset applied on Export ("" keeps the project's output settings)
+  const [exportPresets, setExportPresets] = useState<{ id: string; name: string; format: string }[]>([]);
+  const [exportPresetId, setExportPresetId] = useState("");
   const [selectedKeyframe, setSelectedKeyframe] = useState<{
     trackType: string;
     keyframe: Keyframe;
@@ -694,6 +880,17 @@ function EditorScreen({ onBack }: { onBack: () => void }) {
       // Also load mouse data for cursor preview
       try {
         const { invoke } = await import("@tauri-apps/api/core");
+        const info = await invoke<{ missingAssets: { kind: string; path: string; problem: string }[]; recoveryAvailable: boolean } | null>("get_current_project");
+        setMissingAssets(info?.missingAssets ?? []);
+        if (info?.recoveryAvailable) {
+          const restore = window.confirm("This project has unsaved edits from a previous session. Restore them?");
+          await invoke("resolve_recovery", { restore });
+          if (restore) await loadTimelineFromBackend();
+        }
+        const zoom = await invoke<SmartZoomConfigData>("get_smart_zoom_settings");
+        setZoomPreset(zoom.type === "preset" && zoom.preset ? zoom.preset : "custom");
+        setCameraSpring(cameraSpringName(zoom.settings?.spring ?? null));
+        setExportPresets(await invoke<{ id: string; name: string; format: string }[]>("list_export_presets"));
         const positions = await invoke<MousePositionData[]>("load_mouse_data");
         if (positions && positions.length > 0) {
           setMousePositions(positions);
@@ -740,7 +937,11 @@ function EditorScreen({ onBack }: { onBack: () => void }) {
     (async () => {
       const { listen } = await import("@tauri-apps/api/event");
       unlistenProgress = await listen<ExportProgress>("export-progress", (event) => {
-        setExportProgress(event.payload);
+        lastExportEventAt.current = Date.now();
+        setExportStalled(false);
+        // Ignore events that arrive after a newer one
+        setExportProgress(prev =>
+          prev?.sequence && event.payload.sequence && event.payload.sequence < prev.sequence ? prev : event.payload);
       });
       unlistenComplete = await listen<string>("export-complete", (event) => {
         console.log("Export complete:", event.payload);
@@ -761,13 +962,58 @@ function EditorScreen({ onBack }: { onBack: () => void }) {
     };
   }, []);
 
-  const handleExport = async () => {
+  // Stall detection: the backend sends progress at least twice a second while rendering
+  useEffect(() => {
+    if (!isExporting) {
+      setExportStalled(false);
+      return;
+    }
+    lastExportEventAt.current = Date.now();
+    const timer = setInterval(() => {
+      setExportStalled(Date.now() - lastExportEventAt.current > EXPORT_STALL_SECS * 1000);
+    }, 1000);
+    return () => clearInterval(timer);
+  }, [isExporting]);
+
+  const handleCancelExport = async () => {
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      await invoke("cancel_export");
+    } catch (err) {
+      console.error("Failed to cancel export:", err);
+    }
+  };
+
+  /** Decode the finished export end to end and report anything missing or corrupt */
+  const handleVerifyExport = async () => {
+    const path = window.prompt("Path of the exported video to verify:");
+    if (!path) return;
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      const result = await invoke<{ frameCount: number; duration: number; problems: string[] }>(
+        "verify_export", { path });
+      alert(result.problems.length === 0
+        ? `Export verified: ${result.frameCount} frames, ${result.duration.toFixed(2)}s`
+        : `Export has problems:\n${result.problems.join("\n")}`);
+    } catch (err) {
+      alert(`Verify failed: ${errorMessage(err)}`);
+    }
+  };
+
+  /** Export a video, or numbered PNG frames when `imageSequence` is given */
+  const handleExport = async (imageSequence?: { directory: string | null; padding: number }) => {
     if (isExporting) return;
     setIsExporting(true);
     setExportProgress(null);
     try {
       const { invoke } = await import("@tauri-apps/api/core");
 
+      const estimate = await invoke<{ warnings: string[] }>("estimate_export");
+      if (estimate.warnings.length > 0 && !window.confirm(`${estimate.warnings.join("\n")}\n\nExport anyway?`)) {
+        setIsExporting(false);
+        return;
+      }
+
       // Auto-generate keyframes if timeline is empty (ensures zoom effects are applied)
       const timeline = await invoke<{ tracks: { keyframes: unknown[] }[] }>("get_timeline");
       const totalKfs = timeline.tracks.reduce((sum, t) => sum + t.keyframes.length, 0);
@@ -777,15 +1023,162 @@ function EditorScreen({ onBack }: { onBack: () => void }) {
         await loadTimelineFromBackend();
       }
 
-      await invoke("start_export");
+      await invoke("start_export", { imageSequence: imageSequence ?? null, presetId: exportPresetId || null });
       // Export runs in background — progress comes via events
     } catch (err) {
       console.error("Export failed to start:", err);
+      alert(`Export failed: ${errorMessage(err)}`);
       setExportProgress({ currentFrame: 0, totalFrames: 0, progress: 0, etaSeconds: 0, state: "failed" });
       setIsExporting(false);
     }
   };
 
+  const stepFrame = async (forward: boolean) => {
+    setIsPlaying(false);
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      const t = await invoke<number>("step_frame", { forward, currentTime: playheadTime });
+      setPlayheadTime(t);
+    } catch (err) {
+      console.error("Frame step failed:", err);
+    }
+  };
+
+  const handleAddTake = async () => {
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      const info = await invoke<{ packagePath: string } | null>("get_current_project");
+      if (info) onAddTake(info.packagePath);
+    } catch (err) {
+      console.error("Failed to start another take:", err);
+    }
+  };
+
+  const handleExportFrames = () => {
+    const directory = window.prompt("Folder for the PNG frames (leave empty to write next to the export):", "");
+    if (directory === null) return;
+    const padding = parseInt(window.prompt("Digits in frame numbers:", "5") ?? "5") || 5;
+    handleExport({ directory: directory.trim() || null, padding });
+  };
+
+  const handleExportCaptions = async () => {
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      const path = await invoke<string>("export_annotations", { format: "srt" });
+      alert(`Captions written to ${path}`);
+    } catch (err) {
+      console.error("Caption export failed:", err);
+      alert(`Caption export failed: ${errorMessage(err)}`);
+    }
+  };
+
+  const handleCutIdle = async () => {
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      const idle = await invoke<{ start: number; end: number }[]>("suggest_cuts", { inactivityThreshold: null });
+      if (idle.length === 0) {
+        alert("No idle spans found");
+        return;
+      }
+      const seconds = idle.reduce((sum, s) => sum + (s.end - s.start), 0);
+      const list = idle.map((s) => `${s.start.toFixed(1)}s – ${s.end.toFixed(1)}s`).join("\n");
+      if (!window.confirm(`Speed up ${idle.length} idle span(s), ${seconds.toFixed(1)}s total?\n\n${list}`)) return;
+      const duration = await invoke<number>("apply_idle_cuts", { segments: idle, action: { type: "speedUp", factor: 8 } });
+      await loadTimelineFromBackend();
+      alert(`Sped up ${idle.length} idle span(s); the export now runs ${duration.toFixed(1)}s`);
+    } catch (err) {
+      console.error("Idle detection failed:", err);
+      alert(`Idle detection failed: ${errorMessage(err)}`);
+    }
+  };
+
+  const handleRelinkVideo = async () => {
+    const newVideoPath = window.prompt("Path of the recording to use for this project:", "");
+    if (!newVideoPath?.trim()) return;
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      const current = await invoke<{ packagePath: string } | null>("get_current_project");
+      if (!current) return;
+      const info = await invoke<{ duration: number; missingAssets: { kind: string; path: string; problem: string }[] }>(
+        "relink_media", { packageDir: current.packagePath, newVideoPath: newVideoPath.trim() });
+      setMissingAssets(info.missingAssets);
+      setDuration(info.duration);
+      await loadTimelineFromBackend();
+    } catch (err) {
+      console.error("Re-link failed:", err);
+      alert(`Re-link failed: ${errorMessage(err)}`);
+    }
+  };
+
+  const handleCompactInput = async () => {
+    const rate = window.prompt("Keep at most this many mouse samples per second (turns and pauses are kept):", "30");
+    if (rate === null) return;
+    const targetHz = Number(rate);
+    if (!Number.isFinite(targetHz) || targetHz < 0) return;
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      const result = await invoke<{ positionsBefore: number; positionsAfter: number; bytesBefore: number; bytesAfter: number }>(
+        "compact_input_data", { targetHz, tolerance: null });
+      const kb = (bytes: number) => `${(bytes / 1024).toFixed(0)} KB`;
+      alert(`Mouse samples: ${result.positionsBefore} → ${result.positionsAfter}\nFile size: ${kb(result.bytesBefore)} → ${kb(result.bytesAfter)}`);
+    } catch (err) {
+      console.error("Input compaction failed:", err);
+      alert(`Compaction failed: ${errorMessage(err)}`);
+    }
+  };
+
+  const handleExportStyle = async () => {
+    const path = window.prompt("Save this project's effect style to (JSON file):", "style.json");
+    if (!path?.trim()) return;
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      await invoke("export_style", { path: path.trim(), name: null });
+    } catch (err) {
+      console.error("Style export failed:", err);
+      alert(`Style export failed: ${errorMessage(err)}`);
+    }
+  };
+
+  const handleImportStyle = async () => {
+    const path = window.prompt("Style file to apply to this project:", "");
+    if (!path?.trim()) return;
+    const regenerate = window.confirm("Regenerate keyframes with the new style? (Manual keyframes are kept.)");
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      const style = await invoke<{ smartZoom: SmartZoomConfigData | null }>("import_style", { path: path.trim(), regenerate });
+      if (style.smartZoom) {
+        setZoomPreset(style.smartZoom.type === "preset" && style.smartZoom.preset ? style.smartZoom.preset : "custom");
+      }
+      await loadTimelineFromBackend();
+    } catch (err) {
+      console.error("Style import failed:", err);
+      alert(`Style import failed: ${errorMessage(err)}`);
+    }
+  };
+
+  const handleCameraSpringChange = async (name: string) => {
+    setCameraSpring(name);
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      const zoom = await invoke<SmartZoomConfigData>("set_camera_spring", { spring: CAMERA_SPRINGS[name] ?? null });
+      setZoomPreset(zoom.type === "preset" && zoom.preset ? zoom.preset : "custom");
+      await handleGenerate();
+    } catch (err) {
+      console.error("Failed to set camera spring:", err);
+    }
+  };
+
+  const handleZoomPresetChange = async (preset: string) => {
+    setZoomPreset(preset);
+    setCameraSpring("default");
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      await invoke("set_smart_zoom_settings", { config: { type: "preset", preset } });
+    } catch (err) {
+      console.error("Failed to save zoom preset:", err);
+    }
+  };
+
   const handleGenerate = async () => {
     if (isGenerating) return;
     setIsGenerating(true);
@@ -797,7 +1190,7 @@ function EditorScreen({ onBack }: { onBack: () => void }) {
         keystrokeCount: number;
         cursorCount: number;
         total: number;
-      }>("generate_keyframes");
+      }>("generate_keyframes", { mode: { type: "merge" } });
 
       console.log("Generated keyframes:", result);
 
@@ -812,6 +1205,22 @@ function EditorScreen({ onBack }: { onBack: () => void }) {
     }
   };
 
+  /** Re-run one generator (e.g. after a cursor smoothing change), keeping the other tracks */
+  const handleRegenerateTrack = async (trackType: string) => {
+    if (isGenerating) return;
+    setIsGenerating(true);
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      await invoke<number>("regenerate_track", { trackType });
+      await loadTimelineFromBackend();
+      setSelectedKeyframe(null);
+    } catch (err) {
+      alert(`Failed to regenerate ${trackType} track: ${errorMessage(err)}`);
+    } finally {
+      setIsGenerating(false);
+    }
+  };
+
   const handleKeyframeSelect = (trackType: string, kf: Keyframe) => {
     setSelectedKeyframe({ trackType, keyframe: kf });
     setPlayheadTime(kf.time);
@@ -824,7 +1233,7 @@ function EditorScreen({ onBack }: { onBack: () => void }) {
       case "transform":
         return { id, time, zoom: 1.0, centerX: 0.5, centerY: 0.5, easing: "easeInOut" };
       case "ripple":
-        return { id, time, intensity: 0.8, rippleDuration: 0.4, color: "leftClick" };
+        return { id, time, intensity: 0.8, rippleDuration: 0.4, color: "leftClick", style: "ring" };
       case "keystroke":
         return { id, time, text: "Key", displayDuration: 1.5 };
       default:
@@ -904,12 +1313,18 @@ function EditorScreen({ onBack }: { onBack: () => void }) {
           <button className="transport-btn" onClick={() => setPlayheadTime(0)}>
             ⏮
           </button>
+          <button className="transport-btn" onClick={() => stepFrame(false)} title="Previous frame">
+            ◀|
+          </button>
           <button
             className="transport-btn play"
             onClick={() => setIsPlaying(!isPlaying)}
           >
             {isPlaying ? "⏸" : "▶"}
           </button>
+          <button className="transport-btn" onClick={() => stepFrame(true)} title="Next frame">
+            |▶
+          </button>
           <span className="time-display">
             {formatTimecode(playheadTime)} / {formatTimecode(duration)}
           </span>
@@ -920,17 +1335,110 @@ function EditorScreen({ onBack }: { onBack: () => void }) {
           >
             {isGenerating ? "Generating..." : "Generate"}
           </button>
+          <select
+            className="property-select"
+            value={zoomPreset}
+            onChange={(e) => handleZoomPresetChange(e.target.value)}
+            title="Auto-zoom style used by Generate"
+          >
+            <option value="subtle">Subtle</option>
+            <option value="balanced">Balanced</option>
+            <option value="dramatic">Dramatic</option>
+            {zoomPreset === "custom" && <option value="custom" disabled>Custom</option>}
+          </select>
+          <select
+            className="property-select"
+            value={cameraSpring}
+            onChange={(e) => handleCameraSpringChange(e.target.value)}
+            title="How the camera settles into zooms and pans (regenerates)"
+          >
+            <option value="default">Default motion</option>
+            <option value="critical">No bounce</option>
+            <option value="snappy">Snappy</option>
+            <option value="floaty">Floaty</option>
+            <option value="bouncy">Bouncy</option>
+            {cameraSpring === "custom" && <option value="custom" disabled>Custom</option>}
+          </select>
+          <select
+            className="property-select"
+            value={exportPresetId}
+            onChange={(e) => setExportPresetId(e.target.value)}
+            title="Output resolution, frame rate, codec and quality applied on export"
+          >
+            <option value="">Project settings</option>
+            {exportPresets.map((p) => (
+              <option key={p.id} value={p.id}>{p.name}</option>
+            ))}
+          </select>
           <button
             className="export-btn"
-            onClick={handleExport}
+            onClick={() => handleExport()}
             disabled={isExporting}
           >
             {isExporting ? "Exporting..." : "Export"}
           </button>
+          <button
+            className="generate-btn"
+            onClick={handleExportFrames}
+            disabled={isExporting}
+            title="Export every frame as a numbered PNG (keeps transparency)"
+          >
+            PNG Frames
+          </button>
+          <button
+            className="generate-btn"
+            onClick={handleExportCaptions}
+            title="Write an .srt caption file of clicks and keystrokes next to the export"
+          >
+            Captions
+          </button>
+          <button
+            className="generate-btn"
+            onClick={handleCutIdle}
+            title="Find spans with no mouse or keyboard activity and speed them up"
+          >
+            Cut Idle
+          </button>
+          <button
+            className="generate-btn"
+            onClick={handleCompactInput}
+            title="Thin recorded mouse samples to shrink the project (keeps clicks, keys and path shape)"
+          >
+            Compact Input
+          </button>
+          <button
+            className="generate-btn"
+            onClick={handleExportStyle}
+            title="Save zoom, ripple, keystroke and window styling to a file to reuse or share"
+          >
+            Export Style
+          </button>
+          <button
+            className="generate-btn"
+            onClick={handleImportStyle}
+            title="Apply a saved style file to this project"
+          >
+            Import Style
+          </button>
+          <button
+            className="generate-btn"
+            onClick={handleAddTake}
+            title="Record another take and append it to this project"
+          >
+            Add Take
+          </button>
         </div>
       </div>
 
       <div className="editor-body">
+        {missingAssets.length > 0 && (
+          <div className="update-banner">
+            ⚠ Missing from project: {missingAssets.map((a) => a.path).join(", ")}
+            {missingAssets.some((a) => a.kind === "video") && (
+              <button className="generate-btn" onClick={handleRelinkVideo}>Re-link Video</button>
+            )}
+          </div>
+        )}
         {exportProgress && (
           <div className="export-progress-bar">
             <div className="export-progress-fill" style={{ width: `${exportProgress.progress * 100}%` }} />
@@ -940,7 +1448,14 @@ function EditorScreen({ onBack }: { onBack: () => void }) {
                 : exportProgress.state === "failed"
                 ? "Export failed"
                 : `Exporting ${exportProgress.currentFrame}/${exportProgress.totalFrames} — ${Math.round(exportProgress.progress * 100)}%${exportProgress.etaSeconds > 0 ? ` (${Math.ceil(exportProgress.etaSeconds)}s left)` : ""}`}
+              {exportStalled && ` — no progress for ${EXPORT_STALL_SECS}s+`}
             </span>
+            {isExporting && (
+              <button className="generate-btn" onClick={handleCancelExport}>Cancel</button>
+            )}
+            {exportProgress.state === "completed" && (
+              <button className="generate-btn" onClick={handleVerifyExport}>Verify</button>
+            )}
           </div>
         )}
         <div className="editor-main">
@@ -987,6 +1502,7 @@ function EditorScreen({ onBack }: { onBack: () => void }) {
                     onMoveKeyframe={(kfId, newTime) => {
                       handleUpdateKeyframe(kfId, "time", newTime);
                     }}
+                    onRegenerate={() => handleRegenerateTrack(track.type)}
                   />
                 ))}
               </div>
@@ -1043,6 +1559,49 @@ function interpolateCursor(
   };
 }
 
+interface CameraSpringData {
+  dampingRatio: number;
+  response: number;
+}
+
+interface SmartZoomConfigData {
+  type: string;
+  preset?: string;
+  settings?: { spring?: CameraSpringData | null };
+}
+
+/** Camera motion choices; null keeps the preset's own easing */
+const CAMERA_SPRINGS: Record<string, CameraSpringData | null> = {
+  default: null,
+  critical: { dampingRatio: 1, response: 0.5 },
+  snappy: { dampingRatio: 1, response: 0.25 },
+  floaty: { dampingRatio: 1, response: 0.9 },
+  bouncy: { dampingRatio: 0.55, response: 0.45 },
+};
+
+function cameraSpringName(spring: CameraSpringData | null): string {
+  if (!spring) return "default";
+  const match = Object.entries(CAMERA_SPRINGS).find(
+    ([, s]) => s && s.dampingRatio === spring.dampingRatio && s.response === spring.response,
+  );
+  return match ? match[0] : "custom";
+}
+
+/** Editor-only guides drawn by extract_preview_frame */
+interface PreviewGuides {
+  thirds?: boolean;
+  safeAreas?: boolean;
+  cropAspect?: number;
+}
+
+const PREVIEW_GUIDES: Record<string, PreviewGuides | null> = {
+  none: null,
+  thirds: { thirds: true },
+  safe: { safeAreas: true },
+  vertical: { cropAspect: 9 / 16 },
+  square: { cropAspect: 1 },
+};
+
 function VideoPreview({
   playheadTime,
   duration,
@@ -1056,6 +1615,12 @@ function VideoPreview({
   const canvasRef = useRef<HTMLCanvasElement>(null);
   const lastFetchTime = useRef(-1);
   const fetchTimer = useRef<number | null>(null);
+  // Backend frames already include zoom and cursor; the simulated overlay is a fallback
+  const [hasRenderedFrame, setHasRenderedFrame] = useState(false);
+  const [guide, setGuide] = useState("none");
+
+  // Re-fetch the current frame when the guides change
+  useEffect(() => { lastFetchTime.current = -1; }, [guide]);
 
   // Throttled frame extraction — fetch at most every 100ms
   useEffect(() => {
@@ -1067,7 +1632,15 @@ function VideoPreview({
 
       try {
         const { invoke } = await import("@tauri-apps/api/core");
-        const frame = await invoke<FrameData>("extract_preview_frame", { time: playheadTime });
+        // Request only as many pixels as the canvas shows (HiDPI-aware)
+        const el = canvasRef.current;
+        const dpr = window.devicePixelRatio || 1;
+        const frame = await invoke<FrameData>("extract_preview_frame", {
+          time: playheadTime,
+          maxWidth: el && el.clientWidth > 0 ? Math.round(el.clientWidth * dpr) : null,
+          maxHeight: el && el.clientHeight > 0 ? Math.round(el.clientHeight * dpr) : null,
+          guides: PREVIEW_GUIDES[guide],
+        });
         const canvas = canvasRef.current;
         if (!canvas || !frame) return;
 
@@ -1084,12 +1657,13 @@ function VideoPreview({
         }
         const imageData = new ImageData(bytes, frame.width, frame.height);
         ctx.putImageData(imageData, 0, 0);
+        setHasRenderedFrame(true);
       } catch {
         // Silently fall back to simulated preview
       }
     }, 100);
     return () => { if (fetchTimer.current) clearTimeout(fetchTimer.current); };
-  }, [playheadTime]);
+  }, [playheadTime, guide]);
 
   const cursor = interpolateCursor(mousePositions, playheadTime, progress);
 
@@ -1098,7 +1672,8 @@ function VideoPreview({
       <div className="video-preview">
         <div className="preview-canvas">
           <canvas ref={canvasRef} className="preview-canvas-element" />
-          {/* Overlay: viewport indicator and cursor */}
+          {/* Overlay: simulated viewport indicator and cursor (no backend frame) */}
+          {!hasRenderedFrame && (<>
           <div
             className="viewport-indicator"
             style={{
@@ -1112,8 +1687,17 @@ function VideoPreview({
             left: `${cursor.x}%`,
             top: `${cursor.y}%`,
           }} />
+          </>)}
         </div>
         <div className="preview-overlay">
+          <select className="preview-guides" value={guide} title="Preview guides (not exported)"
+            onChange={(e) => setGuide(e.target.value)}>
+            <option value="none">No Guides</option>
+            <option value="thirds">Thirds</option>
+            <option value="safe">Safe Areas</option>
+            <option value="vertical">9:16 Crop</option>
+            <option value="square">1:1 Crop</option>
+          </select>
           <span className="preview-time">{formatTimecode(playheadTime)}</span>
         </div>
       </div>
@@ -1218,6 +1802,9 @@ function KeyframeProperties({
           <PropertyRow label="Color" value={String(keyframe.color)} type="select"
             options={["leftClick", "rightClick", "middleClick"]}
             onChange={(v) => update("color", v)} />
+          <PropertyRow label="Style" value={String(keyframe.style ?? "ring")} type="select"
+            options={["ring", "filled", "doubleRing", "pulse"]}
+            onChange={(v) => update("style", v)} />
         </>
       )}
 
@@ -1307,6 +1894,7 @@ function PropertyRow({
 
 interface RenderSettingsData {
   outputResolution: { type: string; width?: number; height?: number };
+  aspectPolicy?: "stretch" | "fit" | "fill";
   outputFrameRate: { type: string; fps?: number };
   codec: string;
   quality: string;
@@ -1316,6 +1904,103 @@ interface RenderSettingsData {
   shadowOpacity: number;
   padding: number;
   windowInset: number;
+  background?: BackgroundData;
+  preferredEncoder?: string | null;
+  encoderThreads?: number;
+  encoderPreset?: string | null;
+  keystrokeStyle?: KeystrokeStyleData;
+  frameInterpolation?: "hold" | "blend";
+  clickSfx?: boolean;
+  clickSfxVolume?: number;
+  cursorTrail?: { enabled: boolean; length: number; fade: number; color: RgbaColorData };
+  cursorTheme?: CursorThemeData;
+  cornerMask?: boolean;
+  hideCursorWhileTyping?: boolean;
+  overlayOnly?: boolean;
+  bakeCursorOnly?: boolean;
+  shadowColor?: RgbaColorData;
+  shadowLayers?: number;
+  watermark?: WatermarkData | null;
+  keyframeInterval?: number;
+  closedGop?: boolean;
+  fullRange?: boolean;
+  bitDepth?: "eight" | "ten";
+  writeChapters?: boolean;
+  cropToWindow?: boolean;
+  vignetteStrength?: number;
+  rippleIntensityScale?: number;
+  rippleSizeScale?: number;
+  zoomScale?: number;
+  cursorScale?: number;
+  keystrokeOpacityScale?: number;
+  mouseSmoothing?: { enabled: boolean; minCutoff: number; beta: number };
+  transformQuality?: "auto" | "fast" | "bilinear" | "bicubic";
+}
+
+interface WatermarkData {
+  relativePath: string;
+  corner: "topLeft" | "topRight" | "bottomLeft" | "bottomRight";
+  scale: number;
+  opacity: number;
+}
+
+const DEFAULT_WATERMARK: WatermarkData = { relativePath: "", corner: "bottomRight", scale: 0.12, opacity: 0.8 };
+
+type OverlayPositionData =
+  | { type: "bottomCenter" | "topCenter" | "topLeft" | "topRight" | "bottomLeft" | "bottomRight" }
+  | { type: "custom"; x: number; y: number };
+
+interface KeystrokeStyleData {
+  position: OverlayPositionData;
+  backgroundColor: RgbaColorData;
+  textColor: RgbaColorData;
+  opacity: number;
+}
+
+const DEFAULT_KEYSTROKE_STYLE: KeystrokeStyleData = {
+  position: { type: "bottomCenter" },
+  backgroundColor: { r: 30 / 255, g: 30 / 255, b: 30 / 255, a: 0.75 },
+  textColor: { r: 1, g: 1, b: 1, a: 230 / 255 },
+  opacity: 1,
+};
+
+const DEFAULT_MOUSE_SMOOTHING = { enabled: false, minCutoff: 1, beta: 10 };
+
+const DEFAULT_CURSOR_TRAIL = { enabled: false, length: 0.15, fade: 0.5, color: { r: 1, g: 1, b: 1, a: 1 } };
+
+interface EncoderInfoData {
+  name: string;
+  label: string;
+  codec: string;
+  hardware: boolean;
+  available: boolean;
+  presets: string[];
+}
+
+interface RgbaColorData { r: number; g: number; b: number; a: number }
+
+type BackgroundData =
+  | { type: "solid"; color: RgbaColorData }
+  | { type: "gradient"; start: RgbaColorData; end: RgbaColorData; angle: number }
+  | { type: "image"; relativePath: string }
+  | { type: "blurredSource"; radius: number };
+
+type CursorThemeData =
+  | { type: "dot" }
+  | { type: "system" }
+  | { type: "largeYellow" }
+  | { type: "custom"; relativePath: string };
+
+const DEFAULT_BACKGROUND_COLOR: RgbaColorData = { r: 0.11, g: 0.11, b: 0.13, a: 1 };
+
+function colorToHex(c: RgbaColorData): string {
+  const h = (v: number) => Math.round(Math.max(0, Math.min(1, v)) * 255).toString(16).padStart(2, "0");
+  return `#${h(c.r)}${h(c.g)}${h(c.b)}`;
+}
+
+function hexToColor(hex: string): RgbaColorData {
+  const v = (i: number) => parseInt(hex.slice(i, i + 2), 16) / 255;
+  return { r: v(1), g: v(3), b: v(5), a: 1 };
 }
 
 const RESOLUTION_OPTIONS = [
@@ -1329,6 +2014,8 @@ const RESOLUTION_OPTIONS = [
 const CODEC_OPTIONS = [
   { label: "H.265 (HEVC)", value: "h265" },
   { label: "H.264", value: "h264" },
+  { label: "AV1", value: "av1" },
+  { label: "VP9 (WebM)", value: "vp9" },
 ];
 
 const QUALITY_OPTIONS = [
@@ -1346,6 +2033,11 @@ const FRAMERATE_OPTIONS = [
 
 function RenderSettingsPanel() {
   const [settings, setSettings] = useState<RenderSettingsData | null>(null);
+  const [encoders, setEncoders] = useState<EncoderInfoData[]>([]);
+  // Input-vs-video sync offset, in milliseconds for editing
+  const [inputOffsetMs, setInputOffsetMs] = useState(0);
+  // Seconds of setup input ignored at the start
+  const [warmupSecs, setWarmupSecs] = useState(0);
 
   useEffect(() => {
     (async () => {
@@ -1353,6 +2045,10 @@ function RenderSettingsPanel() {
         const { invoke } = await import("@tauri-apps/api/core");
         const s = await invoke<RenderSettingsData>("get_render_settings");
         setSettings(s);
+        setEncoders(await invoke<EncoderInfoData[]>("list_encoders"));
+        const info = await invoke<{ inputTimeOffset: number; warmupSecs: number } | null>("get_current_project");
+        setInputOffsetMs(Math.round((info?.inputTimeOffset ?? 0) * 1000));
+        setWarmupSecs(info?.warmupSecs ?? 0);
       } catch {
         // No project loaded
       }
@@ -1369,6 +2065,25 @@ function RenderSettingsPanel() {
     }
   };
 
+  const saveInputOffset = async (ms: number) => {
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      const stored = await invoke<number>("set_input_time_offset", { offset: ms / 1000 });
+      setInputOffsetMs(Math.round(stored * 1000));
+    } catch (err) {
+      console.error("Failed to save input offset:", err);
+    }
+  };
+
+  const saveWarmup = async (secs: number, trimVideo: boolean) => {
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      setWarmupSecs(await invoke<number>("set_input_warmup", { secs, trimVideo }));
+    } catch (err) {
+      console.error("Failed to save warmup:", err);
+    }
+  };
+
   if (!settings) {
     return (
       <div className="render-settings">
@@ -1381,6 +2096,10 @@ function RenderSettingsPanel() {
   const frameRateValue = settings.outputFrameRate.type === "fixed"
     ? String(settings.outputFrameRate.fps ?? 60)
     : "original";
+  const background: BackgroundData = settings.background ?? { type: "solid", color: DEFAULT_BACKGROUND_COLOR };
+  const keystrokeStyle = settings.keystrokeStyle ?? DEFAULT_KEYSTROKE_STYLE;
+  const saveKeystrokeStyle = (style: Partial<KeystrokeStyleData>) =>
+    saveSettings({ ...settings, keystrokeStyle: { ...keystrokeStyle, ...style } });
 
   return (
     <div className="render-settings">
@@ -1395,6 +2114,15 @@ function RenderSettingsPanel() {
           {RESOLUTION_OPTIONS.map(o => <option key={o.value} value={o.value}>{o.label}</option>)}
         </select>
       </div>
+      <div className="property-row">
+        <span className="property-label">Aspect</span>
+        <select className="property-select" value={settings.aspectPolicy ?? "fit"}
+          onChange={(e) => saveSettings({ ...settings, aspectPolicy: e.target.value as RenderSettingsData["aspectPolicy"] })}>
+          <option value="fit">Fit (letterbox)</option>
+          <option value="fill">Fill (crop)</option>
+          <option value="stretch">Stretch</option>
+        </select>
+      </div>
       <div className="property-row">
         <span className="property-label">Codec</span>
         <select className="property-select" value={settings.codec}
@@ -1402,6 +2130,52 @@ function RenderSettingsPanel() {
           {CODEC_OPTIONS.map(o => <option key={o.value} value={o.value}>{o.label}</option>)}
         </select>
       </div>
+      <div className="property-row">
+        <span className="property-label">Overlay Only</span>
+        <input type="checkbox" checked={settings.overlayOnly ?? false}
+          title="Export just the cursor, ripples and keystrokes on a transparent background (VP9/WebM)"
+          onChange={(e) => saveSettings({ ...settings, overlayOnly: e.target.checked })} />
+      </div>
+      <div className="property-row">
+        <span className="property-label">Cursor Only</span>
+        <input type="checkbox" checked={settings.bakeCursorOnly ?? false}
+          title="Export the plain recording with just the smooth cursor drawn in: no zoom, ripples, keystrokes or background"
+          onChange={(e) => saveSettings({ ...settings, bakeCursorOnly: e.target.checked })} />
+      </div>
+      <div className="property-row">
+        <span className="property-label">Encoder</span>
+        <select className="property-select" value={settings.preferredEncoder ?? ""}
+          onChange={(e) => saveSettings({ ...settings, preferredEncoder: e.target.value || null, encoderPreset: null })}>
+          <option value="">Auto</option>
+          {encoders.filter(enc => enc.codec === settings.codec).map(enc => (
+            <option key={enc.name} value={enc.name} disabled={!enc.available}>
+              {enc.label}{enc.available ? "" : " (unavailable)"}
+            </option>
+          ))}
+        </select>
+      </div>
+      {(() => {
+        // Presets are per encoder, so they need one picked explicitly
+        const presets = encoders.find(enc => enc.name === settings.preferredEncoder)?.presets ?? [];
+        return presets.length > 0 && (
+          <div className="property-row">
+            <span className="property-label">Preset</span>
+            <select className="property-select" value={settings.encoderPreset ?? ""}
+              title="Listed fastest first: slower presets make smaller files"
+              onChange={(e) => saveSettings({ ...settings, encoderPreset: e.target.value || null })}>
+              <option value="">Default</option>
+              {presets.map(p => <option key={p} value={p}>{p}</option>)}
+            </select>
+          </div>
+        );
+      })()}
+      <div className="property-row">
+        <span className="property-label">Threads</span>
+        <input className="property-input" type="number" min={0} max={64}
+          value={settings.encoderThreads ?? 0}
+          title="Software encoder threads (0 = all cores)"
+          onChange={(e) => saveSettings({ ...settings, encoderThreads: Math.max(0, parseInt(e.target.value) || 0) })} />
+      </div>
       <div className="property-row">
         <span className="property-label">Quality</span>
         <select className="property-select" value={settings.quality}
@@ -1409,6 +2183,38 @@ function RenderSettingsPanel() {
           {QUALITY_OPTIONS.map(o => <option key={o.value} value={o.value}>{o.label}</option>)}
         </select>
       </div>
+      <div className="property-row">
+        <span className="property-label">Keyframe Interval</span>
+        <input className="property-input" type="number" step={10} min={1} max={600}
+          value={settings.keyframeInterval ?? 120}
+          title="Frames between keyframes: shorter seeks faster in editors, longer makes smaller files"
+          onChange={(e) => saveSettings({ ...settings, keyframeInterval: parseInt(e.target.value) || 120 })} />
+      </div>
+      <div className="property-row">
+        <span className="property-label">Closed GOP</span>
+        <input type="checkbox" checked={settings.closedGop ?? false}
+          title="Fixed, self-contained keyframe groups so every keyframe is a clean cut point"
+          onChange={(e) => saveSettings({ ...settings, closedGop: e.target.checked })} />
+      </div>
+      <div className="property-row">
+        <span className="property-label">Full Range</span>
+        <input type="checkbox" checked={settings.fullRange ?? false}
+          title="Full-range (0-255) color. Leave off unless your player or editor expects it"
+          onChange={(e) => saveSettings({ ...settings, fullRange: e.target.checked })} />
+      </div>
+      <div className="property-row">
+        <span className="property-label">10-bit</span>
+        <input type="checkbox" checked={settings.bitDepth === "ten"}
+          disabled={settings.codec === "h264"}
+          title="10-bit color reduces banding in gradients (H.265, AV1 and VP9 only)"
+          onChange={(e) => saveSettings({ ...settings, bitDepth: e.target.checked ? "ten" : "eight" })} />
+      </div>
+      <div className="property-row">
+        <span className="property-label">Chapters</span>
+        <input type="checkbox" checked={settings.writeChapters ?? false}
+          title="Write timeline markers into the video as chapters"
+          onChange={(e) => saveSettings({ ...settings, writeChapters: e.target.checked })} />
+      </div>
       <div className="property-row">
         <span className="property-label">Frame Rate</span>
         <select className="property-select" value={frameRateValue}
@@ -1431,18 +2237,320 @@ function RenderSettingsPanel() {
         <input type="checkbox" checked={settings.backgroundEnabled}
           onChange={(e) => saveSettings({ ...settings, backgroundEnabled: e.target.checked })} />
       </div>
+      <div className="property-row">
+        <span className="property-label">Crop to Window</span>
+        <input type="checkbox" checked={settings.cropToWindow ?? true}
+          title="Frame just the recorded window when the capture covered the whole display"
+          onChange={(e) => saveSettings({ ...settings, cropToWindow: e.target.checked })} />
+      </div>
+      <div className="property-row">
+        <span className="property-label">Fill</span>
+        <select className="property-select" value={background.type}
+          onChange={(e) => {
+            const t = e.target.value;
+            saveSettings({
+              ...settings,
+              background: t === "gradient"
+                ? { type: "gradient", start: DEFAULT_BACKGROUND_COLOR, end: { r: 0.3, g: 0.2, b: 0.5, a: 1 }, angle: 45 }
+                : t === "image"
+                  ? { type: "image", relativePath: "" }
+                  : t === "blurredSource"
+                    ? { type: "blurredSource", radius: 40 }
+                    : { type: "solid", color: DEFAULT_BACKGROUND_COLOR },
+            });
+          }}>
+          <option value="solid">Solid</option>
+          <option value="gradient">Gradient</option>
+          <option value="image">Image</option>
+          <option value="blurredSource">Blurred Desktop</option>
+        </select>
+      </div>
+      {background.type === "solid" && (
+        <div className="property-row">
+          <span className="property-label">Color</span>
+          <input type="color" value={colorToHex(background.color)}
+            onChange={(e) => saveSettings({ ...settings, background: { ...background, color: hexToColor(e.target.value) } })} />
+        </div>
+      )}
+      {background.type === "gradient" && (
+        <>
+          <div className="property-row">
+            <span className="property-label">From / To</span>
+            <input type="color" value={colorToHex(background.start)}
+              onChange={(e) => saveSettings({ ...settings, background: { ...background, start: hexToColor(e.target.value) } })} />
+            <input type="color" value={colorToHex(background.end)}
+              onChange={(e) => saveSettings({ ...settings, background: { ...background, end: hexToColor(e.target.value) } })} />
+          </div>
+          <div className="property-row">
+            <span className="property-label">Angle</span>
+            <input className="property-input" type="number" step={15} min={0} max={360}
+              value={background.angle}
+              onChange={(e) => saveSettings({ ...settings, background: { ...background, angle: parseFloat(e.target.value) || 0 } })} />
+          </div>
+        </>
+      )}
+      {background.type === "image" && (
+        <div className="property-row">
+          <span className="property-label">Image Path</span>
+          <input className="property-input" type="text" value={background.relativePath}
+            onChange={(e) => saveSettings({ ...settings, background: { ...background, relativePath: e.target.value } })} />
+        </div>
+      )}
+      {background.type === "blurredSource" && (
+        <div className="property-row">
+          <span className="property-label">Blur Radius</span>
+          <input className="property-input" type="number" step={5} min={0} max={200}
+            value={background.radius}
+            onChange={(e) => saveSettings({ ...settings, background: { ...background, radius: parseFloat(e.target.value) || 0 } })} />
+        </div>
+      )}
       <div className="property-row">
         <span className="property-label">Corner Radius</span>
         <input className="property-input" type="number" step={1} min={0} max={100}
           value={settings.cornerRadius}
           onChange={(e) => saveSettings({ ...settings, cornerRadius: parseFloat(e.target.value) || 0 })} />
       </div>
+      <div className="property-row">
+        <span className="property-label">Round Corners</span>
+        <input type="checkbox" checked={settings.cornerMask ?? false}
+          title="Without a background: cut the video corners to transparent black"
+          onChange={(e) => saveSettings({ ...settings, cornerMask: e.target.checked })} />
+      </div>
+      <div className="property-row">
+        <span className="property-label">Zoom Vignette</span>
+        <input className="property-input" type="number" step={0.1} min={0} max={1}
+          value={settings.vignetteStrength ?? 0}
+          title="Darken the frame edges while zoomed in (0 = off)"
+          onChange={(e) => saveSettings({ ...settings, vignetteStrength: Math.min(1, Math.max(0, parseFloat(e.target.value) || 0)) })} />
+      </div>
       <div className="property-row">
         <span className="property-label">Shadow Opacity</span>
         <input className="property-input" type="number" step={0.1} min={0} max={1}
           value={settings.shadowOpacity}
           onChange={(e) => saveSettings({ ...settings, shadowOpacity: parseFloat(e.target.value) || 0 })} />
       </div>
+      <div className="property-row">
+        <span className="property-label">Shadow Color / Softness</span>
+        <input type="color" value={colorToHex(settings.shadowColor ?? { r: 0, g: 0, b: 0, a: 1 })}
+          onChange={(e) => saveSettings({ ...settings, shadowColor: hexToColor(e.target.value) })} />
+        <input className="property-input" type="number" step={1} min={1} max={6}
+          value={settings.shadowLayers ?? 3}
+          title="Stacked shadow layers: more gives a softer, more natural falloff"
+          onChange={(e) => saveSettings({ ...settings, shadowLayers: parseInt(e.target.value) || 1 })} />
+      </div>
+      <div className="property-row">
+        <span className="property-label">Zoom Quality</span>
+        <select className="property-select" value={settings.transformQuality ?? "auto"}
+          title="Resampling for zoomed content: sharper modes render slower"
+          onChange={(e) => saveSettings({ ...settings, transformQuality: e.target.value as RenderSettingsData["transformQuality"] })}>
+          <option value="auto">Auto</option>
+          <option value="fast">Fast (nearest)</option>
+          <option value="bilinear">Bilinear</option>
+          <option value="bicubic">Bicubic</option>
+        </select>
+      </div>
+      <div className="property-row">
+        <span className="property-label">Slow Motion</span>
+        <select className="property-select" value={settings.frameInterpolation ?? "hold"}
+          onChange={(e) => saveSettings({ ...settings, frameInterpolation: e.target.value as "hold" | "blend" })}>
+          <option value="hold">Repeat frames</option>
+          <option value="blend">Blend frames</option>
+        </select>
+      </div>
+
+      <label className="section-label">Cursor</label>
+      <div className="property-row">
+        <span className="property-label">Theme</span>
+        <select className="property-select" value={settings.cursorTheme?.type ?? "dot"}
+          onChange={(e) => {
+            const t = e.target.value;
+            saveSettings({
+              ...settings,
+              cursorTheme: t === "custom"
+                ? { type: "custom", relativePath: "" }
+                : { type: t as "dot" | "system" | "largeYellow" },
+            });
+          }}>
+          <option value="dot">Dot</option>
+          <option value="system">System</option>
+          <option value="largeYellow">Large Yellow</option>
+          <option value="custom">Custom Folder</option>
+        </select>
+      </div>
+      {settings.cursorTheme?.type === "custom" && (
+        <div className="property-row">
+          <span className="property-label">Theme Folder</span>
+          <input className="property-input" type="text" value={settings.cursorTheme.relativePath}
+            title="Folder of arrow.png, pointer.png, ibeam.png… with an optional theme.json of hotspots"
+            onChange={(e) => saveSettings({ ...settings, cursorTheme: { type: "custom", relativePath: e.target.value } })} />
+        </div>
+      )}
+      <div className="property-row">
+        <span className="property-label">Motion Trail</span>
+        <input type="checkbox" checked={settings.cursorTrail?.enabled ?? false}
+          onChange={(e) => saveSettings({ ...settings, cursorTrail: { ...DEFAULT_CURSOR_TRAIL, ...settings.cursorTrail, enabled: e.target.checked } })} />
+      </div>
+      {settings.cursorTrail?.enabled && (
+        <div className="property-row">
+          <span className="property-label">Length / Fade</span>
+          <input className="property-input" type="number" step={0.05} min={0.05} max={1}
+            value={settings.cursorTrail.length}
+            onChange={(e) => saveSettings({ ...settings, cursorTrail: { ...settings.cursorTrail!, length: parseFloat(e.target.value) || 0.15 } })} />
+          <input className="property-input" type="number" step={0.1} min={0} max={1}
+            value={settings.cursorTrail.fade}
+            onChange={(e) => saveSettings({ ...settings, cursorTrail: { ...settings.cursorTrail!, fade: parseFloat(e.target.value) || 0 } })} />
+          <input type="color" value={colorToHex(settings.cursorTrail.color)}
+            onChange={(e) => saveSettings({ ...settings, cursorTrail: { ...settings.cursorTrail!, color: hexToColor(e.target.value) } })} />
+        </div>
+      )}
+      <div className="property-row">
+        <span className="property-label">Smooth Path</span>
+        <input type="checkbox" checked={settings.mouseSmoothing?.enabled ?? false}
+          title="Remove jitter from slow cursor movement (one-euro filter)"
+          onChange={(e) => saveSettings({ ...settings, mouseSmoothing: { ...DEFAULT_MOUSE_SMOOTHING, ...settings.mouseSmoothing, enabled: e.target.checked } })} />
+      </div>
+      {settings.mouseSmoothing?.enabled && (
+        <div className="property-row">
+          <span className="property-label">Cutoff / Responsiveness</span>
+          <input className="property-input" type="number" step={0.1} min={0.1} max={10}
+            value={settings.mouseSmoothing.minCutoff}
+            title="Lower smooths more but lags behind slow movement (Hz)"
+            onChange={(e) => saveSettings({ ...settings, mouseSmoothing: { ...settings.mouseSmoothing!, minCutoff: parseFloat(e.target.value) || 1 } })} />
+          <input className="property-input" type="number" step={1} min={0} max={100}
+            value={settings.mouseSmoothing.beta}
+            title="Higher keeps fast moves from lagging"
+            onChange={(e) => saveSettings({ ...settings, mouseSmoothing: { ...settings.mouseSmoothing!, beta: parseFloat(e.target.value) || 0 } })} />
+        </div>
+      )}
+      <div className="property-row">
+        <span className="property-label">Hide While Typing</span>
+        <input type="checkbox" checked={settings.hideCursorWhileTyping ?? false}
+          title="Applies the next time keyframes are generated"
+          onChange={(e) => saveSettings({ ...settings, hideCursorWhileTyping: e.target.checked })} />
+      </div>
+      <div className="property-row">
+        <span className="property-label">Input Offset (ms)</span>
+        <input className="property-input" type="number" step={10} min={-2000} max={2000}
+          value={inputOffsetMs}
+          title="Shift the cursor, clicks and keys relative to the video (negative = earlier)"
+          onChange={(e) => setInputOffsetMs(parseInt(e.target.value) || 0)}
+          onBlur={() => saveInputOffset(inputOffsetMs)} />
+      </div>
+      <div className="property-row">
+        <span className="property-label">Quiet Start (s)</span>
+        <input className="property-input" type="number" step={0.1} min={0} max={10}
+          value={warmupSecs}
+          title="Ignore mouse and keys for this long after recording starts (applies on the next generate)"
+          onChange={(e) => setWarmupSecs(parseFloat(e.target.value) || 0)}
+          onBlur={() => saveWarmup(warmupSecs, false)} />
+        <button className="generate-btn" disabled={warmupSecs <= 0}
+          title="Also trim the video to start after the quiet start"
+          onClick={() => saveWarmup(warmupSecs, true)}>Trim</button>
+      </div>
+      <label className="section-label">Effect Strength</label>
+      {([
+        ["Zoom", "zoomScale", "Scales how far zooms go past 1x"],
+        ["Ripples", "rippleIntensityScale", "0 turns click ripples off"],
+        ["Ripple Size", "rippleSizeScale", "Scales how far click ripples grow"],
+        ["Cursor Size", "cursorScale", "Scales the drawn cursor"],
+        ["Keystrokes", "keystrokeOpacityScale", "Scales keystroke overlay opacity"],
+      ] as const).map(([label, key, title]) => (
+        <div className="property-row" key={key}>
+          <span className="property-label">{label}</span>
+          <input className="property-input" type="number" step={0.1} min={0} max={4}
+            value={settings[key] ?? 1} title={title}
+            onChange={(e) => saveSettings({ ...settings, [key]: Math.max(0, parseFloat(e.target.value) || 0) })} />
+        </div>
+      ))}
+      <label className="section-label">Sound</label>
+      <div className="property-row">
+        <span className="property-label">Click Sounds</span>
+        <input type="checkbox" checked={settings.clickSfx ?? false}
+          onChange={(e) => saveSettings({ ...settings, clickSfx: e.target.checked })} />
+      </div>
+      <div className="property-row">
+        <span className="property-label">Volume</span>
+        <input className="property-input" type="number" step={0.1} min={0} max={1}
+          value={settings.clickSfxVolume ?? 0.5}
+          onChange={(e) => saveSettings({ ...settings, clickSfxVolume: parseFloat(e.target.value) || 0 })} />
+      </div>
+      <label className="section-label">Keystrokes</label>
+      <div className="property-row">
+        <span className="property-label">Position</span>
+        <select className="property-select" value={keystrokeStyle.position.type}
+          onChange={(e) => saveKeystrokeStyle({
+            position: e.target.value === "custom"
+              ? { type: "custom", x: 0.5, y: 0.95 }
+              : { type: e.target.value as Exclude<OverlayPositionData["type"], "custom"> },
+          })}>
+          <option value="bottomCenter">Bottom Center</option>
+          <option value="topCenter">Top Center</option>
+          <option value="topLeft">Top Left</option>
+          <option value="topRight">Top Right</option>
+          <option value="bottomLeft">Bottom Left</option>
+          <option value="bottomRight">Bottom Right</option>
+          <option value="custom">Custom</option>
+        </select>
+      </div>
+      {keystrokeStyle.position.type === "custom" && (
+        <div className="property-row">
+          <span className="property-label">X / Y</span>
+          <input className="property-input" type="number" step={0.05} min={0} max={1}
+            value={keystrokeStyle.position.x}
+            onChange={(e) => saveKeystrokeStyle({ position: { ...keystrokeStyle.position, type: "custom", x: parseFloat(e.target.value) || 0 } as OverlayPositionData })} />
+          <input className="property-input" type="number" step={0.05} min={0} max={1}
+            value={keystrokeStyle.position.y}
+            onChange={(e) => saveKeystrokeStyle({ position: { ...keystrokeStyle.position, type: "custom", y: parseFloat(e.target.value) || 0 } as OverlayPositionData })} />
+        </div>
+      )}
+      <div className="property-row">
+        <span className="property-label">Pill / Text</span>
+        <input type="color" value={colorToHex(keystrokeStyle.backgroundColor)}
+          onChange={(e) => saveKeystrokeStyle({ backgroundColor: { ...hexToColor(e.target.value), a: keystrokeStyle.backgroundColor.a } })} />
+        <input type="color" value={colorToHex(keystrokeStyle.textColor)}
+          onChange={(e) => saveKeystrokeStyle({ textColor: { ...hexToColor(e.target.value), a: keystrokeStyle.textColor.a } })} />
+      </div>
+      <div className="property-row">
+        <span className="property-label">Opacity</span>
+        <input className="property-input" type="number" step={0.1} min={0} max={1}
+          value={keystrokeStyle.opacity}
+          onChange={(e) => saveKeystrokeStyle({ opacity: parseFloat(e.target.value) || 0 })} />
+      </div>
+      <label className="section-label">Watermark</label>
+      <div className="property-row">
+        <span className="property-label">Enabled</span>
+        <input type="checkbox" checked={!!settings.watermark}
+          onChange={(e) => saveSettings({ ...settings, watermark: e.target.checked ? DEFAULT_WATERMARK : null })} />
+      </div>
+      {settings.watermark && (
+        <>
+          <div className="property-row">
+            <span className="property-label">Image Path</span>
+            <input className="property-input" type="text" value={settings.watermark.relativePath}
+              onChange={(e) => saveSettings({ ...settings, watermark: { ...settings.watermark!, relativePath: e.target.value } })} />
+          </div>
+          <div className="property-row">
+            <span className="property-label">Corner</span>
+            <select className="property-select" value={settings.watermark.corner}
+              onChange={(e) => saveSettings({ ...settings, watermark: { ...settings.watermark!, corner: e.target.value as WatermarkData["corner"] } })}>
+              <option value="topLeft">Top Left</option>
+              <option value="topRight">Top Right</option>
+              <option value="bottomLeft">Bottom Left</option>
+              <option value="bottomRight">Bottom Right</option>
+            </select>
+          </div>
+          <div className="property-row">
+            <span className="property-label">Size / Opacity</span>
+            <input className="property-input" type="number" step={0.01} min={0.01} max={1}
+              value={settings.watermark.scale}
+              title="Logo width as a fraction of the video width"
+              onChange={(e) => saveSettings({ ...settings, watermark: { ...settings.watermark!, scale: parseFloat(e.target.value) || 0.12 } })} />
+            <input className="property-input" type="number" step={0.1} min={0} max={1}
+              value={settings.watermark.opacity}
+              onChange={(e) => saveSettings({ ...settings, watermark: { ...settings.watermark!, opacity: parseFloat(e.target.value) || 0 } })} />
+          </div>
+        </>
+      )}
     </div>
   );
 }
@@ -1508,6 +2616,7 @@ function TimelineTrack({
   onSelectKeyframe,
   onAddKeyframe,
   onMoveKeyframe,
+  onRegenerate,
 }: {
   track: Track;
   duration: number;
@@ -1516,6 +2625,7 @@ function TimelineTrack({
   onSelectKeyframe?: (kf: Keyframe) => void;
   onAddKeyframe?: () => void;
   onMoveKeyframe?: (keyframeId: string, newTime: number) => void;
+  onRegenerate?: () => void;
 }) {
   const color = TRACK_COLORS[track.type] || "#888";
   const laneRef = useRef<HTMLDivElement>(null);
@@ -1548,6 +2658,11 @@ function TimelineTrack({
     <div className="timeline-track">
       <div className="track-label" style={{ borderLeftColor: color }}>
         {track.name}
+        {onRegenerate && (
+          <button className="track-regenerate-btn" onClick={onRegenerate} title="Regenerate this track only">
+            ↻
+          </button>
+        )}
       </div>
       <div className="track-lane" ref={laneRef} onDoubleClick={() => onAddKeyframe?.()}>
         {track.keyframes.map(kf => (
@@ -1597,6 +2712,34 @@ function ActionCard({
   );
 }
 
+/** Error returned by backend commands (`CommandError` in error.rs) */
+interface CommandError {
+  kind: "noProject" | "fileMissing" | "permissionDenied" | "invalidInput" | "invalidData"
+    | "encoder" | "capture" | "cancelled" | "io";
+  message: string;
+  /** Set when recording was refused for a missing OS permission */
+  permission?: { which: string; remediation: string } | null;
+}
+
+const ERROR_HINTS: Partial<Record<CommandError["kind"], string>> = {
+  noProject: "Record or open a project first.",
+  fileMissing: "Check that the project's files haven't been moved or deleted.",
+  permissionDenied: "Check file and screen recording permissions in system settings.",
+  encoder: "Try another codec or encoder in the export settings.",
+};
+
+/** Message for a failed invoke, with a hint for errors the user can fix */
+function errorMessage(err: unknown): string {
+  if (typeof err === "object" && err !== null && "kind" in err && "message" in err) {
+    const { kind, message, permission } = err as CommandError;
+    // The message already says how to grant the permission
+    if (permission) return message;
+    const hint = ERROR_HINTS[kind];
+    return hint && !message.includes(hint) ? `${message} ${hint}` : message;
+  }
+  return String(err);
+}
+
 function formatTimecode(seconds: number): string {
   const m = Math.floor(seconds / 60).toString().padStart(2, "0");
   const s = Math.floor(seconds % 60).toString().padStart(2, "0");